@@ -0,0 +1,205 @@
+//! Precompute the cheapest path between every pair of nodes of a small static map.
+//!
+//! On a board game sized map of a few hundred hexagons every path can be worked out up front.
+//! `AllPairs` holds the cost of the cheapest path between each pair of nodes and the first step
+//! to take along it, so a query is a lookup and walking a path costs one lookup per node. The
+//! tables grow with the square of the number of nodes, a map of `500` nodes needs around 2MB.
+//!
+//! Building the tables means a search from every node, which can be done once at build time. The
+//! tables are written out with `AllPairs::to_bytes` and read back with `AllPairs::from_bytes`:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::all_pairs::AllPairs;
+//! use hexagonal_pathfinding_astar::test_support::axial_fixture;
+//! use std::collections::HashSet;
+//! let grid = axial_fixture(3, 1);
+//! let nodes: HashSet<(i32, i32)> = grid.nodes().keys().copied().collect();
+//! let bytes = AllPairs::new(&grid, nodes.iter().copied()).to_bytes();
+//! // e.g `include_bytes!` the tables into a release build
+//! let tables = AllPairs::from_bytes(nodes, &bytes).unwrap();
+//! assert_eq!(Some(0.0), tables.cost(&(0, 0), &(0, 0)));
+//! ```
+
+use crate::node_store::NodeIndex;
+use crate::path::Path;
+use crate::search::MinScored;
+use crate::search::SearchSpace;
+use ::std::collections::BinaryHeap;
+use ::std::convert::TryInto;
+use ::std::hash::Hash;
+
+/// Marks the absence of a first step, when a node cannot reach another or is the other
+const NO_STEP: u32 = u32::MAX;
+
+/// The cost of the cheapest path between every pair of a set of nodes and the first step of
+/// each, see the module documentation
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllPairs<N: Copy + Eq + Hash + Ord> {
+	/// Numbers the nodes, the tables are laid out by these ids
+	index: NodeIndex<N>,
+	/// The cost of the cheapest path from each node to each node, row by row of origin
+	costs: Vec<f32>,
+	/// The id of the first step from each node towards each node, laid out as `costs`
+	next: Vec<u32>,
+}
+
+impl<N: Copy + Eq + Hash + Ord> AllPairs<N> {
+	/// Build the tables for `nodes` of `space`, only paths which stay within `nodes` are
+	/// considered. Duplicates are ignored
+	pub fn new<S, I>(space: &S, nodes: I) -> AllPairs<N>
+	where
+		S: SearchSpace<Node = N>,
+		I: IntoIterator<Item = N>,
+	{
+		let index = NodeIndex::new(nodes);
+		let count = index.len();
+		let mut costs = vec![f32::INFINITY; count * count];
+		let mut next = vec![NO_STEP; count * count];
+		// search backwards from each destination to find the first step of every origin
+		for to in 0..count {
+			let cell = |from: usize| from * count + to;
+			costs[cell(to)] = 0.0;
+			let mut queue = BinaryHeap::new();
+			queue.push(MinScored(0.0, to));
+			while let Some(MinScored(cost, id)) = queue.pop() {
+				// skip stale entries where a cheaper route has since been found
+				if cost > costs[cell(id)] {
+					continue;
+				}
+				for (n, step_complexity) in space.predecessors(index.nodes()[id]) {
+					let from = match index.id(&n) {
+						Some(from) => from,
+						None => continue,
+					};
+					let complexity = cost + step_complexity;
+					if complexity < costs[cell(from)] {
+						costs[cell(from)] = complexity;
+						next[cell(from)] = id as u32;
+						queue.push(MinScored(complexity, from));
+					}
+				}
+			}
+		}
+		AllPairs { index, costs, next }
+	}
+	/// Number of nodes in the tables
+	pub fn len(&self) -> usize {
+		self.index.len()
+	}
+	/// Whether the tables hold no nodes
+	pub fn is_empty(&self) -> bool {
+		self.index.is_empty()
+	}
+	/// The position in the tables of the path from `from` to `to`
+	fn cell(&self, from: &N, to: &N) -> Option<usize> {
+		Some(self.index.id(from)? * self.len() + self.index.id(to)?)
+	}
+	/// The cost of the cheapest path from `from` to `to`, `None` if either is not in the tables or
+	/// `to` cannot be reached
+	pub fn cost(&self, from: &N, to: &N) -> Option<f32> {
+		let cost = self.costs[self.cell(from, to)?];
+		if cost.is_finite() {
+			Some(cost)
+		} else {
+			None
+		}
+	}
+	/// The node to step to from `from` to follow the cheapest path to `to`, `None` if `from` is
+	/// `to`, either is not in the tables or `to` cannot be reached
+	pub fn next_step(&self, from: &N, to: &N) -> Option<N> {
+		match self.next[self.cell(from, to)?] {
+			NO_STEP => None,
+			id => self.index.node(id as usize),
+		}
+	}
+	/// The cheapest path from `from` to `to` walked from the tables, `None` if either is not in
+	/// the tables or `to` cannot be reached
+	pub fn path(&self, from: &N, to: &N) -> Option<Path<N>> {
+		let total = self.cost(from, to)?;
+		let mut nodes = vec![*from];
+		let mut costs = vec![0.0];
+		let mut current = *from;
+		while let Some(next) = self.next_step(&current, to) {
+			nodes.push(next);
+			// the cost so far is whatever of the total is not still to come
+			costs.push(total - self.costs[self.cell(&next, to)?]);
+			current = next;
+		}
+		Some(Path::new(nodes, costs))
+	}
+	/// The tables as bytes, to be read back with `AllPairs::from_bytes`. The nodes themselves are
+	/// not included, only the number of them followed by the costs and first steps in little
+	/// endian order
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::with_capacity(4 + self.costs.len() * 8);
+		bytes.extend_from_slice(&(self.len() as u32).to_le_bytes());
+		for cost in self.costs.iter() {
+			bytes.extend_from_slice(&cost.to_le_bytes());
+		}
+		for next in self.next.iter() {
+			bytes.extend_from_slice(&next.to_le_bytes());
+		}
+		bytes
+	}
+	/// Read tables written by `AllPairs::to_bytes` for the same `nodes`, which may be given in
+	/// any order. `None` if `bytes` do not hold tables for that many nodes
+	pub fn from_bytes<I: IntoIterator<Item = N>>(nodes: I, bytes: &[u8]) -> Option<AllPairs<N>> {
+		let index = NodeIndex::new(nodes);
+		let count = index.len();
+		let words: Vec<[u8; 4]> = bytes
+			.chunks(4)
+			.map(|chunk| chunk.try_into().ok())
+			.collect::<Option<Vec<[u8; 4]>>>()?;
+		if words.len() != 1 + 2 * count * count || u32::from_le_bytes(words[0]) as usize != count {
+			return None;
+		}
+		let (costs, next) = words[1..].split_at(count * count);
+		let costs = costs.iter().map(|w| f32::from_le_bytes(*w)).collect();
+		let next: Vec<u32> = next.iter().map(|w| u32::from_le_bytes(*w)).collect();
+		if next.iter().any(|n| *n != NO_STEP && *n as usize >= count) {
+			return None;
+		}
+		Some(AllPairs { index, costs, next })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::all_pairs::*;
+	use crate::search::astar_path;
+	use crate::test_support::assert_path_optimal;
+	use crate::test_support::axial_fixture;
+
+	#[test]
+	/// Every path walked from the tables is the cheapest
+	fn matches_search() {
+		for seed in 0..4 {
+			let grid = axial_fixture(3, seed);
+			let nodes: Vec<(i32, i32)> = grid.nodes().keys().copied().collect();
+			let tables = AllPairs::new(&grid, nodes.iter().copied());
+			assert_eq!(nodes.len(), tables.len());
+			for from in nodes.iter() {
+				for to in nodes.iter() {
+					let path = tables.path(from, to);
+					assert_path_optimal(&grid, *from, *to, path.as_ref());
+					let expected = astar_path(&grid, *from, *to).map(|p| p.cost());
+					assert_eq!(expected.is_some(), tables.cost(from, to).is_some());
+				}
+			}
+			assert_eq!(None, tables.cost(&(0, 0), &(9, 9)));
+		}
+	}
+	#[test]
+	/// Tables read back from bytes match those written, and bytes for a different number of
+	/// nodes are refused
+	fn bytes_round_trip() {
+		let grid = axial_fixture(2, 3);
+		let mut nodes: Vec<(i32, i32)> = grid.nodes().keys().copied().collect();
+		let tables = AllPairs::new(&grid, nodes.iter().copied());
+		let bytes = tables.to_bytes();
+		nodes.reverse();
+		assert_eq!(Some(tables), AllPairs::from_bytes(nodes.clone(), &bytes));
+		assert!(AllPairs::from_bytes(nodes[1..].iter().copied(), &bytes).is_none());
+		assert!(AllPairs::from_bytes(nodes, &bytes[..bytes.len() - 1]).is_none());
+	}
+}
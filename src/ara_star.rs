@@ -0,0 +1,277 @@
+//! Find the best path which fits in a time budget.
+//!
+//! A game with a frame budget of a couple of milliseconds cannot always afford an optimal search,
+//! but a path which is good enough now is usually better than the best path a few frames late.
+//! `AraStar` is an anytime planner, Anytime Repairing A* by Likhachev, Gordon and Thrun. It begins
+//! with a weighted search, see `search::astar_path_weighted()`, which quickly finds a path costing
+//! at most `epsilon` times the cheapest. While time remains `epsilon` is lowered and the path
+//! improved, reusing the work of the earlier searches rather than starting again, until `epsilon`
+//! reaches `1` and the path is the cheapest.
+//!
+//! A search which runs out of time part way through is resumed by the next call to `improve`, so
+//! a planner can be given a slice of every frame until it is done:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::ara_star::AraStar;
+//! use hexagonal_pathfinding_astar::test_support::axial_fixture;
+//! use std::time::Duration;
+//! let grid = axial_fixture(4, 1);
+//! let mut planner = AraStar::new(&grid, (0, 0), (4, -2), 3.0, 0.5);
+//! while !planner.is_done() {
+//!     if let Some((path, bound)) = planner.improve(&grid, Duration::from_millis(2)) {
+//!         // `path` costs at most `bound` times the cheapest path
+//!         assert!(bound >= 1.0 && !path.is_empty());
+//!     }
+//! }
+//! ```
+
+use crate::path::Path;
+use crate::search::MinScored;
+use crate::search::SearchSpace;
+use ::std::collections::BinaryHeap;
+use ::std::collections::HashMap;
+use ::std::collections::HashSet;
+use ::std::hash::Hash;
+use ::std::time::Duration;
+use ::std::time::Instant;
+
+/// An anytime planner which keeps improving its path for as long as it is given time, see the
+/// module documentation
+#[derive(Debug, Clone)]
+pub struct AraStar<N: Copy + Eq + Hash> {
+	/// Where the path begins
+	start: N,
+	/// Where the path ends
+	goal: N,
+	/// The factor the heuristic is inflated by in the current search
+	epsilon: f32,
+	/// How much `epsilon` is lowered by after each search
+	decrement: f32,
+	/// The cheapest known cost of reaching each discovered node
+	costs: HashMap<N, f32>,
+	/// The node each discovered node was cheapest reached from
+	came_from: HashMap<N, N>,
+	/// Nodes waiting to be expanded in the current search
+	open: HashSet<N>,
+	/// `open` scored by cost plus inflated heuristic, along with stale entries which are skipped
+	queue: BinaryHeap<MinScored<N>>,
+	/// Nodes expanded in the current search
+	closed: HashSet<N>,
+	/// Nodes made cheaper after being expanded in the current search, reopened by the next
+	inconsistent: HashSet<N>,
+	/// The best path found so far and how many times the cheapest it might cost
+	best: Option<(Path<N>, f32)>,
+	/// Whether the cheapest path has been found or shown not to exist
+	done: bool,
+}
+
+impl<N: Copy + Eq + Hash> AraStar<N> {
+	/// Create a planner from `start` to `goal` whose first search inflates the heuristic by
+	/// `epsilon`, lowering it by `decrement` after every search. Panics if `epsilon` is less than
+	/// `1` or `decrement` is not positive
+	pub fn new<S: SearchSpace<Node = N>>(
+		space: &S,
+		start: N,
+		goal: N,
+		epsilon: f32,
+		decrement: f32,
+	) -> AraStar<N> {
+		if epsilon.is_nan() || epsilon < 1.0 {
+			panic!(
+				"Heuristic inflation factor must be at least 1, found {}",
+				epsilon
+			);
+		}
+		if decrement.is_nan() || decrement <= 0.0 {
+			panic!("Inflation decrement must be positive, found {}", decrement);
+		}
+		let mut planner = AraStar {
+			start,
+			goal,
+			epsilon,
+			decrement,
+			costs: HashMap::new(),
+			came_from: HashMap::new(),
+			open: HashSet::new(),
+			queue: BinaryHeap::new(),
+			closed: HashSet::new(),
+			inconsistent: HashSet::new(),
+			best: None,
+			done: false,
+		};
+		planner.costs.insert(start, 0.0);
+		planner.open.insert(start);
+		let score = planner.score(space, start);
+		planner.queue.push(MinScored(score, start));
+		planner
+	}
+	/// The factor the heuristic is inflated by in the current search
+	pub fn epsilon(&self) -> f32 {
+		self.epsilon
+	}
+	/// Whether the cheapest path has been found or shown not to exist, further calls to
+	/// `improve` do nothing
+	pub fn is_done(&self) -> bool {
+		self.done
+	}
+	/// The best path found so far and the bound on how many times the cost of the cheapest path
+	/// it might cost, `None` if no path has been found yet
+	pub fn best(&self) -> Option<(&Path<N>, f32)> {
+		self.best.as_ref().map(|(path, bound)| (path, *bound))
+	}
+	/// Search for at most `budget`, improving the path for as long as time remains. Returns the
+	/// best path found so far with its bound, `None` if there is none yet or `goal` cannot be
+	/// reached
+	pub fn improve<S: SearchSpace<Node = N>>(
+		&mut self,
+		space: &S,
+		budget: Duration,
+	) -> Option<(Path<N>, f32)> {
+		let deadline = Instant::now() + budget;
+		while !self.done {
+			if !self.improve_path(space, deadline) {
+				break;
+			}
+			if self.costs.contains_key(&self.goal) {
+				let path = self.reconstruct_path();
+				let bound = self.bound(space);
+				self.best = Some((path, bound));
+			}
+			if self.epsilon <= 1.0 || !self.costs.contains_key(&self.goal) {
+				self.done = true;
+				break;
+			}
+			// tighten the bound and reopen every node which the last search left inconsistent
+			self.epsilon = (self.epsilon - self.decrement).max(1.0);
+			let mut open: Vec<N> = self.open.drain().collect();
+			open.extend(self.inconsistent.drain());
+			self.closed.clear();
+			self.queue.clear();
+			for node in open {
+				self.open.insert(node);
+				let score = self.score(space, node);
+				self.queue.push(MinScored(score, node));
+			}
+		}
+		self.best.clone()
+	}
+	/// The cost of reaching `node` plus the inflated heuristic
+	fn score<S: SearchSpace<Node = N>>(&self, space: &S, node: N) -> f32 {
+		self.costs[&node] + self.epsilon * space.heuristic(node, self.goal)
+	}
+	/// Expand nodes until no open node could improve the path to the goal under the current
+	/// `epsilon`, returning `false` if `deadline` passes first
+	fn improve_path<S: SearchSpace<Node = N>>(&mut self, space: &S, deadline: Instant) -> bool {
+		loop {
+			let goal_score = match self.costs.get(&self.goal) {
+				Some(c) => *c,
+				None => f32::INFINITY,
+			};
+			let (score, node) = match self.queue.peek() {
+				Some(MinScored(score, node)) => (*score, *node),
+				None => return true,
+			};
+			// skip stale entries for nodes expanded or rescored since
+			if !self.open.contains(&node) || score != self.score(space, node) {
+				self.queue.pop();
+				continue;
+			}
+			if goal_score <= score {
+				return true;
+			}
+			if Instant::now() >= deadline {
+				return false;
+			}
+			self.queue.pop();
+			self.open.remove(&node);
+			self.closed.insert(node);
+			let cost = self.costs[&node];
+			for (n, step_complexity) in space.successors(node) {
+				let complexity = cost + step_complexity;
+				let is_better = match self.costs.get(&n) {
+					Some(existing) => complexity < *existing,
+					None => true,
+				};
+				if is_better {
+					self.costs.insert(n, complexity);
+					self.came_from.insert(n, node);
+					if self.closed.contains(&n) {
+						self.inconsistent.insert(n);
+					} else {
+						self.open.insert(n);
+						let score = self.score(space, n);
+						self.queue.push(MinScored(score, n));
+					}
+				}
+			}
+		}
+	}
+	/// How many times the cheapest path the path to the goal might cost, found from the lowest
+	/// uninflated score of any node which could still lead to a cheaper path
+	fn bound<S: SearchSpace<Node = N>>(&self, space: &S) -> f32 {
+		let goal_cost = self.costs[&self.goal];
+		let lowest = self
+			.open
+			.iter()
+			.chain(self.inconsistent.iter())
+			.map(|n| self.costs[n] + space.heuristic(*n, self.goal))
+			.fold(f32::INFINITY, f32::min);
+		if lowest >= goal_cost {
+			// nothing left open could lead anywhere cheaper
+			return 1.0;
+		}
+		if lowest <= 0.0 {
+			return self.epsilon;
+		}
+		self.epsilon.min(goal_cost / lowest).max(1.0)
+	}
+	/// Walk backwards from the goal through `came_from` to rebuild the path which reached it
+	fn reconstruct_path(&self) -> Path<N> {
+		let mut nodes = vec![self.goal];
+		let mut current = self.goal;
+		while current != self.start {
+			current = self.came_from[&current];
+			nodes.push(current);
+		}
+		nodes.reverse();
+		let costs = nodes.iter().map(|n| self.costs[n]).collect();
+		Path::new(nodes, costs)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::ara_star::*;
+	use crate::test_support::assert_path_optimal;
+	use crate::test_support::axial_fixture;
+
+	#[test]
+	/// Given enough time the planner finishes with the cheapest path and a bound of `1`
+	fn converges_to_optimal() {
+		for seed in 0..6 {
+			let grid = axial_fixture(5, seed);
+			for goal in [(5, -2), (-5, 0), (0, 5), (0, 0)] {
+				let mut planner = AraStar::new(&grid, (0, 0), goal, 3.0, 0.5);
+				let found = planner.improve(&grid, Duration::from_secs(60));
+				assert!(planner.is_done());
+				assert_path_optimal(&grid, (0, 0), goal, found.as_ref().map(|f| &f.0));
+				if let Some((_, bound)) = found {
+					assert_eq!(1.0, bound);
+				}
+			}
+		}
+	}
+	#[test]
+	/// Without any time nothing is searched, the search resumes where it left off when time is
+	/// given
+	fn resumes_after_budget() {
+		let grid = axial_fixture(5, 4);
+		let mut planner = AraStar::new(&grid, (-5, 2), (5, -2), 2.0, 1.0);
+		assert!(planner.improve(&grid, Duration::from_secs(0)).is_none());
+		assert!(planner.best().is_none());
+		assert!(!planner.is_done());
+		let found = planner.improve(&grid, Duration::from_secs(60));
+		assert!(planner.is_done());
+		assert_path_optimal(&grid, (-5, 2), (5, -2), found.as_ref().map(|f| &f.0));
+	}
+}
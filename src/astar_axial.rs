@@ -2,7 +2,9 @@
 //!
 //! Axial coordinates use the convention of `q` for column and `r` for row. In the example below the `r` is a diagonal row.
 //! For hexagon layouts where the pointy tops are facing up the calculations remain exactly the same as you're effectively
-//! just rotating the grid by 30 degrees making `r` horizontal and `q` diagonal.
+//! just rotating the grid by 30 degrees making `r` horizontal and `q` diagonal. `astar_path_oriented()` accepts an
+//! `AxialOrientation` so pointy topped grids can be searched as they are drawn, neighbours are then expanded in
+//! clockwise order from the east rather than from the north which decides between routes of equal cost.
 //!
 //! ```txt
 //!              _______
@@ -23,7 +25,11 @@
 
 use crate::helpers::axial_to_cubic;
 use crate::helpers::node_distance;
-use crate::helpers::node_neighbours_axial;
+use crate::helpers::node_neighbours_axial_oriented;
+use crate::path::Path;
+use crate::search;
+use crate::search::WeightedNodes;
+use crate::AxialOrientation;
 use ::std::collections::HashMap;
 use core::panic;
 
@@ -77,13 +83,35 @@ use core::panic;
 ///
 /// We have 2 rings of hexagons surrounding it.
 ///
-/// The return Vec contains a number of tuples which for `0..n` show the best path to take
+/// The returned `Path` contains the nodes which for `0..n` show the best path to take along with the
+/// cumulative cost of traversing it
 pub fn astar_path(
 	start_node: (i32, i32),
 	nodes: HashMap<(i32, i32), f32>,
 	end_node: (i32, i32),
 	count_rings: i32,
-) -> Vec<(i32, i32)> {
+) -> Path<(i32, i32)> {
+	astar_path_oriented(
+		start_node,
+		nodes,
+		end_node,
+		count_rings,
+		AxialOrientation::FlatTop,
+	)
+}
+
+/// From a starting node calculate the most efficient path to the end node of a grid drawn with
+/// the given `orientation`. The nodes and their neighbours are the same in either orientation, see
+/// `helpers::node_neighbours_axial_oriented()`, so the cost of the returned path is too, only the
+/// choice between routes of equal cost may differ. The other inputs and the returned `Path` are as
+/// for `astar_path()`
+pub fn astar_path_oriented(
+	start_node: (i32, i32),
+	nodes: HashMap<(i32, i32), f32>,
+	end_node: (i32, i32),
+	count_rings: i32,
+	orientation: AxialOrientation,
+) -> Path<(i32, i32)> {
 	// ensure nodes data contains start and end points
 	if !nodes.contains_key(&start_node) {
 		panic!(
@@ -113,117 +141,19 @@ pub fn astar_path(
 	{
 		panic!("End node is outside of searchable grid")
 	}
-	// calculate the weight of each node and produce a new combined data set of everthing we need
-	// keys are nodes and values are a tuple of (complexity, weight)
-	let mut nodes_weighted: HashMap<(i32, i32), (f32, f32)> = HashMap::new();
-	// calculate a weighting for each node based on its distance from the end node
-	for (k, v) in nodes.iter() {
-		nodes_weighted.insert(
-			k.to_owned(),
-			(v.to_owned(), calculate_node_weight(k, &end_node)),
-		);
-	}
-
-	let start_weight: f32 = match nodes_weighted.get(&start_node) {
-		Some(x) => x.1,
-		None => panic!("Unable to find node weight"),
-	};
-
-	// every time we process a new node we add it to a map
-	// if a node has already been recorded then we replace it if it has a better a-star score (smaller number)
-	// otherwise we discard it.
-	// this is used to optimise the searching whereby if we find a new path to a previously
-	// discovered node we can quickly decide to discard or explore the new route
-	let mut node_astar_scores: HashMap<(i32, i32), f32> = HashMap::new();
-	// add starting node a-star score to data set (starting node score is just its weight)
-	node_astar_scores.insert(start_node, start_weight);
-
-	// create a queue of nodes to be processed based on discovery
-	// of form (current_node, a_star_score, vec_previous_nodes_traversed, total_complexity)
-	// start by add starting node to queue
-	let mut queue = vec![(
-		start_node,
-		start_weight, // we haven't moved so starting node score is just its weight
-		Vec::<(i32, i32)>::new(),
-		0.0,
-	)];
-
-	// target node will eventually be shifted to first of queue so finish processing once it arrives, meaning that we know the best path
-	while queue[0].0 != end_node {
-		// remove the first element ready for processing
-		let current_path = queue.swap_remove(0);
-		// expand the node in the current path
-		let available_nodes = node_neighbours_axial(current_path.0, count_rings);
-		// process each new path
-		for n in available_nodes.iter() {
-			let previous_complexities: f32 = current_path.3;
-			// grab the half complexity of the currrent node
-			let current_node_complexity: f32 = match nodes_weighted.get(&current_path.0) {
-				Some(x) => x.0 * 0.5,
-				None => panic!("Unable to find current node complexity for {:?}", &n),
-			};
-			// grab half the complexity of the neighbour node
-			let target_node_complexity: f32 = match nodes_weighted.get(n) {
-				Some(x) => x.0 * 0.5,
-				None => panic!("Unable to find target node complexity for {:?}", &n),
-			};
-			// calculate its fields
-			let complexity =
-				previous_complexities + target_node_complexity + current_node_complexity;
-			let target_weight: f32 = match nodes_weighted.get(n) {
-				Some(x) => x.1,
-				None => panic!("Unable to find node weight for {:?}", &n),
-			};
-			let astar = a_star_score(complexity, target_weight);
-			let mut previous_nodes_traversed = current_path.2.clone();
-			previous_nodes_traversed.push(current_path.0);
-			// update the a-star data set
-			if node_astar_scores.contains_key(n) {
-				if node_astar_scores.get(n) >= Some(&astar) {
-					// data set contains a worse score so update the set with the better score
-					node_astar_scores.insert(*n, astar);
-					// search the queue to see if we already have a route to this node.
-					// If we do but this new path is better then replace it, otherwise discard
-					let mut new_queue_item_required_for_node = true;
-					for mut q in queue.iter_mut() {
-						if &q.0 == n {
-							// if existing score is worse then replace the queue item and
-							// don't allow a fresh queue item to be added
-							if q.1 >= astar {
-								new_queue_item_required_for_node = false;
-								q.1 = astar;
-								q.2 = previous_nodes_traversed.clone();
-								q.3 = complexity;
-							}
-						}
-					}
-					// queue doesn't contain a route to this node, as we have now found a better route
-					// update the queue with it so it can be explored
-					if new_queue_item_required_for_node {
-						queue.push((*n, astar, previous_nodes_traversed, complexity));
-					}
-				}
-			} else {
-				// no record of node and new path required in queue
-				// update the a-star score data
-				node_astar_scores.insert(*n, astar);
-				// update the queue to process through
-				queue.push((*n, astar, previous_nodes_traversed, complexity));
-			}
-		}
-
-		// sort the queue by a-star sores so each loop processes the best
-		queue.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+	// weight each node by its distance from the end node as it is discovered
+	let nodes_weighted = WeightedNodes::new(
+		&nodes,
+		|k| calculate_node_weight(k, &end_node),
+		|n| node_neighbours_axial_oriented(n, count_rings, &orientation),
+	);
+	match search::astar_path(&nodes_weighted, start_node, end_node) {
+		Some(path) => path,
+		None => panic!(
+			"No path exists between start node ({},{}) and end node ({},{})",
+			start_node.0, start_node.1, end_node.0, end_node.1
+		),
 	}
-	let mut best_path = queue[0].2.clone();
-	// add end node to data
-	best_path.push(end_node);
-	best_path
-}
-
-/// Determines a score to rank a chosen path, lower scores are better
-fn a_star_score(complexity: f32, weighting: f32) -> f32 {
-	complexity + weighting
 }
 
 /// Finds a nodes weight based on the number of 'jumps' you'd have to make from
@@ -240,7 +170,13 @@ fn calculate_node_weight(current_node: &(i32, i32), end_node: &(i32, i32)) -> f3
 #[cfg(test)]
 mod tests {
 	use crate::astar_axial::astar_path;
+	use crate::astar_axial::astar_path_oriented;
 	use crate::astar_axial::calculate_node_weight;
+	use crate::helpers::node_neighbours_axial;
+	use crate::search;
+	use crate::search::WeightedNodes;
+	use crate::AxialOrientation;
+	use std::cell::Cell;
 	use std::collections::HashMap;
 
 	#[test]
@@ -276,6 +212,34 @@ mod tests {
 		assert_eq!(actual_weight, weight);
 	}
 	#[test]
+	/// A short path across a large grid only weighs the nodes the search discovers rather than
+	/// every node of the grid
+	fn weights_only_discovered_nodes() {
+		let count_rings: i32 = 60;
+		let mut nodes: HashMap<(i32, i32), f32> = HashMap::new();
+		for q in -count_rings..=count_rings {
+			for r in -count_rings..=count_rings {
+				if (q + r).abs() <= count_rings {
+					nodes.insert((q, r), 1.0);
+				}
+			}
+		}
+		let end_node = (3, 0);
+		let weighed = Cell::new(0);
+		let nodes_weighted = WeightedNodes::new(
+			&nodes,
+			|k| {
+				weighed.set(weighed.get() + 1);
+				calculate_node_weight(k, &end_node)
+			},
+			|n| node_neighbours_axial(n, count_rings),
+		);
+		let path = search::astar_path(&nodes_weighted, (0, 0), end_node).unwrap();
+		assert_eq!(3.0, path.cost());
+		assert!(weighed.get() < 100, "weighed {} nodes", weighed.get());
+		assert!(nodes.len() > 10000);
+	}
+	#[test]
 	/// Calcualtes the best path from S to E
 	///```txt
 	///                              _________
@@ -336,6 +300,55 @@ mod tests {
 		let rings = 2;
 		let best = astar_path(start_node, nodes, end_node, rings);
 		let actual = vec![(0, 0), (0, -1), (1, -2), (2, -2), (2, -1), (2, 0)];
-		assert_eq!(actual, best);
+		assert_eq!(actual, best.into_nodes());
+	}
+	#[test]
+	/// The best path reports the cumulative cost of reaching each node, moving between two
+	/// nodes costs half the complexity of each
+	fn astar_path_cost() {
+		let start_node: (i32, i32) = (0, 0);
+		let mut nodes: HashMap<(i32, i32), f32> = HashMap::new();
+		nodes.insert((0, 0), 1.0);
+		nodes.insert((0, 1), 1.0);
+		nodes.insert((1, 0), 15.0);
+		nodes.insert((1, -1), 14.0);
+		nodes.insert((0, -1), 2.0);
+		nodes.insert((-1, 0), 6.0);
+		nodes.insert((-1, 1), 7.0);
+		nodes.insert((0, 2), 1.0);
+		nodes.insert((1, 1), 14.0);
+		nodes.insert((2, 0), 1.0);
+		nodes.insert((2, -1), 1.0);
+		nodes.insert((2, -2), 1.0);
+		nodes.insert((1, -2), 1.0);
+		nodes.insert((0, -2), 1.0);
+		nodes.insert((-1, -1), 3.0);
+		nodes.insert((-2, 0), 1.0);
+		nodes.insert((-2, 1), 8.0);
+		nodes.insert((-2, 2), 1.0);
+		nodes.insert((-1, 2), 2.0);
+		let end_node: (i32, i32) = (2, 0);
+		let rings = 2;
+		let best = astar_path(start_node, nodes, end_node, rings);
+		assert_eq!(6.0, best.cost());
+		assert_eq!(5, best.hops());
+		let actual = vec![0.0, 1.5, 3.0, 4.0, 5.0, 6.0];
+		assert_eq!(actual, best.cumulative_costs());
+	}
+	#[test]
+	/// A pointy topped grid finds a path of the same cost as the flat topped grid it is drawn from
+	fn pointy_top_same_cost() {
+		let mut nodes: HashMap<(i32, i32), f32> = HashMap::new();
+		for q in -2..=2_i32 {
+			for r in -2..=2_i32 {
+				if (q + r).abs() <= 2 {
+					nodes.insert((q, r), 1.0 + ((q * 3 + r * 5).rem_euclid(4)) as f32);
+				}
+			}
+		}
+		let flat = astar_path((-2, 1), nodes.clone(), (2, -1), 2);
+		let pointy = astar_path_oriented((-2, 1), nodes, (2, -1), 2, AxialOrientation::PointyTop);
+		assert_eq!(flat.cost(), pointy.cost());
+		assert_eq!(Some(&(2, -1)), pointy.last());
 	}
 }
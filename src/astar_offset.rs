@@ -40,8 +40,14 @@
 //!
 
 use crate::helpers::node_distance;
+use crate::helpers::node_distance_offset_wrapping;
 use crate::helpers::node_neighbours_offset;
+use crate::helpers::node_neighbours_offset_wrapping;
 use crate::helpers::offset_to_cubic;
+use crate::path::Path;
+use crate::search;
+use crate::search::WeightedNodes;
+use crate::GridBounds;
 use crate::HexOrientation;
 use ::std::collections::HashMap;
 use core::panic;
@@ -98,7 +104,8 @@ use core::panic;
 ///
 /// `orientation` refers to your hexagonal grid layout.
 ///
-/// The return Vec contains a number of tuples which for `0..n` show the best path to take
+/// The returned `Path` contains the nodes which for `0..n` show the best path to take along with the
+/// cumulative cost of traversing it
 #[allow(clippy::too_many_arguments)]
 pub fn astar_path(
 	start_node: (i32, i32),
@@ -109,7 +116,7 @@ pub fn astar_path(
 	min_row: i32,
 	max_row: i32,
 	orientation: HexOrientation,
-) -> Vec<(i32, i32)> {
+) -> Path<(i32, i32)> {
 	// ensure nodes data contains start and end points
 	if !nodes.contains_key(&start_node) {
 		panic!(
@@ -139,129 +146,161 @@ pub fn astar_path(
 	{
 		panic!("End node is outside of searchable grid")
 	}
-	// calculate the weight of each node and produce a new combined data set of everthing we need
-	// keys are nodes and values are a tuple of (complexity, weight)
-	let mut nodes_weighted: HashMap<(i32, i32), (f32, f32)> = HashMap::new();
-	// calculate a weighting for each node based on its distance from the end node
-	for (k, v) in nodes.iter() {
-		nodes_weighted.insert(
-			k.to_owned(),
-			(
-				v.to_owned(),
-				calculate_node_weight(k, &end_node, &orientation),
-			),
-		);
+	// weight each node by its distance from the end node as it is discovered
+	let nodes_weighted = WeightedNodes::new(
+		&nodes,
+		|k| calculate_node_weight(k, &end_node, &orientation),
+		|n| node_neighbours_offset(n, &orientation, min_column, max_column, min_row, max_row),
+	);
+	match search::astar_path(&nodes_weighted, start_node, end_node) {
+		Some(path) => path,
+		None => panic!(
+			"No path exists between start node ({},{}) and end node ({},{})",
+			start_node.0, start_node.1, end_node.0, end_node.1
+		),
 	}
+}
 
-	let start_weight: f32 = match nodes_weighted.get(&start_node) {
-		Some(x) => x.1,
-		None => panic!("Unable to find node weight"),
-	};
-
-	// every time we process a new node we add it to a map
-	// if a node has already been recorded then we replace it if it has a better a-star score (smaller number)
-	// otherwise we discard it.
-	// this is used to optimise the searching whereby if we find a new path to a previously
-	// discovered node we can quickly decide to discard or explore the new route
-	let mut node_astar_scores: HashMap<(i32, i32), f32> = HashMap::new();
-	// add starting node a-star score to data set (starting node score is just its weight)
-	node_astar_scores.insert(start_node, start_weight);
-
-	// create a queue of nodes to be processed based on discovery
-	// of form (current_node, a_star_score, vec_previous_nodes_traversed, total_complexity)
-	// start by add starting node to queue
-	let mut queue = vec![(
+/// From a starting node calculate the most efficient path to the end node of a grid described
+/// by inclusive `GridBounds`, e.g `GridBounds::from_size(4, 4)` for the grid shown in
+/// `astar_path()`. The other inputs and the returned `Path` are as for `astar_path()`
+pub fn astar_path_in_bounds(
+	start_node: (i32, i32),
+	nodes: HashMap<(i32, i32), f32>,
+	end_node: (i32, i32),
+	bounds: GridBounds,
+	orientation: HexOrientation,
+) -> Path<(i32, i32)> {
+	let (min_column, max_column, min_row, max_row) = bounds.exclusive();
+	astar_path(
 		start_node,
-		start_weight, // we haven't moved so starting node score is just its weight
-		Vec::<(i32, i32)>::new(),
-		0.0,
-	)];
+		nodes,
+		end_node,
+		min_column,
+		max_column,
+		min_row,
+		max_row,
+		orientation,
+	)
+}
 
-	// target node will eventually be shifted to first of queue so finish processing once it arrives, meaning that we know the best path
-	while queue[0].0 != end_node {
-		// println!("QUEUE");
-		// println!("{:?}", queue);
-		// remove the first element ready for processing
-		let current_path = queue.swap_remove(0);
-		// expand the node in the current path
-		let available_nodes = node_neighbours_offset(
-			current_path.0,
-			&orientation,
-			min_column,
-			max_column,
-			min_row,
-			max_row,
+/// From a starting node calculate the most efficient path to the end node across a grid which
+/// wraps around at its boundaries, like the map of a planet. When `wrap_columns` is true a path
+/// can leave the left edge of the grid and arrive at the right edge and vice versa,
+/// `wrap_rows` does the same for the top and bottom edges. The other inputs are as for
+/// `astar_path()`.
+///
+/// Alternate columns, or rows, are shifted in an Offset grid so a wrapping axis must hold an even
+/// number of columns for flat topped orientations or an even number of rows for pointy topped
+/// orientations, otherwise the hexagons either side of the seam would not line up.
+///
+/// The returned `Path` contains the nodes which for `0..n` show the best path to take along with the
+/// cumulative cost of traversing it
+#[allow(clippy::too_many_arguments)]
+pub fn astar_path_wrapping(
+	start_node: (i32, i32),
+	nodes: HashMap<(i32, i32), f32>,
+	end_node: (i32, i32),
+	min_column: i32,
+	max_column: i32,
+	min_row: i32,
+	max_row: i32,
+	orientation: HexOrientation,
+	wrap_columns: bool,
+	wrap_rows: bool,
+) -> Path<(i32, i32)> {
+	// ensure nodes data contains start and end points
+	if !nodes.contains_key(&start_node) {
+		panic!(
+			"Node data does not contain start node ({},{})",
+			start_node.0, start_node.1
 		);
-		// process each new path
-		for n in available_nodes.iter() {
-			let previous_complexities: f32 = current_path.3;
-			// grab the half complexity of the currrent node
-			let current_node_complexity: f32 = match nodes_weighted.get(&current_path.0) {
-				Some(x) => x.0 * 0.5,
-				None => panic!("Unable to find current node complexity for {:?}", &n),
-			};
-			// grab half the complexity of the neighbour node
-			let target_node_complexity: f32 = match nodes_weighted.get(n) {
-				Some(x) => x.0 * 0.5,
-				None => panic!("Unable to find target node complexity for {:?}", &n),
-			};
-			// calculate its fields
-			let complexity =
-				previous_complexities + target_node_complexity + current_node_complexity;
-			let target_weight: f32 = match nodes_weighted.get(n) {
-				Some(x) => x.1,
-				None => panic!("Unable to find node weight for {:?}", &n),
-			};
-			let astar = a_star_score(complexity, target_weight);
-			let mut previous_nodes_traversed = current_path.2.clone();
-			previous_nodes_traversed.push(current_path.0);
-			// update the a-star data set
-			if node_astar_scores.contains_key(n) {
-				if node_astar_scores.get(n) >= Some(&astar) {
-					// data set contains a worse score so update the set with the better score
-					node_astar_scores.insert(*n, astar);
-					// search the queue to see if we already have a route to this node.
-					// If we do but this new path is better then replace it, otherwise discard
-					let mut new_queue_item_required_for_node = true;
-					for mut q in queue.iter_mut() {
-						if &q.0 == n {
-							// if existing score is worse then replace the queue item and
-							// don't allow a fresh queue item to be added
-							if q.1 >= astar {
-								new_queue_item_required_for_node = false;
-								q.1 = astar;
-								q.2 = previous_nodes_traversed.clone();
-								q.3 = complexity;
-							}
-						}
-					}
-					// queue doesn't contain a route to this node, as we have now found a better route
-					// update the queue with it so it can be explored
-					if new_queue_item_required_for_node {
-						queue.push((*n, astar, previous_nodes_traversed, complexity));
-					}
-				}
-			} else {
-				// no record of node and new path required in queue
-				// update the a-star score data
-				node_astar_scores.insert(*n, astar);
-				// update the queue to process through
-				queue.push((*n, astar, previous_nodes_traversed, complexity));
+	}
+	if !nodes.contains_key(&end_node) {
+		panic!(
+			"Node data does not contain end node ({},{})",
+			end_node.0, end_node.1
+		);
+	}
+	// ensure start and end nodes are within the max bounds of the grid
+	// max bounds are exclusive hence equal to or greater than
+	if start_node.0 >= max_column
+		|| start_node.0 <= min_column
+		|| start_node.1 >= max_row
+		|| start_node.1 <= min_row
+	{
+		panic!("Start node is outside of searchable grid")
+	}
+	if end_node.0 >= max_column
+		|| end_node.0 <= min_column
+		|| end_node.1 >= max_row
+		|| end_node.1 <= min_row
+	{
+		panic!("End node is outside of searchable grid")
+	}
+	// ensure the shifted columns or rows line up across a seam
+	let width = max_column - min_column - 1;
+	let height = max_row - min_row - 1;
+	match orientation {
+		HexOrientation::FlatTopOddUp
+		| HexOrientation::FlatTopOddDown
+		| HexOrientation::FlatTopEvenUp
+		| HexOrientation::FlatTopEvenDown => {
+			if wrap_columns && width % 2 != 0 {
+				panic!(
+					"Wrapping columns requires an even number of columns, found {}",
+					width
+				)
+			}
+		}
+		HexOrientation::PointyTopOddRight
+		| HexOrientation::PointyTopOddLeft
+		| HexOrientation::PointyTopEvenRight
+		| HexOrientation::PointyTopEvenLeft => {
+			if wrap_rows && height % 2 != 0 {
+				panic!(
+					"Wrapping rows requires an even number of rows, found {}",
+					height
+				)
 			}
 		}
-
-		// sort the queue by a-star sores so each loop processes the best
-		queue.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
 	}
-	let mut best_path = queue[0].2.clone();
-	// add end node to data
-	best_path.push(end_node);
-	best_path
-}
-
-/// Determines a score to rank a chosen path, lower scores are better
-fn a_star_score(complexity: f32, weighting: f32) -> f32 {
-	complexity + weighting
+	// weight each node by its wrapped distance from the end node as it is discovered
+	let nodes_weighted = WeightedNodes::new(
+		&nodes,
+		|k| {
+			node_distance_offset_wrapping(
+				*k,
+				end_node,
+				&orientation,
+				min_column,
+				max_column,
+				min_row,
+				max_row,
+				wrap_columns,
+				wrap_rows,
+			) as f32
+		},
+		|n| {
+			node_neighbours_offset_wrapping(
+				n,
+				&orientation,
+				min_column,
+				max_column,
+				min_row,
+				max_row,
+				wrap_columns,
+				wrap_rows,
+			)
+		},
+	);
+	match search::astar_path(&nodes_weighted, start_node, end_node) {
+		Some(path) => path,
+		None => panic!(
+			"No path exists between start node ({},{}) and end node ({},{})",
+			start_node.0, start_node.1, end_node.0, end_node.1
+		),
+	}
 }
 
 /// Finds a nodes weight based on the number of 'jumps' you'd have to make from
@@ -282,7 +321,10 @@ fn calculate_node_weight(
 #[cfg(test)]
 mod tests {
 	use crate::astar_offset::astar_path;
+	use crate::astar_offset::astar_path_in_bounds;
+	use crate::astar_offset::astar_path_wrapping;
 	use crate::astar_offset::calculate_node_weight;
+	use crate::GridBounds;
 	use crate::HexOrientation;
 	use std::collections::HashMap;
 
@@ -405,7 +447,7 @@ mod tests {
 			orientation,
 		);
 		let actual = vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 3), (3, 3)];
-		assert_eq!(actual, best);
+		assert_eq!(actual, best.into_nodes());
 	}
 	#[test]
 	/// Calcualtes the best path from S to E
@@ -475,7 +517,7 @@ mod tests {
 			orientation,
 		);
 		let actual = vec![(0, 0), (1, 0), (2, 0), (3, 0), (3, 1), (3, 2), (3, 3)];
-		assert_eq!(actual, best);
+		assert_eq!(actual, best.into_nodes());
 	}
 	#[test]
 	/// Calcualtes the best path from S (3, 3) to E (0, 0)
@@ -545,7 +587,7 @@ mod tests {
 			orientation,
 		);
 		let actual = vec![(3, 3), (2, 3), (1, 2), (0, 2), (0, 1), (0, 0)];
-		assert_eq!(actual, best);
+		assert_eq!(actual, best.into_nodes());
 	}
 	#[test]
 	/// Calcualtes the best path from S to E
@@ -615,7 +657,7 @@ mod tests {
 			orientation,
 		);
 		let actual = vec![(3, 3), (2, 3), (1, 2), (0, 2), (0, 1), (0, 0)];
-		assert_eq!(actual, best);
+		assert_eq!(actual, best.into_nodes());
 	}
 	#[test]
 	/// Calcualtes the best path from S to E
@@ -685,6 +727,63 @@ mod tests {
 			orientation,
 		);
 		let actual = vec![(0, 0), (0, 1), (0, 2), (1, 3), (2, 3)];
-		assert_eq!(actual, best);
+		assert_eq!(actual, best.into_nodes());
+	}
+	#[test]
+	/// A path on a wrapping grid crosses the seam between the left and right edges rather than
+	/// walking the width of the grid
+	/// ```txt
+	///    _______                                   _______
+	///   /       \                                 /       \
+	///  /  (0,1)  \_______                 _______/  (5,1)  \
+	///  \    E    /       \               /       \         /
+	///   \_______/  (1,1)  \_____ ... ___/  (4,1)  \_______/
+	///   /       \         /               \         /       \
+	///  /  (0,0)  \_______/                 \_______/  (5,0)  \
+	///  \         /                                 \    S    /
+	///   \_______/                                   \_______/
+	/// ```
+	fn wrapping_crosses_seam() {
+		let mut nodes: HashMap<(i32, i32), f32> = HashMap::new();
+		for column in 0..6 {
+			for row in 0..2 {
+				nodes.insert((column, row), 1.0);
+			}
+		}
+		let start_node: (i32, i32) = (5, 0);
+		let end_node: (i32, i32) = (0, 1);
+		let orientation = HexOrientation::FlatTopOddUp;
+		let best = astar_path_wrapping(
+			start_node,
+			nodes.clone(),
+			end_node,
+			-1,
+			6,
+			-1,
+			2,
+			orientation,
+			true,
+			false,
+		);
+		assert_eq!(vec![(5, 0), (0, 1)], best.into_nodes());
+		let unwrapped = astar_path(start_node, nodes, end_node, -1, 6, -1, 2, orientation);
+		assert_eq!(6, unwrapped.len());
+	}
+	#[test]
+	/// Inclusive bounds find the same path as the equivalent exclusive limits
+	fn bounds_match_exclusive_limits() {
+		let mut nodes: HashMap<(i32, i32), f32> = HashMap::new();
+		for column in 0..4 {
+			for row in 0..4 {
+				nodes.insert((column, row), 1.0);
+			}
+		}
+		nodes.insert((1, 2), 4.0);
+		let orientation = HexOrientation::FlatTopOddUp;
+		let bounds = GridBounds::from_size(4, 4);
+		assert_eq!(GridBounds::from_exclusive(-1, 4, -1, 4), bounds);
+		let expected = astar_path((0, 0), nodes.clone(), (2, 3), -1, 4, -1, 4, orientation);
+		let actual = astar_path_in_bounds((0, 0), nodes, (2, 3), bounds, orientation);
+		assert_eq!(expected.into_nodes(), actual.into_nodes());
 	}
 }
@@ -0,0 +1,134 @@
+//! Answer many independent path queries over the same search space at once.
+//!
+//! Resolving a turn of a strategy game can ask for hundreds of paths across a map which does not
+//! change while they are found. `astar_batch` answers them one after another reusing the memory of
+//! a single `SearchContext`. `astar_batch_parallel` spreads them over the thread pool of `rayon`
+//! when the `rayon` feature is enabled, each thread reusing its own context, and otherwise answers
+//! them one after another just as `astar_batch` does:
+//!
+//! ```toml
+//! [dependencies]
+//! hexagonal_pathfinding_astar = { version = "1", features = ["rayon"] }
+//! ```
+//!
+//! The feature builds on Rust 1.59 like the rest of the crate, `rayon` being held below 1.8 which
+//! raised its minimum. The crates beneath `rayon` have raised theirs since, so a lock file for an
+//! older compiler is best made by cargo 1.84 or later with the `fallback` resolver of
+//! `.cargo/config.toml`, which picks the newest versions supporting the `rust-version`.
+//!
+//! The space is shared between the threads so it must be `Sync`, which every `HexGrid` of this
+//! crate is:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::batch::astar_batch_parallel;
+//! use hexagonal_pathfinding_astar::search::SearchError;
+//! use hexagonal_pathfinding_astar::test_support::axial_fixture;
+//! let grid = axial_fixture(4, 1);
+//! let queries = vec![((0, 0), (4, 0)), ((0, 4), (-4, 0)), ((0, 0), (9, 9))];
+//! let paths = astar_batch_parallel(&grid, &queries);
+//! assert_eq!(3, paths.len());
+//! assert_eq!(Err(SearchError::Unreachable), paths[2]);
+//! ```
+
+use crate::path::Path;
+use crate::search::SearchContext;
+use crate::search::SearchError;
+use crate::search::SearchSpace;
+#[cfg(feature = "rayon")]
+use ::rayon::prelude::*;
+use ::std::hash::Hash;
+
+/// Find the cheapest path for each `(start, end)` query in turn, reusing the memory of one search
+/// for the next. Returns a result for each query in the same order as `queries`,
+/// `SearchError::Unreachable` where the end cannot be reached
+pub fn astar_batch<S: SearchSpace>(
+	space: &S,
+	queries: &[(S::Node, S::Node)],
+) -> Vec<Result<Path<S::Node>, SearchError>>
+where
+	S::Node: Hash,
+{
+	let mut context = SearchContext::new();
+	queries
+		.iter()
+		.map(|(start, end)| context.astar_path_limited(space, *start, *end, usize::MAX))
+		.collect()
+}
+
+/// Find the cheapest path for each `(start, end)` query on the thread pool of `rayon`, each
+/// thread reusing the memory of one search for its next query. Without the `rayon` feature the
+/// queries are answered in turn as by `astar_batch()`. Returns a result for each query in the
+/// same order as `queries`, `SearchError::Unreachable` where the end cannot be reached
+pub fn astar_batch_parallel<S>(
+	space: &S,
+	queries: &[(S::Node, S::Node)],
+) -> Vec<Result<Path<S::Node>, SearchError>>
+where
+	S: SearchSpace + Sync,
+	S::Node: Hash + Send + Sync,
+{
+	#[cfg(feature = "rayon")]
+	let paths = queries
+		.par_iter()
+		.map_init(SearchContext::new, |context, (start, end)| {
+			context.astar_path_limited(space, *start, *end, usize::MAX)
+		})
+		.collect();
+	#[cfg(not(feature = "rayon"))]
+	let paths = astar_batch(space, queries);
+	paths
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::batch::*;
+	use crate::grid::*;
+	use ::std::collections::HashMap;
+
+	#[test]
+	/// Every answer of a batch, in turn or in parallel, is the cheapest path for its query and
+	/// is given in the order of the queries. From `a` to `b` the path goes around the swamp `5`,
+	/// from `c` the swamp is the goal, a node beyond the grid cannot be reached and `d` is
+	/// already where it is going
+	/// ```txt
+	///   column:  -2  -1   0   1   2
+	///                             b
+	///                         d
+	///                     5
+	///                 .       .
+	///             a       c
+	/// ```
+	fn answers_in_query_order() {
+		let mut nodes = HashMap::new();
+		for node in [(-2, 0), (-1, 0), (1, 0), (2, 0), (0, -1), (1, -1)] {
+			nodes.insert(node, 1.0);
+		}
+		nodes.insert((0, 0), 5.0);
+		let grid = HexGrid::new(AxialLayout::new(2), nodes);
+		let queries = [
+			((-2, 0), (2, 0)),
+			((2, 0), (-2, 0)),
+			((0, -1), (0, 0)),
+			((-2, 0), (2, 3)),
+			((1, 0), (1, 0)),
+		];
+		let around = vec![(-2, 0), (-1, 0), (0, -1), (1, -1), (1, 0), (2, 0)];
+		let back = around.iter().rev().copied().collect();
+		let expected = vec![
+			Ok(around),
+			Ok(back),
+			Ok(vec![(0, -1), (0, 0)]),
+			Err(SearchError::Unreachable),
+			Ok(vec![(1, 0)]),
+		];
+		let nodes = |answers: Vec<Result<Path<(i32, i32)>, SearchError>>| {
+			answers
+				.into_iter()
+				.map(|answer| answer.map(Path::into_nodes))
+				.collect::<Vec<Result<Vec<(i32, i32)>, SearchError>>>()
+		};
+		assert_eq!(expected, nodes(astar_batch(&grid, &queries)));
+		assert_eq!(expected, nodes(astar_batch_parallel(&grid, &queries)));
+		assert!(astar_batch_parallel(&grid, &[]).is_empty());
+	}
+}
@@ -0,0 +1,241 @@
+//! Worlds split into chunks which are loaded and unloaded as the player moves, for open world
+//! maps too large to hold in memory.
+//!
+//! A chunk is a parallelogram of `chunk_size` by `chunk_size` Axial nodes, chunk `(0, 0)` holding
+//! the nodes `(0, 0)` to `(chunk_size - 1, chunk_size - 1)`. Each chunk's complexities are held in
+//! a flat `Vec<f32>` row by row, the node `(q, r)` of a chunk at `r * chunk_size + q` counting from
+//! the chunk's first node, with `NaN` marking a hole:
+//!
+//! ```txt
+//!  r
+//!  ^   chunk (0, 1)  chunk (1, 1)
+//!  |   chunk (0, 0)  chunk (1, 0)
+//!  +----------------------------> q
+//! ```
+//!
+//! `ChunkedNodes` holds whichever chunks have been loaded, nodes of any other chunk do not exist
+//! so a search ends gracefully at the border of the loaded world. `StreamingNodes` instead asks a
+//! loader for each missing chunk the search reaches, e.g reading it from disk or generating it:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::chunked::StreamingNodes;
+//! use hexagonal_pathfinding_astar::grid::{AxialLayout, HexGrid};
+//! use hexagonal_pathfinding_astar::search::astar_path;
+//! let nodes = StreamingNodes::new(16, |chunk: (i32, i32)| Some(vec![1.0; 16 * 16]));
+//! let grid = HexGrid::new(AxialLayout::unbounded(), nodes);
+//! let path = astar_path(&grid, (0, 0), (40, 0)).unwrap();
+//! assert_eq!(40.0, path.cost());
+//! // only chunks the search reached were loaded
+//! assert!(grid.nodes().loaded().len() < 10);
+//! ```
+
+use crate::node_store::NodeStore;
+use ::std::cell::RefCell;
+use ::std::collections::HashMap;
+use ::std::collections::HashSet;
+
+/// The chunks of a world which have been loaded, see the module documentation
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkedNodes {
+	/// Number of columns and rows of nodes in each chunk
+	chunk_size: i32,
+	/// Complexities of each loaded chunk
+	chunks: HashMap<(i32, i32), Vec<f32>>,
+}
+
+impl ChunkedNodes {
+	/// Create a world of chunks `chunk_size` nodes across with none loaded. Panics if
+	/// `chunk_size` is not positive
+	pub fn new(chunk_size: i32) -> ChunkedNodes {
+		if chunk_size < 1 {
+			panic!(
+				"Chunks must be at least one node across, found {}",
+				chunk_size
+			);
+		}
+		ChunkedNodes {
+			chunk_size,
+			chunks: HashMap::new(),
+		}
+	}
+	/// Number of columns and rows of nodes in each chunk
+	pub fn chunk_size(&self) -> i32 {
+		self.chunk_size
+	}
+	/// The chunk holding `node`
+	pub fn chunk_of(&self, node: (i32, i32)) -> (i32, i32) {
+		(
+			node.0.div_euclid(self.chunk_size),
+			node.1.div_euclid(self.chunk_size),
+		)
+	}
+	/// Load `chunk` with `complexities` laid out as described in the module documentation,
+	/// replacing it if already loaded. Returns `false` and loads nothing if there is not exactly
+	/// one complexity for each node of the chunk
+	pub fn load(&mut self, chunk: (i32, i32), complexities: Vec<f32>) -> bool {
+		if complexities.len() != (self.chunk_size * self.chunk_size) as usize {
+			return false;
+		}
+		self.chunks.insert(chunk, complexities);
+		true
+	}
+	/// Unload `chunk`, returning its complexities if it was loaded
+	pub fn unload(&mut self, chunk: (i32, i32)) -> Option<Vec<f32>> {
+		self.chunks.remove(&chunk)
+	}
+	/// Whether `chunk` is loaded
+	pub fn is_loaded(&self, chunk: (i32, i32)) -> bool {
+		self.chunks.contains_key(&chunk)
+	}
+	/// Every loaded chunk
+	pub fn loaded(&self) -> Vec<(i32, i32)> {
+		self.chunks.keys().copied().collect()
+	}
+	/// Set the complexity of `node`, returning `false` if its chunk is not loaded
+	pub fn set_complexity(&mut self, node: (i32, i32), complexity: f32) -> bool {
+		let (chunk, index) = self.locate(node);
+		match self.chunks.get_mut(&chunk) {
+			Some(complexities) => {
+				complexities[index] = complexity;
+				true
+			}
+			None => false,
+		}
+	}
+	/// The chunk holding `node` and the position of `node` within it
+	fn locate(&self, node: (i32, i32)) -> ((i32, i32), usize) {
+		let chunk = self.chunk_of(node);
+		let q = node.0 - chunk.0 * self.chunk_size;
+		let r = node.1 - chunk.1 * self.chunk_size;
+		(chunk, (r * self.chunk_size + q) as usize)
+	}
+}
+
+impl NodeStore<(i32, i32)> for ChunkedNodes {
+	fn complexity(&self, node: &(i32, i32)) -> Option<f32> {
+		let (chunk, index) = self.locate(*node);
+		let complexity = self.chunks.get(&chunk)?[index];
+		if complexity.is_nan() {
+			None
+		} else {
+			Some(complexity)
+		}
+	}
+}
+
+/// Chunks loaded on demand as a search reaches them, see the module documentation.
+///
+/// Loading happens behind a shared reference so this store cannot be shared between threads,
+/// load the chunks into a `ChunkedNodes` first for that
+#[derive(Debug)]
+pub struct StreamingNodes<F> {
+	/// The chunks loaded so far
+	chunks: RefCell<ChunkedNodes>,
+	/// Chunks the loader could not supply, not asked for again
+	missing: RefCell<HashSet<(i32, i32)>>,
+	/// Supplies the complexities of a chunk, `None` if it does not exist
+	loader: F,
+}
+
+impl<F: Fn((i32, i32)) -> Option<Vec<f32>>> StreamingNodes<F> {
+	/// Create a world of chunks `chunk_size` nodes across where `loader` supplies each chunk the
+	/// first time one of its nodes is needed, returning `None` for chunks which do not exist.
+	/// Panics if `chunk_size` is not positive
+	pub fn new(chunk_size: i32, loader: F) -> StreamingNodes<F> {
+		StreamingNodes::with_chunks(ChunkedNodes::new(chunk_size), loader)
+	}
+	/// Create a world from chunks which have already been loaded, `loader` supplying any others
+	pub fn with_chunks(chunks: ChunkedNodes, loader: F) -> StreamingNodes<F> {
+		StreamingNodes {
+			chunks: RefCell::new(chunks),
+			missing: RefCell::new(HashSet::new()),
+			loader,
+		}
+	}
+	/// The chunk holding `node`
+	pub fn chunk_of(&self, node: (i32, i32)) -> (i32, i32) {
+		self.chunks.borrow().chunk_of(node)
+	}
+	/// Every chunk loaded so far
+	pub fn loaded(&self) -> Vec<(i32, i32)> {
+		self.chunks.borrow().loaded()
+	}
+	/// Forget which chunks the loader could not supply so they are asked for again
+	pub fn retry_missing(&mut self) {
+		self.missing.get_mut().clear();
+	}
+	/// The chunks loaded so far, e.g to unload those far from the player before streaming on
+	pub fn into_inner(self) -> ChunkedNodes {
+		self.chunks.into_inner()
+	}
+}
+
+impl<F: Fn((i32, i32)) -> Option<Vec<f32>>> NodeStore<(i32, i32)> for StreamingNodes<F> {
+	fn complexity(&self, node: &(i32, i32)) -> Option<f32> {
+		let chunk = self.chunk_of(*node);
+		if !self.chunks.borrow().is_loaded(chunk) {
+			if self.missing.borrow().contains(&chunk) {
+				return None;
+			}
+			let loaded = match (self.loader)(chunk) {
+				Some(complexities) => self.chunks.borrow_mut().load(chunk, complexities),
+				None => false,
+			};
+			if !loaded {
+				self.missing.borrow_mut().insert(chunk);
+				return None;
+			}
+		}
+		self.chunks.borrow().complexity(node)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::chunked::*;
+	use crate::grid::AxialLayout;
+	use crate::grid::HexGrid;
+	use crate::search::astar_path;
+	use ::std::cell::Cell;
+
+	#[test]
+	/// Nodes of loaded chunks are searched and the search ends at the border of the loaded
+	/// world, until the chunk beyond is loaded
+	fn ends_at_unloaded_chunks() {
+		let mut nodes = ChunkedNodes::new(4);
+		assert!(nodes.load((0, 0), vec![1.0; 16]));
+		assert!(nodes.load((1, 0), vec![2.0; 16]));
+		assert!(!nodes.load((2, 0), vec![1.0; 3]));
+		assert_eq!((-1, 1), nodes.chunk_of((-1, 7)));
+		assert!(nodes.set_complexity((5, 0), f32::NAN));
+		assert_eq!(None, nodes.complexity(&(5, 0)));
+		assert_eq!(Some(2.0), nodes.complexity(&(5, 1)));
+		let mut grid = HexGrid::new(AxialLayout::unbounded(), nodes);
+		assert_eq!(4.5, astar_path(&grid, (0, 0), (4, 0)).unwrap().cost());
+		assert!(astar_path(&grid, (0, 0), (9, 0)).is_none());
+		grid.nodes_mut().load((2, 0), vec![1.0; 16]);
+		assert!(astar_path(&grid, (0, 0), (9, 0)).is_some());
+		assert_eq!(Some(vec![1.0; 16]), grid.nodes_mut().unload((2, 0)));
+		assert!(astar_path(&grid, (0, 0), (9, 0)).is_none());
+	}
+	#[test]
+	/// Streaming asks for each chunk at most once, and a missing chunk is routed around
+	fn streams_chunks_once() {
+		let requests = Cell::new(0);
+		let loader = |chunk: (i32, i32)| {
+			requests.set(requests.get() + 1);
+			if chunk == (1, 0) {
+				None
+			} else {
+				Some(vec![1.0; 16])
+			}
+		};
+		let grid = HexGrid::new(AxialLayout::unbounded(), StreamingNodes::new(4, loader));
+		let path = astar_path(&grid, (0, 1), (9, 1)).unwrap();
+		for node in path.iter() {
+			assert_ne!((1, 0), grid.nodes().chunk_of(*node));
+		}
+		let loaded = grid.nodes().loaded().len();
+		assert_eq!(loaded + 1, requests.get());
+	}
+}
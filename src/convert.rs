@@ -0,0 +1,563 @@
+//! Convert whole paths between coordinate systems.
+//!
+//! Codebases which mix coordinate systems, an Axial search feeding an Offset renderer say, end up
+//! mapping over paths with a conversion call per node. Each call takes the orientation again and
+//! nothing checks that the nodes are really in the system being converted from, so a path of
+//! Offset nodes passed through `helpers::axial_to_cubic()` silently becomes a different path.
+//!
+//! `convert_path` converts an entire path in one call. Each node carries the system it is written
+//! in as a `HexCoord` and every node is checked before anything is converted:
+//!
+//! * the node is in the system being converted from
+//! * Cubic nodes satisfy `x + y + z = 0`
+//! * Spiral Hex indices are not negative
+//!
+//! The first node failing a check is returned as the error. Cumulative costs are carried over
+//! unchanged as the path still visits the same hexagons.
+//!
+//! Maps convert in one call too, `convert_map_offset_to_cubic` and friends rewriting the keys of
+//! the `nodes` taken by the searches and keeping the complexities. Each system names a hexagon
+//! exactly once so no two keys collide. The Cubic search needs every node within its rings of the
+//! origin, so a hexagonal map kept in Offset coordinates can be searched with
+//! `astar_cubic::astar_path()`:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::astar_cubic;
+//! use hexagonal_pathfinding_astar::convert::{convert_map_offset_to_cubic, rings_enclosing};
+//! use hexagonal_pathfinding_astar::helpers::{nodes_within_range_offset, offset_to_cubic};
+//! use hexagonal_pathfinding_astar::HexOrientation;
+//! use std::collections::HashMap;
+//! let orientation = HexOrientation::FlatTopOddUp;
+//! let mut nodes = HashMap::new();
+//! for node in nodes_within_range_offset((0, 0), 3, &orientation) {
+//!     nodes.insert(node, 1.0);
+//! }
+//! let cubic = convert_map_offset_to_cubic(&nodes, &orientation);
+//! let start = offset_to_cubic((-2, -1), &orientation);
+//! let end = offset_to_cubic((3, 0), &orientation);
+//! let rings = rings_enclosing(&cubic);
+//! let path = astar_cubic::astar_path(start, cubic, end, rings);
+//! assert_eq!(Some(&end), path.last());
+//! ```
+//!
+//! Converting to Offset coordinates, `GridBounds::enclosing()` finds the bounds to pass to
+//! `astar_offset::astar_path_in_bounds()`.
+//!
+//! `path_to_directions` turns a path into moves, dropping the coordinates altogether to leave the
+//! `HexDirection` of each step, a compact record of the moves for animation or replays. The paths
+//! returned by the searches go straight in to `offset_path_to_directions`,
+//! `axial_path_to_directions` and `cubic_path_to_directions`:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::astar_axial;
+//! use hexagonal_pathfinding_astar::convert::axial_path_to_directions;
+//! use hexagonal_pathfinding_astar::helpers::nodes_within_range_axial;
+//! use hexagonal_pathfinding_astar::{AxialOrientation, HexDirection};
+//! use std::collections::HashMap;
+//! let mut nodes = HashMap::new();
+//! for node in nodes_within_range_axial((0, 0), 2) {
+//!     nodes.insert(node, 1.0);
+//! }
+//! let path = astar_axial::astar_path((0, 0), nodes, (0, 2), 2);
+//! let directions = axial_path_to_directions(&path, &AxialOrientation::FlatTop).unwrap();
+//! assert_eq!(vec![HexDirection::North, HexDirection::North], directions);
+//! ```
+
+use crate::helpers::axial_to_cubic;
+use crate::helpers::axial_to_offset;
+use crate::helpers::cubic_to_axial;
+use crate::helpers::cubic_to_offset;
+use crate::helpers::cubic_to_spiral_hex;
+use crate::helpers::node_distance;
+use crate::helpers::offset_to_cubic;
+use crate::helpers::spiral_hex_to_cubic;
+use crate::path::Path;
+use crate::AxialOrientation;
+use crate::HexDirection;
+use crate::HexOrientation;
+use ::std::collections::HashMap;
+
+/// A coordinate system a node can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexSystem {
+	/// Offset `(column, row)`, dependent on a `HexOrientation`
+	Offset,
+	/// Axial `(q, r)`
+	Axial,
+	/// Cubic `(x, y, z)`
+	Cubic,
+	/// A single Spiral Hex index, see `helpers::spiral_hex_to_cubic()`
+	Spiral,
+}
+
+/// A node tagged with the coordinate system it is written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexCoord {
+	/// Offset `(column, row)`, dependent on a `HexOrientation`
+	Offset((i32, i32)),
+	/// Axial `(q, r)`
+	Axial((i32, i32)),
+	/// Cubic `(x, y, z)`, valid when `x + y + z = 0`
+	Cubic((i32, i32, i32)),
+	/// A single Spiral Hex index, valid when not negative
+	Spiral(i32),
+}
+
+impl HexCoord {
+	/// The coordinate system the node is written in
+	pub fn system(&self) -> HexSystem {
+		match self {
+			HexCoord::Offset(_) => HexSystem::Offset,
+			HexCoord::Axial(_) => HexSystem::Axial,
+			HexCoord::Cubic(_) => HexSystem::Cubic,
+			HexCoord::Spiral(_) => HexSystem::Spiral,
+		}
+	}
+	/// Whether the node is a valid coordinate of its system
+	pub fn is_valid(&self) -> bool {
+		match self {
+			HexCoord::Cubic(n) => n.0 + n.1 + n.2 == 0,
+			HexCoord::Spiral(index) => *index >= 0,
+			HexCoord::Offset(_) | HexCoord::Axial(_) => true,
+		}
+	}
+	/// The node in Cubic coordinates, `orientation` is only used by Offset nodes. The node must be
+	/// valid, see `is_valid()`
+	pub fn to_cubic(self, orientation: &HexOrientation) -> (i32, i32, i32) {
+		match self {
+			HexCoord::Offset(n) => offset_to_cubic(n, orientation),
+			HexCoord::Axial(n) => axial_to_cubic(n),
+			HexCoord::Cubic(n) => n,
+			HexCoord::Spiral(index) => spiral_hex_to_cubic(index),
+		}
+	}
+	/// Write the Cubic node `cube` in `system`, `orientation` is only used by Offset nodes
+	pub fn from_cubic(
+		cube: (i32, i32, i32),
+		system: HexSystem,
+		orientation: &HexOrientation,
+	) -> HexCoord {
+		match system {
+			HexSystem::Offset => HexCoord::Offset(cubic_to_offset(cube, orientation)),
+			HexSystem::Axial => HexCoord::Axial(cubic_to_axial(cube)),
+			HexSystem::Cubic => HexCoord::Cubic(cube),
+			HexSystem::Spiral => HexCoord::Spiral(cubic_to_spiral_hex(cube)),
+		}
+	}
+	/// Write the node in `system`, `orientation` is only used by Offset nodes. The node must be
+	/// valid, see `is_valid()`
+	pub fn convert(self, system: HexSystem, orientation: &HexOrientation) -> HexCoord {
+		if self.system() == system {
+			return self;
+		}
+		HexCoord::from_cubic(self.to_cubic(orientation), system, orientation)
+	}
+}
+
+/// Convert every node of `path` from `from_system` to `to_system`, keeping the cumulative costs.
+/// `orientation` describes the Offset grid if either system is Offset. Returns the first node not
+/// written in `from_system`, or not a valid coordinate of it, as the error
+pub fn convert_path(
+	path: &Path<HexCoord>,
+	from_system: HexSystem,
+	to_system: HexSystem,
+	orientation: HexOrientation,
+) -> Result<Path<HexCoord>, HexCoord> {
+	for node in path.iter() {
+		if node.system() != from_system || !node.is_valid() {
+			return Err(*node);
+		}
+	}
+	let nodes = path
+		.iter()
+		.map(|n| n.convert(to_system, &orientation))
+		.collect();
+	Ok(Path::new(nodes, path.cumulative_costs().to_vec()))
+}
+
+/// The direction of each step along `path` drawn with `orientation`, one fewer than there are
+/// nodes. Nodes may be written in any system, `orientation` also describing the Offset grid of
+/// Offset nodes. Returns the first invalid node, or the first node which is not a neighbour of
+/// the one before it, as the error
+pub fn path_to_directions(
+	path: &Path<HexCoord>,
+	orientation: HexOrientation,
+) -> Result<Vec<HexDirection>, HexCoord> {
+	if let Some(node) = path.iter().find(|n| !n.is_valid()) {
+		return Err(*node);
+	}
+	let nodes = path
+		.iter()
+		.map(|n| cubic_to_axial(n.to_cubic(&orientation)));
+	axial_directions(nodes, &orientation.axial_orientation()).map_err(|i| path.nodes()[i])
+}
+
+/// The direction of each step along a path of Offset nodes in `orientation`, such as those
+/// returned by `astar_offset`, one fewer than there are nodes. Returns the first node which is not
+/// a neighbour of the one before it as the error
+pub fn offset_path_to_directions(
+	path: &Path<(i32, i32)>,
+	orientation: HexOrientation,
+) -> Result<Vec<HexDirection>, (i32, i32)> {
+	let nodes = path
+		.iter()
+		.map(|n| cubic_to_axial(offset_to_cubic(*n, &orientation)));
+	axial_directions(nodes, &orientation.axial_orientation()).map_err(|i| path.nodes()[i])
+}
+
+/// The direction of each step along a path of Axial nodes drawn with `orientation`, such as those
+/// returned by `astar_axial`, one fewer than there are nodes. Returns the first node which is not
+/// a neighbour of the one before it as the error
+pub fn axial_path_to_directions(
+	path: &Path<(i32, i32)>,
+	orientation: &AxialOrientation,
+) -> Result<Vec<HexDirection>, (i32, i32)> {
+	axial_directions(path.iter().copied(), orientation).map_err(|i| path.nodes()[i])
+}
+
+/// The direction of each step along a path of Cubic nodes drawn with `orientation`, such as those
+/// returned by `astar_cubic`, one fewer than there are nodes. Returns the first node which does
+/// not satisfy `x + y + z = 0`, or is not a neighbour of the one before it, as the error
+pub fn cubic_path_to_directions(
+	path: &Path<(i32, i32, i32)>,
+	orientation: &AxialOrientation,
+) -> Result<Vec<HexDirection>, (i32, i32, i32)> {
+	if let Some(node) = path.iter().find(|n| n.0 + n.1 + n.2 != 0) {
+		return Err(*node);
+	}
+	let nodes = path.iter().map(|n| cubic_to_axial(*n));
+	axial_directions(nodes, orientation).map_err(|i| path.nodes()[i])
+}
+
+/// The direction of each step between consecutive Axial `nodes` drawn with `orientation`, or the
+/// index of the first node which is not a neighbour of the one before it
+fn axial_directions<I: Iterator<Item = (i32, i32)>>(
+	nodes: I,
+	orientation: &AxialOrientation,
+) -> Result<Vec<HexDirection>, usize> {
+	let mut directions = Vec::new();
+	let mut previous: Option<(i32, i32)> = None;
+	for (i, current) in nodes.enumerate() {
+		if let Some(from) = previous {
+			let step = (current.0 - from.0, current.1 - from.1);
+			let direction = HexDirection::all(orientation)
+				.into_iter()
+				.find(|d| d.axial_offset(orientation) == Some(step));
+			match direction {
+				Some(d) => directions.push(d),
+				None => return Err(i),
+			}
+		}
+		previous = Some(current);
+	}
+	Ok(directions)
+}
+
+/// Rewrite the keys of an Offset map in `orientation` as Cubic coordinates, keeping the
+/// complexities
+pub fn convert_map_offset_to_cubic(
+	nodes: &HashMap<(i32, i32), f32>,
+	orientation: &HexOrientation,
+) -> HashMap<(i32, i32, i32), f32> {
+	nodes
+		.iter()
+		.map(|(k, v)| (offset_to_cubic(*k, orientation), *v))
+		.collect()
+}
+/// Rewrite the keys of an Offset map in `orientation` as Axial coordinates, keeping the
+/// complexities
+pub fn convert_map_offset_to_axial(
+	nodes: &HashMap<(i32, i32), f32>,
+	orientation: &HexOrientation,
+) -> HashMap<(i32, i32), f32> {
+	nodes
+		.iter()
+		.map(|(k, v)| (cubic_to_axial(offset_to_cubic(*k, orientation)), *v))
+		.collect()
+}
+/// Rewrite the keys of an Axial map as Cubic coordinates, keeping the complexities
+pub fn convert_map_axial_to_cubic(
+	nodes: &HashMap<(i32, i32), f32>,
+) -> HashMap<(i32, i32, i32), f32> {
+	nodes
+		.iter()
+		.map(|(k, v)| (axial_to_cubic(*k), *v))
+		.collect()
+}
+/// Rewrite the keys of an Axial map as Offset coordinates in `orientation`, keeping the
+/// complexities
+pub fn convert_map_axial_to_offset(
+	nodes: &HashMap<(i32, i32), f32>,
+	orientation: &HexOrientation,
+) -> HashMap<(i32, i32), f32> {
+	nodes
+		.iter()
+		.map(|(k, v)| (axial_to_offset(*k, orientation), *v))
+		.collect()
+}
+/// Rewrite the keys of a Cubic map as Axial coordinates, keeping the complexities. Every key
+/// must satisfy `x + y + z = 0`
+pub fn convert_map_cubic_to_axial(
+	nodes: &HashMap<(i32, i32, i32), f32>,
+) -> HashMap<(i32, i32), f32> {
+	nodes
+		.iter()
+		.map(|(k, v)| (cubic_to_axial(*k), *v))
+		.collect()
+}
+/// Rewrite the keys of a Cubic map as Offset coordinates in `orientation`, keeping the
+/// complexities. Every key must satisfy `x + y + z = 0`
+pub fn convert_map_cubic_to_offset(
+	nodes: &HashMap<(i32, i32, i32), f32>,
+	orientation: &HexOrientation,
+) -> HashMap<(i32, i32), f32> {
+	nodes
+		.iter()
+		.map(|(k, v)| (cubic_to_offset(*k, orientation), *v))
+		.collect()
+}
+/// The fewest rings around the origin holding every node of a Cubic map, the `count_rings` to
+/// search it with `astar_cubic::astar_path()`. An Axial map can be measured once converted with
+/// `convert_map_axial_to_cubic()`
+pub fn rings_enclosing(nodes: &HashMap<(i32, i32, i32), f32>) -> i32 {
+	nodes
+		.keys()
+		.map(|n| node_distance(*n, (0, 0, 0)))
+		.max()
+		.unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::astar_axial;
+	use crate::convert::*;
+	use crate::helpers::node_neighbour_axial;
+	use crate::helpers::nodes_within_range_axial;
+	use crate::GridBounds;
+
+	/// A path of three Axial nodes heading north from the origin
+	/// ```txt
+	///     _______
+	///    /       \
+	///   /   0,2   \
+	///   \         /
+	///    \_______/
+	///    /       \
+	///   /   0,1   \
+	///   \         /
+	///    \_______/
+	///    /       \
+	///   /   0,0   \
+	///   \         /
+	///    \_______/
+	/// ```
+	fn north() -> Path<HexCoord> {
+		Path::new(
+			vec![
+				HexCoord::Axial((0, 0)),
+				HexCoord::Axial((0, 1)),
+				HexCoord::Axial((0, 2)),
+			],
+			vec![0.0, 1.0, 2.5],
+		)
+	}
+	#[test]
+	/// Converting through every system and back again gives the original path and costs
+	fn round_trip() {
+		let orientation = HexOrientation::FlatTopOddUp;
+		let path = north();
+		let mut current = path.clone();
+		let mut from = HexSystem::Axial;
+		for to in [
+			HexSystem::Cubic,
+			HexSystem::Spiral,
+			HexSystem::Offset,
+			HexSystem::Axial,
+		] {
+			current = convert_path(&current, from, to, orientation).unwrap();
+			assert!(current.iter().all(|n| n.system() == to));
+			from = to;
+		}
+		assert_eq!(path, current);
+		let cubic = convert_path(&path, HexSystem::Axial, HexSystem::Cubic, orientation).unwrap();
+		assert_eq!(
+			&[
+				HexCoord::Cubic((0, 0, 0)),
+				HexCoord::Cubic((0, -1, 1)),
+				HexCoord::Cubic((0, -2, 2)),
+			],
+			cubic.nodes()
+		);
+		assert_eq!(2.5, cubic.cost());
+	}
+	#[test]
+	/// Nodes of the wrong system and invalid nodes are reported rather than converted
+	fn rejects_mismatched_nodes() {
+		let orientation = HexOrientation::PointyTopOddRight;
+		let mixed = Path::new(
+			vec![HexCoord::Axial((0, 0)), HexCoord::Offset((0, 1))],
+			vec![0.0, 1.0],
+		);
+		assert_eq!(
+			Err(HexCoord::Offset((0, 1))),
+			convert_path(&mixed, HexSystem::Axial, HexSystem::Cubic, orientation)
+		);
+		let invalid = Path::new(vec![HexCoord::Cubic((1, 1, 0))], vec![0.0]);
+		assert_eq!(
+			Err(HexCoord::Cubic((1, 1, 0))),
+			convert_path(&invalid, HexSystem::Cubic, HexSystem::Axial, orientation)
+		);
+		let negative = Path::new(vec![HexCoord::Spiral(-3)], vec![0.0]);
+		assert_eq!(
+			Err(HexCoord::Spiral(-3)),
+			convert_path(&negative, HexSystem::Spiral, HexSystem::Axial, orientation)
+		);
+	}
+	#[test]
+	/// Each step of a path becomes its direction whichever systems the nodes are written in, and
+	/// replaying the directions from the start visits the same nodes
+	fn directions_of_path() {
+		let orientation = HexOrientation::FlatTopOddUp;
+		let directions = path_to_directions(&north(), orientation).unwrap();
+		assert_eq!(vec![HexDirection::North, HexDirection::North], directions);
+		let path = Path::new(
+			vec![
+				HexCoord::Axial((0, 0)),
+				HexCoord::Cubic((1, -1, 0)),
+				HexCoord::from_cubic((2, 1, -1), HexSystem::Offset, &orientation),
+				HexCoord::Spiral(0),
+			],
+			vec![0.0, 1.0, 2.0, 3.0],
+		);
+		assert_eq!(
+			Err(HexCoord::Spiral(0)),
+			path_to_directions(&path, orientation)
+		);
+		let pointy = HexOrientation::PointyTopOddRight;
+		let mixed = Path::new(
+			vec![
+				HexCoord::Cubic((0, 0, 0)),
+				HexCoord::Axial((1, -1)),
+				HexCoord::from_cubic((0, 1, -1), HexSystem::Spiral, &pointy),
+			],
+			vec![0.0, 1.0, 2.0],
+		);
+		let directions = path_to_directions(&mixed, pointy).unwrap();
+		assert_eq!(
+			vec![HexDirection::SouthEast, HexDirection::West],
+			directions
+		);
+		let mut node = (0, 0);
+		for direction in directions {
+			node = node_neighbour_axial(node, direction, &pointy.axial_orientation()).unwrap();
+		}
+		assert_eq!(
+			HexCoord::Axial(node),
+			mixed.nodes()[2].convert(HexSystem::Axial, &pointy)
+		);
+		let invalid = Path::new(vec![HexCoord::Cubic((1, 1, 0))], vec![0.0]);
+		assert_eq!(
+			Err(HexCoord::Cubic((1, 1, 0))),
+			path_to_directions(&invalid, orientation)
+		);
+		let single = Path::new(vec![HexCoord::Spiral(3)], vec![0.0]);
+		assert_eq!(Ok(vec![]), path_to_directions(&single, orientation));
+	}
+	#[test]
+	/// Paths straight out of each search become the same directions whichever system they are
+	/// written in, and a gap in a path is returned as the error
+	fn directions_of_search_paths() {
+		let orientation = HexOrientation::FlatTopOddUp;
+		let mut axial_nodes = HashMap::new();
+		for node in nodes_within_range_axial((0, 0), 2) {
+			axial_nodes.insert(node, 1.0);
+		}
+		let axial = astar_axial::astar_path((0, 0), axial_nodes, (2, -1), 2);
+		let axial_orientation = orientation.axial_orientation();
+		let expected = axial_path_to_directions(&axial, &axial_orientation).unwrap();
+		assert_eq!(axial.hops(), expected.len());
+		let cubic = Path::new(
+			axial.iter().map(|n| axial_to_cubic(*n)).collect(),
+			axial.cumulative_costs().to_vec(),
+		);
+		assert_eq!(
+			Ok(expected.clone()),
+			cubic_path_to_directions(&cubic, &axial_orientation)
+		);
+		let offset = Path::new(
+			axial
+				.iter()
+				.map(|n| axial_to_offset(*n, &orientation))
+				.collect(),
+			axial.cumulative_costs().to_vec(),
+		);
+		assert_eq!(
+			Ok(expected.clone()),
+			offset_path_to_directions(&offset, orientation)
+		);
+		let mut node = (0, 0);
+		for direction in expected {
+			node = node_neighbour_axial(node, direction, &axial_orientation).unwrap();
+		}
+		assert_eq!(Some(&node), axial.last());
+		let gap = Path::new(vec![(0, 0), (0, 1), (0, 3)], vec![0.0, 1.0, 2.0]);
+		assert_eq!(
+			Err((0, 3)),
+			axial_path_to_directions(&gap, &axial_orientation)
+		);
+		let invalid = Path::new(vec![(0, 0, 0), (1, 0, 0)], vec![0.0, 1.0]);
+		assert_eq!(
+			Err((1, 0, 0)),
+			cubic_path_to_directions(&invalid, &axial_orientation)
+		);
+	}
+	#[test]
+	/// Maps convert between every pair of systems keeping their complexities, converting back
+	/// again gives the original map, and the bounds and rings of the converted map enclose it
+	/// ```txt
+	///              _______
+	///             /       \
+	///     _______/  (1,2)  \_______
+	///    /       \         /       \
+	///   /  (0,2)  \_______/  (2,2)  \
+	///   \         /       \         /
+	///    \_______/  (1,1)  \_______/
+	///    /       \         /       \
+	///   /  (0,1)  \_______/  (2,1)  \
+	///   \         /       \         /
+	///    \_______/  (1,0)  \_______/
+	///            \         /
+	///             \_______/
+	/// ```
+	fn convert_maps() {
+		let orientation = HexOrientation::FlatTopOddUp;
+		let mut offset = HashMap::new();
+		for (i, node) in [(1, 0), (0, 1), (1, 1), (2, 1), (0, 2), (1, 2), (2, 2)]
+			.iter()
+			.enumerate()
+		{
+			offset.insert(*node, i as f32);
+		}
+		let cubic = convert_map_offset_to_cubic(&offset, &orientation);
+		assert_eq!(offset.len(), cubic.len());
+		let centre = offset_to_cubic((1, 1), &orientation);
+		assert_eq!(Some(&2.0), cubic.get(&centre));
+		let axial = convert_map_offset_to_axial(&offset, &orientation);
+		assert_eq!(axial, convert_map_cubic_to_axial(&cubic));
+		assert_eq!(cubic, convert_map_axial_to_cubic(&axial));
+		assert_eq!(offset, convert_map_cubic_to_offset(&cubic, &orientation));
+		assert_eq!(offset, convert_map_axial_to_offset(&axial, &orientation));
+		assert_eq!(
+			Some(GridBounds::new(0, 2, 0, 2)),
+			GridBounds::enclosing(offset.keys().copied())
+		);
+		assert_eq!(
+			None,
+			GridBounds::enclosing(HashMap::<(i32, i32), f32>::new().into_keys())
+		);
+		let ring = convert_map_axial_to_cubic(&[((0, 0), 1.0), ((2, -3), 1.0)].into());
+		assert_eq!(3, rings_enclosing(&ring));
+		assert_eq!(0, rings_enclosing(&HashMap::new()));
+		assert_eq!(3, rings_enclosing(&cubic));
+	}
+}
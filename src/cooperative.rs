@@ -0,0 +1,224 @@
+//! Plan paths for several units so they do not walk into each other, Cooperative A*.
+//!
+//! Units which each search the same map pick the same cheapest route and pile into one another.
+//! A `CooperativePlanner` plans units one after another through space and time: each path it
+//! finds reserves every hexagon for the step the unit occupies it in a `Traffic` table, see
+//! `traffic`, and later units plan around those reservations. A unit may wait in place for a step
+//! to let another pass, and never moves into a hexagon at a step it is reserved for nor swaps
+//! places with another unit.
+//!
+//! Once a unit reaches its goal it is assumed to stay there, so the goal stays reserved until the
+//! planner's horizon. Release a unit's commitment when it finishes or abandons its path:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::cooperative::CooperativePlanner;
+//! use hexagonal_pathfinding_astar::test_support::axial_fixture;
+//! let grid = axial_fixture(3, 1);
+//! let mut planner = CooperativePlanner::new(32, 1.0);
+//! let first = planner.plan(&grid, (0, 0), (3, 0));
+//! let second = planner.plan(&grid, (3, 0), (0, 0));
+//! if let Some((id, _path)) = first {
+//!     planner.release(id);
+//! }
+//! ```
+
+use crate::path::Path;
+use crate::search::FnHooks;
+use crate::search::Limits;
+use crate::search::SearchContext;
+use crate::search::SearchSpace;
+use crate::traffic::CommitmentId;
+use crate::traffic::Traffic;
+use ::std::hash::Hash;
+
+/// Plans units one after another so each avoids the space and time reserved by those before,
+/// see the module documentation
+#[derive(Debug, Clone)]
+pub struct CooperativePlanner<N> {
+	/// Holds the reservations of every planned path
+	traffic: Traffic<N>,
+	/// Number of steps ahead planned and reserved
+	horizon: usize,
+	/// The cost of waiting in place for a step
+	wait_cost: f32,
+}
+
+impl<N: Copy + Eq + Hash> CooperativePlanner<N> {
+	/// Create a planner which looks `horizon` steps ahead, where waiting in place for a step
+	/// costs `wait_cost`. Panics if `wait_cost` is negative
+	pub fn new(horizon: usize, wait_cost: f32) -> CooperativePlanner<N> {
+		if wait_cost.is_nan() || wait_cost < 0.0 {
+			panic!("Cost of waiting cannot be negative, found {}", wait_cost);
+		}
+		CooperativePlanner {
+			traffic: Traffic::new(0.0),
+			horizon,
+			wait_cost,
+		}
+	}
+	/// The reservations of every path planned and not yet released
+	pub fn traffic(&self) -> &Traffic<N> {
+		&self.traffic
+	}
+	/// Number of steps ahead planned and reserved
+	pub fn horizon(&self) -> usize {
+		self.horizon
+	}
+	/// Plan the cheapest path from `start` to `goal` which avoids every reservation, reaching the
+	/// goal within the horizon, and reserve it. Waiting in place shows in the path as a node
+	/// repeated for a step. Returns the commitment holding the reservations along with the
+	/// path, `None` if no such path exists
+	pub fn plan<S: SearchSpace<Node = N>>(
+		&mut self,
+		space: &S,
+		start: N,
+		goal: N,
+	) -> Option<(CommitmentId, Path<N>)> {
+		let path = self.search(space, start, goal)?;
+		// the unit stays at its goal once there so hold it until the horizon
+		let mut nodes = path.nodes().to_vec();
+		let mut costs = path.cumulative_costs().to_vec();
+		while nodes.len() <= self.horizon {
+			nodes.push(goal);
+			costs.push(path.cost());
+		}
+		let id = self.traffic.commit(&Path::new(nodes, costs));
+		Some((id, path))
+	}
+	/// Remove the reservations of a planned path, returning `false` if they had already been
+	/// released
+	pub fn release(&mut self, id: CommitmentId) -> bool {
+		self.traffic.release(id)
+	}
+	/// Whether `goal` is free from `step` until the horizon, so a unit can stop there
+	fn can_stop(&self, goal: &N, step: usize) -> bool {
+		(step + 1..=self.horizon).all(|s| self.traffic.reserved_by(goal, s).is_none())
+	}
+	/// Whether moving from `from` to `to` between `step` and the next step keeps clear of every
+	/// reservation, neither entering a reserved node nor swapping places with another unit
+	fn can_move(&self, from: &N, to: &N, step: usize) -> bool {
+		if self.traffic.reserved_by(to, step + 1).is_some() {
+			return false;
+		}
+		match self.traffic.reserved_by(to, step) {
+			Some(other) if from != to => self.traffic.reserved_by(from, step + 1) != Some(other),
+			_ => true,
+		}
+	}
+	/// Search through space and time for the cheapest path which avoids every reservation, each
+	/// node of the search a node of `space` and the step it is occupied at
+	fn search<S: SearchSpace<Node = N>>(&self, space: &S, start: N, goal: N) -> Option<Path<N>> {
+		let mut hooks = FnHooks {
+			estimate: |(node, _)| space.heuristic(node, goal),
+			is_goal: |(node, step)| node == goal && self.can_stop(&goal, step),
+			successors: |(node, step): (N, usize), _| {
+				if step >= self.horizon {
+					return Vec::new();
+				}
+				let mut moves = space.successors(node);
+				moves.push((node, self.wait_cost));
+				moves
+					.into_iter()
+					.filter(|(n, _)| self.can_move(&node, n, step))
+					.map(|(n, step_complexity)| ((n, step + 1), step_complexity))
+					.collect()
+			},
+		};
+		let mut context = SearchContext::new();
+		let end = context
+			.run(&[(start, 0)], &mut hooks, &Limits::expansions(usize::MAX))
+			.ok()?;
+		let path = context.path_to(end);
+		let nodes = path.iter().map(|(node, _)| *node).collect();
+		Some(Path::new(nodes, path.cumulative_costs().to_vec()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::cooperative::*;
+	use crate::grid::AxialLayout;
+	use crate::grid::HexGrid;
+	use ::std::collections::HashMap;
+
+	/// The node `path` occupies at `step`, the last node once it has finished
+	fn at(path: &Path<(i32, i32)>, step: usize) -> (i32, i32) {
+		path.nodes()[step.min(path.len() - 1)]
+	}
+	#[test]
+	/// Units crossing a small grid in opposite directions never share a hexagon at the same step
+	/// nor swap places
+	fn units_keep_apart() {
+		let mut nodes = HashMap::new();
+		for q in -2..=2i32 {
+			for r in -2..=2i32 {
+				if (q + r).abs() <= 2 {
+					nodes.insert((q, r), 1.0);
+				}
+			}
+		}
+		let grid = HexGrid::new(AxialLayout::new(2), nodes);
+		let mut planner = CooperativePlanner::new(20, 1.0);
+		let units = [
+			((-2, 0), (2, 0)),
+			((2, 0), (-2, 0)),
+			((0, -2), (0, 2)),
+			((0, 2), (0, -2)),
+		];
+		let mut paths = Vec::new();
+		for (start, goal) in units {
+			let (_, path) = planner.plan(&grid, start, goal).unwrap();
+			assert_eq!(Some(&start), path.first());
+			assert_eq!(Some(&goal), path.last());
+			paths.push(path);
+		}
+		assert_eq!(4.0, paths[0].cost());
+		for (i, a) in paths.iter().enumerate() {
+			for b in paths[i + 1..].iter() {
+				for step in 0..20 {
+					assert_ne!(at(a, step), at(b, step));
+					let swapped = at(a, step) == at(b, step + 1) && at(b, step) == at(a, step + 1);
+					assert!(!swapped);
+				}
+			}
+		}
+	}
+	#[test]
+	/// A unit waits for the way to clear rather than walking through another, and takes the
+	/// direct route once the other unit is released
+	/// ```txt
+	///    _______
+	///   /       \
+	///  /  (0,1)  \
+	///  \         /
+	///   \_______/
+	///   /       \
+	///  /  (0,0)  \
+	///  \         /
+	///   \_______/
+	///   /       \
+	///  /  (0,-1) \
+	///  \         /
+	///   \_______/
+	/// ```
+	fn waits_for_way_to_clear() {
+		let mut nodes = HashMap::new();
+		nodes.insert((0, -1), 1.0);
+		nodes.insert((0, 0), 1.0);
+		nodes.insert((0, 1), 1.0);
+		let grid = HexGrid::new(AxialLayout::new(1), nodes);
+		let mut planner = CooperativePlanner::new(6, 0.5);
+		// a unit stopped at the end of the corridor blocks it for good
+		let (first, _) = planner.plan(&grid, (0, 0), (0, 1)).unwrap();
+		assert!(planner.plan(&grid, (0, -1), (0, 1)).is_none());
+		assert!(planner.release(first));
+		assert_eq!(3, planner.plan(&grid, (0, -1), (0, 1)).unwrap().1.len());
+		// a unit which lingers in the middle before moving on is waited for
+		let mut planner = CooperativePlanner::new(6, 0.5);
+		let lingering = Path::new(vec![(0, 0), (0, 0), (0, 1)], vec![0.0, 0.5, 1.5]);
+		planner.traffic.commit(&lingering);
+		let (_, waiting) = planner.plan(&grid, (0, -1), (0, 0)).unwrap();
+		assert_eq!(&[(0, -1), (0, -1), (0, 0)], waiting.nodes());
+		assert_eq!(1.5, waiting.cost());
+	}
+}
@@ -0,0 +1,159 @@
+//! Find the dead-end corridors of a grid so searches can skip them.
+//!
+//! Maze-like cave maps are full of passages which lead nowhere. A search exploring outwards from
+//! its start wanders down each of them before giving up, although no shortest path between two
+//! points outside a dead end ever enters it. The dead ends are found once, ahead of any queries,
+//! by repeatedly trimming every node joined to at most one other node:
+//!
+//! ```txt
+//!      _______
+//!     /       \
+//!    /    D    \
+//!    \         /
+//!     \_______/
+//!     /       \
+//!    /    D    \
+//!    \         /
+//!     \_______/_______         _______
+//!     /       \       \_______/       \
+//!    /    S    \             /    G    \
+//!    \         /             \         /
+//!     \_______/               \_______/
+//! ```
+//!
+//! Trimming `D` at the tip exposes the next `D` which is trimmed in turn until the corridor
+//! reaches a junction. Nodes which must stay reachable, the sources and goals of later queries,
+//! are never trimmed. Only corridors one node wide are found, a pocket where a node touches two
+//! neighbours which also touch each other survives trimming although a shortest path never
+//! needs it.
+
+use crate::grid::GridLayout;
+use crate::grid::HexGrid;
+use crate::search::SearchSpace;
+use ::std::collections::HashSet;
+use ::std::collections::VecDeque;
+
+/// Find the nodes of `grid` lying in dead-end corridors which no shortest path between nodes of
+/// `keep` can pass through. Nodes in `keep` are never reported as dead ends. The set is
+/// unordered
+pub fn dead_ends<L: GridLayout>(grid: &HexGrid<L>, keep: &[L::Coord]) -> HashSet<L::Coord> {
+	let keep: HashSet<L::Coord> = keep.iter().copied().collect();
+	let mut dead: HashSet<L::Coord> = HashSet::new();
+	// visit nodes in order so the trimming does not depend on hashing
+	let mut nodes: Vec<L::Coord> = grid.nodes().keys().copied().collect();
+	nodes.sort();
+	let mut frontier: VecDeque<L::Coord> = nodes.into_iter().collect();
+	while let Some(node) = frontier.pop_front() {
+		if dead.contains(&node) || keep.contains(&node) || !grid.is_passable(&node) {
+			continue;
+		}
+		// a node is joined to another if a step, or portal, leads between them in either direction
+		let mut joined: Vec<L::Coord> = grid
+			.successors(node)
+			.into_iter()
+			.chain(grid.predecessors(node))
+			.map(|(n, _)| n)
+			.filter(|n| *n != node && !dead.contains(n))
+			.collect();
+		joined.sort();
+		joined.dedup();
+		if joined.len() <= 1 {
+			dead.insert(node);
+			// trimming this node may leave its last neighbour as the tip of the corridor
+			frontier.extend(joined);
+		}
+	}
+	dead
+}
+
+/// A `SearchSpace` which never enters the nodes of a dead-end set, created with `cull`
+pub struct Culled<'a, S: SearchSpace> {
+	/// The space being searched
+	space: &'a S,
+	/// Nodes which are skipped
+	dead_ends: &'a HashSet<S::Node>,
+}
+
+/// A view of `space` skipping the nodes in `dead_ends`, as found by `dead_ends`. The start and
+/// goal of a search through the view must have been kept when the dead ends were found
+pub fn cull<'a, S: SearchSpace>(space: &'a S, dead_ends: &'a HashSet<S::Node>) -> Culled<'a, S> {
+	Culled { space, dead_ends }
+}
+
+impl<'a, S: SearchSpace> SearchSpace for Culled<'a, S> {
+	type Node = S::Node;
+	fn successors(&self, node: S::Node) -> Vec<(S::Node, f32)> {
+		self.space
+			.successors(node)
+			.into_iter()
+			.filter(|(n, _)| !self.dead_ends.contains(n))
+			.collect()
+	}
+	fn heuristic(&self, node: S::Node, goal: S::Node) -> f32 {
+		self.space.heuristic(node, goal)
+	}
+	fn predecessors(&self, node: S::Node) -> Vec<(S::Node, f32)> {
+		self.space
+			.predecessors(node)
+			.into_iter()
+			.filter(|(n, _)| !self.dead_ends.contains(n))
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::dead_ends::*;
+	use crate::grid::AxialLayout;
+	use crate::search::astar_path;
+	use ::std::collections::HashMap;
+
+	/// A corridor from `(0,0)` to `(4,0)` with a spur heading off `(2,0)`
+	/// ```txt
+	///            (2,-3)
+	///              |
+	///            (2,-2)
+	///              |
+	///            (2,-1)
+	///           /      \
+	/// (0,0)-(1,0)-(2,0)-(3,0)-(4,0)
+	/// ```
+	fn spur_grid() -> HexGrid<AxialLayout> {
+		let mut nodes = HashMap::new();
+		for q in 0..5 {
+			nodes.insert((q, 0), 1.0);
+		}
+		for r in 1..4 {
+			nodes.insert((2, -r), 1.0);
+		}
+		HexGrid::new(AxialLayout::unbounded(), nodes)
+	}
+	#[test]
+	/// The tip of the spur is trimmed back to where it touches two corridor nodes
+	fn trim_spur() {
+		let grid = spur_grid();
+		let dead = dead_ends(&grid, &[(0, 0), (4, 0)]);
+		let mut dead: Vec<(i32, i32)> = dead.into_iter().collect();
+		dead.sort();
+		assert_eq!(vec![(2, -3), (2, -2)], dead);
+	}
+	#[test]
+	/// Kept nodes are never trimmed, and the corridor past the goal is
+	fn keep_endpoints() {
+		let grid = spur_grid();
+		let dead = dead_ends(&grid, &[(0, 0), (2, -3)]);
+		let mut dead: Vec<(i32, i32)> = dead.into_iter().collect();
+		dead.sort();
+		assert_eq!(vec![(3, 0), (4, 0)], dead);
+	}
+	#[test]
+	/// Searching with the dead ends culled finds a path of the same cost
+	fn culled_search() {
+		let grid = spur_grid();
+		let dead = dead_ends(&grid, &[(0, 0), (4, 0)]);
+		let full = astar_path(&grid, (0, 0), (4, 0)).unwrap();
+		let culled = astar_path(&cull(&grid, &dead), (0, 0), (4, 0)).unwrap();
+		assert_eq!(full.cost(), culled.cost());
+		assert!(culled.iter().all(|n| !dead.contains(n)));
+	}
+}
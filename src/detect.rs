@@ -0,0 +1,151 @@
+//! Inspect an imported map of nodes to find which coordinate systems it is consistent with.
+//!
+//! Feeding Offset coordinates to the Axial search, or the reverse, does not fail, it quietly
+//! produces strange paths because the neighbours of each node are not the ones intended. Before
+//! searching an imported map its keys can be checked against the shape each coordinate system
+//! expects:
+//!
+//! * Cubic - every node satisfies `x + y + z = 0`, the grid is the rings around `(0, 0, 0)`
+//! * Axial - the nodes fill every ring around `(0, 0)` out to some `count_rings`, a hexagon shape
+//! * Offset - the nodes fill a rectangle of columns and rows
+//!
+//! A two element key is ambiguous, a single node or a small hexagon of nodes may fit both, so
+//! every consistent system is reported. The orientation of an Offset grid cannot be recovered
+//! from the coordinates alone as every orientation labels the same rectangle of nodes, it must be
+//! known from the source of the data.
+
+use crate::helpers::axial_to_cubic;
+use crate::helpers::node_distance;
+use ::std::collections::HashMap;
+
+/// A coordinate system, and the extent of the grid, which a set of nodes is consistent with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordinateSystem {
+	/// Axial coordinates `(q, r)` filling `count_rings` rings around the origin
+	Axial {
+		/// Number of rings around the origin
+		count_rings: i32,
+	},
+	/// Cubic coordinates `(x, y, z)` lying within `count_rings` rings around the origin
+	Cubic {
+		/// Number of rings around the origin needed to contain every node
+		count_rings: i32,
+	},
+	/// Offset coordinates `(column, row)` filling a rectangle, the bounds are exclusive as
+	/// expected by `astar_offset::astar_path`
+	Offset {
+		/// Column to the left of the grid
+		min_column: i32,
+		/// Column to the right of the grid
+		max_column: i32,
+		/// Row below the grid
+		min_row: i32,
+		/// Row above the grid
+		max_row: i32,
+	},
+}
+
+/// Check that every node of a Cubic map satisfies `x + y + z = 0`. Returns the system with the
+/// number of rings needed to contain every node, or an error holding the first offending node
+pub fn detect_cubic(
+	nodes: &HashMap<(i32, i32, i32), f32>,
+) -> Result<CoordinateSystem, (i32, i32, i32)> {
+	let mut count_rings = 0;
+	for node in nodes.keys() {
+		if node.0 + node.1 + node.2 != 0 {
+			return Err(*node);
+		}
+		count_rings = count_rings.max(node_distance(*node, (0, 0, 0)));
+	}
+	Ok(CoordinateSystem::Cubic { count_rings })
+}
+
+/// Find which coordinate systems a map with two element keys is consistent with, Axial if the
+/// nodes form a complete hexagon around the origin and Offset if they form a complete rectangle.
+/// An empty list means the map fits neither shape, e.g it has holes or irregular edges, and the
+/// coordinate system should be checked by hand
+pub fn detect_planar(nodes: &HashMap<(i32, i32), f32>) -> Vec<CoordinateSystem> {
+	let mut systems = Vec::new();
+	if nodes.is_empty() {
+		return systems;
+	}
+	// a complete hexagon of `count_rings` rings holds `3 * count_rings * (count_rings + 1) + 1` nodes
+	let count_rings = nodes
+		.keys()
+		.map(|n| node_distance(axial_to_cubic(*n), (0, 0, 0)))
+		.max()
+		.unwrap_or(0);
+	if nodes.len() as i64 == 3 * count_rings as i64 * (count_rings as i64 + 1) + 1 {
+		systems.push(CoordinateSystem::Axial { count_rings });
+	}
+	// a complete rectangle holds `width * height` nodes
+	let min_column = nodes.keys().map(|n| n.0).min().unwrap_or(0);
+	let max_column = nodes.keys().map(|n| n.0).max().unwrap_or(0);
+	let min_row = nodes.keys().map(|n| n.1).min().unwrap_or(0);
+	let max_row = nodes.keys().map(|n| n.1).max().unwrap_or(0);
+	let width = (max_column - min_column + 1) as i64;
+	let height = (max_row - min_row + 1) as i64;
+	if nodes.len() as i64 == width * height {
+		systems.push(CoordinateSystem::Offset {
+			min_column: min_column - 1,
+			max_column: max_column + 1,
+			min_row: min_row - 1,
+			max_row: max_row + 1,
+		});
+	}
+	systems
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::detect::*;
+
+	#[test]
+	/// A hexagon of Axial nodes is recognised as Axial and not as Offset
+	fn detect_axial_hexagon() {
+		let mut nodes = HashMap::new();
+		for q in -1..=1_i32 {
+			for r in -1..=1_i32 {
+				if (q + r).abs() <= 1 {
+					nodes.insert((q, r), 1.0);
+				}
+			}
+		}
+		assert_eq!(
+			vec![CoordinateSystem::Axial { count_rings: 1 }],
+			detect_planar(&nodes)
+		);
+	}
+	#[test]
+	/// A rectangle of Offset nodes is recognised as Offset with exclusive bounds
+	fn detect_offset_rectangle() {
+		let mut nodes = HashMap::new();
+		for column in 0..4 {
+			for row in 0..3 {
+				nodes.insert((column, row), 1.0);
+			}
+		}
+		let expected = CoordinateSystem::Offset {
+			min_column: -1,
+			max_column: 4,
+			min_row: -1,
+			max_row: 3,
+		};
+		assert_eq!(vec![expected], detect_planar(&nodes));
+		nodes.remove(&(2, 1));
+		assert!(detect_planar(&nodes).is_empty());
+	}
+	#[test]
+	/// Cubic nodes must satisfy `x + y + z = 0`
+	fn detect_cubic_invariant() {
+		let mut nodes = HashMap::new();
+		nodes.insert((0, 0, 0), 1.0);
+		nodes.insert((2, -1, -1), 1.0);
+		assert_eq!(
+			Ok(CoordinateSystem::Cubic { count_rings: 2 }),
+			detect_cubic(&nodes)
+		);
+		nodes.insert((1, 1, 0), 1.0);
+		assert_eq!(Err((1, 1, 0)), detect_cubic(&nodes));
+	}
+}
@@ -0,0 +1,348 @@
+//! Repair a path as the grid changes rather than planning it again from scratch.
+//!
+//! A unit walking across a dynamic map sees the odd hexagon change as it goes, a gate opens or
+//! another unit steps into its way. Searching again from scratch after every change repeats all
+//! the work of the first search even though almost none of it was affected. `DStarLite` keeps the
+//! state of its search between queries and, when told which nodes changed, only reworks the part
+//! of the search those changes touched:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::dstar_lite::DStarLite;
+//! use hexagonal_pathfinding_astar::grid::{AxialLayout, HexGrid};
+//! use std::collections::HashMap;
+//! let mut nodes = HashMap::new();
+//! for q in -2..=2_i32 {
+//!     for r in -2..=2_i32 {
+//!         if (q + r).abs() <= 2 {
+//!             nodes.insert((q, r), 1.0);
+//!         }
+//!     }
+//! }
+//! let mut grid = HexGrid::new(AxialLayout::new(2), nodes);
+//! let mut planner = DStarLite::new(&grid, (0, -2), (0, 2));
+//! assert_eq!(4.0, planner.path(&grid).unwrap().cost());
+//! // the centre is blocked, tell the planner and it repairs the path around it
+//! grid.set_complexity((0, 0), f32::INFINITY);
+//! planner.update_nodes(&grid, &[(0, 0)]);
+//! let path = planner.path(&grid).unwrap();
+//! assert!(!path.contains(&(0, 0)));
+//! ```
+//!
+//! The search runs backwards from the goal so that as the unit walks, reported with `move_to`,
+//! the work already done stays valid. Changes close to the unit are the cheapest to repair and,
+//! conveniently, the unit usually only sees the hexagons close to it.
+//!
+//! The planner is an implementation of D* Lite by Koenig and Likhachev. The space is passed to
+//! every call rather than held by the planner so the grid can be edited between calls, but every
+//! node whose complexity or edges change must be reported with `update_nodes` before the next
+//! call to `path`.
+
+use crate::path::Path;
+use crate::search::SearchSpace;
+use ::std::cmp::Ordering;
+use ::std::collections::BinaryHeap;
+use ::std::collections::HashMap;
+use ::std::collections::HashSet;
+use ::std::hash::Hash;
+
+/// The priority of a node in the queue of a `DStarLite`, compared by the first value and then
+/// the second
+type Key = (f32, f32);
+
+/// Whether `a` is ordered before `b`
+fn key_less(a: Key, b: Key) -> bool {
+	a.0 < b.0 || (a.0 == b.0 && a.1 < b.1)
+}
+
+/// A node paired with its key, ordered such that a `BinaryHeap` pops the smallest key first
+#[derive(Debug, Clone, Copy)]
+struct Keyed<N>(Key, N);
+
+impl<N> PartialEq for Keyed<N> {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl<N> Eq for Keyed<N> {}
+
+impl<N> PartialOrd for Keyed<N> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<N> Ord for Keyed<N> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		// reversed so that the smallest key is the greatest item in the heap
+		if key_less(self.0, other.0) {
+			Ordering::Greater
+		} else if key_less(other.0, self.0) {
+			Ordering::Less
+		} else {
+			Ordering::Equal
+		}
+	}
+}
+
+/// An incremental planner which keeps its search between queries, see the module documentation
+#[derive(Debug, Clone)]
+pub struct DStarLite<N: Copy + Eq + Hash> {
+	/// Where the unit is now
+	start: N,
+	/// Where the unit is heading
+	goal: N,
+	/// Where the unit was when the keys of the queue were last consistent with the heuristic
+	last: N,
+	/// The heuristic distance the unit has moved since the search began, added to every key
+	/// rather than reordering the queue after every move
+	key_modifier: f32,
+	/// The cost of reaching the goal from each node as settled by the search
+	g: HashMap<N, f32>,
+	/// The cost of reaching the goal from each node as seen from its successors, where this
+	/// differs from `g` the node is queued to be settled
+	rhs: HashMap<N, f32>,
+	/// The current key of every queued node, the heap may hold older entries which are skipped
+	queued: HashMap<N, Key>,
+	/// Queued nodes by key
+	queue: BinaryHeap<Keyed<N>>,
+	/// The nodes whose `rhs` was calculated from each node, so they can be revisited if the node
+	/// changes even once it can no longer be moved to
+	watchers: HashMap<N, HashSet<N>>,
+	/// Number of nodes expanded over the life of the planner
+	expansions: usize,
+}
+
+impl<N: Copy + Eq + Hash> DStarLite<N> {
+	/// Create a planner from `start` to `goal` in `space`. No searching is done until `path` is
+	/// called
+	pub fn new<S: SearchSpace<Node = N>>(space: &S, start: N, goal: N) -> DStarLite<N> {
+		let mut planner = DStarLite {
+			start,
+			goal,
+			last: start,
+			key_modifier: 0.0,
+			g: HashMap::new(),
+			rhs: HashMap::new(),
+			queued: HashMap::new(),
+			queue: BinaryHeap::new(),
+			watchers: HashMap::new(),
+			expansions: 0,
+		};
+		planner.rhs.insert(goal, 0.0);
+		let key = planner.key(space, goal);
+		planner.enqueue(goal, key);
+		planner
+	}
+	/// Where the unit is now
+	pub fn start(&self) -> N {
+		self.start
+	}
+	/// Where the unit is heading
+	pub fn goal(&self) -> N {
+		self.goal
+	}
+	/// Number of nodes expanded over the life of the planner, useful for seeing how much work a
+	/// repair took
+	pub fn expansions(&self) -> usize {
+		self.expansions
+	}
+	/// The cheapest path from the current start to the goal, searching only as much as the
+	/// changes reported since the last call require. Returns `None` if the goal cannot be reached
+	pub fn path<S: SearchSpace<Node = N>>(&mut self, space: &S) -> Option<Path<N>> {
+		self.compute(space);
+		// the start itself may be left unsettled, its successors are what matter
+		if self.rhs_of(&self.start) == f32::INFINITY {
+			return None;
+		}
+		// walk downhill through the settled costs towards the goal
+		let mut nodes = vec![self.start];
+		let mut costs = vec![0.0];
+		let mut current = self.start;
+		let mut visited = HashSet::new();
+		visited.insert(current);
+		while current != self.goal {
+			let mut best: Option<(N, f32)> = None;
+			let mut best_total = f32::INFINITY;
+			for (n, step_complexity) in space.successors(current) {
+				let total = step_complexity + self.g_of(&n);
+				if total < best_total {
+					best_total = total;
+					best = Some((n, step_complexity));
+				}
+			}
+			let (next, step_complexity) = best?;
+			// unreported changes can leave the costs pointing in a circle
+			if !visited.insert(next) {
+				return None;
+			}
+			costs.push(costs[costs.len() - 1] + step_complexity);
+			nodes.push(next);
+			current = next;
+		}
+		Some(Path::new(nodes, costs))
+	}
+	/// The unit has moved to `node`, later paths begin from there
+	pub fn move_to<S: SearchSpace<Node = N>>(&mut self, space: &S, node: N) {
+		self.key_modifier += space.heuristic(self.last, node);
+		self.last = node;
+		self.start = node;
+	}
+	/// Report that the complexity or the edges of each of `nodes` have changed in `space`, the
+	/// search is repaired around them on the next call to `path`
+	pub fn update_nodes<S: SearchSpace<Node = N>>(&mut self, space: &S, nodes: &[N]) {
+		for node in nodes {
+			self.update_vertex(space, *node);
+			for n in self.affected_by(space, *node) {
+				self.update_vertex(space, n);
+			}
+		}
+	}
+	/// The settled cost of reaching the goal from `node`
+	fn g_of(&self, node: &N) -> f32 {
+		self.g.get(node).copied().unwrap_or(f32::INFINITY)
+	}
+	/// The cost of reaching the goal from `node` as seen from its successors
+	fn rhs_of(&self, node: &N) -> f32 {
+		self.rhs.get(node).copied().unwrap_or(f32::INFINITY)
+	}
+	/// The key `node` is queued with
+	fn key<S: SearchSpace<Node = N>>(&self, space: &S, node: N) -> Key {
+		let cost = self.g_of(&node).min(self.rhs_of(&node));
+		(
+			cost + space.heuristic(self.start, node) + self.key_modifier,
+			cost,
+		)
+	}
+	/// Queue `node` with `key`, replacing any earlier entry
+	fn enqueue(&mut self, node: N, key: Key) {
+		self.queued.insert(node, key);
+		self.queue.push(Keyed(key, node));
+	}
+	/// The queued node with the smallest key, discarding stale entries from the heap
+	fn top(&mut self) -> Option<(Key, N)> {
+		while let Some(Keyed(key, node)) = self.queue.peek() {
+			if self.queued.get(node) == Some(key) {
+				return Some((*key, *node));
+			}
+			self.queue.pop();
+		}
+		None
+	}
+	/// The nodes whose cost may depend on `node`, those which can move to it now and those which
+	/// could when their cost was last calculated
+	fn affected_by<S: SearchSpace<Node = N>>(&self, space: &S, node: N) -> Vec<N> {
+		let mut affected: Vec<N> = space.predecessors(node).into_iter().map(|p| p.0).collect();
+		if let Some(watchers) = self.watchers.get(&node) {
+			for w in watchers {
+				if !affected.contains(w) {
+					affected.push(*w);
+				}
+			}
+		}
+		affected
+	}
+	/// Recalculate the cost of reaching the goal from `node` through its successors and queue it
+	/// if that disagrees with its settled cost
+	fn update_vertex<S: SearchSpace<Node = N>>(&mut self, space: &S, node: N) {
+		if node != self.goal {
+			let mut rhs = f32::INFINITY;
+			for (n, step_complexity) in space.successors(node) {
+				rhs = rhs.min(step_complexity + self.g_of(&n));
+				self.watchers.entry(n).or_default().insert(node);
+			}
+			self.rhs.insert(node, rhs);
+		}
+		if self.g_of(&node) != self.rhs_of(&node) {
+			let key = self.key(space, node);
+			self.enqueue(node, key);
+		} else {
+			self.queued.remove(&node);
+		}
+	}
+	/// Settle nodes until the cost of reaching the goal from the start is known
+	fn compute<S: SearchSpace<Node = N>>(&mut self, space: &S) {
+		while let Some((old_key, node)) = self.top() {
+			let start_key = self.key(space, self.start);
+			let start_g = self.g_of(&self.start);
+			if !key_less(old_key, start_key) && self.rhs_of(&self.start) <= start_g {
+				break;
+			}
+			let new_key = self.key(space, node);
+			if key_less(old_key, new_key) {
+				// the unit has moved since the node was queued
+				self.enqueue(node, new_key);
+				continue;
+			}
+			self.expansions += 1;
+			self.queued.remove(&node);
+			let g = self.g_of(&node);
+			let rhs = self.rhs_of(&node);
+			if g > rhs {
+				self.g.insert(node, rhs);
+			} else {
+				self.g.insert(node, f32::INFINITY);
+				self.update_vertex(space, node);
+			}
+			for n in self.affected_by(space, node) {
+				self.update_vertex(space, n);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::dstar_lite::*;
+	use crate::test_support::assert_path_optimal;
+	use crate::test_support::axial_fixture;
+
+	#[test]
+	/// The first path is the cheapest, as is every repaired path after nodes are blocked and
+	/// unblocked along the way
+	fn repairs_stay_optimal() {
+		for seed in 0..6 {
+			let mut grid = axial_fixture(4, seed);
+			let start = (-4, 2);
+			let goal = (4, -2);
+			let mut planner = DStarLite::new(&grid, start, goal);
+			let path = planner.path(&grid);
+			assert_path_optimal(&grid, start, goal, path.as_ref());
+			let path = match path {
+				Some(p) if p.len() > 3 => p,
+				_ => continue,
+			};
+			// block the middle of the path
+			let blocked = path.nodes()[path.len() / 2];
+			let original = grid.complexity(&blocked).unwrap();
+			grid.set_complexity(blocked, f32::INFINITY);
+			planner.update_nodes(&grid, &[blocked]);
+			let repaired = planner.path(&grid);
+			assert_path_optimal(&grid, start, goal, repaired.as_ref());
+			// walk a step and reopen it
+			let step = path.nodes()[1];
+			planner.move_to(&grid, step);
+			grid.set_complexity(blocked, original);
+			planner.update_nodes(&grid, &[blocked]);
+			let reopened = planner.path(&grid);
+			assert_path_optimal(&grid, step, goal, reopened.as_ref());
+		}
+	}
+	#[test]
+	/// Repairing after a change beside the unit takes far less work than the first search
+	fn repair_is_incremental() {
+		let mut grid = axial_fixture(6, 3);
+		for node in grid.nodes().keys().copied().collect::<Vec<(i32, i32)>>() {
+			grid.set_complexity(node, 1.0);
+		}
+		let mut planner = DStarLite::new(&grid, (-6, 3), (6, -3));
+		let path = planner.path(&grid).unwrap();
+		let first = planner.expansions();
+		let blocked = path.nodes()[1];
+		grid.set_complexity(blocked, f32::INFINITY);
+		planner.update_nodes(&grid, &[blocked]);
+		let repaired = planner.path(&grid);
+		assert_path_optimal(&grid, (-6, 3), (6, -3), repaired.as_ref());
+		assert!(planner.expansions() - first < first / 2);
+	}
+}
@@ -0,0 +1,125 @@
+//! Choose where to go next when exploring a partially revealed map.
+//!
+//! The classic roguelike auto-explore loop repeatedly walks to the nearest part of the map which
+//! borders the unknown until nothing is left to discover. The frontier is every known, passable
+//! hexagon with at least one unknown neighbour:
+//!
+//! ```txt
+//!              _______
+//!             /       \
+//!     _______/    ?    \_______
+//!    /       \         /       \
+//!   /    F    \_______/    ?    \
+//!   \         /       \         /
+//!    \_______/    F    \_______/
+//!    /       \         /       \
+//!   /    S    \_______/    ?    \
+//!   \         /       \         /
+//!    \_______/    F    \_______/
+//!            \         /
+//!             \_______/
+//! ```
+//!
+//! Here `?` are unknown hexagons and `F` the frontier. Only known hexagons are walked across while
+//! searching, a unit never plans a route through terrain it has not seen.
+
+use crate::grid::GridLayout;
+use crate::grid::HexGrid;
+use crate::node_store::NodeStore;
+use crate::path::Path;
+use crate::search::dijkstra_to_first;
+use crate::search::SearchSpace;
+use ::std::collections::HashSet;
+
+/// A grid restricted to the nodes which are known
+struct KnownNodes<'a, L: GridLayout, S> {
+	/// The full grid
+	grid: &'a HexGrid<L, S>,
+	/// Nodes which have been revealed
+	known: &'a HashSet<L::Coord>,
+}
+
+impl<'a, L: GridLayout, S: NodeStore<L::Coord>> SearchSpace for KnownNodes<'a, L, S> {
+	type Node = L::Coord;
+	fn successors(&self, node: L::Coord) -> Vec<(L::Coord, f32)> {
+		self.grid
+			.successors(node)
+			.into_iter()
+			.filter(|(n, _)| self.known.contains(n))
+			.collect()
+	}
+	fn heuristic(&self, node: L::Coord, goal: L::Coord) -> f32 {
+		self.grid.heuristic(node, goal)
+	}
+}
+
+/// Whether `node` is known, passable and borders an unknown node of the grid
+pub fn is_frontier<L: GridLayout, S: NodeStore<L::Coord>>(
+	grid: &HexGrid<L, S>,
+	known: &HashSet<L::Coord>,
+	node: &L::Coord,
+) -> bool {
+	known.contains(node)
+		&& grid.is_passable(node)
+		&& grid
+			.layout()
+			.neighbours(*node)
+			.iter()
+			.any(|n| !known.contains(n))
+}
+
+/// Find the cheapest path from `start` across the `known` nodes of `grid` to the nearest
+/// frontier node, one which borders the unknown. Returns `None` once exploration is complete,
+/// when no frontier node can be reached.
+///
+/// If `start` is itself on the frontier the path is just `start`
+pub fn explore_target<L: GridLayout, S: NodeStore<L::Coord>>(
+	grid: &HexGrid<L, S>,
+	known: &HashSet<L::Coord>,
+	start: L::Coord,
+) -> Option<Path<L::Coord>> {
+	let space = KnownNodes { grid, known };
+	dijkstra_to_first(&space, &[start], |n| is_frontier(grid, known, n))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::explore::*;
+	use crate::grid::CubicLayout;
+	use ::std::collections::HashMap;
+
+	/// A cubic grid of 2 rings where every node has the same complexity
+	fn uniform_grid() -> HexGrid<CubicLayout> {
+		let mut nodes = HashMap::new();
+		for x in -2..=2_i32 {
+			for y in -2..=2_i32 {
+				if (x + y).abs() <= 2 {
+					nodes.insert((x, y, -x - y), 1.0);
+				}
+			}
+		}
+		HexGrid::new(CubicLayout::new(2), nodes)
+	}
+
+	#[test]
+	/// The nearest frontier is chosen and the path only crosses known nodes
+	fn nearest_frontier() {
+		let grid = uniform_grid();
+		// everything but the far north east corner is known
+		let mut known: HashSet<(i32, i32, i32)> = grid.nodes().keys().copied().collect();
+		known.remove(&(2, -2, 0));
+		let path = explore_target(&grid, &known, (-2, 2, 0)).unwrap();
+		assert_eq!(3.0, path.cost());
+		let target = *path.last().unwrap();
+		assert_eq!(1, grid.layout().distance(target, (2, -2, 0)));
+		assert!(path.iter().all(|n| known.contains(n)));
+	}
+	#[test]
+	/// Once every node is known there is nothing left to explore
+	fn exploration_complete() {
+		let grid = uniform_grid();
+		let known: HashSet<(i32, i32, i32)> = grid.nodes().keys().copied().collect();
+		// coordinates beyond the boundary of the layout are not neighbours so never count as unknown
+		assert!(explore_target(&grid, &known, (0, 0, 0)).is_none());
+	}
+}
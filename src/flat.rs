@@ -0,0 +1,193 @@
+//! Searches taking and returning flat arrays of numbers, for calling from other languages.
+//!
+//! Tuples and `HashMap`s do not cross language boundaries. Browser games calling this crate
+//! through WebAssembly, or any caller going through a foreign function interface, need grids and
+//! paths laid out as plain arrays instead. Every function here takes and returns only slices of
+//! `i32` and `f32`, numbers and `FlatPath`, which map directly onto JavaScript's `Int32Array` and
+//! `Float32Array`, so a binding layer such as `wasm-bindgen` needs only to mark them for export.
+//! With the `wasm` feature enabled they are marked with `#[wasm_bindgen]`, along with
+//! `HexOrientation`, ready for `wasm-pack` to build a package for JavaScript:
+//!
+//! ```toml
+//! [dependencies]
+//! hexagonal_pathfinding_astar = { version = "1", features = ["wasm"] }
+//! ```
+//!
+//! The feature builds on Rust 1.59 like the rest of the crate, `wasm-bindgen` being held below
+//! 0.2.106 which raised its minimum. As with the `rayon` feature of `batch`, the crates beneath it
+//! are best resolved by cargo 1.84 or later with the `fallback` resolver of `.cargo/config.toml`.
+//!
+//! Coordinates are flattened pair by pair, the nodes `(0, 0)`, `(1, 0)` and `(1, 1)` being
+//! `[0, 0, 1, 0, 1, 1]`:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::flat::astar_axial_flat;
+//! // a line of three nodes running up from the origin
+//! let coords = [0, 0, 0, 1, 0, 2];
+//! let complexities = [1.0, 3.0, 1.0];
+//! let path = astar_axial_flat(2, &coords, &complexities, 0, 0, 0, 2).unwrap();
+//! assert_eq!(vec![0, 0, 0, 1, 0, 2], path.coords());
+//! assert_eq!(4.0, path.cost());
+//! ```
+
+use crate::grid::AxialLayout;
+use crate::grid::HexGrid;
+use crate::node_store::DenseNodeStore;
+use crate::path::Path;
+use crate::rect_grid::RectGrid;
+use crate::search::astar_path;
+use crate::HexOrientation;
+use ::std::collections::HashMap;
+#[cfg(feature = "wasm")]
+use ::wasm_bindgen::prelude::wasm_bindgen;
+
+/// A path flattened into arrays, see the module documentation
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlatPath {
+	/// The coordinates of each node in order, two numbers per node
+	coords: Vec<i32>,
+	/// The cost of reaching each node
+	cumulative_costs: Vec<f32>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl FlatPath {
+	/// The coordinates of each node in order, two numbers per node
+	pub fn coords(&self) -> Vec<i32> {
+		self.coords.clone()
+	}
+	/// The cost of reaching each node, starting with `0.0` for the start node
+	pub fn cumulative_costs(&self) -> Vec<f32> {
+		self.cumulative_costs.clone()
+	}
+	/// The cost of the whole path
+	pub fn cost(&self) -> f32 {
+		self.cumulative_costs.last().copied().unwrap_or(0.0)
+	}
+	/// Number of nodes in the path
+	pub fn len(&self) -> usize {
+		self.cumulative_costs.len()
+	}
+	/// Whether the path holds no nodes
+	pub fn is_empty(&self) -> bool {
+		self.cumulative_costs.is_empty()
+	}
+}
+
+impl From<Path<(i32, i32)>> for FlatPath {
+	fn from(path: Path<(i32, i32)>) -> FlatPath {
+		let coords = path.iter().flat_map(|n| [n.0, n.1]).collect();
+		FlatPath {
+			coords,
+			cumulative_costs: path.cumulative_costs().to_vec(),
+		}
+	}
+}
+
+/// The orientation numbered `code`, the position of the variant in `HexOrientation`, `None` for
+/// any other number
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn orientation_from_code(code: u32) -> Option<HexOrientation> {
+	match code {
+		0 => Some(HexOrientation::FlatTopOddUp),
+		1 => Some(HexOrientation::FlatTopOddDown),
+		2 => Some(HexOrientation::PointyTopOddRight),
+		3 => Some(HexOrientation::PointyTopOddLeft),
+		4 => Some(HexOrientation::FlatTopEvenUp),
+		5 => Some(HexOrientation::FlatTopEvenDown),
+		6 => Some(HexOrientation::PointyTopEvenRight),
+		7 => Some(HexOrientation::PointyTopEvenLeft),
+		_ => None,
+	}
+}
+
+/// Find the cheapest path across an Axial grid of `count_rings` rings whose nodes are given by
+/// `coords`, two numbers per node, with the complexity of each in `complexities`. Returns `None`
+/// if the arrays do not describe the same number of nodes or the end node cannot be reached
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn astar_axial_flat(
+	count_rings: i32,
+	coords: &[i32],
+	complexities: &[f32],
+	start_q: i32,
+	start_r: i32,
+	end_q: i32,
+	end_r: i32,
+) -> Option<FlatPath> {
+	if coords.len() != complexities.len() * 2 {
+		return None;
+	}
+	let nodes: HashMap<(i32, i32), f32> = coords
+		.chunks_exact(2)
+		.zip(complexities.iter())
+		.map(|(c, complexity)| ((c[0], c[1]), *complexity))
+		.collect();
+	let grid = HexGrid::new(AxialLayout::new(count_rings), nodes);
+	astar_path(&grid, (start_q, start_r), (end_q, end_r)).map(FlatPath::from)
+}
+
+/// Find the cheapest path across a rectangular Offset grid of `width` columns and `height` rows
+/// in the orientation numbered `orientation`, see `orientation_from_code()`. `complexities`
+/// holds the complexity of each node row by row from the bottom, the node `(column, row)` at
+/// `row * width + column`, with `NaN` marking a hole. Returns `None` if the orientation is not
+/// known, the array does not fit the grid or the end node cannot be reached
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[allow(clippy::too_many_arguments)]
+pub fn astar_offset_flat(
+	width: i32,
+	height: i32,
+	orientation: u32,
+	complexities: &[f32],
+	start_column: i32,
+	start_row: i32,
+	end_column: i32,
+	end_row: i32,
+) -> Option<FlatPath> {
+	let orientation = orientation_from_code(orientation)?;
+	if width < 0 || height < 0 || complexities.len() != (width * height) as usize {
+		return None;
+	}
+	let mut nodes = DenseNodeStore::new(width, height, f32::NAN);
+	for (i, complexity) in complexities.iter().enumerate() {
+		let i = i as i32;
+		nodes.set_complexity((i % width, i / width), *complexity);
+	}
+	let grid = RectGrid::from_store(orientation, nodes);
+	grid.astar_path((start_column, start_row), (end_column, end_row))
+		.map(FlatPath::from)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::flat::*;
+	use crate::grid::OffsetLayout;
+	use crate::search;
+
+	#[test]
+	/// A flat Offset grid finds the same path as the grid it describes, and arrays which do not
+	/// fit are refused
+	fn offset_matches_grid() {
+		let (width, height) = (6, 5);
+		let complexities: Vec<f32> = (0..width * height).map(|i| (i % 4 + 1) as f32).collect();
+		for code in 0..8 {
+			let orientation = orientation_from_code(code).unwrap();
+			let flat = astar_offset_flat(width, height, code, &complexities, 0, 0, 5, 4).unwrap();
+			let mut nodes = HashMap::new();
+			for (i, complexity) in complexities.iter().enumerate() {
+				nodes.insert((i as i32 % width, i as i32 / width), *complexity);
+			}
+			let layout = OffsetLayout::new(orientation, -1, width, -1, height);
+			let grid = HexGrid::new(layout, nodes);
+			let expected = search::astar_path(&grid, (0, 0), (5, 4)).unwrap();
+			assert_eq!(expected.cost(), flat.cost());
+			assert_eq!(flat.len() * 2, flat.coords().len());
+			assert_eq!(vec![0, 0], flat.coords()[..2].to_vec());
+			assert_eq!(vec![5, 4], flat.coords()[flat.len() * 2 - 2..].to_vec());
+		}
+		assert!(orientation_from_code(8).is_none());
+		assert!(astar_offset_flat(width, height, 8, &complexities, 0, 0, 5, 4).is_none());
+		assert!(astar_offset_flat(width, height, 0, &complexities[1..], 0, 0, 5, 4).is_none());
+		assert!(astar_axial_flat(1, &[0, 0, 1], &[1.0, 1.0], 0, 0, 0, 1).is_none());
+	}
+}
@@ -0,0 +1,231 @@
+//! Point every node of a grid towards a goal so any number of units can follow it.
+//!
+//! When hundreds of units share a destination a path for each is wasted effort. A `FlowField`
+//! records, for every node which can reach the goal, the cheapest cost of getting there and the
+//! neighbour to step to next. A unit anywhere on the grid simply keeps taking the next step.
+//!
+//! On a dynamic map the odd hexagon changes cost as units move and doors open. Rebuilding the
+//! whole field for every change throws away almost all of it, so `FlowField::update` is given the
+//! nodes which changed and works out which part of the field they affect:
+//!
+//! * nodes whose route to the goal passed through a changed node lose their cost and direction
+//!   and are reconnected from the undisturbed nodes around them
+//! * from the changed nodes and the reconnected ones any cheaper routes which now exist are
+//!   spread outwards, stopping wherever the field does not improve
+//!
+//! Only the nodes whose cost or direction actually changed are returned, so units standing
+//! anywhere else can carry on without checking the field again.
+
+use crate::search::MinScored;
+use crate::search::SearchSpace;
+use ::std::collections::BinaryHeap;
+use ::std::collections::HashMap;
+use ::std::collections::HashSet;
+use ::std::hash::Hash;
+
+/// The cost of reaching a goal from every node which can and the next step to take towards it
+#[derive(Debug, Clone)]
+pub struct FlowField<N: Copy + Eq + Hash> {
+	/// Where every node is heading
+	goal: N,
+	/// The cheapest cost of reaching the goal from each node
+	costs: HashMap<N, f32>,
+	/// The node to step to next from each node, the goal has none
+	next: HashMap<N, N>,
+	/// The nodes stepping to each node, the reverse of `next`
+	upstream: HashMap<N, Vec<N>>,
+}
+
+impl<N: Copy + Eq + Hash> FlowField<N> {
+	/// Build the field of every node in `space` which can reach `goal`
+	pub fn new<S: SearchSpace<Node = N>>(space: &S, goal: N) -> FlowField<N> {
+		let mut field = FlowField {
+			goal,
+			costs: HashMap::new(),
+			next: HashMap::new(),
+			upstream: HashMap::new(),
+		};
+		field.costs.insert(goal, 0.0);
+		let mut queue = BinaryHeap::new();
+		queue.push(MinScored(0.0, goal));
+		field.spread(space, queue, &mut HashMap::new());
+		field
+	}
+	/// Where every node is heading
+	pub fn goal(&self) -> N {
+		self.goal
+	}
+	/// The cheapest cost of reaching the goal from `node`, `None` if it cannot be reached
+	pub fn cost(&self, node: &N) -> Option<f32> {
+		self.costs.get(node).copied()
+	}
+	/// The node to step to next from `node`, `None` for the goal or a node which cannot reach it
+	pub fn next_step(&self, node: &N) -> Option<N> {
+		self.next.get(node).copied()
+	}
+	/// Number of nodes which can reach the goal
+	pub fn len(&self) -> usize {
+		self.costs.len()
+	}
+	/// Whether no node can reach the goal, not even the goal itself
+	pub fn is_empty(&self) -> bool {
+		self.costs.is_empty()
+	}
+	/// Repair the field after the complexity or edges of each of `changed` were altered in
+	/// `space`. A node which was added or removed counts as changed, as do both ends of an edge
+	/// which was blocked, unblocked or given a new cost. Returns every node whose cost or next
+	/// step differs from before, in no particular order
+	pub fn update<S: SearchSpace<Node = N>>(&mut self, space: &S, changed: &[N]) -> Vec<N> {
+		// the cost and next step of every node touched, from before it was touched
+		let mut before: HashMap<N, (Option<f32>, Option<N>)> = HashMap::new();
+		// every node whose route to the goal passed through a changed node is disturbed
+		let mut disturbed: HashSet<N> = HashSet::new();
+		let mut pending: Vec<N> = changed.to_vec();
+		while let Some(node) = pending.pop() {
+			if !disturbed.insert(node) {
+				continue;
+			}
+			if let Some(upstream) = self.upstream.get(&node) {
+				pending.extend(upstream.iter().copied());
+			}
+		}
+		for node in disturbed.iter() {
+			before.insert(*node, (self.cost(node), self.next_step(node)));
+			self.costs.remove(node);
+			self.set_next(*node, None);
+		}
+		// reconnect each disturbed node through its undisturbed successors, the goal needing none
+		let mut queue = BinaryHeap::new();
+		for node in disturbed.iter() {
+			if *node == self.goal {
+				self.costs.insert(*node, 0.0);
+				queue.push(MinScored(0.0, *node));
+				continue;
+			}
+			let mut best: Option<(f32, N)> = None;
+			for (n, step_complexity) in space.successors(*node) {
+				if let Some(cost) = self.cost(&n) {
+					let total = cost + step_complexity;
+					if best.map_or(true, |b| total < b.0) {
+						best = Some((total, n));
+					}
+				}
+			}
+			if let Some((cost, n)) = best {
+				self.costs.insert(*node, cost);
+				self.set_next(*node, Some(n));
+				queue.push(MinScored(cost, *node));
+			}
+		}
+		// spreading from the reconnected nodes, changed ones included, finds any cheaper routes
+		// they now offer to the rest of the field
+		self.spread(space, queue, &mut before);
+		before
+			.into_iter()
+			.filter(|(node, old)| *old != (self.cost(node), self.next_step(node)))
+			.map(|(node, _)| node)
+			.collect()
+	}
+	/// Point `node` at `next`, keeping `upstream` in step
+	fn set_next(&mut self, node: N, next: Option<N>) {
+		if let Some(old) = self.next.remove(&node) {
+			if let Some(upstream) = self.upstream.get_mut(&old) {
+				upstream.retain(|n| *n != node);
+			}
+		}
+		if let Some(n) = next {
+			self.next.insert(node, n);
+			self.upstream.entry(n).or_default().push(node);
+		}
+	}
+	/// Spread cheaper routes backwards from the queued nodes until no cost improves, recording
+	/// the cost and next step of each node improved along the way from before it was first
+	/// touched
+	fn spread<S: SearchSpace<Node = N>>(
+		&mut self,
+		space: &S,
+		mut queue: BinaryHeap<MinScored<N>>,
+		before: &mut HashMap<N, (Option<f32>, Option<N>)>,
+	) {
+		while let Some(MinScored(cost, node)) = queue.pop() {
+			// skip stale entries where a cheaper route has since been found
+			if self.cost(&node).map_or(true, |c| cost > c) {
+				continue;
+			}
+			for (n, step_complexity) in space.predecessors(node) {
+				let complexity = cost + step_complexity;
+				if self.cost(&n).map_or(true, |c| complexity < c) {
+					before
+						.entry(n)
+						.or_insert((self.cost(&n), self.next_step(&n)));
+					self.costs.insert(n, complexity);
+					self.set_next(n, Some(node));
+					queue.push(MinScored(complexity, n));
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::flow_field::*;
+	use crate::grid::AxialLayout;
+	use crate::grid::HexGrid;
+	use crate::test_support::axial_fixture;
+
+	/// Panic unless `field` holds the same costs as a field built from scratch and every next step
+	/// is consistent with them
+	fn assert_matches_rebuilt(grid: &HexGrid<AxialLayout>, field: &FlowField<(i32, i32)>) {
+		let rebuilt = FlowField::new(grid, field.goal());
+		assert_eq!(rebuilt.len(), field.len());
+		for (node, cost) in rebuilt.costs.iter() {
+			let found = field.cost(node).unwrap();
+			assert!(
+				(found - cost).abs() < 1e-4,
+				"{:?} costs {} not {}",
+				node,
+				found,
+				cost
+			);
+			if let Some(next) = field.next_step(node) {
+				let step = grid.step_cost(*node, next).unwrap();
+				assert!((step + field.cost(&next).unwrap() - found).abs() < 1e-4);
+			}
+		}
+	}
+	#[test]
+	/// Blocking, reopening and repricing nodes leaves the same field as rebuilding it
+	fn updates_match_rebuild() {
+		for seed in 0..6 {
+			let mut grid = axial_fixture(5, seed);
+			let mut field = FlowField::new(&grid, (0, 0));
+			let edits = [
+				((2, -1), f32::INFINITY),
+				((-3, 1), 1.0),
+				((0, 1), 9.0),
+				((2, -1), 1.0),
+				((1, 1), f32::INFINITY),
+			];
+			for (node, complexity) in edits {
+				grid.set_complexity(node, complexity);
+				field.update(&grid, &[node]);
+				assert_matches_rebuilt(&grid, &field);
+			}
+		}
+	}
+	#[test]
+	/// A change at the edge of the grid only disturbs the nodes around it
+	fn update_is_local() {
+		let mut grid = axial_fixture(6, 2);
+		for node in grid.nodes().keys().copied().collect::<Vec<(i32, i32)>>() {
+			grid.set_complexity(node, 1.0);
+		}
+		let mut field = FlowField::new(&grid, (0, 0));
+		grid.set_complexity((6, -6), 5.0);
+		let touched = field.update(&grid, &[(6, -6)]);
+		assert!(!touched.is_empty());
+		assert!(touched.len() < field.len() / 4);
+		assert_matches_rebuilt(&grid, &field);
+	}
+}
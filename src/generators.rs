@@ -0,0 +1,228 @@
+//! Reproducible maps of node complexities for benchmarks and stress tests.
+//!
+//! Each generator fills a grid of the usual shape for its coordinate system, a hexagon of rings
+//! for Axial and Cubic and a rectangle for Offset, with complexities in the style of a `Terrain`:
+//!
+//! * `Terrain::Uniform` - every node an independent whole number from `1` to `9`
+//! * `Terrain::Patches` - clusters of nodes sharing the same complexity, like fields, forests and
+//!   lakes, some patches being impassable
+//! * `Terrain::Corridors` - impassable rock cut by a lattice of narrow corridors, some of them
+//!   caved in, which forces long detours
+//!
+//! The complexity of a node depends only on the seed and where the node lies, so the same seed
+//! always gives the same map and the same place has the same complexity whichever coordinate
+//! system it is generated in:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::generators::{axial_map, Terrain};
+//! let nodes = axial_map(20, Terrain::Patches, 7);
+//! assert_eq!(nodes, axial_map(20, Terrain::Patches, 7));
+//! ```
+
+use crate::helpers::axial_to_cubic;
+use crate::helpers::node_distance;
+use crate::helpers::offset_to_cubic;
+use crate::GridBounds;
+use crate::HexOrientation;
+use ::std::collections::HashMap;
+
+/// The style of complexities of a generated map, see the module documentation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Terrain {
+	Uniform,
+	Patches,
+	Corridors,
+}
+
+/// Distance between the centres of neighbouring patches
+const PATCH_SPACING: i32 = 6;
+/// Distance between parallel corridors
+const CORRIDOR_SPACING: i32 = 4;
+
+/// Mix `seed` and the cube coordinate `cube` into a pseudo-random number, the finaliser of
+/// SplitMix64 applied after each part so neighbouring nodes give unrelated numbers
+fn mix(seed: u64, cube: (i32, i32, i32)) -> u64 {
+	let mut hash = seed;
+	for part in [cube.0, cube.1, cube.2] {
+		hash = hash
+			.wrapping_add(part as u32 as u64)
+			.wrapping_add(0x9E37_79B9_7F4A_7C15);
+		hash = (hash ^ (hash >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		hash = (hash ^ (hash >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		hash ^= hash >> 31;
+	}
+	hash
+}
+
+/// The complexity of the node at `cube` in a map of `terrain` generated from `seed`
+pub fn complexity(terrain: Terrain, seed: u64, cube: (i32, i32, i32)) -> f32 {
+	match terrain {
+		Terrain::Uniform => (mix(seed, cube) % 9 + 1) as f32,
+		Terrain::Patches => patch_complexity(seed, cube),
+		Terrain::Corridors => corridor_complexity(seed, cube),
+	}
+}
+
+/// The complexity of the patch whose centre lies nearest to `cube`. Space is cut into cells of
+/// `PATCH_SPACING` columns and rows each holding one centre somewhere within it
+fn patch_complexity(seed: u64, cube: (i32, i32, i32)) -> f32 {
+	let cell = (
+		cube.0.div_euclid(PATCH_SPACING),
+		cube.2.div_euclid(PATCH_SPACING),
+	);
+	let mut nearest = (i32::MAX, 0);
+	for dq in -1..=1 {
+		for dr in -1..=1 {
+			let (q, r) = (cell.0 + dq, cell.1 + dr);
+			let hash = mix(seed, (q, r, -q - r));
+			let centre = axial_to_cubic((
+				q * PATCH_SPACING + (hash % PATCH_SPACING as u64) as i32,
+				r * PATCH_SPACING + ((hash >> 8) % PATCH_SPACING as u64) as i32,
+			));
+			let distance = node_distance(cube, centre);
+			// ties go to the smaller hash so every node agrees which patch it belongs to
+			if (distance, hash) < (nearest.0, nearest.1) {
+				nearest = (distance, hash);
+			}
+		}
+	}
+	match (nearest.1 >> 16) % 8 {
+		0 => f32::INFINITY,
+		1..=3 => 1.0,
+		4 | 5 => 2.0,
+		6 => 4.0,
+		_ => 8.0,
+	}
+}
+
+/// Corridors run along every `CORRIDOR_SPACING`th line of each of the three axes, one node in
+/// twelve between crossings having caved in, and everything else is rock
+fn corridor_complexity(seed: u64, cube: (i32, i32, i32)) -> f32 {
+	let on_line = |c: i32| c.rem_euclid(CORRIDOR_SPACING) == 0;
+	let lines = [cube.0, cube.1, cube.2]
+		.iter()
+		.filter(|c| on_line(**c))
+		.count();
+	let hash = mix(seed, cube);
+	match lines {
+		0 => f32::INFINITY,
+		// crossings always stay open
+		1 if hash % 12 == 0 => f32::INFINITY,
+		_ => (hash % 3 + 1) as f32,
+	}
+}
+
+/// A hexagon of Axial nodes `count_rings` rings around the origin with complexities of `terrain`
+/// generated from `seed`
+pub fn axial_map(count_rings: i32, terrain: Terrain, seed: u64) -> HashMap<(i32, i32), f32> {
+	cubic_map(count_rings, terrain, seed)
+		.into_iter()
+		.map(|(cube, c)| ((cube.0, cube.2), c))
+		.collect()
+}
+
+/// A hexagon of Cubic nodes `count_rings` rings around the origin with complexities of `terrain`
+/// generated from `seed`
+pub fn cubic_map(count_rings: i32, terrain: Terrain, seed: u64) -> HashMap<(i32, i32, i32), f32> {
+	let mut nodes = HashMap::new();
+	for x in -count_rings..=count_rings {
+		for z in -count_rings..=count_rings {
+			let y = -x - z;
+			if y.abs() <= count_rings {
+				let cube = (x, y, z);
+				nodes.insert(cube, complexity(terrain, seed, cube));
+			}
+		}
+	}
+	nodes
+}
+
+/// A rectangle of Offset nodes filling `bounds` in `orientation` with complexities of `terrain`
+/// generated from `seed`
+pub fn offset_map(
+	bounds: GridBounds,
+	orientation: HexOrientation,
+	terrain: Terrain,
+	seed: u64,
+) -> HashMap<(i32, i32), f32> {
+	let mut nodes = HashMap::new();
+	for column in bounds.min_column..=bounds.max_column {
+		for row in bounds.min_row..=bounds.max_row {
+			let cube = offset_to_cubic((column, row), &orientation);
+			nodes.insert((column, row), complexity(terrain, seed, cube));
+		}
+	}
+	nodes
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::generators::*;
+	use crate::grid::AxialLayout;
+	use crate::grid::HexGrid;
+	use crate::search::astar_path;
+
+	/// The share of nodes of `nodes` which are impassable
+	fn impassable_share(nodes: &HashMap<(i32, i32), f32>) -> f32 {
+		let closed = nodes.values().filter(|c| c.is_infinite()).count();
+		closed as f32 / nodes.len() as f32
+	}
+	#[test]
+	/// Maps are the same for the same seed, differ between seeds and agree between coordinate
+	/// systems over the same place
+	fn reproducible() {
+		for terrain in [Terrain::Uniform, Terrain::Patches, Terrain::Corridors] {
+			let axial = axial_map(10, terrain, 3);
+			assert_eq!(331, axial.len());
+			assert_eq!(axial, axial_map(10, terrain, 3));
+			assert_ne!(axial, axial_map(10, terrain, 4));
+			let cubic = cubic_map(10, terrain, 3);
+			for (cube, c) in cubic.iter() {
+				assert_eq!(Some(c), axial.get(&(cube.0, cube.2)));
+			}
+			let bounds = GridBounds::from_size(8, 6);
+			let orientation = HexOrientation::PointyTopOddRight;
+			let offset = offset_map(bounds, orientation, terrain, 3);
+			assert_eq!(48, offset.len());
+			for (node, c) in offset.iter() {
+				assert_eq!(
+					complexity(terrain, 3, offset_to_cubic(*node, &orientation)),
+					*c
+				);
+			}
+		}
+	}
+	#[test]
+	/// Each terrain has its own character, patches cluster and corridors are mostly rock yet
+	/// still lead across the map
+	fn terrain_character() {
+		let uniform = axial_map(20, Terrain::Uniform, 1);
+		assert_eq!(0.0, impassable_share(&uniform));
+		// neighbouring nodes of a patch usually share a complexity
+		let patches = axial_map(20, Terrain::Patches, 1);
+		let mut same = 0;
+		let mut pairs = 0;
+		for ((q, r), c) in patches.iter() {
+			if let Some(other) = patches.get(&(q + 1, *r)) {
+				pairs += 1;
+				if other == c {
+					same += 1;
+				}
+			}
+		}
+		assert!(
+			same * 10 > pairs * 6,
+			"{} of {} neighbours alike",
+			same,
+			pairs
+		);
+		let corridors = axial_map(20, Terrain::Corridors, 1);
+		assert!(impassable_share(&corridors) > 0.4);
+		let grid = HexGrid::new(AxialLayout::new(20), corridors);
+		let path = astar_path(&grid, (0, 0), (16, -8)).unwrap();
+		assert!(path.len() >= 17);
+		for node in path.iter() {
+			assert!(grid.complexity(node).unwrap().is_finite());
+		}
+	}
+}
@@ -0,0 +1,1660 @@
+//! A searchable collection of weighted hexagons independent of the coordinate system in use.
+//!
+//! A `HexGrid` pairs the complexity of each node with a layout describing how nodes are
+//! connected and where the boundary of the grid sits. A layout exists for each of the supported
+//! coordinate systems:
+//!
+//! * `AxialLayout` - a circular grid of `count_rings` rings around the origin `(0, 0)`
+//! * `CubicLayout` - a circular grid of `count_rings` rings around the origin `(0, 0, 0)`
+//! * `OffsetLayout` - a rectangular grid bounded by exclusive column and row limits
+//!
+//! ```txt
+//!              _______
+//!             /   0   \
+//!     _______/         \_______
+//!    /  -1   \       1 /   1   \
+//!   /         \_______/         \
+//!   \       1 /   q   \       0 /
+//!    \_______/         \_______/
+//!    /  -1   \       r /   1   \
+//!   /         \_______/         \
+//!   \       0 /   0   \      -1 /
+//!    \_______/         \_______/
+//!            \      -1 /
+//!             \_______/
+//! ```
+//!
+//! The complexities are held in any `NodeStore`, such as a `HashMap` or, for large rectangular
+//! Offset grids, a `DenseNodeStore`. Infinite procedurally generated worlds can be described by
+//! combining an unbounded layout, e.g `CubicLayout::unbounded()`, with `ProceduralNodes`. Note
+//! that searching an infinite world for an end node which cannot be reached never finishes.
+//!
+//! Algorithms which are not specific to a coordinate system, such as building a road network,
+//! accept a `HexGrid` of any layout and the grid can be searched with `search::astar_path`.
+//!
+//! Searching only ever reads a grid, so one grid can serve the searches of many threads without
+//! being copied. A grid is `Send` and `Sync` whenever its node store is, as every store of this
+//! crate is, and an `Arc` of anything searchable is searchable itself:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::search::astar_path;
+//! use hexagonal_pathfinding_astar::test_support::axial_fixture;
+//! use std::sync::Arc;
+//! use std::thread;
+//! let grid = Arc::new(axial_fixture(4, 1));
+//! let shared = Arc::clone(&grid);
+//! let worker = thread::spawn(move || astar_path(&shared, (0, 0), (4, 0)));
+//! assert_eq!(astar_path(&grid, (0, 0), (4, 0)), worker.join().unwrap());
+//! ```
+
+use crate::helpers::axial_to_cubic;
+use crate::helpers::cubic_to_axial;
+use crate::helpers::cubic_to_offset;
+use crate::helpers::field_of_view_cubic;
+use crate::helpers::has_line_of_sight_cubic;
+use crate::helpers::node_distance;
+use crate::helpers::node_distance_offset_wrapping;
+use crate::helpers::node_line_cubic;
+use crate::helpers::node_neighbours_axial;
+use crate::helpers::node_neighbours_cubic;
+use crate::helpers::node_neighbours_offset;
+use crate::helpers::node_neighbours_offset_wrapping;
+use crate::helpers::nodes_within_range_cubic;
+use crate::helpers::nodes_within_range_offset;
+use crate::helpers::offset_to_cubic;
+use crate::node_store::LayeredNodes;
+use crate::node_store::NodeStore;
+use crate::node_store::NodeStoreMut;
+use crate::path::Path;
+use crate::search::astar_path_to_any;
+use crate::search::SearchSpace;
+use crate::GridBounds;
+use crate::HexOrientation;
+use ::std::collections::HashMap;
+use ::std::collections::HashSet;
+use ::std::fmt::Debug;
+use ::std::hash::Hash;
+
+/// Describes how the nodes of a grid are connected and where its boundary lies
+pub trait GridLayout {
+	/// The coordinate type used to label nodes. Coordinates are ordered so that helpers which
+	/// gather nodes from a `HashMap` can return them in a stable order, independent of hashing
+	type Coord: Copy + Eq + Hash + Ord + Debug;
+	/// The nodes surrounding `node` which sit within the boundary of the grid
+	fn neighbours(&self, node: Self::Coord) -> Vec<Self::Coord>;
+	/// The number of 'jumps' required to move from node `a` to node `b`
+	fn distance(&self, a: Self::Coord, b: Self::Coord) -> i32;
+	/// Convert `node` to Cubic coordinates `(x, y, z)`
+	fn node_to_cubic(&self, node: Self::Coord) -> (i32, i32, i32);
+	/// Convert Cubic coordinates `(x, y, z)` to the coordinates of this layout
+	fn cubic_to_node(&self, cube: (i32, i32, i32)) -> Self::Coord;
+	/// The coordinates within `range` jumps of `centre`, `centre` included, however the nodes
+	/// between them are arranged, see `nodes_within_range_cubic()`. Layouts which join their
+	/// edges fold the coordinates back onto the grid
+	fn nodes_within_range(&self, centre: Self::Coord, range: i32) -> Vec<Self::Coord> {
+		nodes_within_range_cubic(self.node_to_cubic(centre), range)
+			.into_iter()
+			.map(|cube| self.cubic_to_node(cube))
+			.collect()
+	}
+}
+
+/// A circular grid of Axial coordinates `(q, r)` with `count_rings` rings around the origin `(0, 0)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AxialLayout {
+	/// Number of rings around the origin, inclusive
+	count_rings: i32,
+}
+
+impl AxialLayout {
+	/// Create a circular layout with `count_rings` rings around the origin
+	pub fn new(count_rings: i32) -> AxialLayout {
+		AxialLayout { count_rings }
+	}
+	/// Create a layout without a boundary, for infinite worlds
+	pub fn unbounded() -> AxialLayout {
+		AxialLayout {
+			count_rings: i32::MAX,
+		}
+	}
+}
+
+impl GridLayout for AxialLayout {
+	type Coord = (i32, i32);
+	fn neighbours(&self, node: (i32, i32)) -> Vec<(i32, i32)> {
+		node_neighbours_axial(node, self.count_rings)
+	}
+	fn distance(&self, a: (i32, i32), b: (i32, i32)) -> i32 {
+		node_distance(axial_to_cubic(a), axial_to_cubic(b))
+	}
+	fn node_to_cubic(&self, node: (i32, i32)) -> (i32, i32, i32) {
+		axial_to_cubic(node)
+	}
+	fn cubic_to_node(&self, cube: (i32, i32, i32)) -> (i32, i32) {
+		cubic_to_axial(cube)
+	}
+}
+
+/// A circular grid of Cubic coordinates `(x, y, z)` with `count_rings` rings around the origin `(0, 0, 0)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CubicLayout {
+	/// Number of rings around the origin, inclusive
+	count_rings: i32,
+}
+
+impl CubicLayout {
+	/// Create a circular layout with `count_rings` rings around the origin
+	pub fn new(count_rings: i32) -> CubicLayout {
+		CubicLayout { count_rings }
+	}
+	/// Create a layout without a boundary, for infinite worlds
+	pub fn unbounded() -> CubicLayout {
+		CubicLayout {
+			count_rings: i32::MAX,
+		}
+	}
+}
+
+impl GridLayout for CubicLayout {
+	type Coord = (i32, i32, i32);
+	fn neighbours(&self, node: (i32, i32, i32)) -> Vec<(i32, i32, i32)> {
+		node_neighbours_cubic(node, self.count_rings)
+	}
+	fn distance(&self, a: (i32, i32, i32), b: (i32, i32, i32)) -> i32 {
+		node_distance(a, b)
+	}
+	fn node_to_cubic(&self, node: (i32, i32, i32)) -> (i32, i32, i32) {
+		node
+	}
+	fn cubic_to_node(&self, cube: (i32, i32, i32)) -> (i32, i32, i32) {
+		cube
+	}
+}
+
+/// A rectangular grid of Offset coordinates `(column, row)` in a given `orientation`. The
+/// `min_column`, `max_column`, `min_row` and `max_row` limits are exclusive.
+///
+/// The grid can wrap around at its boundaries, see `OffsetLayout::with_wrapping`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OffsetLayout {
+	/// The arrangement of the hexagons
+	orientation: HexOrientation,
+	/// Exclusive lower column limit
+	min_column: i32,
+	/// Exclusive upper column limit
+	max_column: i32,
+	/// Exclusive lower row limit
+	min_row: i32,
+	/// Exclusive upper row limit
+	max_row: i32,
+	/// Whether the left and right edges are joined
+	wrap_columns: bool,
+	/// Whether the top and bottom edges are joined
+	wrap_rows: bool,
+}
+
+impl OffsetLayout {
+	/// Create a rectangular layout bounded by the exclusive column and row limits
+	pub fn new(
+		orientation: HexOrientation,
+		min_column: i32,
+		max_column: i32,
+		min_row: i32,
+		max_row: i32,
+	) -> OffsetLayout {
+		OffsetLayout {
+			orientation,
+			min_column,
+			max_column,
+			min_row,
+			max_row,
+			wrap_columns: false,
+			wrap_rows: false,
+		}
+	}
+	/// Create a rectangular layout covering the inclusive `bounds`
+	pub fn with_bounds(orientation: HexOrientation, bounds: GridBounds) -> OffsetLayout {
+		let (min_column, max_column, min_row, max_row) = bounds.exclusive();
+		OffsetLayout::new(orientation, min_column, max_column, min_row, max_row)
+	}
+	/// Create a layout without a boundary, for infinite worlds
+	pub fn unbounded(orientation: HexOrientation) -> OffsetLayout {
+		OffsetLayout::new(orientation, i32::MIN, i32::MAX, i32::MIN, i32::MAX)
+	}
+	/// Join the left and right edges of the grid when `wrap_columns` is true and the top and
+	/// bottom edges when `wrap_rows` is true, so paths can cross the seams like on the map of a
+	/// planet. A wrapping axis must hold an even number of columns for flat topped orientations
+	/// or rows for pointy topped orientations, see `node_neighbours_offset_wrapping()`
+	pub fn with_wrapping(self, wrap_columns: bool, wrap_rows: bool) -> OffsetLayout {
+		OffsetLayout {
+			wrap_columns,
+			wrap_rows,
+			..self
+		}
+	}
+}
+
+impl GridLayout for OffsetLayout {
+	type Coord = (i32, i32);
+	fn neighbours(&self, node: (i32, i32)) -> Vec<(i32, i32)> {
+		if self.wrap_columns || self.wrap_rows {
+			return node_neighbours_offset_wrapping(
+				node,
+				&self.orientation,
+				self.min_column,
+				self.max_column,
+				self.min_row,
+				self.max_row,
+				self.wrap_columns,
+				self.wrap_rows,
+			);
+		}
+		node_neighbours_offset(
+			node,
+			&self.orientation,
+			self.min_column,
+			self.max_column,
+			self.min_row,
+			self.max_row,
+		)
+	}
+	fn distance(&self, a: (i32, i32), b: (i32, i32)) -> i32 {
+		if self.wrap_columns || self.wrap_rows {
+			return node_distance_offset_wrapping(
+				a,
+				b,
+				&self.orientation,
+				self.min_column,
+				self.max_column,
+				self.min_row,
+				self.max_row,
+				self.wrap_columns,
+				self.wrap_rows,
+			);
+		}
+		node_distance(
+			offset_to_cubic(a, &self.orientation),
+			offset_to_cubic(b, &self.orientation),
+		)
+	}
+	fn node_to_cubic(&self, node: (i32, i32)) -> (i32, i32, i32) {
+		offset_to_cubic(node, &self.orientation)
+	}
+	fn cubic_to_node(&self, cube: (i32, i32, i32)) -> (i32, i32) {
+		cubic_to_offset(cube, &self.orientation)
+	}
+	fn nodes_within_range(&self, centre: (i32, i32), range: i32) -> Vec<(i32, i32)> {
+		let width = self.max_column - self.min_column - 1;
+		let height = self.max_row - self.min_row - 1;
+		let mut nodes: Vec<(i32, i32)> =
+			nodes_within_range_offset(centre, range, &self.orientation)
+				.into_iter()
+				.filter_map(|(column, row)| {
+					// fold nodes across a joined edge back onto the grid
+					let column = if self.wrap_columns {
+						(column - self.min_column - 1).rem_euclid(width) + self.min_column + 1
+					} else if column > self.min_column && column < self.max_column {
+						column
+					} else {
+						return None;
+					};
+					let row = if self.wrap_rows {
+						(row - self.min_row - 1).rem_euclid(height) + self.min_row + 1
+					} else if row > self.min_row && row < self.max_row {
+						row
+					} else {
+						return None;
+					};
+					Some((column, row))
+				})
+				.collect();
+		nodes.sort_unstable();
+		nodes.dedup();
+		nodes
+	}
+}
+
+/// Decides which coordinates lie within the boundary of a grid
+pub trait Boundary<C> {
+	/// Whether `node` lies within the boundary
+	fn contains(&self, node: &C) -> bool;
+}
+
+impl<C: Eq + Hash, H: ::std::hash::BuildHasher> Boundary<C> for HashSet<C, H> {
+	fn contains(&self, node: &C) -> bool {
+		HashSet::contains(self, node)
+	}
+}
+
+impl<C, F: Fn(&C) -> bool> Boundary<C> for F {
+	fn contains(&self, node: &C) -> bool {
+		self(node)
+	}
+}
+
+/// A grid of any shape, such as an L-shaped room, a ring or a coastline, formed by limiting
+/// `layout` to the nodes within `boundary`. The boundary is either a `HashSet` of the valid
+/// nodes or a closure returning whether a node is valid:
+///
+/// ```
+/// use hexagonal_pathfinding_astar::grid::{AxialLayout, GridLayout, ShapedLayout};
+///
+/// // a single ring of nodes around the origin
+/// let ring = ShapedLayout::new(AxialLayout::new(3), |n: &(i32, i32)| {
+///     let d = (n.0.abs() + n.1.abs() + (n.0 + n.1).abs()) / 2;
+///     d == 2
+/// });
+/// assert!(ring.neighbours((0, 0)).is_empty());
+/// assert_eq!(2, ring.neighbours((0, 2)).len());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShapedLayout<L, B> {
+	/// Arrangement of the nodes
+	layout: L,
+	/// Decides which nodes are part of the grid
+	boundary: B,
+}
+
+impl<L: GridLayout, B: Boundary<L::Coord>> ShapedLayout<L, B> {
+	/// Create a layout limiting `layout` to the nodes within `boundary`
+	pub fn new(layout: L, boundary: B) -> ShapedLayout<L, B> {
+		ShapedLayout { layout, boundary }
+	}
+	/// The layout before it was shaped
+	pub fn inner(&self) -> &L {
+		&self.layout
+	}
+	/// Whether `node` lies within the shape
+	pub fn contains(&self, node: &L::Coord) -> bool {
+		self.boundary.contains(node)
+	}
+}
+
+impl<L: GridLayout, B: Boundary<L::Coord>> GridLayout for ShapedLayout<L, B> {
+	type Coord = L::Coord;
+	fn neighbours(&self, node: L::Coord) -> Vec<L::Coord> {
+		if !self.boundary.contains(&node) {
+			return Vec::new();
+		}
+		self.layout
+			.neighbours(node)
+			.into_iter()
+			.filter(|n| self.boundary.contains(n))
+			.collect()
+	}
+	fn distance(&self, a: L::Coord, b: L::Coord) -> i32 {
+		self.layout.distance(a, b)
+	}
+	fn node_to_cubic(&self, node: L::Coord) -> (i32, i32, i32) {
+		self.layout.node_to_cubic(node)
+	}
+	fn cubic_to_node(&self, cube: (i32, i32, i32)) -> L::Coord {
+		self.layout.cubic_to_node(cube)
+	}
+	fn nodes_within_range(&self, centre: L::Coord, range: i32) -> Vec<L::Coord> {
+		self.layout
+			.nodes_within_range(centre, range)
+			.into_iter()
+			.filter(|n| self.boundary.contains(n))
+			.collect()
+	}
+}
+
+/// A grid of `count_levels` vertical levels stacked on top of each other, each level arranged
+/// by `layout`, e.g the floors of a dungeon. Nodes are labelled `(coordinate, level)` with levels
+/// counting up from `0`.
+///
+/// Neighbours always share a level, the levels are joined by transitions such as stairs, ramps
+/// and cave entrances added with `HexGrid::add_transition`. Conversions to Cubic coordinates
+/// describe a position on a level, converting from Cubic coordinates gives a node on level `0`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackedLayout<L> {
+	/// Arrangement of each level
+	layout: L,
+	/// Number of levels, levels are labelled from `0` to `count_levels - 1`
+	count_levels: i32,
+}
+
+impl<L: GridLayout> StackedLayout<L> {
+	/// Create a layout of `count_levels` levels each arranged by `layout`
+	pub fn new(layout: L, count_levels: i32) -> StackedLayout<L> {
+		StackedLayout {
+			layout,
+			count_levels,
+		}
+	}
+	/// Arrangement of each level
+	pub fn level_layout(&self) -> &L {
+		&self.layout
+	}
+	/// Number of levels
+	pub fn count_levels(&self) -> i32 {
+		self.count_levels
+	}
+}
+
+impl<L: GridLayout> GridLayout for StackedLayout<L> {
+	type Coord = (L::Coord, i32);
+	fn neighbours(&self, node: (L::Coord, i32)) -> Vec<(L::Coord, i32)> {
+		if node.1 < 0 || node.1 >= self.count_levels {
+			return Vec::new();
+		}
+		self.layout
+			.neighbours(node.0)
+			.into_iter()
+			.map(|n| (n, node.1))
+			.collect()
+	}
+	/// The number of 'jumps' across a level between `a` and `b`, moving between levels is free
+	/// as the cost is held by the transitions
+	fn distance(&self, a: (L::Coord, i32), b: (L::Coord, i32)) -> i32 {
+		self.layout.distance(a.0, b.0)
+	}
+	fn node_to_cubic(&self, node: (L::Coord, i32)) -> (i32, i32, i32) {
+		self.layout.node_to_cubic(node.0)
+	}
+	fn cubic_to_node(&self, cube: (i32, i32, i32)) -> (L::Coord, i32) {
+		(self.layout.cubic_to_node(cube), 0)
+	}
+	/// The coordinates within `range` jumps of `centre` on the same level
+	fn nodes_within_range(&self, centre: (L::Coord, i32), range: i32) -> Vec<(L::Coord, i32)> {
+		if centre.1 < 0 || centre.1 >= self.count_levels {
+			return Vec::new();
+		}
+		self.layout
+			.nodes_within_range(centre.0, range)
+			.into_iter()
+			.map(|n| (n, centre.1))
+			.collect()
+	}
+}
+
+/// A collection of weighted hexagons arranged according to a `GridLayout`.
+///
+/// The complexity of traversing each hexagon is provided by a `NodeStore`, by default a
+/// `HashMap` keyed by the coordinates of each hexagon. A coordinate within the layout boundary
+/// that is absent from the store is treated as a hole in the grid which cannot be traversed, as
+/// is a node with a complexity which is not finite (e.g `f32::INFINITY`).
+///
+/// When the store is a `NodeStoreMut` the grid can be edited in place as terrain changes, e.g a
+/// bridge is destroyed or a fire spreads. The grid holds no precomputed weights or adjacency,
+/// every search reads the store directly, so an edit takes effect from the next search without
+/// rebuilding the grid.
+///
+/// Features of the boundary between two neighbouring hexagons, such as a river or a cliff, are
+/// described by an edge cost which is added on top of the complexities when stepping across it:
+///
+/// ```txt
+/// step_cost = 0.5 * complexity(a) + 0.5 * complexity(b) + edge_cost(a, b)
+/// ```
+///
+/// A boundary can also be blocked entirely, e.g by a wall or a closed gate, so that the two
+/// hexagons either side are no longer neighbours even though both can be traversed.
+///
+/// Portals connect hexagons which are not neighbours, such as stairs, tunnels or teleporters.
+/// A portal leads one way from its entrance to its exit at a fixed cost, independent of the
+/// complexities of either end, and a portal in each direction makes a two way link.
+///
+/// Every change to the grid advances its `generation`. Anything derived from the grid, a path, a
+/// cost field or a cache, can be wrapped with `HexGrid::stamp` to remember the generation it was
+/// computed from and later checked with `Stamped::is_stale` to see whether it needs computing
+/// again.
+#[derive(Debug, Clone)]
+pub struct HexGrid<L: GridLayout, S = HashMap<<L as GridLayout>::Coord, f32>> {
+	/// How the nodes are arranged
+	layout: L,
+	/// The complexity of traversing each node
+	nodes: S,
+	/// Additional cost of crossing the boundary between two neighbouring nodes, keyed by the
+	/// pair of nodes with the smaller first
+	edges: HashMap<(L::Coord, L::Coord), f32>,
+	/// Boundaries between neighbouring nodes which cannot be crossed, keyed by the pair of nodes
+	/// with the smaller first
+	blocked: HashSet<(L::Coord, L::Coord)>,
+	/// The exits and costs of the portals leading from each entrance
+	portals: HashMap<L::Coord, Vec<(L::Coord, f32)>>,
+	/// Advanced by every change to the grid
+	generation: u64,
+}
+
+/// Key of the boundary between `a` and `b` in the edge costs and blocked edges, independent of the
+/// direction of travel
+fn edge_key<C: Ord>(a: C, b: C) -> (C, C) {
+	if a <= b {
+		(a, b)
+	} else {
+		(b, a)
+	}
+}
+
+impl<L: GridLayout, S: NodeStore<L::Coord>> HexGrid<L, S> {
+	/// Create a grid from a layout and the complexity of each node
+	pub fn new(layout: L, nodes: S) -> HexGrid<L, S> {
+		HexGrid {
+			layout,
+			nodes,
+			edges: HashMap::new(),
+			blocked: HashSet::new(),
+			portals: HashMap::new(),
+			generation: 0,
+		}
+	}
+	/// The number of changes made to the grid since it was created, anything computed from the
+	/// grid is out of date once this has moved on
+	pub fn generation(&self) -> u64 {
+		self.generation
+	}
+	/// Wrap `value`, which has been computed from the grid as it is now, with the current
+	/// generation so it can later be checked for being out of date
+	pub fn stamp<T>(&self, value: T) -> Stamped<T> {
+		Stamped::new(value, self.generation)
+	}
+	/// The layout of the grid
+	pub fn layout(&self) -> &L {
+		&self.layout
+	}
+	/// The store of node complexities
+	pub fn nodes(&self) -> &S {
+		&self.nodes
+	}
+	/// Mutable access to the store of node complexities, e.g to edit a layer of `LayeredNodes`.
+	/// Whether or not the store is then changed the generation advances
+	pub fn nodes_mut(&mut self) -> &mut S {
+		self.generation += 1;
+		&mut self.nodes
+	}
+	/// The complexity of traversing `node`, `None` if the node is not part of the grid
+	pub fn complexity(&self, node: &L::Coord) -> Option<f32> {
+		self.nodes.complexity(node)
+	}
+	/// Whether `node` is part of the grid
+	pub fn contains(&self, node: &L::Coord) -> bool {
+		self.nodes.complexity(node).is_some()
+	}
+	/// Whether `node` can be traversed, i.e it is part of the grid and its complexity is finite
+	pub fn is_passable(&self, node: &L::Coord) -> bool {
+		match self.nodes.complexity(node) {
+			Some(c) => c.is_finite(),
+			None => false,
+		}
+	}
+	/// The passable nodes of the grid surrounding `node` which are not behind a blocked edge
+	pub fn neighbours(&self, node: L::Coord) -> Vec<L::Coord> {
+		self.layout
+			.neighbours(node)
+			.into_iter()
+			.filter(|n| self.is_passable(n) && !self.is_edge_blocked(node, *n))
+			.collect()
+	}
+	/// Set an additional `cost` for crossing the boundary between the neighbouring nodes `a` and
+	/// `b` in either direction, e.g to model fording a river. Returns `false` if the nodes are not
+	/// neighbours in which case nothing is changed
+	pub fn set_edge_cost(&mut self, a: L::Coord, b: L::Coord, cost: f32) -> bool {
+		if self.layout.distance(a, b) != 1 {
+			return false;
+		}
+		self.edges.insert(edge_key(a, b), cost);
+		self.generation += 1;
+		true
+	}
+	/// The additional cost of crossing the boundary between `a` and `b`, `0.0` if none has been set
+	pub fn edge_cost(&self, a: L::Coord, b: L::Coord) -> f32 {
+		self.edges.get(&edge_key(a, b)).copied().unwrap_or(0.0)
+	}
+	/// Remove the additional cost of crossing between `a` and `b`, returning it if one was set
+	pub fn clear_edge_cost(&mut self, a: L::Coord, b: L::Coord) -> Option<f32> {
+		let cost = self.edges.remove(&edge_key(a, b))?;
+		self.generation += 1;
+		Some(cost)
+	}
+	/// Block the boundary between the neighbouring nodes `a` and `b` so it cannot be crossed in
+	/// either direction, e.g a wall or a closed gate. Returns `false` if the nodes are not
+	/// neighbours in which case nothing is changed
+	pub fn block_edge(&mut self, a: L::Coord, b: L::Coord) -> bool {
+		if self.layout.distance(a, b) != 1 {
+			return false;
+		}
+		self.blocked.insert(edge_key(a, b));
+		self.generation += 1;
+		true
+	}
+	/// Reopen the boundary between `a` and `b`, returning `false` if it was not blocked
+	pub fn unblock_edge(&mut self, a: L::Coord, b: L::Coord) -> bool {
+		let unblocked = self.blocked.remove(&edge_key(a, b));
+		if unblocked {
+			self.generation += 1;
+		}
+		unblocked
+	}
+	/// Whether the boundary between `a` and `b` is blocked
+	pub fn is_edge_blocked(&self, a: L::Coord, b: L::Coord) -> bool {
+		!self.blocked.is_empty() && self.blocked.contains(&edge_key(a, b))
+	}
+	/// Add a one way portal from `entrance` to `exit` which costs `cost` to travel through,
+	/// replacing the cost of any existing portal between them
+	pub fn add_portal(&mut self, entrance: L::Coord, exit: L::Coord, cost: f32) {
+		let exits = self.portals.entry(entrance).or_default();
+		match exits.iter_mut().find(|(e, _)| *e == exit) {
+			Some(existing) => existing.1 = cost,
+			None => exits.push((exit, cost)),
+		}
+		self.generation += 1;
+	}
+	/// Remove the portal from `entrance` to `exit`, returning its cost if it existed
+	pub fn remove_portal(&mut self, entrance: L::Coord, exit: L::Coord) -> Option<f32> {
+		let exits = self.portals.get_mut(&entrance)?;
+		let index = exits.iter().position(|(e, _)| *e == exit)?;
+		let (_, cost) = exits.remove(index);
+		if exits.is_empty() {
+			self.portals.remove(&entrance);
+		}
+		self.generation += 1;
+		Some(cost)
+	}
+	/// The exits of the portals leading from `entrance` along with the cost of each
+	pub fn portals_from(&self, entrance: &L::Coord) -> &[(L::Coord, f32)] {
+		match self.portals.get(entrance) {
+			Some(exits) => exits,
+			None => &[],
+		}
+	}
+	/// The entrances of the portals leading to `exit` along with the cost of each
+	fn portals_into(&self, exit: &L::Coord) -> Vec<(L::Coord, f32)> {
+		let mut entrances = Vec::new();
+		for (entrance, exits) in self.portals.iter() {
+			for (e, cost) in exits.iter() {
+				if e == exit {
+					entrances.push((*entrance, *cost));
+				}
+			}
+		}
+		entrances
+	}
+	/// The portals which can be travelled through from `node`, both ends must be passable
+	fn portal_steps_from(&self, node: L::Coord) -> Vec<(L::Coord, f32)> {
+		if !self.is_passable(&node) {
+			return Vec::new();
+		}
+		self.portals_from(&node)
+			.iter()
+			.filter(|(exit, _)| self.is_passable(exit))
+			.copied()
+			.collect()
+	}
+	/// The portals which can be travelled through to reach `node`, both ends must be passable
+	fn portal_steps_into(&self, node: L::Coord) -> Vec<(L::Coord, f32)> {
+		if !self.is_passable(&node) {
+			return Vec::new();
+		}
+		self.portals_into(&node)
+			.into_iter()
+			.filter(|(entrance, _)| self.is_passable(entrance))
+			.collect()
+	}
+	/// Estimate the cost of moving from `node` to `goal` for a search. Without portals this is
+	/// the distance between them, with portals a route may instead walk to the nearest entrance,
+	/// pay the cheapest portal and walk on from the exit nearest the goal, so the estimate never
+	/// exceeds the true cost when each step costs at least `1.0`
+	fn estimate(&self, node: L::Coord, goal: L::Coord) -> f32 {
+		let walk = self.layout.distance(node, goal) as f32;
+		if self.portals.is_empty() {
+			return walk;
+		}
+		let mut to_entrance = i32::MAX;
+		let mut cheapest = f32::INFINITY;
+		let mut from_exit = i32::MAX;
+		for (entrance, exits) in self.portals.iter() {
+			to_entrance = to_entrance.min(self.layout.distance(node, *entrance));
+			for (exit, cost) in exits.iter() {
+				cheapest = cheapest.min(*cost);
+				from_exit = from_exit.min(self.layout.distance(*exit, goal));
+			}
+		}
+		walk.min(to_entrance as f32 + cheapest.max(0.0) + from_exit as f32)
+	}
+	/// The cost of stepping from `from` to its neighbour `to`, `None` if either node cannot be
+	/// traversed or the edge between them is blocked
+	pub fn step_cost(&self, from: L::Coord, to: L::Coord) -> Option<f32> {
+		if self.is_edge_blocked(from, to) {
+			return None;
+		}
+		let a = self.nodes.complexity(&from)?;
+		let b = self.nodes.complexity(&to)?;
+		if !a.is_finite() || !b.is_finite() {
+			return None;
+		}
+		// moving between nodes costs half the complexity of each
+		Some(a * 0.5 + b * 0.5 + self.edge_cost(from, to))
+	}
+	/// The nodes lying on a straight line from `a` to `b`, including both ends
+	pub fn line(&self, a: L::Coord, b: L::Coord) -> Vec<L::Coord> {
+		node_line_cubic(self.layout.node_to_cubic(a), self.layout.node_to_cubic(b))
+			.into_iter()
+			.map(|cube| self.layout.cubic_to_node(cube))
+			.collect()
+	}
+	/// The cost of travelling in a straight line from `a` to `b` without searching, e.g to price
+	/// a power line or quickly check whether a direct route is open. `None` if any node on the
+	/// line cannot be traversed.
+	///
+	/// As the line is one possible route between the nodes its cost is never less than the cost
+	/// of the cheapest path
+	pub fn line_cost(&self, a: L::Coord, b: L::Coord) -> Option<f32> {
+		let line = self.line(a, b);
+		if !self.is_passable(&a) {
+			return None;
+		}
+		let mut cost = 0.0;
+		for pair in line.windows(2) {
+			cost += self.step_cost(pair[0], pair[1])?;
+		}
+		Some(cost)
+	}
+	/// Whether `b` can be seen from `a`, sight being blocked by any impassable node or hole lying
+	/// between them, see `helpers::has_line_of_sight_cubic()`
+	pub fn has_line_of_sight(&self, a: L::Coord, b: L::Coord) -> bool {
+		has_line_of_sight_cubic(
+			self.layout.node_to_cubic(a),
+			self.layout.node_to_cubic(b),
+			|cube| !self.is_passable(&self.layout.cubic_to_node(*cube)),
+		)
+	}
+	/// The nodes of the grid visible from `origin` within `radius` jumps, sight being blocked by
+	/// impassable nodes and holes, see `helpers::field_of_view_cubic()`. The set is unordered
+	pub fn field_of_view(&self, origin: L::Coord, radius: i32) -> HashSet<L::Coord> {
+		field_of_view_cubic(self.layout.node_to_cubic(origin), radius, |cube| {
+			!self.is_passable(&self.layout.cubic_to_node(*cube))
+		})
+		.into_iter()
+		.map(|cube| self.layout.cubic_to_node(cube))
+		.filter(|node| self.contains(node))
+		.collect()
+	}
+	/// Find the cheapest path from `start` to any passable node within `range` jumps of `target`,
+	/// e.g for an archer who only needs to be in range of its target. The search finishes at
+	/// whichever node in range is cheapest to reach, so expensive nodes close to the target are
+	/// never paid for as they would be by trimming the end off a path to the target itself.
+	/// Returns `None` if no node in range can be reached
+	pub fn path_within_range(
+		&self,
+		start: L::Coord,
+		target: L::Coord,
+		range: i32,
+	) -> Option<Path<L::Coord>> {
+		// nodes in range by hex distance, a hole or wall between a node and the target does not
+		// put it out of range
+		let mut in_range = self.layout.nodes_within_range(target, range);
+		in_range.retain(|n| self.layout.distance(*n, target) <= range && self.is_passable(n));
+		astar_path_to_any(self, start, &in_range)
+	}
+	/// A view of the grid where the cost of stepping between two neighbouring nodes is decided by
+	/// `step_cost(from, to)`, allowing the cost of moving from `a` to `b` to differ from `b` to `a`,
+	/// e.g for slopes or conveyor belts. `step_cost` is only asked about passable nodes and
+	/// returns `None` to forbid a step. The symmetric cost can still be read from the grid with
+	/// `HexGrid::step_cost` inside the closure.
+	///
+	/// The search heuristic assumes each step costs at least `1.0`, cheaper steps may give paths
+	/// which are not the cheapest
+	pub fn with_step_costs<F: Fn(L::Coord, L::Coord) -> Option<f32>>(
+		&self,
+		step_cost: F,
+	) -> DirectedCosts<'_, L, S, F> {
+		DirectedCosts {
+			grid: self,
+			step_cost,
+		}
+	}
+}
+
+impl<L: GridLayout, S: NodeStore<(L::Coord, i32)>> HexGrid<StackedLayout<L>, S> {
+	/// Join two nodes on different levels, such as the bottom and top of a staircase, so they
+	/// can be travelled between in both directions at a cost of `cost`
+	pub fn add_transition(&mut self, a: (L::Coord, i32), b: (L::Coord, i32), cost: f32) {
+		self.add_portal(a, b, cost);
+		self.add_portal(b, a, cost);
+	}
+}
+
+impl<L: GridLayout> HexGrid<L, LayeredNodes<L::Coord>> {
+	/// A view of the grid using the complexities of `layer`, which can be searched like a grid of
+	/// its own. Edge costs are shared by every layer
+	pub fn layer(&self, layer: usize) -> GridLayer<'_, L> {
+		GridLayer { grid: self, layer }
+	}
+}
+
+/// A single layer of a `HexGrid` of `LayeredNodes`, created with `HexGrid::layer`
+#[derive(Debug, Clone, Copy)]
+pub struct GridLayer<'a, L: GridLayout> {
+	/// The grid holding every layer
+	grid: &'a HexGrid<L, LayeredNodes<L::Coord>>,
+	/// Which layer of complexities is in use
+	layer: usize,
+}
+
+impl<'a, L: GridLayout> GridLayer<'a, L> {
+	/// Which layer of complexities is in use
+	pub fn index(&self) -> usize {
+		self.layer
+	}
+	/// The complexity of traversing `node` in this layer, `None` if the node is not part of the grid
+	pub fn complexity(&self, node: &L::Coord) -> Option<f32> {
+		self.grid.nodes.layer_complexity(node, self.layer)
+	}
+	/// Whether `node` can be traversed in this layer
+	pub fn is_passable(&self, node: &L::Coord) -> bool {
+		match self.complexity(node) {
+			Some(c) => c.is_finite(),
+			None => false,
+		}
+	}
+	/// The cost of stepping from `from` to its neighbour `to` in this layer, `None` if either
+	/// node cannot be traversed or the edge between them is blocked
+	pub fn step_cost(&self, from: L::Coord, to: L::Coord) -> Option<f32> {
+		if self.grid.is_edge_blocked(from, to) {
+			return None;
+		}
+		let a = self.complexity(&from)?;
+		let b = self.complexity(&to)?;
+		if !a.is_finite() || !b.is_finite() {
+			return None;
+		}
+		Some(a * 0.5 + b * 0.5 + self.grid.edge_cost(from, to))
+	}
+}
+
+impl<'a, L: GridLayout> SearchSpace for GridLayer<'a, L> {
+	type Node = L::Coord;
+	fn successors(&self, node: L::Coord) -> Vec<(L::Coord, f32)> {
+		if !self.is_passable(&node) {
+			return Vec::new();
+		}
+		let mut successors = Vec::new();
+		for n in self.grid.layout.neighbours(node) {
+			if let Some(cost) = self.step_cost(node, n) {
+				successors.push((n, cost));
+			}
+		}
+		for (exit, cost) in self.grid.portals_from(&node) {
+			if self.is_passable(exit) {
+				successors.push((*exit, *cost));
+			}
+		}
+		successors
+	}
+	fn heuristic(&self, node: L::Coord, goal: L::Coord) -> f32 {
+		self.grid.estimate(node, goal)
+	}
+	fn predecessors(&self, node: L::Coord) -> Vec<(L::Coord, f32)> {
+		if !self.is_passable(&node) {
+			return Vec::new();
+		}
+		let mut predecessors = Vec::new();
+		for n in self.grid.layout.neighbours(node) {
+			if let Some(cost) = self.step_cost(n, node) {
+				predecessors.push((n, cost));
+			}
+		}
+		for (entrance, cost) in self.grid.portals_into(&node) {
+			if self.is_passable(&entrance) {
+				predecessors.push((entrance, cost));
+			}
+		}
+		predecessors
+	}
+}
+
+/// A `HexGrid` with direction dependent step costs, created with `HexGrid::with_step_costs`
+pub struct DirectedCosts<'a, L: GridLayout, S, F> {
+	/// The grid being searched
+	grid: &'a HexGrid<L, S>,
+	/// Provides the cost of stepping from one node to another
+	step_cost: F,
+}
+
+impl<'a, L: GridLayout, S: NodeStore<L::Coord>, F: Fn(L::Coord, L::Coord) -> Option<f32>>
+	DirectedCosts<'a, L, S, F>
+{
+	/// The grid being searched
+	pub fn grid(&self) -> &HexGrid<L, S> {
+		self.grid
+	}
+	/// The cost of stepping from `from` to its neighbour `to`, `None` if the step is not allowed
+	pub fn step_cost(&self, from: L::Coord, to: L::Coord) -> Option<f32> {
+		if self.grid.is_passable(&from)
+			&& self.grid.is_passable(&to)
+			&& !self.grid.is_edge_blocked(from, to)
+		{
+			(self.step_cost)(from, to)
+		} else {
+			None
+		}
+	}
+}
+
+impl<'a, L: GridLayout, S: NodeStore<L::Coord>, F: Fn(L::Coord, L::Coord) -> Option<f32>>
+	SearchSpace for DirectedCosts<'a, L, S, F>
+{
+	type Node = L::Coord;
+	fn successors(&self, node: L::Coord) -> Vec<(L::Coord, f32)> {
+		let mut successors = Vec::new();
+		for n in self.grid.layout.neighbours(node) {
+			if let Some(cost) = self.step_cost(node, n) {
+				successors.push((n, cost));
+			}
+		}
+		successors.extend(self.grid.portal_steps_from(node));
+		successors
+	}
+	fn heuristic(&self, node: L::Coord, goal: L::Coord) -> f32 {
+		self.grid.estimate(node, goal)
+	}
+	fn predecessors(&self, node: L::Coord) -> Vec<(L::Coord, f32)> {
+		let mut predecessors = Vec::new();
+		for n in self.grid.layout.neighbours(node) {
+			if let Some(cost) = self.step_cost(n, node) {
+				predecessors.push((n, cost));
+			}
+		}
+		predecessors.extend(self.grid.portal_steps_into(node));
+		predecessors
+	}
+}
+
+/// A single change to the nodes of a `HexGrid`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridEdit<C> {
+	/// Change the complexity of an existing node
+	SetComplexity(C, f32),
+	/// Add a node, or overwrite the complexity of an existing one
+	InsertNode(C, f32),
+	/// Remove a node leaving a hole in the grid
+	RemoveNode(C),
+}
+
+impl<L: GridLayout, S: NodeStoreMut<L::Coord>> HexGrid<L, S> {
+	/// Change the complexity of traversing `node`, returning `false` if the node is not part of
+	/// the grid in which case nothing is changed
+	pub fn set_complexity(&mut self, node: L::Coord, complexity: f32) -> bool {
+		if self.contains(&node) {
+			self.insert_node(node, complexity)
+		} else {
+			false
+		}
+	}
+	/// Add `node` to the grid, or overwrite its complexity if it already exists. Returns `false`
+	/// if the store cannot hold `node`
+	pub fn insert_node(&mut self, node: L::Coord, complexity: f32) -> bool {
+		let inserted = self.nodes.insert(node, complexity);
+		if inserted {
+			self.generation += 1;
+		}
+		inserted
+	}
+	/// Remove `node` from the grid leaving a hole, returning its complexity or `None` if it was
+	/// not part of the grid
+	pub fn remove_node(&mut self, node: &L::Coord) -> Option<f32> {
+		let complexity = self.nodes.remove(node)?;
+		self.generation += 1;
+		Some(complexity)
+	}
+	/// Apply a batch of `edits` in order, returning the number which changed the grid
+	pub fn apply<I: IntoIterator<Item = GridEdit<L::Coord>>>(&mut self, edits: I) -> usize {
+		let mut applied = 0;
+		for edit in edits {
+			let changed = match edit {
+				GridEdit::SetComplexity(node, complexity) => self.set_complexity(node, complexity),
+				GridEdit::InsertNode(node, complexity) => self.insert_node(node, complexity),
+				GridEdit::RemoveNode(node) => self.remove_node(&node).is_some(),
+			};
+			if changed {
+				applied += 1;
+			}
+		}
+		applied
+	}
+}
+
+/// A value computed from a `HexGrid` along with the generation of the grid it was computed from,
+/// created with `HexGrid::stamp`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stamped<T> {
+	/// The computed value
+	value: T,
+	/// The generation of the grid the value was computed from
+	generation: u64,
+}
+
+impl<T> Stamped<T> {
+	/// Pair `value` with the `generation` of the grid it was computed from
+	pub fn new(value: T, generation: u64) -> Stamped<T> {
+		Stamped { value, generation }
+	}
+	/// The computed value
+	pub fn get(&self) -> &T {
+		&self.value
+	}
+	/// Unwrap the computed value
+	pub fn into_inner(self) -> T {
+		self.value
+	}
+	/// The generation of the grid the value was computed from
+	pub fn generation(&self) -> u64 {
+		self.generation
+	}
+	/// Whether `grid` has changed since the value was computed from it
+	pub fn is_stale<L: GridLayout, S: NodeStore<L::Coord>>(&self, grid: &HexGrid<L, S>) -> bool {
+		self.generation != grid.generation()
+	}
+}
+
+impl<L: GridLayout, S: NodeStore<L::Coord>> SearchSpace for HexGrid<L, S> {
+	type Node = L::Coord;
+	fn successors(&self, node: L::Coord) -> Vec<(L::Coord, f32)> {
+		if self.nodes.complexity(&node).is_none() {
+			return Vec::new();
+		}
+		let mut successors = Vec::new();
+		for n in self.layout.neighbours(node) {
+			if let Some(cost) = self.step_cost(node, n) {
+				successors.push((n, cost));
+			}
+		}
+		successors.extend(self.portal_steps_from(node));
+		successors
+	}
+	fn heuristic(&self, node: L::Coord, goal: L::Coord) -> f32 {
+		self.estimate(node, goal)
+	}
+	fn predecessors(&self, node: L::Coord) -> Vec<(L::Coord, f32)> {
+		if self.nodes.complexity(&node).is_none() {
+			return Vec::new();
+		}
+		// steps between neighbours cost the same in either direction
+		let mut predecessors = Vec::new();
+		for n in self.layout.neighbours(node) {
+			if let Some(cost) = self.step_cost(n, node) {
+				predecessors.push((n, cost));
+			}
+		}
+		predecessors.extend(self.portal_steps_into(node));
+		predecessors
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::*;
+	use crate::node_store::CostDistribution;
+	use crate::node_store::DenseNodeStore;
+	use crate::node_store::IndexedNodes;
+	use crate::node_store::ProceduralNodes;
+	use crate::node_store::UncertainNodes;
+	use crate::search::astar_from_goal;
+	use crate::search::astar_path;
+	use crate::search::SearchContext;
+	use crate::test_support::axial_fixture;
+	use ::std::sync::Arc;
+	use ::std::thread;
+
+	#[test]
+	/// Nodes absent from the grid are not returned as neighbours
+	/// ```txt
+	///    _______
+	///   /       \
+	///  /  (0,1)  \_______
+	///  \         /       \
+	///   \_______/ absent  \
+	///   /       \         /
+	///  /  (0,0)  \_______/
+	///  \    S    /
+	///   \_______/
+	///  ```
+	fn neighbours_skip_holes() {
+		let mut nodes = HashMap::new();
+		nodes.insert((0, 0), 1.0);
+		nodes.insert((0, 1), 1.0);
+		let layout = OffsetLayout::new(HexOrientation::FlatTopOddUp, -1, 2, -1, 2);
+		let grid = HexGrid::new(layout, nodes);
+		assert_eq!(vec![(0, 1)], grid.neighbours((0, 0)));
+	}
+	#[test]
+	/// Distances are measured in jumps for each layout
+	fn layout_distances() {
+		assert_eq!(2, AxialLayout::new(2).distance((0, 0), (2, -1)));
+		assert_eq!(2, CubicLayout::new(2).distance((0, 0, 0), (2, -1, -1)));
+		let offset = OffsetLayout::new(HexOrientation::FlatTopOddUp, -1, 4, -1, 4);
+		assert_eq!(3, offset.distance((2, 2), (4, 4)));
+	}
+	#[test]
+	/// A grid backed by a dense store finds the same path as one backed by a map
+	fn dense_store_matches_map() {
+		let mut nodes: HashMap<(i32, i32), f32> = HashMap::new();
+		nodes.insert((0, 0), 1.0);
+		nodes.insert((0, 1), 1.0);
+		nodes.insert((0, 2), 1.0);
+		nodes.insert((0, 3), 3.0);
+		nodes.insert((1, 0), 2.0);
+		nodes.insert((1, 1), 9.0);
+		nodes.insert((1, 2), 4.0);
+		nodes.insert((1, 3), 2.0);
+		nodes.insert((2, 0), 2.0);
+		nodes.insert((2, 1), 6.0);
+		nodes.insert((2, 2), 8.0);
+		nodes.insert((2, 3), 9.0);
+		nodes.insert((3, 0), 3.0);
+		nodes.insert((3, 1), 4.0);
+		nodes.insert((3, 2), 5.0);
+		nodes.insert((3, 3), 2.0);
+		let layout = OffsetLayout::new(HexOrientation::FlatTopOddUp, -1, 4, -1, 4);
+		let dense = HexGrid::new(layout, DenseNodeStore::from_map(4, 4, &nodes));
+		let sparse = HexGrid::new(layout, nodes);
+		let dense_path = astar_path(&dense, (0, 0), (3, 3)).unwrap();
+		let sparse_path = astar_path(&sparse, (0, 0), (3, 3)).unwrap();
+		assert_eq!(sparse_path, dense_path);
+		let actual = vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 3), (3, 3)];
+		assert_eq!(actual, dense_path.into_nodes());
+	}
+	#[test]
+	/// An infinite world generated on demand can be searched, here a wall along `x = 1` with a
+	/// single gap at `z = -3` forces the path to detour
+	fn procedural_unbounded_world() {
+		let nodes = ProceduralNodes::new(|node: &(i32, i32, i32)| {
+			if node.0 == 1 && node.2 != -3 {
+				None
+			} else {
+				Some(1.0)
+			}
+		});
+		let grid = HexGrid::new(CubicLayout::unbounded(), nodes);
+		let path = astar_path(&grid, (0, 0, 0), (2, -2, 0)).unwrap();
+		assert!(path.contains(&(1, 2, -3)));
+		assert_eq!(Some(&(2, -2, 0)), path.last());
+	}
+	#[test]
+	/// Editing a grid changes the path of the next search
+	/// ```txt
+	///       (1,-1,0)                    absent
+	///      /   C:1  \
+	///     S          E      ==>     S          E
+	///      \   C:2  /                  \   C:3  /
+	///       (1,0,-1)                   (1,0,-1)
+	/// ```
+	fn edits_reroute_search() {
+		let mut nodes = HashMap::new();
+		for node in [(0, 0, 0), (1, -1, 0), (2, -1, -1)] {
+			nodes.insert(node, 1.0);
+		}
+		nodes.insert((1, 0, -1), 2.0);
+		let mut grid = HexGrid::new(CubicLayout::new(2), nodes);
+		let path = astar_path(&grid, (0, 0, 0), (2, -1, -1)).unwrap();
+		assert!(path.contains(&(1, -1, 0)));
+		// the bridge is destroyed and the detour becomes harder going
+		let applied = grid.apply(vec![
+			GridEdit::RemoveNode((1, -1, 0)),
+			GridEdit::SetComplexity((1, 0, -1), 3.0),
+			GridEdit::SetComplexity((5, 5, -10), 1.0),
+		]);
+		assert_eq!(2, applied);
+		let path = astar_path(&grid, (0, 0, 0), (2, -1, -1)).unwrap();
+		assert_eq!(vec![(0, 0, 0), (1, 0, -1), (2, -1, -1)], path.nodes());
+		assert_eq!(4.0, path.cost());
+		// rebuilding the bridge restores the original route
+		assert!(grid.insert_node((1, -1, 0), 1.0));
+		let path = astar_path(&grid, (0, 0, 0), (2, -1, -1)).unwrap();
+		assert!(path.contains(&(1, -1, 0)));
+	}
+	#[test]
+	/// A dense store can be edited through the grid but not beyond its bounds
+	fn edit_dense_store() {
+		let layout = OffsetLayout::new(HexOrientation::FlatTopOddUp, -1, 2, -1, 2);
+		let mut grid = HexGrid::new(layout, DenseNodeStore::new(2, 2, 1.0));
+		assert_eq!(Some(1.0), grid.remove_node(&(1, 1)));
+		assert!(!grid.contains(&(1, 1)));
+		assert!(!grid.set_complexity((1, 1), 3.0));
+		assert!(grid.insert_node((1, 1), 3.0));
+		assert_eq!(Some(3.0), grid.complexity(&(1, 1)));
+		assert!(!grid.insert_node((2, 0), 1.0));
+	}
+	#[test]
+	/// Crossing a river costs extra so the path walks round to the ford
+	/// ```txt
+	///      o - ford - o
+	///     /            \
+	///    o    ~river~   o
+	///   /                \
+	///  S      ~river~     E
+	/// ```
+	fn edge_costs_divert_path() {
+		let mut nodes = HashMap::new();
+		for x in -2..=2_i32 {
+			for y in -2..=2_i32 {
+				if (x + y).abs() <= 2 {
+					nodes.insert((x, y, -x - y), 1.0);
+				}
+			}
+		}
+		let mut grid = HexGrid::new(CubicLayout::new(2), nodes);
+		// a river between the x = 0 and x = 1 columns, except for the ford at the top
+		for y in -2..=2_i32 {
+			for other in [(1, y - 1, -y), (1, y, -y - 1)] {
+				if grid.contains(&other) && other != (1, 1, -2) {
+					assert!(grid.set_edge_cost((0, y, -y), other, 10.0));
+				}
+			}
+		}
+		assert!(!grid.set_edge_cost((0, 0, 0), (2, -1, -1), 10.0));
+		assert_eq!(10.0, grid.edge_cost((1, -1, 0), (0, 0, 0)));
+		assert_eq!(Some(11.0), grid.step_cost((1, -1, 0), (0, 0, 0)));
+		let path = astar_path(&grid, (0, 0, 0), (1, -1, 0)).unwrap();
+		assert_eq!(4.0, path.cost());
+		assert!(path.contains(&(1, 1, -2)));
+		assert_eq!(Some(10.0), grid.clear_edge_cost((0, 0, 0), (1, -1, 0)));
+		let path = astar_path(&grid, (0, 0, 0), (1, -1, 0)).unwrap();
+		assert_eq!(1.0, path.cost());
+	}
+	#[test]
+	/// Land and sea units share a grid but search their own layers
+	/// ```txt
+	///  S - land - E
+	///   \        /
+	///    - sea --
+	/// ```
+	fn layers_searched_separately() {
+		let mut nodes = LayeredNodes::new(2);
+		// complexities are [land, sea]
+		nodes.insert((0, 0, 0), vec![1.0, 1.0]);
+		nodes.insert((2, -1, -1), vec![1.0, 1.0]);
+		nodes.insert((1, -1, 0), vec![1.0, f32::INFINITY]);
+		nodes.insert((1, 0, -1), vec![f32::INFINITY, 1.0]);
+		let mut grid = HexGrid::new(CubicLayout::new(2), nodes);
+		let land = astar_path(&grid.layer(0), (0, 0, 0), (2, -1, -1)).unwrap();
+		assert!(land.contains(&(1, -1, 0)));
+		let sea = astar_path(&grid.layer(1), (0, 0, 0), (2, -1, -1)).unwrap();
+		assert!(sea.contains(&(1, 0, -1)));
+		// the land route floods, leaving no path for land units
+		assert!(grid
+			.nodes_mut()
+			.set_layer_complexity(&(1, -1, 0), 0, f32::INFINITY));
+		assert!(astar_path(&grid.layer(0), (0, 0, 0), (2, -1, -1)).is_none());
+	}
+	#[test]
+	/// Climbing a slope costs more than descending it so a round trip is not symmetric
+	/// ```txt
+	///  (0,0,0) - (1,-1,0) - (2,-2,0)
+	///   low                   high
+	/// ```
+	fn asymmetric_slope() {
+		let mut nodes = HashMap::new();
+		for x in 0..=2_i32 {
+			nodes.insert((x, -x, 0), 1.0);
+		}
+		let grid = HexGrid::new(CubicLayout::new(2), nodes);
+		let slope = grid.with_step_costs(|from, to| {
+			let base = grid.step_cost(from, to)?;
+			if to.0 > from.0 {
+				Some(base * 3.0)
+			} else {
+				Some(base)
+			}
+		});
+		let up = astar_path(&slope, (0, 0, 0), (2, -2, 0)).unwrap();
+		let down = astar_path(&slope, (2, -2, 0), (0, 0, 0)).unwrap();
+		assert_eq!(6.0, up.cost());
+		assert_eq!(2.0, down.cost());
+		assert_eq!(None, slope.step_cost((0, 0, 0), (5, -5, 0)));
+	}
+	#[test]
+	/// The cost of a straight line sums the steps along it and is blocked by walls
+	fn straight_line_cost() {
+		let mut nodes = HashMap::new();
+		for x in -2..=2_i32 {
+			for y in -2..=2_i32 {
+				if (x + y).abs() <= 2 {
+					nodes.insert((x, y, -x - y), 1.0);
+				}
+			}
+		}
+		nodes.insert((0, -1, 1), 3.0);
+		let mut grid = HexGrid::new(CubicLayout::new(2), nodes);
+		// 0.5 + 2 + 2 + 0.5
+		assert_eq!(Some(5.0), grid.line_cost((0, 1, -1), (0, -2, 2)));
+		let path = astar_path(&grid, (0, 1, -1), (0, -2, 2)).unwrap();
+		assert!(path.cost() <= 5.0);
+		grid.insert_node((0, 0, 0), f32::INFINITY);
+		assert_eq!(None, grid.line_cost((0, 1, -1), (0, -2, 2)));
+		assert_eq!(Some(0.0), grid.line_cost((1, 0, -1), (1, 0, -1)));
+	}
+	#[test]
+	/// Walls and holes between two nodes block sight while expensive nodes do not, in Offset
+	/// coordinates as in any other
+	fn grid_line_of_sight() {
+		let layout = OffsetLayout::new(HexOrientation::FlatTopOddUp, -1, 6, -1, 6);
+		let mut grid = HexGrid::new(layout, DenseNodeStore::new(6, 6, 1.0));
+		assert!(grid.has_line_of_sight((0, 0), (0, 5)));
+		grid.set_complexity((0, 2), 50.0);
+		assert!(grid.has_line_of_sight((0, 0), (0, 5)));
+		grid.set_complexity((0, 2), f32::INFINITY);
+		assert!(!grid.has_line_of_sight((0, 0), (0, 5)));
+		assert!(grid.has_line_of_sight((0, 0), (0, 2)));
+		grid.set_complexity((0, 2), 1.0);
+		grid.remove_node(&(0, 3));
+		assert!(!grid.has_line_of_sight((0, 0), (0, 5)));
+		assert!(grid.has_line_of_sight((0, 0), (5, 0)));
+	}
+	#[test]
+	/// Only nodes of the grid are seen, holes and walls hiding what lies behind them
+	fn grid_field_of_view() {
+		let mut grid = axial_fixture(3, 0);
+		for node in grid.nodes().clone().keys() {
+			grid.insert_node(*node, 1.0);
+		}
+		assert_eq!(37, grid.field_of_view((0, 0), 5).len());
+		grid.insert_node((0, 1), f32::INFINITY);
+		grid.remove_node(&(1, 0));
+		let seen = grid.field_of_view((0, 0), 3);
+		assert!(seen.contains(&(0, 1)));
+		assert!(!seen.contains(&(1, 0)));
+		assert!(!seen.contains(&(0, 2)));
+		assert!(!seen.contains(&(2, 0)));
+		assert!(seen.contains(&(-1, 2)));
+	}
+	#[test]
+	/// Each layout converts its coordinates to and from Cubic coordinates
+	fn layout_cubic_conversion() {
+		let axial = AxialLayout::new(2);
+		assert_eq!((1, -2), axial.cubic_to_node(axial.node_to_cubic((1, -2))));
+		let offset = OffsetLayout::new(HexOrientation::PointyTopOddLeft, -1, 4, -1, 4);
+		assert_eq!((3, 1), offset.cubic_to_node(offset.node_to_cubic((3, 1))));
+		assert_eq!(
+			vec![(0, 0), (1, 0), (2, 0)],
+			HexGrid::new(offset, HashMap::new()).line((0, 0), (2, 0))
+		);
+	}
+	#[test]
+	/// A wall between two hexes forces a detour, opening a gate in it restores the direct route
+	/// ```txt
+	///        (1,-1,0)
+	///       /        \
+	///  (0,0,0) |wall| (1,0,-1)
+	/// ```
+	fn blocked_edge_detour() {
+		let mut nodes = HashMap::new();
+		for node in [(0, 0, 0), (1, -1, 0), (1, 0, -1)] {
+			nodes.insert(node, 1.0);
+		}
+		let mut grid = HexGrid::new(CubicLayout::new(2), nodes);
+		assert!(grid.block_edge((1, 0, -1), (0, 0, 0)));
+		assert!(!grid.block_edge((0, 0, 0), (2, -1, -1)));
+		assert!(grid.is_edge_blocked((0, 0, 0), (1, 0, -1)));
+		assert_eq!(vec![(1, -1, 0)], grid.neighbours((0, 0, 0)));
+		assert_eq!(None, grid.step_cost((0, 0, 0), (1, 0, -1)));
+		let path = astar_path(&grid, (0, 0, 0), (1, 0, -1)).unwrap();
+		assert_eq!(vec![(0, 0, 0), (1, -1, 0), (1, 0, -1)], path.nodes());
+		assert!(grid.unblock_edge((0, 0, 0), (1, 0, -1)));
+		let path = astar_path(&grid, (0, 0, 0), (1, 0, -1)).unwrap();
+		assert_eq!(1.0, path.cost());
+	}
+	#[test]
+	/// Every change to the grid advances its generation, making values stamped earlier stale,
+	/// while attempted changes which do nothing leave it alone
+	fn stamped_values_go_stale() {
+		let mut grid = axial_fixture(2, 1);
+		let path = grid.stamp(astar_path(&grid, (0, 0), (2, 0)));
+		assert!(!path.is_stale(&grid));
+		assert!(!grid.set_complexity((5, 5), 1.0));
+		assert!(!grid.unblock_edge((0, 0), (1, 0)));
+		assert_eq!(None, grid.remove_portal((0, 0), (2, 0)));
+		assert!(!path.is_stale(&grid));
+		grid.add_portal((0, 0), (2, 0), 1.0);
+		assert!(path.is_stale(&grid));
+		let mut generations = vec![grid.generation()];
+		assert!(grid.set_complexity((1, 0), 3.0));
+		generations.push(grid.generation());
+		assert!(grid.block_edge((0, 0), (1, 0)));
+		generations.push(grid.generation());
+		assert!(grid.unblock_edge((0, 0), (1, 0)));
+		generations.push(grid.generation());
+		assert!(grid.set_edge_cost((0, 0), (1, 0), 2.0));
+		generations.push(grid.generation());
+		assert_eq!(Some(2.0), grid.clear_edge_cost((0, 0), (1, 0)));
+		generations.push(grid.generation());
+		assert!(grid.remove_node(&(1, 0)).is_some());
+		generations.push(grid.generation());
+		assert_eq!(1, grid.apply(vec![GridEdit::InsertNode((1, 0), 1.0)]));
+		generations.push(grid.generation());
+		for pair in generations.windows(2) {
+			assert!(pair[1] > pair[0]);
+		}
+		let generation = grid.generation();
+		let fresh = grid.stamp(0);
+		assert_eq!(generation, fresh.generation());
+		assert!(!fresh.is_stale(&grid));
+		assert_eq!(0, fresh.into_inner());
+	}
+	#[test]
+	/// Within range `2` of the target `T` lie the swamp `5` and the node at the end of the lane
+	/// around it, the path stops at the end of the lane which is cheaper to reach
+	/// ```txt
+	///   column:  -2  -1   0   1   2
+	///                             T
+	///                         .
+	///                     5
+	///                 .       .
+	///             S       .
+	/// ```
+	fn path_into_range() {
+		let mut nodes = HashMap::new();
+		for node in [(-2, 0), (-1, 0), (1, 0), (2, 0), (0, -1), (1, -1)] {
+			nodes.insert(node, 1.0);
+		}
+		nodes.insert((0, 0), 5.0);
+		let grid = HexGrid::new(AxialLayout::new(2), nodes);
+		let path = grid.path_within_range((-2, 0), (2, 0), 2).unwrap();
+		assert_eq!(&[(-2, 0), (-1, 0), (0, -1), (1, -1)], path.nodes());
+		assert_eq!(3.0, path.cost());
+		let there = grid.path_within_range((-2, 0), (2, 0), 0).unwrap();
+		assert_eq!(Some(&(2, 0)), there.last());
+		assert_eq!(5.0, there.cost());
+		let still = grid.path_within_range((-2, 0), (2, 0), 4).unwrap();
+		assert_eq!(&[(-2, 0)], still.nodes());
+		assert!(grid.path_within_range((-2, 0), (2, 3), 1).is_none());
+	}
+	#[test]
+	/// Range is measured in hex distance rather than steps through the grid, so a node across a
+	/// wall from the target `T` is in range even though walking to the target from it takes the
+	/// long way round. Within range `2` of `T` the search stops one step from `S` at `E`
+	/// ```txt
+	///   column:  -2  -1   0   1   2
+	///                     #
+	///                 .       .
+	///             .       #       .
+	///                 .       T
+	///             .       #       .
+	///                 E       .
+	///             S       #       .
+	///                 .       .
+	///                     .
+	/// ```
+	fn path_into_range_across_wall() {
+		let wall = [(0, 2), (0, 1), (0, 0), (0, -1)];
+		let layout =
+			ShapedLayout::new(AxialLayout::new(2), move |n: &(i32, i32)| !wall.contains(n));
+		let mut nodes = HashMap::new();
+		for q in -2..=2_i32 {
+			for r in (-2).max(-q - 2)..=2.min(-q + 2) {
+				nodes.insert((q, r), 1.0);
+			}
+		}
+		let grid = HexGrid::new(layout, nodes);
+		assert!(astar_path(&grid, (-1, 0), (1, 0)).unwrap().hops() > 2);
+		let path = grid.path_within_range((-2, 0), (1, 0), 2).unwrap();
+		assert_eq!(vec![(-2, 0), (-1, 0)], path.into_nodes());
+	}
+	#[test]
+	/// Nodes across the seam of a wrapping layout are in range of a target on the other side
+	fn path_into_range_across_seam() {
+		let layout = OffsetLayout::new(HexOrientation::FlatTopOddUp, -1, 6, -1, 2)
+			.with_wrapping(true, false);
+		let mut nodes = HashMap::new();
+		for column in 0..6 {
+			nodes.insert((column, 0), 1.0);
+		}
+		let grid = HexGrid::new(layout, nodes);
+		let path = grid.path_within_range((1, 0), (5, 0), 1).unwrap();
+		assert_eq!(vec![(1, 0), (0, 0)], path.into_nodes());
+	}
+	/// Compiles only for types which can be shared between threads
+	fn assert_send_sync<T: Send + Sync>() {}
+	#[test]
+	/// Grids of every layout and node store, and what searching them produces, can be shared
+	/// between threads
+	fn shareable_between_threads() {
+		assert_send_sync::<HexGrid<AxialLayout>>();
+		assert_send_sync::<HexGrid<CubicLayout>>();
+		assert_send_sync::<HexGrid<OffsetLayout, DenseNodeStore>>();
+		assert_send_sync::<HexGrid<StackedLayout<AxialLayout>, LayeredNodes<(i32, i32, i32)>>>();
+		assert_send_sync::<HexGrid<AxialLayout, UncertainNodes<(i32, i32)>>>();
+		assert_send_sync::<HexGrid<AxialLayout, IndexedNodes<(i32, i32)>>>();
+		assert_send_sync::<Path<(i32, i32)>>();
+		assert_send_sync::<SearchContext<(i32, i32)>>();
+		let grid = Arc::new(axial_fixture(4, 3));
+		let expected = astar_path(grid.as_ref(), (0, 0), (-4, 4));
+		let workers: Vec<thread::JoinHandle<_>> = (0..4)
+			.map(|_| {
+				let grid = Arc::clone(&grid);
+				thread::spawn(move || astar_path(&grid, (0, 0), (-4, 4)))
+			})
+			.collect();
+		for worker in workers {
+			assert_eq!(expected, worker.join().unwrap());
+		}
+	}
+	#[test]
+	/// A one way tunnel joins two islands, it can be taken out but there is no way back
+	/// ```txt
+	///  (-2,2,0)  (-1,1,0)  ~~sea~~  (1,-1,0)  (2,-2,0)
+	///      \________tunnel__________/
+	/// ```
+	fn one_way_portal() {
+		let mut nodes = HashMap::new();
+		for node in [(-2, 2, 0), (-1, 1, 0), (1, -1, 0), (2, -2, 0)] {
+			nodes.insert(node, 1.0);
+		}
+		let mut grid = HexGrid::new(CubicLayout::new(2), nodes);
+		assert!(astar_path(&grid, (-2, 2, 0), (2, -2, 0)).is_none());
+		grid.add_portal((-2, 2, 0), (1, -1, 0), 5.0);
+		let path = astar_path(&grid, (-2, 2, 0), (2, -2, 0)).unwrap();
+		assert_eq!(vec![(-2, 2, 0), (1, -1, 0), (2, -2, 0)], path.nodes());
+		assert_eq!(6.0, path.cost());
+		assert!(astar_path(&grid, (2, -2, 0), (-2, 2, 0)).is_none());
+		// searching backwards follows the tunnel in its direction of travel only
+		let to_goal = astar_from_goal(&grid, &[(-1, 1, 0), (2, -2, 0)], (2, -2, 0));
+		assert_eq!(7.0, to_goal[0].as_ref().unwrap().cost());
+		assert_eq!(&[((1, -1, 0), 5.0)], grid.portals_from(&(-2, 2, 0)));
+		assert_eq!(Some(5.0), grid.remove_portal((-2, 2, 0), (1, -1, 0)));
+		assert!(astar_path(&grid, (-2, 2, 0), (2, -2, 0)).is_none());
+	}
+	#[test]
+	/// A dungeon of two floors joined by a staircase, the only route between the ends of the
+	/// ground floor passes through the upper floor
+	/// ```txt
+	///  level 1:            (1,-1,0) - (2,-2,0)
+	///                      stairs      stairs
+	///  level 0:  (0,0,0) - (1,-1,0)    (2,-2,0) - (3,-3,0)
+	///                      wall between
+	/// ```
+	fn stacked_levels() {
+		let mut nodes = HashMap::new();
+		for x in 0..=3_i32 {
+			nodes.insert(((x, -x, 0), 0), 1.0);
+		}
+		nodes.insert(((1, -1, 0), 1), 1.0);
+		nodes.insert(((2, -2, 0), 1), 1.0);
+		let layout = StackedLayout::new(CubicLayout::new(3), 2);
+		let mut grid = HexGrid::new(layout, nodes);
+		assert!(grid.block_edge(((1, -1, 0), 0), ((2, -2, 0), 0)));
+		assert!(astar_path(&grid, ((0, 0, 0), 0), ((3, -3, 0), 0)).is_none());
+		grid.add_transition(((1, -1, 0), 0), ((1, -1, 0), 1), 2.0);
+		grid.add_transition(((2, -2, 0), 1), ((2, -2, 0), 0), 2.0);
+		let path = astar_path(&grid, ((0, 0, 0), 0), ((3, -3, 0), 0)).unwrap();
+		let actual = vec![
+			((0, 0, 0), 0),
+			((1, -1, 0), 0),
+			((1, -1, 0), 1),
+			((2, -2, 0), 1),
+			((2, -2, 0), 0),
+			((3, -3, 0), 0),
+		];
+		assert_eq!(actual, path.into_nodes());
+		assert!(layout.neighbours(((1, -1, 0), 1)).iter().all(|n| n.1 == 1));
+		assert!(layout.neighbours(((0, 0, 0), 2)).is_empty());
+	}
+	#[test]
+	/// A risk averse search avoids a ford which is usually quick but sometimes flooded
+	/// ```txt
+	///        ford (mean 1, varies)
+	///       /                \
+	///  (0,0,0)              (2,-1,-1)
+	///       \                /
+	///        bridge (always 2)
+	/// ```
+	fn risk_averse_route() {
+		let mut nodes = HashMap::new();
+		nodes.insert((0, 0, 0), CostDistribution::new(1.0, 0.0));
+		nodes.insert((2, -1, -1), CostDistribution::new(1.0, 0.0));
+		nodes.insert((1, -1, 0), CostDistribution::uniform(0.0, 2.0));
+		nodes.insert((1, 0, -1), CostDistribution::new(2.0, 0.0));
+		let mut grid = HexGrid::new(CubicLayout::new(2), UncertainNodes::new(nodes));
+		let expected = astar_path(&grid, (0, 0, 0), (2, -1, -1)).unwrap();
+		assert!(expected.contains(&(1, -1, 0)));
+		grid.nodes_mut().set_risk_aversion(2.0);
+		let cautious = astar_path(&grid, (0, 0, 0), (2, -1, -1)).unwrap();
+		assert!(cautious.contains(&(1, 0, -1)));
+		assert_eq!(3.0, cautious.cost());
+	}
+	#[test]
+	/// A wrapping layout joins its left and right edges
+	fn wrapping_layout() {
+		let layout = OffsetLayout::new(HexOrientation::FlatTopOddUp, -1, 6, -1, 2)
+			.with_wrapping(true, false);
+		assert!(layout.neighbours((5, 0)).contains(&(0, 1)));
+		assert_eq!(1, layout.distance((5, 0), (0, 0)));
+		let mut nodes = HashMap::new();
+		for column in 0..6 {
+			nodes.insert((column, 0), 1.0);
+		}
+		let grid = HexGrid::new(layout, nodes);
+		let path = astar_path(&grid, (1, 0), (5, 0)).unwrap();
+		assert_eq!(vec![(1, 0), (0, 0), (5, 0)], path.into_nodes());
+	}
+	#[test]
+	/// An L-shaped room bounded by a set of hexes is searched without padding fake nodes
+	/// ```txt
+	///  (0,2)
+	///     \
+	///      (0,1)
+	///     /     \
+	///  (0,0) - (1,0) - (2,0)
+	/// ```
+	fn l_shaped_boundary() {
+		let shape: HashSet<(i32, i32)> = [(0, 0), (0, 1), (0, 2), (1, 0), (2, 0)]
+			.iter()
+			.copied()
+			.collect();
+		let layout = ShapedLayout::new(
+			OffsetLayout::unbounded(HexOrientation::PointyTopOddRight),
+			shape,
+		);
+		assert_eq!(vec![(0, 1)], {
+			let mut n = layout.neighbours((0, 2));
+			n.sort();
+			n
+		});
+		// the store covers a whole rectangle but only the shape is searched
+		let grid = HexGrid::new(layout, DenseNodeStore::new(3, 3, 1.0));
+		let path = astar_path(&grid, (0, 2), (2, 0)).unwrap();
+		assert_eq!(vec![(0, 2), (0, 1), (1, 0), (2, 0)], path.nodes());
+		assert!(path.iter().all(|n| grid.layout().contains(n)));
+	}
+}
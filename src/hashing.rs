@@ -0,0 +1,125 @@
+//! A fast, non-cryptographic, hasher for the maps used inside a search.
+//!
+//! The standard library hashes with SipHash, which resists collision attacks at the cost of
+//! speed. A search hashes a small coordinate every time it looks up the score of a node and has
+//! nothing to fear from crafted keys, so with the `fast-hash` feature enabled the internal maps
+//! of the searches in `search` use `FastHasher` instead, the multiply and rotate hash of the
+//! Firefox and rustc "FxHash". On large maps this is commonly a 20-30% speed up:
+//!
+//! ```toml
+//! [dependencies]
+//! hexagonal_pathfinding_astar = { version = "1", features = ["fast-hash"] }
+//! ```
+//!
+//! `FastBuildHasher` is public so maps of nodes built by the caller, such as the complexities
+//! given to a `HexGrid`, can use it too:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::hashing::FastBuildHasher;
+//! use std::collections::HashMap;
+//! let mut nodes: HashMap<(i32, i32), f32, FastBuildHasher> = HashMap::default();
+//! nodes.insert((0, 0), 1.0);
+//! assert_eq!(Some(&1.0), nodes.get(&(0, 0)));
+//! ```
+
+use ::std::collections::HashMap;
+use ::std::convert::TryInto;
+use ::std::hash::BuildHasherDefault;
+use ::std::hash::Hasher;
+
+/// The multiplier of FxHash, derived from the golden ratio
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// A hasher mixing each word written into its state with a rotate, xor and multiply, see the
+/// module documentation
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FastHasher {
+	/// The hash so far
+	hash: u64,
+}
+
+impl FastHasher {
+	/// Mix `word` into the hash
+	fn add_to_hash(&mut self, word: u64) {
+		self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+	}
+}
+
+impl Hasher for FastHasher {
+	fn write(&mut self, bytes: &[u8]) {
+		let mut chunks = bytes.chunks_exact(8);
+		for chunk in &mut chunks {
+			// chunks_exact guarantees eight bytes
+			let word: [u8; 8] = chunk.try_into().unwrap();
+			self.add_to_hash(u64::from_le_bytes(word));
+		}
+		for byte in chunks.remainder() {
+			self.add_to_hash(*byte as u64);
+		}
+	}
+	fn write_u8(&mut self, i: u8) {
+		self.add_to_hash(i as u64);
+	}
+	fn write_u16(&mut self, i: u16) {
+		self.add_to_hash(i as u64);
+	}
+	fn write_u32(&mut self, i: u32) {
+		self.add_to_hash(i as u64);
+	}
+	fn write_u64(&mut self, i: u64) {
+		self.add_to_hash(i);
+	}
+	fn write_usize(&mut self, i: usize) {
+		self.add_to_hash(i as u64);
+	}
+	fn write_i32(&mut self, i: i32) {
+		self.add_to_hash(i as u32 as u64);
+	}
+	fn finish(&self) -> u64 {
+		self.hash
+	}
+}
+
+/// Builds a `FastHasher` for each key, for use as the hasher of a `HashMap` or `HashSet`
+pub type FastBuildHasher = BuildHasherDefault<FastHasher>;
+
+/// The map used inside a search, hashed by `FastHasher` when the `fast-hash` feature is enabled.
+/// Create one with `NodeMap::default()`
+#[cfg(feature = "fast-hash")]
+pub(crate) type NodeMap<K, V> = HashMap<K, V, FastBuildHasher>;
+
+/// The map used inside a search, hashed by `FastHasher` when the `fast-hash` feature is enabled.
+/// Create one with `NodeMap::default()`
+#[cfg(not(feature = "fast-hash"))]
+pub(crate) type NodeMap<K, V> = HashMap<K, V>;
+
+#[cfg(test)]
+mod tests {
+	use crate::hashing::*;
+	use ::std::hash::BuildHasher;
+	use ::std::hash::Hash;
+
+	/// Hash `value` with a fresh `FastHasher`
+	fn hash<T: Hash>(value: &T) -> u64 {
+		let mut hasher = FastBuildHasher::default().build_hasher();
+		value.hash(&mut hasher);
+		hasher.finish()
+	}
+	#[test]
+	/// Equal coordinates always hash the same and every coordinate of a grid hashes differently
+	fn spreads_coordinates() {
+		assert_eq!(hash(&(3, -4)), hash(&(3, -4)));
+		let mut hashes: Vec<u64> = Vec::new();
+		for q in -20..=20i32 {
+			for r in -20..=20i32 {
+				hashes.push(hash(&(q, r)));
+			}
+		}
+		let count = hashes.len();
+		hashes.sort_unstable();
+		hashes.dedup();
+		assert_eq!(count, hashes.len());
+		// bytes written loose are mixed in too
+		assert_ne!(hash(&"hexagon"), hash(&"hexagons"));
+	}
+}
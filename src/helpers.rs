@@ -234,7 +234,12 @@
 //! north-west = (column - 1, row + 1)
 //! ```
 
+use crate::AxialOrientation;
+use crate::CubicAxis;
+use crate::GridBounds;
+use crate::HexDirection;
 use crate::HexOrientation;
+use ::std::collections::HashSet;
 
 /// Converts Offset coordinates (based on an orientation) to Cubic coordinates.
 /// FlatTopOddUp:
@@ -263,25 +268,25 @@ use crate::HexOrientation;
 /// ```
 pub fn offset_to_cubic(node_coords: (i32, i32), orientation: &HexOrientation) -> (i32, i32, i32) {
 	match orientation {
-		HexOrientation::FlatTopOddUp => {
+		HexOrientation::FlatTopOddUp | HexOrientation::FlatTopEvenDown => {
 			let x: i32 = node_coords.0;
 			let z: i32 = node_coords.1 - (node_coords.0 - (node_coords.0 & 1)) / 2;
 			let y: i32 = -x - z;
 			(x, y, z)
 		}
-		HexOrientation::FlatTopOddDown => {
+		HexOrientation::FlatTopOddDown | HexOrientation::FlatTopEvenUp => {
 			let x: i32 = node_coords.0;
 			let z: i32 = node_coords.1 - (node_coords.0 + (node_coords.0 & 1)) / 2;
 			let y: i32 = -x - z;
 			(x, y, z)
 		}
-		HexOrientation::PointyTopOddRight => {
+		HexOrientation::PointyTopOddRight | HexOrientation::PointyTopEvenLeft => {
 			let x: i32 = node_coords.0 - (node_coords.1 - (node_coords.1 & 1)) / 2;
 			let z: i32 = node_coords.1;
 			let y: i32 = -x - z;
 			(x, y, z)
 		}
-		HexOrientation::PointyTopOddLeft => {
+		HexOrientation::PointyTopOddLeft | HexOrientation::PointyTopEvenRight => {
 			let x: i32 = node_coords.0 - (node_coords.1 + (node_coords.1 & 1)) / 2;
 			let z: i32 = node_coords.1;
 			let y: i32 = -x - z;
@@ -338,22 +343,22 @@ pub fn axial_to_cubic(node_coords: (i32, i32)) -> (i32, i32, i32) {
 /// ```
 pub fn axial_to_offset(node_coords: (i32, i32), orientation: &HexOrientation) -> (i32, i32) {
 	match orientation {
-		HexOrientation::FlatTopOddUp => {
+		HexOrientation::FlatTopOddUp | HexOrientation::FlatTopEvenDown => {
 			let x: i32 = node_coords.0;
 			let y: i32 = node_coords.1 + (node_coords.0 - (node_coords.0 & 1)) / 2;
 			(x, y)
 		}
-		HexOrientation::FlatTopOddDown => {
+		HexOrientation::FlatTopOddDown | HexOrientation::FlatTopEvenUp => {
 			let x: i32 = node_coords.0;
 			let y: i32 = node_coords.1 + (node_coords.0 + (node_coords.0 & 1)) / 2;
 			(x, y)
 		}
-		HexOrientation::PointyTopOddRight => {
+		HexOrientation::PointyTopOddRight | HexOrientation::PointyTopEvenLeft => {
 			let x: i32 = node_coords.0 + (node_coords.1 - (node_coords.1 & 1)) / 2;
 			let y: i32 = node_coords.1;
 			(x, y)
 		}
-		HexOrientation::PointyTopOddLeft => {
+		HexOrientation::PointyTopOddLeft | HexOrientation::PointyTopEvenRight => {
 			let x: i32 = node_coords.0 + (node_coords.1 + (node_coords.1 & 1)) / 2;
 			let y: i32 = node_coords.1;
 			(x, y)
@@ -397,22 +402,22 @@ pub fn cubic_to_axial(node_coords: (i32, i32, i32)) -> (i32, i32) {
 /// ```
 pub fn cubic_to_offset(node_coords: (i32, i32, i32), orientation: &HexOrientation) -> (i32, i32) {
 	match orientation {
-		HexOrientation::FlatTopOddUp => {
+		HexOrientation::FlatTopOddUp | HexOrientation::FlatTopEvenDown => {
 			let q: i32 = node_coords.0;
 			let r: i32 = node_coords.2 + (node_coords.0 - (node_coords.0 & 1)) / 2;
 			(q, r)
 		}
-		HexOrientation::FlatTopOddDown => {
+		HexOrientation::FlatTopOddDown | HexOrientation::FlatTopEvenUp => {
 			let q: i32 = node_coords.0;
 			let r: i32 = node_coords.2 + (node_coords.0 + (node_coords.0 & 1)) / 2;
 			(q, r)
 		}
-		HexOrientation::PointyTopOddRight => {
+		HexOrientation::PointyTopOddRight | HexOrientation::PointyTopEvenLeft => {
 			let q: i32 = node_coords.0 + (node_coords.2 - (node_coords.2 & 1)) / 2;
 			let r: i32 = node_coords.2;
 			(q, r)
 		}
-		HexOrientation::PointyTopOddLeft => {
+		HexOrientation::PointyTopOddLeft | HexOrientation::PointyTopEvenRight => {
 			let q: i32 = node_coords.0 + (node_coords.2 + (node_coords.2 & 1)) / 2;
 			let r: i32 = node_coords.2;
 			(q, r)
@@ -450,7 +455,7 @@ pub fn node_neighbours_offset(
 		//  /   \___/
 		//  \___/
 		// flat topped arrangemnt of hexagns, odd columns shifted up
-		HexOrientation::FlatTopOddUp => {
+		HexOrientation::FlatTopOddUp | HexOrientation::FlatTopEvenDown => {
 			// even column
 			if source.0 & 1 == 0 {
 				// north
@@ -510,7 +515,7 @@ pub fn node_neighbours_offset(
 		//  \___/   \
 		//      \___/
 		// flat topped arrangemnt of hexagns, odd columns shifted down
-		HexOrientation::FlatTopOddDown => {
+		HexOrientation::FlatTopOddDown | HexOrientation::FlatTopEvenUp => {
 			// even column with BitwiseAND
 			if source.0 & 1 == 0 {
 				// north
@@ -566,7 +571,7 @@ pub fn node_neighbours_offset(
 			}
 		}
 		// pointy top hexagons with odd rows shifted to the right
-		HexOrientation::PointyTopOddRight => {
+		HexOrientation::PointyTopOddRight | HexOrientation::PointyTopEvenLeft => {
 			// even row with BitwiseAND
 			if source.1 & 1 == 0 {
 				// north-east
@@ -621,7 +626,7 @@ pub fn node_neighbours_offset(
 			}
 		}
 		// pointy top hexagons with odd rows shifted to the left
-		HexOrientation::PointyTopOddLeft => {
+		HexOrientation::PointyTopOddLeft | HexOrientation::PointyTopEvenRight => {
 			// even row with BitwiseAND
 			if source.1 & 1 == 0 {
 				// north-east
@@ -776,6 +781,164 @@ pub fn node_neighbours_axial(source: (i32, i32), count_rings_from_origin: i32) -
 	}
 	neighbours
 }
+/// Finds the neighbouring nodes of an Axial coordinate system in clockwise order, starting from
+/// the north for a flat topped grid and from the east for a pointy topped grid:
+///
+/// | Flat-top      | `(q, r)`  | Pointy-top    | `(q, r)`  |
+/// |---------------|-----------|---------------|-----------|
+/// | north         | `(0, 1)`  | east          | `(1, 0)`  |
+/// | north-east    | `(1, 0)`  | south-east    | `(1, -1)` |
+/// | south-east    | `(1, -1)` | south-west    | `(0, -1)` |
+/// | south         | `(0, -1)` | west          | `(-1, 0)` |
+/// | south-west    | `(-1, 0)` | north-west    | `(-1, 1)` |
+/// | north-west    | `(-1, 1)` | north-east    | `(0, 1)`  |
+///
+/// The offsets are relative to `source`. As with `node_neighbours_axial()` neighbours beyond
+/// `count_rings_from_origin` are left out, so the position of a direction in the returned list is
+/// only fixed for nodes away from the edge of the grid
+pub fn node_neighbours_axial_oriented(
+	source: (i32, i32),
+	count_rings_from_origin: i32,
+	orientation: &AxialOrientation,
+) -> Vec<(i32, i32)> {
+	let directions = match orientation {
+		AxialOrientation::FlatTop => [(0, 1), (1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1)],
+		AxialOrientation::PointyTop => [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)],
+	};
+	let mut neighbours = Vec::new();
+	for d in directions.iter() {
+		let n = (source.0 + d.0, source.1 + d.1);
+		if node_distance(axial_to_cubic(n), (0, 0, 0)) <= count_rings_from_origin {
+			neighbours.push(n);
+		}
+	}
+	neighbours
+}
+/// The neighbour of `source` in `direction` in an Axial coordinate system drawn in
+/// `orientation`, see `HexDirection`. `None` if the direction does not exist in the orientation,
+/// e.g north with pointy tops
+pub fn node_neighbour_axial(
+	source: (i32, i32),
+	direction: HexDirection,
+	orientation: &AxialOrientation,
+) -> Option<(i32, i32)> {
+	let (q, r) = direction.axial_offset(orientation)?;
+	Some((source.0 + q, source.1 + r))
+}
+/// The neighbour of `source` in `direction` in a Cubic coordinate system drawn in `orientation`,
+/// see `node_neighbour_axial()`
+pub fn node_neighbour_cubic(
+	source: (i32, i32, i32),
+	direction: HexDirection,
+	orientation: &AxialOrientation,
+) -> Option<(i32, i32, i32)> {
+	let axial = node_neighbour_axial(cubic_to_axial(source), direction, orientation)?;
+	Some(axial_to_cubic(axial))
+}
+/// The neighbour of `source` in `direction` in an Offset coordinate system based on
+/// `orientation`, see `node_neighbour_axial()`. The neighbour may lie beyond the edge of the grid
+pub fn node_neighbour_offset(
+	source: (i32, i32),
+	direction: HexDirection,
+	orientation: &HexOrientation,
+) -> Option<(i32, i32)> {
+	let cube = offset_to_cubic(source, orientation);
+	let neighbour = node_neighbour_cubic(cube, direction, &orientation.axial_orientation())?;
+	Some(cubic_to_offset(neighbour, orientation))
+}
+/// Finds the neighbouring nodes in an Offset coordinate system where the grid can wrap around
+/// at its boundaries, like the map of a planet. `min_column`, `max_column`, `min_row` and
+/// `max_row` are the exclusive boundary of the grid as with `node_neighbours_offset()`. When
+/// `wrap_columns` is true moving off the left edge arrives at the right edge and vice versa,
+/// `wrap_rows` does the same for the top and bottom edges.
+///
+/// For a grid 4 columns wide, expanding a node in column 3 discovers nodes in column 0:
+/// ```txt
+///    _______                           _______
+///   /       \                         /       \
+///  /  (0,1)  \_______         _______/  (3,1)  \
+///  \         /       \       /       \         /
+///   \_______/   ...   \ ... /  (2,1)  \_______/
+///   /       \         /     \         /       \
+///  /  (0,0)  \_______/       \_______/  (3,0)  \
+///  \         /                       \    S    /
+///   \_______/                         \_______/
+/// ```
+/// Offset coordinates shift alternate columns (or rows), for the wrapped neighbours to line up
+/// a wrapping axis must hold an even number of columns for flat topped orientations, or rows for
+/// pointy topped orientations.
+#[allow(clippy::too_many_arguments)]
+pub fn node_neighbours_offset_wrapping(
+	source: (i32, i32),
+	orientation: &HexOrientation,
+	min_column: i32,
+	max_column: i32,
+	min_row: i32,
+	max_row: i32,
+	wrap_columns: bool,
+	wrap_rows: bool,
+) -> Vec<(i32, i32)> {
+	let mut neighbours = Vec::new();
+	// find the neighbours as if there were no boundary then fold them back onto the grid
+	let unbounded =
+		node_neighbours_offset(source, orientation, i32::MIN, i32::MAX, i32::MIN, i32::MAX);
+	let width = max_column - min_column - 1;
+	let height = max_row - min_row - 1;
+	for n in unbounded.iter() {
+		let column = if wrap_columns {
+			(n.0 - min_column - 1).rem_euclid(width) + min_column + 1
+		} else if n.0 > min_column && n.0 < max_column {
+			n.0
+		} else {
+			continue;
+		};
+		let row = if wrap_rows {
+			(n.1 - min_row - 1).rem_euclid(height) + min_row + 1
+		} else if n.1 > min_row && n.1 < max_row {
+			n.1
+		} else {
+			continue;
+		};
+		// on very small grids several neighbours can wrap onto the same node
+		let node = (column, row);
+		if node != source && !neighbours.contains(&node) {
+			neighbours.push(node);
+		}
+	}
+	neighbours
+}
+/// The distance between two nodes of an Offset grid which wraps around at its boundaries, see
+/// `node_neighbours_offset_wrapping()`. The shortest route may cross a seam of the grid so
+/// each wrapped copy of `end` is measured and the nearest taken
+#[allow(clippy::too_many_arguments)]
+pub fn node_distance_offset_wrapping(
+	start: (i32, i32),
+	end: (i32, i32),
+	orientation: &HexOrientation,
+	min_column: i32,
+	max_column: i32,
+	min_row: i32,
+	max_row: i32,
+	wrap_columns: bool,
+	wrap_rows: bool,
+) -> i32 {
+	let width = max_column - min_column - 1;
+	let height = max_row - min_row - 1;
+	let column_shifts: &[i32] = if wrap_columns { &[-1, 0, 1] } else { &[0] };
+	let row_shifts: &[i32] = if wrap_rows { &[-1, 0, 1] } else { &[0] };
+	let cubic_start = offset_to_cubic(start, orientation);
+	let mut shortest = i32::MAX;
+	for c in column_shifts.iter() {
+		for r in row_shifts.iter() {
+			let shifted = (end.0 + c * width, end.1 + r * height);
+			shortest = shortest.min(node_distance(
+				cubic_start,
+				offset_to_cubic(shifted, orientation),
+			));
+		}
+	}
+	shortest
+}
 /// Finds the nodes on a ring around a given source point in a Cubic coordinate system. `source` is of the form
 /// `(x, y, z)`. `radius` is the particular ring you want to know the nodes of.
 ///
@@ -814,14 +977,7 @@ pub fn node_ring_cubic(source: (i32, i32, i32), radius: i32) -> Vec<(i32, i32, i
 	// SW \         / SE
 	//     \_______/
 	//         S
-	let cube_directions = [
-		(0, -1, 1),
-		(1, -1, 0),
-		(1, 0, -1),
-		(0, 1, -1),
-		(-1, 1, 0),
-		(-1, 0, 1),
-	];
+	let cube_directions = CUBE_DIRECTIONS;
 	// from the starting node move to the node joining the south-west and west faces, e.g for radius =2:
 	//                            _________
 	//                           /         \
@@ -854,18 +1010,10 @@ pub fn node_ring_cubic(source: (i32, i32, i32), radius: i32) -> Vec<(i32, i32, i
 	//                         \             /
 	//                          \           /
 	//                           \_________/
-	let scaled_x = cube_directions[4].0 * radius;
-	let scaled_y = cube_directions[4].1 * radius;
-	let scaled_z = cube_directions[4].2 * radius;
-	// start
-	let mut ring_node_current = (
-		source.0 + scaled_x,
-		source.1 + scaled_y,
-		source.2 + scaled_z,
-	);
+	let mut ring_node_current = cube_add(source, cube_scale(cube_directions[4], radius));
 	// from the node starting on the ring we can walk around the ring discovering all the nodes on it
 	// iterate to 6 as a hexagon has 6 faces, we walk along each side of the hex ring
-	for i in 0..6 {
+	for direction in cube_directions.iter() {
 		// the length of each face is denoted by the radius
 		// e.g radius + 1, so for radius = 2 the sides have length 3 but we only take two steps at a time as to not overlap:
 		//                            _________
@@ -901,20 +1049,727 @@ pub fn node_ring_cubic(source: (i32, i32, i32), radius: i32) -> Vec<(i32, i32, i
 		//                           \_________/
 		for _j in 0..radius {
 			// move to next node
-			ring_node_current.0 += cube_directions[i].0;
-			ring_node_current.1 += cube_directions[i].1;
-			ring_node_current.2 += cube_directions[i].2;
+			ring_node_current = cube_add(ring_node_current, *direction);
 			// store node
 			ring_nodes.push(ring_node_current);
 		}
 	}
 	ring_nodes
 }
+/// Finds the nodes on a ring `radius` jumps around `source` in an Axial coordinate system, in the
+/// order given by `node_ring_cubic()`
+pub fn node_ring_axial(source: (i32, i32), radius: i32) -> Vec<(i32, i32)> {
+	node_ring_cubic(axial_to_cubic(source), radius)
+		.into_iter()
+		.map(cubic_to_axial)
+		.collect()
+}
+/// Finds the nodes on a ring `radius` jumps around `source` in an Offset coordinate system based
+/// on `orientation`, in the order given by `node_ring_cubic()`. Nodes of the ring lying beyond
+/// `bounds` are left out
+pub fn node_ring_offset(
+	source: (i32, i32),
+	radius: i32,
+	orientation: &HexOrientation,
+	bounds: &GridBounds,
+) -> Vec<(i32, i32)> {
+	node_ring_cubic(offset_to_cubic(source, orientation), radius)
+		.into_iter()
+		.map(|cube| cubic_to_offset(cube, orientation))
+		.filter(|node| bounds.contains(*node))
+		.collect()
+}
+/// Finds every node within `range` jumps of `centre` in a Cubic coordinate system, `centre`
+/// included, a filled hexagon of `3 * range * (range + 1) + 1` nodes. Nodes are ordered by `x`
+/// then `y`. For `range = 1`:
+/// ```txt
+///              _______
+///             /       \
+///     _______/    *    \_______
+///    /       \         /       \
+///   /    *    \_______/    *    \
+///   \         /       \         /
+///    \_______/ centre  \_______/
+///    /       \         /       \
+///   /    *    \_______/    *    \
+///   \         /       \         /
+///    \_______/    *    \_______/
+///            \         /
+///             \_______/
+/// ```
+/// An empty list is returned for a negative `range`
+pub fn nodes_within_range_cubic(centre: (i32, i32, i32), range: i32) -> Vec<(i32, i32, i32)> {
+	let mut nodes = Vec::new();
+	for x in -range..=range {
+		for y in (-range).max(-x - range)..=range.min(-x + range) {
+			let z = -x - y;
+			nodes.push((centre.0 + x, centre.1 + y, centre.2 + z));
+		}
+	}
+	nodes
+}
+/// Finds every node within `range` jumps of `centre` in an Axial coordinate system, `centre`
+/// included, see `nodes_within_range_cubic()`
+pub fn nodes_within_range_axial(centre: (i32, i32), range: i32) -> Vec<(i32, i32)> {
+	nodes_within_range_cubic(axial_to_cubic(centre), range)
+		.into_iter()
+		.map(cubic_to_axial)
+		.collect()
+}
+/// Finds every node within `range` jumps of `centre` in an Offset coordinate system based on
+/// `orientation`, `centre` included, see `nodes_within_range_cubic()`. The grid is unbounded so
+/// nodes beyond the edge of a map, e.g with negative coordinates, are included and can be left
+/// out with `GridBounds::contains()`
+pub fn nodes_within_range_offset(
+	centre: (i32, i32),
+	range: i32,
+	orientation: &HexOrientation,
+) -> Vec<(i32, i32)> {
+	nodes_within_range_cubic(offset_to_cubic(centre, orientation), range)
+		.into_iter()
+		.map(|cube| cubic_to_offset(cube, orientation))
+		.collect()
+}
 
+/// The unit length to move through the north face of a flat topped hexagon in Cubic coordinates
+pub const CUBE_NORTH: (i32, i32, i32) = (0, -1, 1);
+/// The unit length to move through the north-east face of a flat topped hexagon in Cubic
+/// coordinates
+pub const CUBE_NORTH_EAST: (i32, i32, i32) = (1, -1, 0);
+/// The unit length to move through the south-east face of a flat topped hexagon in Cubic
+/// coordinates
+pub const CUBE_SOUTH_EAST: (i32, i32, i32) = (1, 0, -1);
+/// The unit length to move through the south face of a flat topped hexagon in Cubic coordinates
+pub const CUBE_SOUTH: (i32, i32, i32) = (0, 1, -1);
+/// The unit length to move through the south-west face of a flat topped hexagon in Cubic
+/// coordinates
+pub const CUBE_SOUTH_WEST: (i32, i32, i32) = (-1, 1, 0);
+/// The unit length to move through the north-west face of a flat topped hexagon in Cubic
+/// coordinates
+pub const CUBE_NORTH_WEST: (i32, i32, i32) = (-1, 0, 1);
+/// The unit lengths to move in the direction of each face, starting with North and moving
+/// clockwise, the order in which `node_ring_cubic()` walks the sides of a ring. With pointy tops
+/// the same directions start from the north-east
+pub const CUBE_DIRECTIONS: [(i32, i32, i32); 6] = [
+	CUBE_NORTH,
+	CUBE_NORTH_EAST,
+	CUBE_SOUTH_EAST,
+	CUBE_SOUTH,
+	CUBE_SOUTH_WEST,
+	CUBE_NORTH_WEST,
+];
+/// Add two Cubic coordinates, e.g moving `node` by one of `CUBE_DIRECTIONS`
+pub fn cube_add(node: (i32, i32, i32), other: (i32, i32, i32)) -> (i32, i32, i32) {
+	(node.0 + other.0, node.1 + other.1, node.2 + other.2)
+}
+/// Subtract `other` from `node` in Cubic coordinates, giving the vector from `other` to `node`
+pub fn cube_sub(node: (i32, i32, i32), other: (i32, i32, i32)) -> (i32, i32, i32) {
+	(node.0 - other.0, node.1 - other.1, node.2 - other.2)
+}
+/// Multiply each part of a Cubic coordinate by `factor`, e.g a direction scaled to a number of
+/// steps
+pub fn cube_scale(node: (i32, i32, i32), factor: i32) -> (i32, i32, i32) {
+	(node.0 * factor, node.1 * factor, node.2 * factor)
+}
+/// The index of the first node of the ring of `radius` in the Spiral Hex numbering
+fn spiral_ring_start(radius: i64) -> i64 {
+	if radius == 0 {
+		0
+	} else {
+		3 * radius * (radius - 1) + 1
+	}
+}
+/// Convert a node in Spiral Hex coordinates to Cubic coordinates.
+///
+/// Spiral Hex numbers every hexagon with a single index by spiralling outwards from the origin.
+/// The origin is `0`, the first ring around it is `1` to `6` and the second ring `7` to `18`,
+/// each ring numbered in the order given by `node_ring_cubic()`:
+/// ```txt
+///              _______
+///             /       \
+///     _______/    2    \_______
+///    /       \         /       \
+///   /    1    \_______/    3    \
+///   \         /       \         /
+///    \_______/    0    \_______/
+///    /       \         /       \
+///   /    6    \_______/    4    \
+///   \         /       \         /
+///    \_______/    5    \_______/
+///            \         /
+///             \_______/
+/// ```
+/// The ring holding `index` is found directly rather than by walking the spiral. Panics if
+/// `index` is negative
+pub fn spiral_hex_to_cubic(index: i32) -> (i32, i32, i32) {
+	if index < 0 {
+		panic!("Spiral Hex index {} cannot be negative", index);
+	}
+	if index == 0 {
+		return (0, 0, 0);
+	}
+	let index = index as i64;
+	// ring r holds the indices 3r(r-1)+1 to 3r(r+1), estimate r and correct any rounding error
+	let mut radius = ((3.0 + ((12 * index - 3) as f64).sqrt()) / 6.0) as i64;
+	while spiral_ring_start(radius) > index {
+		radius -= 1;
+	}
+	while spiral_ring_start(radius + 1) <= index {
+		radius += 1;
+	}
+	let position = index - spiral_ring_start(radius);
+	let side = (position / radius) as usize;
+	let steps = position % radius + 1;
+	// the ring is walked from `radius` steps south-west of the origin, see `node_ring_cubic()`,
+	// and each direction added to the one before it gives the next so each side begins `radius`
+	// steps out along the direction four after its own
+	let corner = CUBE_DIRECTIONS[(side + 4) % 6];
+	let direction = CUBE_DIRECTIONS[side];
+	(
+		(corner.0 as i64 * radius + direction.0 as i64 * steps) as i32,
+		(corner.1 as i64 * radius + direction.1 as i64 * steps) as i32,
+		(corner.2 as i64 * radius + direction.2 as i64 * steps) as i32,
+	)
+}
+/// Convert a node in Cubic coordinates to its Spiral Hex index, the inverse of
+/// `spiral_hex_to_cubic()`. `node_coords` must satisfy `x + y + z = 0`
+pub fn cubic_to_spiral_hex(node_coords: (i32, i32, i32)) -> i32 {
+	let radius = node_distance(node_coords, (0, 0, 0));
+	if radius == 0 {
+		return 0;
+	}
+	// each side of the ring holds one coordinate at `radius` or `-radius` while stepping along
+	// it, which side and how many steps along it, `steps`, is read straight from the coordinates
+	let (x, y, z) = node_coords;
+	let (side, steps) = if x == -radius && z > 0 {
+		(0, z)
+	} else if z == radius && x > -radius {
+		(1, x + radius)
+	} else if y == -radius && x > 0 {
+		(2, x)
+	} else if x == radius && y > -radius {
+		(3, y + radius)
+	} else if z == -radius && y > 0 {
+		(4, y)
+	} else {
+		(5, z + radius)
+	};
+	let position = side * radius as i64 + steps as i64 - 1;
+	(spiral_ring_start(radius as i64) + position) as i32
+}
+/// Convert a node in Spiral Hex coordinates to Axial coordinates, see `spiral_hex_to_cubic()`.
+/// Panics if `index` is negative
+pub fn spiral_hex_to_axial(index: i32) -> (i32, i32) {
+	cubic_to_axial(spiral_hex_to_cubic(index))
+}
+/// Convert a node in Axial coordinates to its Spiral Hex index, the inverse of
+/// `spiral_hex_to_axial()`
+pub fn axial_to_spiral_hex(node_coords: (i32, i32)) -> i32 {
+	cubic_to_spiral_hex(axial_to_cubic(node_coords))
+}
+/// Convert a node in Spiral Hex coordinates to Offset coordinates based on `orientation`, see
+/// `spiral_hex_to_cubic()`. Panics if `index` is negative
+pub fn spiral_hex_to_offset(index: i32, orientation: &HexOrientation) -> (i32, i32) {
+	cubic_to_offset(spiral_hex_to_cubic(index), orientation)
+}
+/// Convert a node in Offset coordinates based on `orientation` to its Spiral Hex index, the
+/// inverse of `spiral_hex_to_offset()`
+pub fn offset_to_spiral_hex(node_coords: (i32, i32), orientation: &HexOrientation) -> i32 {
+	cubic_to_spiral_hex(offset_to_cubic(node_coords, orientation))
+}
+/// Iterate over every node within `radius` jumps of `centre` in a Cubic coordinate system in
+/// Spiral Hex order, `centre` first and then each ring outwards walked as `node_ring_cubic()`
+/// does, see `spiral_hex_to_cubic()`. Useful for finding the nearest node which matches a
+/// condition, the nodes of each ring come before any further away:
+///
+/// ```
+/// use hexagonal_pathfinding_astar::helpers::node_spiral_cubic;
+/// let free = node_spiral_cubic((2, -1, -1), 5).find(|n| n.0 > 4);
+/// assert_eq!(Some((5, -4, -1)), free);
+/// ```
+///
+/// Nothing is yielded for a negative `radius`
+pub fn node_spiral_cubic(
+	centre: (i32, i32, i32),
+	radius: i32,
+) -> impl Iterator<Item = (i32, i32, i32)> {
+	let count = if radius < 0 {
+		0
+	} else {
+		3 * radius * (radius + 1) + 1
+	};
+	(0..count).map(move |index| cube_add(centre, spiral_hex_to_cubic(index)))
+}
+/// Iterate over every node within `radius` jumps of `centre` in an Axial coordinate system in
+/// Spiral Hex order, see `node_spiral_cubic()`
+pub fn node_spiral_axial(centre: (i32, i32), radius: i32) -> impl Iterator<Item = (i32, i32)> {
+	node_spiral_cubic(axial_to_cubic(centre), radius).map(cubic_to_axial)
+}
 /// The distance between two nodes by using cubic coordinates
 pub fn node_distance(start: (i32, i32, i32), end: (i32, i32, i32)) -> i32 {
 	((start.0 - end.0).abs() + (start.1 - end.1).abs() + (start.2 - end.2).abs()) / 2
 }
+/// The distance between two nodes in an Axial coordinate system
+pub fn node_distance_axial(start: (i32, i32), end: (i32, i32)) -> i32 {
+	node_distance(axial_to_cubic(start), axial_to_cubic(end))
+}
+/// The distance between two nodes in an Offset coordinate system based on `orientation`, see
+/// `node_distance_offset_wrapping()` for grids which wrap around
+pub fn node_distance_offset(
+	start: (i32, i32),
+	end: (i32, i32),
+	orientation: &HexOrientation,
+) -> i32 {
+	node_distance(
+		offset_to_cubic(start, orientation),
+		offset_to_cubic(end, orientation),
+	)
+}
+/// The distance between two nodes given as Spiral Hex indices, see `spiral_hex_to_cubic()`.
+/// Panics if either index is negative
+pub fn node_distance_spiral_hex(start: i32, end: i32) -> i32 {
+	node_distance(spiral_hex_to_cubic(start), spiral_hex_to_cubic(end))
+}
+/// The distance from `from` to each of `to` by using cubic coordinates, in the same order as
+/// `to`. Measuring from one node to thousands at once, e.g to score the influence of a unit, the
+/// loop has no branches so the compiler is free to vectorise it. Nodes held in another
+/// coordinate system can be converted first with `axial_to_cubic_all()` or
+/// `offset_to_cubic_all()`
+pub fn node_distances(from: (i32, i32, i32), to: &[(i32, i32, i32)]) -> Vec<i32> {
+	to.iter()
+		.map(|n| ((n.0 - from.0).abs() + (n.1 - from.1).abs() + (n.2 - from.2).abs()) / 2)
+		.collect()
+}
+/// Convert each of `nodes` from Axial to Cubic coordinates, in the same order
+pub fn axial_to_cubic_all(nodes: &[(i32, i32)]) -> Vec<(i32, i32, i32)> {
+	nodes.iter().map(|n| axial_to_cubic(*n)).collect()
+}
+/// Convert each of `nodes` from Offset coordinates in `orientation` to Cubic coordinates, in
+/// the same order
+pub fn offset_to_cubic_all(
+	nodes: &[(i32, i32)],
+	orientation: &HexOrientation,
+) -> Vec<(i32, i32, i32)> {
+	nodes
+		.iter()
+		.map(|n| offset_to_cubic(*n, orientation))
+		.collect()
+}
+
+/// Round fractional Cubic coordinates to the node containing them. Each coordinate is rounded
+/// and the one which moved furthest is recalculated from the other two so that `x + y + z = 0`.
+///
+/// Rounding each coordinate alone can give a node which does not exist, `(0.45, 0.35, -0.8)`
+/// rounding to `(0, 0, -1)`, or the wrong neighbour near the edge of a hexagon. The coordinate
+/// which moved furthest is the one least certain:
+/// ```txt
+///          _______
+///         /       \
+///        /    .    \   (0.45, 0.35, -0.8) lies in (1, 0, -1)
+///        \ (1,0,-1)/
+///         \_______/
+/// ```
+pub fn cube_round(fractional: (f64, f64, f64)) -> (i32, i32, i32) {
+	let mut x = fractional.0.round();
+	let mut y = fractional.1.round();
+	let mut z = fractional.2.round();
+	let x_diff = (x - fractional.0).abs();
+	let y_diff = (y - fractional.1).abs();
+	let z_diff = (z - fractional.2).abs();
+	if x_diff > y_diff && x_diff > z_diff {
+		x = -y - z;
+	} else if y_diff > z_diff {
+		y = -x - z;
+	} else {
+		z = -x - y;
+	}
+	(x as i32, y as i32, z as i32)
+}
+
+/// Rotate `node` around `centre` in a Cubic coordinate system by `rotations` sixths of a full
+/// turn. Each positive rotation moves the node one corner around the ring it sits on, taking
+/// `(1, -1, 0)` to `(0, -1, 1)` when rotating around the origin, negative rotations turn the
+/// other way. Six rotations return the node to where it started
+pub fn node_rotate_cubic(
+	node: (i32, i32, i32),
+	centre: (i32, i32, i32),
+	rotations: i32,
+) -> (i32, i32, i32) {
+	let mut relative = (node.0 - centre.0, node.1 - centre.1, node.2 - centre.2);
+	for _ in 0..rotations.rem_euclid(6) {
+		relative = (-relative.2, -relative.0, -relative.1);
+	}
+	(
+		centre.0 + relative.0,
+		centre.1 + relative.1,
+		centre.2 + relative.2,
+	)
+}
+/// Rotate `node` around `centre` in an Axial coordinate system by `rotations` sixths of a full
+/// turn, see `node_rotate_cubic()`
+pub fn node_rotate_axial(node: (i32, i32), centre: (i32, i32), rotations: i32) -> (i32, i32) {
+	cubic_to_axial(node_rotate_cubic(
+		axial_to_cubic(node),
+		axial_to_cubic(centre),
+		rotations,
+	))
+}
+/// Rotate `node` around `centre` in an Offset coordinate system based on `orientation` by
+/// `rotations` sixths of a full turn, see `node_rotate_cubic()`. The rotated node may lie beyond
+/// the edge of the grid
+pub fn node_rotate_offset(
+	node: (i32, i32),
+	centre: (i32, i32),
+	rotations: i32,
+	orientation: &HexOrientation,
+) -> (i32, i32) {
+	let rotated = node_rotate_cubic(
+		offset_to_cubic(node, orientation),
+		offset_to_cubic(centre, orientation),
+		rotations,
+	);
+	cubic_to_offset(rotated, orientation)
+}
+/// Mirror `node` across the `axis` running through `centre` in a Cubic coordinate system, see
+/// `CubicAxis`. The coordinate of the axis is kept and the other two are swapped, relative to
+/// `centre`, so with flat tops mirroring across `CubicAxis::X` swaps north and south:
+/// ```txt
+///          _______
+///         /       \
+///        /    A    \
+///        \         /
+///         \_______/
+///         /       \
+///  X --- /  centre \ --- X
+///        \         /
+///         \_______/
+///         /       \
+///        /    B    \
+///        \         /
+///         \_______/
+/// ```
+/// Mirroring twice across the same axis returns the node to where it started
+pub fn node_reflect_cubic(
+	node: (i32, i32, i32),
+	centre: (i32, i32, i32),
+	axis: CubicAxis,
+) -> (i32, i32, i32) {
+	let (x, y, z) = (node.0 - centre.0, node.1 - centre.1, node.2 - centre.2);
+	let mirrored = match axis {
+		CubicAxis::X => (x, z, y),
+		CubicAxis::Y => (z, y, x),
+		CubicAxis::Z => (y, x, z),
+	};
+	(
+		centre.0 + mirrored.0,
+		centre.1 + mirrored.1,
+		centre.2 + mirrored.2,
+	)
+}
+/// Mirror each of `nodes` across the `axis` running through `centre` in a Cubic coordinate
+/// system, see `node_reflect_cubic()`
+pub fn node_reflect_cubic_all(
+	nodes: &[(i32, i32, i32)],
+	centre: (i32, i32, i32),
+	axis: CubicAxis,
+) -> Vec<(i32, i32, i32)> {
+	nodes
+		.iter()
+		.map(|n| node_reflect_cubic(*n, centre, axis))
+		.collect()
+}
+/// Mirror `node` across the `axis` running through `centre` in an Axial coordinate system, see
+/// `node_reflect_cubic()`
+pub fn node_reflect_axial(node: (i32, i32), centre: (i32, i32), axis: CubicAxis) -> (i32, i32) {
+	cubic_to_axial(node_reflect_cubic(
+		axial_to_cubic(node),
+		axial_to_cubic(centre),
+		axis,
+	))
+}
+/// Mirror `node` across the `axis` running through `centre` in an Offset coordinate system based
+/// on `orientation`, see `node_reflect_cubic()`. The mirrored node may lie beyond the edge of the
+/// grid
+pub fn node_reflect_offset(
+	node: (i32, i32),
+	centre: (i32, i32),
+	axis: CubicAxis,
+	orientation: &HexOrientation,
+) -> (i32, i32) {
+	let mirrored = node_reflect_cubic(
+		offset_to_cubic(node, orientation),
+		offset_to_cubic(centre, orientation),
+		axis,
+	);
+	cubic_to_offset(mirrored, orientation)
+}
+
+/// Finds the nodes lying on a straight line between `start` and `end` in a Cubic coordinate
+/// system, including both ends. Consecutive nodes of the line are always neighbours.
+///
+/// The line is sampled at evenly spaced points which are rounded to the node containing them,
+/// where a point falls exactly on the boundary of two nodes it is nudged consistently to one
+/// side:
+/// ```txt
+///              _______
+///             /       \
+///     _______/    E    \
+///    /       \    |    /
+///   /         \___|___/
+///   \         /   |   \
+///    \_______/    o    \
+///    /       \   /     /
+///   /    S    \_/_____/
+///   \         /
+///    \_______/
+/// ```
+pub fn node_line_cubic(start: (i32, i32, i32), end: (i32, i32, i32)) -> Vec<(i32, i32, i32)> {
+	node_line_nudged(start, end, 1.0)
+}
+/// The line of `node_line_cubic()` with the start nudged `side` millionths of a node to one side
+/// so points never sit exactly on a boundary, a negative `side` nudging to the other side
+fn node_line_nudged(
+	start: (i32, i32, i32),
+	end: (i32, i32, i32),
+	side: f64,
+) -> Vec<(i32, i32, i32)> {
+	let distance = node_distance(start, end);
+	if distance == 0 {
+		return vec![start];
+	}
+	// keep x + y + z = 0
+	let nudge = side * 1e-6;
+	let a = (
+		start.0 as f64 + nudge,
+		start.1 as f64 + nudge,
+		start.2 as f64 - 2.0 * nudge,
+	);
+	let b = (end.0 as f64, end.1 as f64, end.2 as f64);
+	let mut line = Vec::with_capacity(distance as usize + 1);
+	for i in 0..=distance {
+		let t = i as f64 / distance as f64;
+		line.push(cube_round((
+			a.0 + (b.0 - a.0) * t,
+			a.1 + (b.1 - a.1) * t,
+			a.2 + (b.2 - a.2) * t,
+		)));
+	}
+	line
+}
+/// Whether `end` can be seen from `start` in a Cubic coordinate system, `is_blocked` telling
+/// which nodes block sight. Only the nodes lying between the two on the line of
+/// `node_line_cubic()` are tested, so a wall can see and be seen.
+///
+/// Where the line runs exactly along the edges between nodes either side of it may be followed,
+/// sight is blocked only if both sides are:
+/// ```txt
+///                    _______
+///                   /       \
+///           _______/    E    \
+///          /       \         /
+///         /    #    \_______/
+///         \         /       \
+///          \_______/         \
+///          /       \         /
+///         /    S    \_______/
+///         \         /
+///          \_______/
+/// ```
+/// The line from `S` to `E` runs along the edge between `#` and the node to its right, with `#`
+/// blocked `E` can still be seen from `S` past the other side.
+///
+/// A set of blocked nodes is tested with `|node| blocked.contains(node)`
+pub fn has_line_of_sight_cubic<F: Fn(&(i32, i32, i32)) -> bool>(
+	start: (i32, i32, i32),
+	end: (i32, i32, i32),
+	is_blocked: F,
+) -> bool {
+	let clear = |line: Vec<(i32, i32, i32)>| {
+		let between = line.len().saturating_sub(1);
+		line.iter().take(between).skip(1).all(|n| !is_blocked(n))
+	};
+	clear(node_line_nudged(start, end, 1.0)) || clear(node_line_nudged(start, end, -1.0))
+}
+/// The nodes visible from `origin` within `radius` rings in a Cubic coordinate system,
+/// `is_blocked` telling which nodes block sight, found by shadowcasting ring by ring.
+///
+/// Going around a ring of `node_ring_cubic()` covers a full turn so each node of a ring spans a
+/// slice of the turn, the `i`th node from a corner of `6 * radius` centred `i / (6 * radius)`
+/// of the way round. A node is hidden if its centre lies in the shadow of a blocking node of an inner ring,
+/// the slice that blocking node spans:
+/// ```txt
+///                     _______
+///                    /       \
+///            _______/ hidden  \_______
+///           /       \         /       \
+///          /         \_______/         \
+///          \         /       \         /
+///           \_______/    #    \_______/
+///           /       \         /       \
+///          /         \_______/         \
+///          \         /       \         /
+///           \_______/ origin  \_______/
+///                   \         /
+///                    \_______/
+/// ```
+/// Blocking nodes can themselves be seen, as can `origin`. A set of blocked nodes is tested
+/// with `|node| blocked.contains(node)`. The visible nodes are returned unordered, sort them
+/// where a stable order is needed
+pub fn field_of_view_cubic<F: Fn(&(i32, i32, i32)) -> bool>(
+	origin: (i32, i32, i32),
+	radius: i32,
+	is_blocked: F,
+) -> HashSet<(i32, i32, i32)> {
+	let mut visible = HashSet::new();
+	visible.insert(origin);
+	// the slices of a turn in shadow, sorted and merged wherever they meet so a node centred on
+	// the seam between two shadows is hidden. Each slice is held a turn either side as well so
+	// slices spanning the start of the rings merge with those at the end
+	let mut shadows: Vec<(f64, f64)> = Vec::new();
+	let in_shadow = |shadows: &[(f64, f64)], turn: f64| {
+		shadows
+			.iter()
+			.any(|(start, end)| *start < turn && turn < *end)
+	};
+	for ring in 1..=radius {
+		let nodes = node_ring_cubic(origin, ring);
+		let count = nodes.len() as f64;
+		let mut cast = Vec::new();
+		for (i, node) in nodes.iter().enumerate() {
+			// each ring begins `ring - 1` nodes before the same corner
+			let step = (i as i32 - ring + 1) as f64;
+			if in_shadow(&shadows, step / count) {
+				continue;
+			}
+			visible.insert(*node);
+			if is_blocked(node) {
+				cast.push(((step - 0.5) / count, (step + 0.5) / count));
+			}
+		}
+		for (start, end) in cast {
+			for turn in [-1.0, 0.0, 1.0] {
+				shadows.push((start + turn, end + turn));
+			}
+		}
+		shadows.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+		let mut merged: Vec<(f64, f64)> = Vec::with_capacity(shadows.len());
+		for (start, end) in shadows {
+			match merged.last_mut() {
+				// allow for rounding in the slices shifted a turn
+				Some(last) if start <= last.1 + 1e-9 => last.1 = last.1.max(end),
+				_ => merged.push((start, end)),
+			}
+		}
+		shadows = merged;
+	}
+	visible
+}
+
+/// The centre of the node `node_coords` of an Axial grid drawn in `orientation`, measured from
+/// the centre of `(0, 0)` drawn at `origin`. `hex_size` is the distance from the centre of a
+/// hexagon to any of its corners. The y axis points north, the same way as `r` in the diagrams
+/// above, so for screens whose y axis points down negate the y of the result.
+///
+/// Flat-top hexagons are `2 * hex_size` wide and `sqrt(3) * hex_size` tall:
+/// ```txt
+///          _______
+///         /       \
+///        /  (0,1)  \
+///        \    .    /       - sqrt(3) * size
+///         \_______/
+///         /       \
+///        /  (0,0)  \
+///        \    .    /       - 0
+///         \_______/
+///              ._______
+///              size
+/// ```
+pub fn axial_to_pixel(
+	node_coords: (i32, i32),
+	orientation: &AxialOrientation,
+	hex_size: f32,
+	origin: (f32, f32),
+) -> (f32, f32) {
+	let q = node_coords.0 as f32;
+	let r = node_coords.1 as f32;
+	let sqrt_3 = 3.0_f32.sqrt();
+	let (x, y) = match orientation {
+		AxialOrientation::FlatTop => (1.5 * q, sqrt_3 * (r + q / 2.0)),
+		AxialOrientation::PointyTop => (sqrt_3 * (q + r / 2.0), 1.5 * r),
+	};
+	(origin.0 + x * hex_size, origin.1 + y * hex_size)
+}
+/// The centre of the node `node_coords` of a Cubic grid drawn in `orientation`, see
+/// `axial_to_pixel()`
+pub fn cubic_to_pixel(
+	node_coords: (i32, i32, i32),
+	orientation: &AxialOrientation,
+	hex_size: f32,
+	origin: (f32, f32),
+) -> (f32, f32) {
+	axial_to_pixel(cubic_to_axial(node_coords), orientation, hex_size, origin)
+}
+/// The centre of the node `node_coords` of an Offset grid in `orientation`, measured from the
+/// centre of `(0, 0)` drawn at `origin`, see `axial_to_pixel()`. Columns run along the x axis and
+/// rows up the y axis
+pub fn offset_to_pixel(
+	node_coords: (i32, i32),
+	orientation: &HexOrientation,
+	hex_size: f32,
+	origin: (f32, f32),
+) -> (f32, f32) {
+	cubic_to_pixel(
+		offset_to_cubic(node_coords, orientation),
+		&orientation.axial_orientation(),
+		hex_size,
+		origin,
+	)
+}
+/// The node of an Axial grid drawn in `orientation` whose hexagon contains `point`, the inverse
+/// of `axial_to_pixel()` with the same `hex_size` and `origin`. Points on the edge between two
+/// hexagons go to either, useful for picking the node under the mouse
+pub fn pixel_to_axial(
+	point: (f32, f32),
+	orientation: &AxialOrientation,
+	hex_size: f32,
+	origin: (f32, f32),
+) -> (i32, i32) {
+	cubic_to_axial(pixel_to_cubic(point, orientation, hex_size, origin))
+}
+/// The node of a Cubic grid drawn in `orientation` whose hexagon contains `point`, see
+/// `pixel_to_axial()`
+pub fn pixel_to_cubic(
+	point: (f32, f32),
+	orientation: &AxialOrientation,
+	hex_size: f32,
+	origin: (f32, f32),
+) -> (i32, i32, i32) {
+	let x = (point.0 - origin.0) as f64 / hex_size as f64;
+	let y = (point.1 - origin.1) as f64 / hex_size as f64;
+	let sqrt_3 = 3.0_f64.sqrt();
+	let (q, r) = match orientation {
+		AxialOrientation::FlatTop => {
+			let q = x / 1.5;
+			(q, y / sqrt_3 - q / 2.0)
+		}
+		AxialOrientation::PointyTop => {
+			let r = y / 1.5;
+			(x / sqrt_3 - r / 2.0, r)
+		}
+	};
+	cube_round((q, -q - r, r))
+}
+/// The node of an Offset grid in `orientation` whose hexagon contains `point`, see
+/// `pixel_to_axial()`
+pub fn pixel_to_offset(
+	point: (f32, f32),
+	orientation: &HexOrientation,
+	hex_size: f32,
+	origin: (f32, f32),
+) -> (i32, i32) {
+	let cube = pixel_to_cubic(point, &orientation.axial_orientation(), hex_size, origin);
+	cubic_to_offset(cube, orientation)
+}
 
 mod tests {
 	#[cfg(test)]
@@ -1463,4 +2318,723 @@ mod tests {
 		let actual: (i32, i32, i32) = (0, -1, 1);
 		assert_eq!(actual, result);
 	}
+	#[test]
+	/// A line runs between two nodes through neighbouring nodes
+	fn line_cubic() {
+		let result = node_line_cubic((0, 0, 0), (3, -1, -2));
+		assert_eq!(4, result.len());
+		assert_eq!(Some(&(0, 0, 0)), result.first());
+		assert_eq!(Some(&(3, -1, -2)), result.last());
+		for pair in result.windows(2) {
+			assert_eq!(1, node_distance(pair[0], pair[1]));
+		}
+		let straight = node_line_cubic((0, 0, 0), (0, -3, 3));
+		assert_eq!(
+			vec![(0, 0, 0), (0, -1, 1), (0, -2, 2), (0, -3, 3)],
+			straight
+		);
+		assert_eq!(vec![(2, -1, -1)], node_line_cubic((2, -1, -1), (2, -1, -1)));
+	}
+	#[test]
+	/// Nodes on the right edge of a wrapping grid neighbour those on the left edge
+	fn wrapping_neighbours() {
+		let result = node_neighbours_offset_wrapping(
+			(3, 0),
+			&HexOrientation::FlatTopOddUp,
+			-1,
+			4,
+			-1,
+			4,
+			true,
+			false,
+		);
+		let actual = [(3, 1), (0, 1), (0, 0), (2, 0), (2, 1)];
+		assert_eq!(actual.len(), result.len());
+		for n in actual.iter() {
+			assert!(result.contains(n));
+		}
+		let distance = node_distance_offset_wrapping(
+			(3, 0),
+			(0, 0),
+			&HexOrientation::FlatTopOddUp,
+			-1,
+			4,
+			-1,
+			4,
+			true,
+			false,
+		);
+		assert_eq!(1, distance);
+	}
+	#[test]
+	/// Each even shifted orientation finds the same neighbours and cubic coordinates as the odd
+	/// orientation describing the same arrangement
+	fn even_orientations_match_odd() {
+		let pairs = [
+			(
+				HexOrientation::FlatTopEvenUp,
+				HexOrientation::FlatTopOddDown,
+			),
+			(
+				HexOrientation::FlatTopEvenDown,
+				HexOrientation::FlatTopOddUp,
+			),
+			(
+				HexOrientation::PointyTopEvenRight,
+				HexOrientation::PointyTopOddLeft,
+			),
+			(
+				HexOrientation::PointyTopEvenLeft,
+				HexOrientation::PointyTopOddRight,
+			),
+		];
+		for (even, odd) in pairs.iter() {
+			for column in 0..4 {
+				for row in 0..4 {
+					let node = (column, row);
+					assert_eq!(
+						node_neighbours_offset(node, odd, -1, 4, -1, 4),
+						node_neighbours_offset(node, even, -1, 4, -1, 4)
+					);
+					let cube = offset_to_cubic(node, even);
+					assert_eq!(offset_to_cubic(node, odd), cube);
+					assert_eq!(node, cubic_to_offset(cube, even));
+					assert_eq!(node, axial_to_offset(cubic_to_axial(cube), even));
+				}
+			}
+		}
+		// even-q, the odd columns sit lower than the even columns
+		let neighbours =
+			node_neighbours_offset((1, 1), &HexOrientation::FlatTopEvenUp, -1, 4, -1, 4);
+		assert!(neighbours.contains(&(0, 1)));
+		assert!(neighbours.contains(&(0, 0)));
+		assert!(!neighbours.contains(&(0, 2)));
+	}
+	#[test]
+	/// Rotating a node keeps it on its ring around the centre and six rotations are a full turn
+	fn rotate_around_centre() {
+		let centre = (1, -1, 0);
+		let node = (3, -2, -1);
+		assert_eq!((0, -1, 1), node_rotate_cubic((1, -1, 0), (0, 0, 0), 1));
+		assert_eq!((1, 0, -1), node_rotate_cubic((1, -1, 0), (0, 0, 0), -1));
+		for rotations in 0..6 {
+			let rotated = node_rotate_cubic(node, centre, rotations);
+			assert_eq!(0, rotated.0 + rotated.1 + rotated.2);
+			assert_eq!(2, node_distance(centre, rotated));
+		}
+		assert_eq!(node, node_rotate_cubic(node, centre, 6));
+		assert_eq!(
+			node_rotate_cubic(node, centre, -2),
+			node_rotate_cubic(node, centre, 4)
+		);
+	}
+	#[test]
+	/// Pointy topped neighbours start from the east, flat topped from the north, and both find
+	/// the same nodes
+	fn axial_oriented_neighbours() {
+		let flat = node_neighbours_axial_oriented((0, 0), 2, &AxialOrientation::FlatTop);
+		let pointy = node_neighbours_axial_oriented((0, 0), 2, &AxialOrientation::PointyTop);
+		assert_eq!(node_neighbours_axial((0, 0), 2), flat);
+		assert_eq!((1, 0), pointy[0]);
+		assert_eq!((0, 1), pointy[5]);
+		for n in flat.iter() {
+			assert!(pointy.contains(n));
+		}
+		let edge = node_neighbours_axial_oriented((2, 0), 2, &AxialOrientation::PointyTop);
+		assert_eq!(vec![(2, -1), (1, 0), (1, 1)], edge);
+	}
+	#[test]
+	/// Spiral Hex indices number the origin then each ring in turn and convert back again
+	/// ```txt
+	///              _______
+	///             /       \
+	///     _______/    2    \_______
+	///    /       \         /       \
+	///   /    1    \_______/    3    \
+	///   \         /       \         /
+	///    \_______/    0    \_______/
+	///    /       \         /       \
+	///   /    6    \_______/    4    \
+	///   \         /       \         /
+	///    \_______/    5    \_______/
+	///            \         /
+	///             \_______/
+	/// ```
+	fn spiral_hex_round_trip() {
+		assert_eq!((0, 0, 0), spiral_hex_to_cubic(0));
+		let mut index = 1;
+		for radius in 1..6 {
+			for node in node_ring_cubic((0, 0, 0), radius) {
+				assert_eq!(node, spiral_hex_to_cubic(index));
+				assert_eq!(index, cubic_to_spiral_hex(node));
+				index += 1;
+			}
+		}
+		assert_eq!(91, index);
+		let far = spiral_hex_to_cubic(1_000_000_000);
+		assert_eq!(1_000_000_000, cubic_to_spiral_hex(far));
+	}
+	#[test]
+	/// Distances measured in bulk match those measured one at a time, whichever coordinate
+	/// system the nodes were converted from
+	fn bulk_distances() {
+		let from = (2, -5, 3);
+		let axial: Vec<(i32, i32)> = (-6..=6)
+			.flat_map(|q| (-6..=6).map(move |r| (q, r)))
+			.collect();
+		let cubes = axial_to_cubic_all(&axial);
+		let distances = node_distances(from, &cubes);
+		assert_eq!(axial.len(), distances.len());
+		for (i, cube) in cubes.iter().enumerate() {
+			assert_eq!(axial_to_cubic(axial[i]), *cube);
+			assert_eq!(node_distance(from, *cube), distances[i]);
+		}
+		let orientation = HexOrientation::FlatTopOddDown;
+		let offset = [(0, 0), (3, 4), (7, 1)];
+		let converted = offset_to_cubic_all(&offset, &orientation);
+		for (node, cube) in offset.iter().zip(converted.iter()) {
+			assert_eq!(offset_to_cubic(*node, &orientation), *cube);
+		}
+		assert!(node_distances(from, &[]).is_empty());
+	}
+	#[test]
+	/// Indices in the millions convert directly and back, each following index being a neighbour
+	/// on the same ring until the ring ends and the next begins one ring further out
+	fn spiral_hex_large_indices() {
+		// the ring of radius 578 begins at index 3 * 578 * 577 + 1
+		let first = 1_000_519;
+		let last_of_inner = spiral_hex_to_cubic(first - 1);
+		assert_eq!(577, node_distance(last_of_inner, (0, 0, 0)));
+		let mut previous = spiral_hex_to_cubic(first);
+		assert_eq!((-578, 577, 1), previous);
+		for index in first + 1..first + 6 * 578 {
+			let node = spiral_hex_to_cubic(index);
+			assert_eq!(578, node_distance(node, (0, 0, 0)));
+			assert_eq!(1, node_distance(previous, node));
+			assert_eq!(index, cubic_to_spiral_hex(node));
+			previous = node;
+		}
+		// the last node of the ring sits beside its first
+		assert_eq!(1, node_distance(previous, spiral_hex_to_cubic(first)));
+	}
+	#[test]
+	/// Neighbouring centres are `sqrt(3)` sizes apart in every orientation, and Offset nodes sit
+	/// in upright columns or rows on the x and y axes
+	/// ```txt
+	///          _______
+	///         /       \
+	///        /  (0,1)  \_______
+	///        \         /       \
+	///         \_______/  (1,0)  \
+	///         /       \         /
+	///        /  (0,0)  \_______/
+	///        \         /
+	///         \_______/
+	/// ```
+	fn pixel_centres() {
+		let origin = (10.0, -4.0);
+		let flat = axial_to_pixel((0, 1), &AxialOrientation::FlatTop, 2.0, origin);
+		assert_eq!((10.0, -4.0 + 2.0 * 3.0_f32.sqrt()), flat);
+		let north_east = axial_to_pixel((1, 0), &AxialOrientation::FlatTop, 2.0, origin);
+		assert_eq!((13.0, -4.0 + 3.0_f32.sqrt()), north_east);
+		let east = axial_to_pixel((1, 0), &AxialOrientation::PointyTop, 2.0, origin);
+		assert_eq!((10.0 + 2.0 * 3.0_f32.sqrt(), -4.0), east);
+		for orientation in [AxialOrientation::FlatTop, AxialOrientation::PointyTop] {
+			let centre = cubic_to_pixel((0, 0, 0), &orientation, 2.0, origin);
+			assert_eq!(origin, centre);
+			for n in node_neighbours_cubic((0, 0, 0), 1) {
+				let p = cubic_to_pixel(n, &orientation, 2.0, origin);
+				let gap = ((p.0 - centre.0).powi(2) + (p.1 - centre.1).powi(2)).sqrt();
+				assert!((gap - 2.0 * 3.0_f32.sqrt()).abs() < 1e-5);
+			}
+		}
+		let orientations = [
+			HexOrientation::FlatTopOddUp,
+			HexOrientation::FlatTopOddDown,
+			HexOrientation::PointyTopOddRight,
+			HexOrientation::PointyTopOddLeft,
+			HexOrientation::FlatTopEvenUp,
+			HexOrientation::FlatTopEvenDown,
+			HexOrientation::PointyTopEvenRight,
+			HexOrientation::PointyTopEvenLeft,
+		];
+		for orientation in orientations.iter() {
+			for column in 0..4 {
+				for row in 0..4 {
+					let p = offset_to_pixel((column, row), orientation, 1.0, (0.0, 0.0));
+					let (column, row) = (column as f32, row as f32);
+					match orientation.axial_orientation() {
+						// columns stand upright, odd or even ones shifted half a hexagon
+						AxialOrientation::FlatTop => {
+							assert!((p.0 / 1.5 - column).abs() < 1e-5);
+							assert!((p.1 / 3.0_f32.sqrt() - row).abs() <= 0.5 + 1e-5);
+						}
+						// rows lie flat, odd or even ones shifted half a hexagon
+						AxialOrientation::PointyTop => {
+							assert!((p.1 / 1.5 - row).abs() < 1e-5);
+							assert!((p.0 / 3.0_f32.sqrt() - column).abs() <= 0.5 + 1e-5);
+						}
+					}
+				}
+			}
+		}
+	}
+	#[test]
+	/// Fractional coordinates round to the node containing them, never to a node which does
+	/// not exist
+	fn cube_rounding() {
+		assert_eq!((1, 0, -1), cube_round((0.45, 0.35, -0.8)));
+		assert_eq!((0, 0, 0), cube_round((0.1, 0.2, -0.3)));
+		assert_eq!((-2, 3, -1), cube_round((-2.2, 3.1, -0.9)));
+		for i in 0..50 {
+			let x = i as f64 * 0.37 - 9.0;
+			let z = i as f64 * -0.23 + 4.0;
+			let node = cube_round((x, -x - z, z));
+			assert_eq!(0, node.0 + node.1 + node.2);
+		}
+	}
+	#[test]
+	/// Every point of a hexagon, its centre and points just inside each of its corners, picks
+	/// the node of that hexagon in every coordinate system and orientation
+	fn pixel_picking() {
+		let (size, origin) = (3.0, (-7.0, 12.0));
+		let nodes: Vec<(i32, i32, i32)> = (-3..=3)
+			.flat_map(|x| (-3..=3).map(move |z| (x, -x - z, z)))
+			.collect();
+		for orientation in [AxialOrientation::FlatTop, AxialOrientation::PointyTop] {
+			// the first corner lies along the x axis for flat tops and the y axis for pointy tops
+			let turn = match orientation {
+				AxialOrientation::FlatTop => 0.0,
+				AxialOrientation::PointyTop => 30.0_f32.to_radians(),
+			};
+			for node in nodes.iter() {
+				let centre = cubic_to_pixel(*node, &orientation, size, origin);
+				assert_eq!(*node, pixel_to_cubic(centre, &orientation, size, origin));
+				let axial = cubic_to_axial(*node);
+				assert_eq!(axial, pixel_to_axial(centre, &orientation, size, origin));
+				for corner in 0..6 {
+					let angle = turn + (corner as f32 * 60.0).to_radians();
+					let point = (
+						centre.0 + angle.cos() * size * 0.95,
+						centre.1 + angle.sin() * size * 0.95,
+					);
+					assert_eq!(*node, pixel_to_cubic(point, &orientation, size, origin));
+				}
+			}
+		}
+		let orientation = HexOrientation::PointyTopEvenLeft;
+		for column in 0..5 {
+			for row in 0..5 {
+				let centre = offset_to_pixel((column, row), &orientation, size, origin);
+				let picked = pixel_to_offset(centre, &orientation, size, origin);
+				assert_eq!((column, row), picked);
+			}
+		}
+	}
+	#[test]
+	/// Sight is blocked by nodes between the ends but not by the ends themselves, and a line
+	/// along the edges between nodes is only blocked when both sides are
+	/// ```txt
+	///                    _______
+	///                   /       \
+	///           _______/    E    \
+	///          /       \         /
+	///         /    A    \_______/
+	///         \         /       \
+	///          \_______/    B    \
+	///          /       \         /
+	///         /    S    \_______/
+	///         \         /
+	///          \_______/
+	/// ```
+	fn line_of_sight() {
+		let start = (0, 0, 0);
+		let blocked: HashSet<(i32, i32, i32)> = [(0, -1, 1)].iter().copied().collect();
+		assert!(!has_line_of_sight_cubic(start, (0, -3, 3), |n| blocked.contains(n)));
+		assert!(has_line_of_sight_cubic(start, (0, -1, 1), |n| blocked.contains(n)));
+		assert!(has_line_of_sight_cubic(start, start, |n| blocked.contains(n)));
+		assert!(has_line_of_sight_cubic(start, (3, -3, 0), |n| blocked.contains(n)));
+		// E lies beyond the edge between A and B
+		let (end, a, b) = ((1, -2, 1), (0, -1, 1), (1, -1, 0));
+		let one_side: HashSet<(i32, i32, i32)> = [a].iter().copied().collect();
+		assert!(has_line_of_sight_cubic(start, end, |n| one_side.contains(n)));
+		let both_sides: HashSet<(i32, i32, i32)> = [a, b].iter().copied().collect();
+		assert!(!has_line_of_sight_cubic(start, end, |n| both_sides.contains(n)));
+	}
+	#[test]
+	/// Every node in range is visible on open ground, and a wall hides the nodes behind it but
+	/// not itself or the nodes beside it
+	fn field_of_view() {
+		let origin = (2, -1, -1);
+		let open = field_of_view_cubic(origin, 4, |_| false);
+		assert_eq!(3 * 4 * 5 + 1, open.len());
+		for node in open.iter() {
+			assert!(node_distance(origin, *node) <= 4);
+		}
+		// a wall north of the origin
+		let wall = (2, -2, 0);
+		let blocked: HashSet<(i32, i32, i32)> = [wall].iter().copied().collect();
+		let seen = field_of_view_cubic(origin, 4, |n| blocked.contains(n));
+		assert!(seen.contains(&origin));
+		assert!(seen.contains(&wall));
+		for behind in [(2, -3, 1), (2, -4, 2), (2, -5, 3)] {
+			assert!(!seen.contains(&behind));
+		}
+		for beside in [(3, -2, -1), (1, -1, 0), (3, -3, 0), (1, -2, 1)] {
+			assert!(seen.contains(&beside));
+		}
+		for node in seen.iter() {
+			if *node != wall {
+				assert!(has_line_of_sight_cubic(origin, *node, |n| blocked.contains(n)));
+			}
+		}
+		assert_eq!(1, field_of_view_cubic(origin, 0, |_| true).len());
+	}
+	#[test]
+	/// Two neighbouring walls hide the nodes behind the seam between them, the same nodes line
+	/// of sight finds hidden
+	fn field_of_view_seam() {
+		let origin = (0, 0, 0);
+		let blocked: HashSet<(i32, i32, i32)> = [(0, -1, 1), (1, -1, 0)].iter().copied().collect();
+		let seen = field_of_view_cubic(origin, 4, |n| blocked.contains(n));
+		for behind in [(1, -2, 1), (2, -4, 2)] {
+			assert!(!has_line_of_sight_cubic(origin, behind, |n| blocked.contains(n)));
+			assert!(!seen.contains(&behind));
+		}
+		for node in seen.iter() {
+			if !blocked.contains(node) {
+				assert!(has_line_of_sight_cubic(origin, *node, |n| blocked.contains(n)));
+			}
+		}
+	}
+	#[test]
+	/// Walling in the origin leaves only the origin and the walls around it visible
+	fn field_of_view_enclosed() {
+		let origin = (2, -1, -1);
+		let ring: HashSet<(i32, i32, i32)> = node_ring_cubic(origin, 1).into_iter().collect();
+		let seen = field_of_view_cubic(origin, 4, |n| ring.contains(n));
+		assert_eq!(7, seen.len());
+		assert!(seen.contains(&origin));
+		assert!(ring.iter().all(|n| seen.contains(n)));
+	}
+	#[test]
+	/// A filled hexagon holds every node within range and no others, in each coordinate system
+	fn within_range() {
+		let centre = (3, -5, 2);
+		for range in 0..5 {
+			let nodes = nodes_within_range_cubic(centre, range);
+			assert_eq!((3 * range * (range + 1) + 1) as usize, nodes.len());
+			let unique: HashSet<(i32, i32, i32)> = nodes.iter().copied().collect();
+			assert_eq!(nodes.len(), unique.len());
+			for node in nodes.iter() {
+				assert_eq!(0, node.0 + node.1 + node.2);
+				assert!(node_distance(centre, *node) <= range);
+			}
+			let axial = nodes_within_range_axial((3, 2), range);
+			assert_eq!(axial_to_cubic_all(&axial), nodes);
+			let orientation = HexOrientation::PointyTopOddLeft;
+			let offset = nodes_within_range_offset((1, 1), range, &orientation);
+			let expected = nodes_within_range_cubic(offset_to_cubic((1, 1), &orientation), range);
+			assert_eq!(offset_to_cubic_all(&offset, &orientation), expected);
+		}
+		assert!(nodes_within_range_cubic(centre, -1).is_empty());
+	}
+	#[test]
+	/// A spiral visits every node in range once, ring by ring outwards, and numbers them as
+	/// Spiral Hex does around the origin
+	fn spiral_order() {
+		let centre = (-1, 4, -3);
+		let spiral: Vec<(i32, i32, i32)> = node_spiral_cubic(centre, 4).collect();
+		let mut expected = nodes_within_range_cubic(centre, 4);
+		let mut sorted = spiral.clone();
+		sorted.sort_unstable();
+		expected.sort_unstable();
+		assert_eq!(expected, sorted);
+		assert_eq!(centre, spiral[0]);
+		assert_eq!(node_ring_cubic(centre, 1), spiral[1..7].to_vec());
+		for pair in spiral.windows(2) {
+			assert!(node_distance(centre, pair[0]) <= node_distance(centre, pair[1]));
+		}
+		for (index, node) in node_spiral_cubic((0, 0, 0), 3).enumerate() {
+			assert_eq!(index as i32, cubic_to_spiral_hex(node));
+		}
+		let axial: Vec<(i32, i32)> = node_spiral_axial((-1, -3), 4).collect();
+		assert_eq!(axial_to_cubic_all(&axial), spiral);
+		assert_eq!(0, node_spiral_cubic(centre, -1).count());
+	}
+	#[test]
+	/// Rings in Axial and Offset coordinates are the Cubic ring converted, an Offset ring losing
+	/// the nodes beyond the edge of the grid
+	/// ```txt
+	///              _______
+	///             /       \
+	///     _______/  (1,1)  \_______
+	///    /       \         /       \
+	///   /  (0,1)  \_______/  (2,1)  \
+	///   \         /       \         /
+	///    \_______/  (1,0)  \_______/
+	///    /       \         /       \
+	///   /  (0,0)  \_______/  (2,0)  \
+	///   \         /       \         /
+	///    \_______/         \_______/
+	/// ```
+	fn rings_axial_and_offset() {
+		let axial = node_ring_axial((2, -1), 2);
+		assert_eq!(axial_to_cubic_all(&axial), node_ring_cubic((2, -1, -1), 2));
+		let orientation = HexOrientation::FlatTopOddUp;
+		let bounds = GridBounds::from_size(3, 2);
+		let ring = node_ring_offset((1, 0), 1, &orientation, &bounds);
+		let mut sorted = ring.clone();
+		sorted.sort_unstable();
+		assert_eq!(vec![(0, 0), (0, 1), (1, 1), (2, 0), (2, 1)], sorted);
+		let unbounded = GridBounds::new(-10, 10, -10, 10);
+		let full = node_ring_offset((1, 0), 2, &orientation, &unbounded);
+		let expected = node_ring_cubic(offset_to_cubic((1, 0), &orientation), 2);
+		assert_eq!(offset_to_cubic_all(&full, &orientation), expected);
+	}
+	#[test]
+	/// Axial and Offset nodes rotate as their Cubic equivalents, a ring of neighbours turning
+	/// one place per rotation
+	/// ```txt
+	///              _______
+	///             /       \
+	///     _______/  (1,2)  \_______
+	///    /       \         /       \
+	///   /  (0,2)  \_______/  (2,2)  \
+	///   \         /       \         /
+	///    \_______/  (1,1)  \_______/
+	///    /       \         /       \
+	///   /  (0,1)  \_______/  (2,1)  \
+	///   \         /       \         /
+	///    \_______/  (1,0)  \_______/
+	///            \         /
+	///             \_______/
+	/// ```
+	fn rotate_axial_and_offset() {
+		assert_eq!((0, 1), node_rotate_axial((1, 0), (0, 0), 1));
+		assert_eq!((3, -2), node_rotate_axial((3, -2), (3, -2), 5));
+		let orientation = HexOrientation::FlatTopOddUp;
+		// around (1, 1) the northern neighbour turns to the north-west then the south-west
+		assert_eq!((0, 2), node_rotate_offset((1, 2), (1, 1), 1, &orientation));
+		assert_eq!((0, 1), node_rotate_offset((1, 2), (1, 1), 2, &orientation));
+		assert_eq!((1, 2), node_rotate_offset((1, 2), (1, 1), -6, &orientation));
+		for node in node_ring_axial((2, -1), 2) {
+			let cube = node_rotate_cubic(axial_to_cubic(node), (2, -1, -1), 2);
+			assert_eq!(cubic_to_axial(cube), node_rotate_axial(node, (2, -1), 2));
+		}
+	}
+	#[test]
+	/// Mirroring keeps the coordinate of the axis, keeps the distance from the centre, and undoes
+	/// itself, in every coordinate system
+	fn reflect_across_axes() {
+		let centre = (1, -3, 2);
+		let node = (4, -5, 1);
+		for axis in [CubicAxis::X, CubicAxis::Y, CubicAxis::Z] {
+			let mirrored = node_reflect_cubic(node, centre, axis);
+			assert_eq!(0, mirrored.0 + mirrored.1 + mirrored.2);
+			assert_eq!(node_distance(centre, node), node_distance(centre, mirrored));
+			assert_eq!(node, node_reflect_cubic(mirrored, centre, axis));
+			assert_eq!(centre, node_reflect_cubic(centre, centre, axis));
+		}
+		// north and south swap across the east to west axis, as do north-east and south-east
+		assert_eq!(
+			(0, 1, -1),
+			node_reflect_cubic((0, -1, 1), (0, 0, 0), CubicAxis::X)
+		);
+		assert_eq!(
+			(1, 0, -1),
+			node_reflect_cubic((1, -1, 0), (0, 0, 0), CubicAxis::X)
+		);
+		assert_eq!((4, -4, 0), node_reflect_cubic(node, centre, CubicAxis::X));
+		assert_eq!((0, -5, 5), node_reflect_cubic(node, centre, CubicAxis::Y));
+		assert_eq!((-1, 0, 1), node_reflect_cubic(node, centre, CubicAxis::Z));
+		let shape = [(0, 0, 0), (1, -1, 0), (2, -1, -1)];
+		let mirrored = node_reflect_cubic_all(&shape, (0, 0, 0), CubicAxis::Z);
+		assert_eq!(vec![(0, 0, 0), (-1, 1, 0), (-1, 2, -1)], mirrored);
+		assert_eq!((1, -1), node_reflect_axial((-1, 1), (0, 0), CubicAxis::Y));
+		let orientation = HexOrientation::PointyTopOddRight;
+		let offset = node_reflect_offset((3, 2), (1, 1), CubicAxis::Z, &orientation);
+		let expected = node_reflect_cubic(
+			offset_to_cubic((3, 2), &orientation),
+			offset_to_cubic((1, 1), &orientation),
+			CubicAxis::Z,
+		);
+		assert_eq!(offset_to_cubic(offset, &orientation), expected);
+	}
+	#[test]
+	/// Stepping in a direction and back in the opposite direction returns to the start, the
+	/// directions of an orientation giving the neighbours in the oriented order
+	/// ```txt
+	///              _______
+	///             /       \
+	///     _______/  (1,2)  \_______
+	///    /       \         /       \
+	///   /  (0,2)  \_______/  (2,2)  \
+	///   \         /       \         /
+	///    \_______/  (1,1)  \_______/
+	///    /       \         /       \
+	///   /  (0,1)  \_______/  (2,1)  \
+	///   \         /       \         /
+	///    \_______/  (1,0)  \_______/
+	///            \         /
+	///             \_______/
+	/// ```
+	fn neighbour_in_direction() {
+		for orientation in [AxialOrientation::FlatTop, AxialOrientation::PointyTop] {
+			let directions = HexDirection::all(&orientation);
+			let neighbours: Vec<(i32, i32)> = directions
+				.iter()
+				.map(|d| node_neighbour_axial((2, -1), *d, &orientation).unwrap())
+				.collect();
+			assert_eq!(
+				node_neighbours_axial_oriented((2, -1), 10, &orientation),
+				neighbours
+			);
+			for direction in directions {
+				assert_eq!(direction, direction.opposite().opposite());
+				let cube = node_neighbour_cubic((1, 0, -1), direction, &orientation).unwrap();
+				assert_eq!(1, node_distance((1, 0, -1), cube));
+				let back = node_neighbour_cubic(cube, direction.opposite(), &orientation);
+				assert_eq!(Some((1, 0, -1)), back);
+			}
+		}
+		let flat = AxialOrientation::FlatTop;
+		assert_eq!(
+			None,
+			node_neighbour_axial((0, 0), HexDirection::East, &flat)
+		);
+		let pointy = AxialOrientation::PointyTop;
+		assert_eq!(
+			None,
+			node_neighbour_axial((0, 0), HexDirection::North, &pointy)
+		);
+		let orientation = HexOrientation::FlatTopOddUp;
+		let north_east = node_neighbour_offset((1, 1), HexDirection::NorthEast, &orientation);
+		assert_eq!(Some((2, 2)), north_east);
+		let south = node_neighbour_offset((1, 1), HexDirection::South, &orientation);
+		assert_eq!(Some((1, 0)), south);
+		assert_eq!(
+			None,
+			node_neighbour_offset((1, 1), HexDirection::West, &orientation)
+		);
+	}
+	#[test]
+	/// Spiral Hex indices convert directly to and from Axial and Offset coordinates, the first
+	/// ring running clockwise from the north-west of the origin
+	/// ```txt
+	///              _______
+	///             /       \
+	///     _______/  (1,2)  \_______
+	///    /       \    2    /       \
+	///   /  (0,2)  \_______/  (2,2)  \
+	///   \    1    /       \    3    /
+	///    \_______/  (1,1)  \_______/
+	///    /       \    0    /       \
+	///   /  (0,1)  \_______/  (2,1)  \
+	///   \    6    /       \    4    /
+	///    \_______/  (1,0)  \_______/
+	///            \    5    /
+	///             \_______/
+	/// ```
+	fn spiral_hex_conversions() {
+		assert_eq!((0, 0), spiral_hex_to_axial(0));
+		assert_eq!((-1, 1), spiral_hex_to_axial(1));
+		assert_eq!((0, 1), spiral_hex_to_axial(2));
+		assert_eq!(5, axial_to_spiral_hex((0, -1)));
+		let orientation = HexOrientation::FlatTopOddUp;
+		let origin = offset_to_cubic((1, 1), &orientation);
+		let expected = [(1, 1), (0, 2), (1, 2), (2, 2), (2, 1), (1, 0), (0, 1)];
+		for (index, node) in expected.iter().enumerate() {
+			let cube = offset_to_cubic(*node, &orientation);
+			let relative = (cube.0 - origin.0, cube.1 - origin.1, cube.2 - origin.2);
+			assert_eq!(index as i32, cubic_to_spiral_hex(relative));
+		}
+		for index in 0..200 {
+			let axial = spiral_hex_to_axial(index);
+			assert_eq!(index, axial_to_spiral_hex(axial));
+			assert_eq!(axial_to_cubic(axial), spiral_hex_to_cubic(index));
+			for orientation in [
+				HexOrientation::FlatTopOddUp,
+				HexOrientation::FlatTopOddDown,
+				HexOrientation::PointyTopOddRight,
+				HexOrientation::PointyTopOddLeft,
+			] {
+				let offset = spiral_hex_to_offset(index, &orientation);
+				assert_eq!(index, offset_to_spiral_hex(offset, &orientation));
+				assert_eq!(axial_to_offset(axial, &orientation), offset);
+			}
+		}
+	}
+	#[test]
+	/// Distances measured in each coordinate system agree with the Cubic distance between the
+	/// same nodes
+	/// ```txt
+	///              _______
+	///             /       \
+	///     _______/  (1,2)  \_______
+	///    /       \         /       \
+	///   /  (0,2)  \_______/  (2,2)  \
+	///   \         /       \         /
+	///    \_______/  (1,1)  \_______/
+	///    /       \         /       \
+	///   /  (0,1)  \_______/  (2,1)  \
+	///   \         /       \         /
+	///    \_______/  (1,0)  \_______/
+	///            \         /
+	///             \_______/
+	/// ```
+	fn distance_in_each_system() {
+		assert_eq!(3, node_distance_axial((0, 0), (2, 1)));
+		assert_eq!(5, node_distance_axial((-2, 3), (3, 0)));
+		let orientation = HexOrientation::FlatTopOddUp;
+		assert_eq!(1, node_distance_offset((0, 1), (1, 1), &orientation));
+		assert_eq!(2, node_distance_offset((0, 1), (2, 1), &orientation));
+		assert_eq!(2, node_distance_offset((1, 0), (1, 2), &orientation));
+		assert_eq!(2, node_distance_offset((0, 2), (2, 1), &orientation));
+		assert_eq!(0, node_distance_spiral_hex(4, 4));
+		assert_eq!(1, node_distance_spiral_hex(0, 6));
+		assert_eq!(2, node_distance_spiral_hex(1, 4));
+		for start in 0..40 {
+			for end in 0..40 {
+				let cubic = node_distance(spiral_hex_to_cubic(start), spiral_hex_to_cubic(end));
+				assert_eq!(cubic, node_distance_spiral_hex(start, end));
+				let axial = (spiral_hex_to_axial(start), spiral_hex_to_axial(end));
+				assert_eq!(cubic, node_distance_axial(axial.0, axial.1));
+				let offset = (
+					spiral_hex_to_offset(start, &orientation),
+					spiral_hex_to_offset(end, &orientation),
+				);
+				assert_eq!(
+					cubic,
+					node_distance_offset(offset.0, offset.1, &orientation)
+				);
+			}
+		}
+	}
+	#[test]
+	/// Cubic arithmetic walks between nodes, each direction being a single step and undone by the
+	/// direction three further round
+	fn cube_arithmetic() {
+		assert_eq!((3, -1, -2), cube_add((1, 0, -1), (2, -1, -1)));
+		assert_eq!((-1, 1, 0), cube_sub((1, 0, -1), (2, -1, -1)));
+		assert_eq!((0, -3, 3), cube_scale(CUBE_NORTH, 3));
+		assert_eq!(
+			(2, 0, -2),
+			cube_add((0, 0, 0), cube_scale(CUBE_SOUTH_EAST, 2))
+		);
+		for (i, direction) in CUBE_DIRECTIONS.iter().enumerate() {
+			assert_eq!(0, direction.0 + direction.1 + direction.2);
+			assert_eq!(1, node_distance(*direction, (0, 0, 0)));
+			let opposite = CUBE_DIRECTIONS[(i + 3) % 6];
+			assert_eq!((0, 0, 0), cube_add(*direction, opposite));
+			let node = cube_add((4, -1, -3), *direction);
+			assert_eq!(*direction, cube_sub(node, (4, -1, -3)));
+		}
+		let flat = AxialOrientation::FlatTop;
+		let north = node_neighbour_cubic((0, 0, 0), HexDirection::North, &flat);
+		assert_eq!(Some(CUBE_NORTH), north);
+		let north_west = node_neighbour_cubic((0, 0, 0), HexDirection::NorthWest, &flat);
+		assert_eq!(Some(CUBE_NORTH_WEST), north_west);
+		assert_eq!(CUBE_SOUTH_WEST, cube_scale(CUBE_NORTH_EAST, -1));
+	}
 }
@@ -0,0 +1,379 @@
+//! Hierarchical pathfinding for grids too large to search node by node, HPA*.
+//!
+//! On a map of a million hexagons an A* search across the map expands hundreds of thousands of
+//! nodes. HPA*, by Botea, Müller and Schaeffer, trades a little optimality for searching far
+//! fewer nodes. The grid is cut into clusters once, up front:
+//!
+//! ```txt
+//!   _______________ _______________
+//!  |               |               |
+//!  |               E - E           |
+//!  |   cluster A   |   cluster B   |
+//!  |               E - E           |
+//!  |_______________|_______________|
+//! ```
+//!
+//! Wherever a run of nodes can cross from one cluster into a neighbouring cluster the middle
+//! crossing becomes an entrance, `E`. Entrances are joined to the entrances of the neighbouring
+//! cluster by the crossing itself and to every other entrance of their own cluster by the
+//! cheapest route which stays inside the cluster. This forms a much smaller abstract graph.
+//!
+//! A query links the start and end to the entrances of their clusters, searches the abstract
+//! graph and then refines each hop of the abstract path into hexagons with a search confined to
+//! a single cluster. Paths are rarely more than a few percent dearer than the cheapest, the cost
+//! of passing through the chosen entrances, and any end node which can be reached is found.
+//!
+//! Clusters are parallelograms of `cluster_size` by `cluster_size` hexagons in Axial coordinates,
+//! so the same hierarchy works for every layout. The hierarchy describes the grid as it was when
+//! built, rebuild it after the grid changes, see `HexGrid::stamp`.
+
+use crate::grid::GridLayout;
+use crate::grid::HexGrid;
+use crate::node_store::NodeStore;
+use crate::path::Path;
+use crate::search::astar_path;
+use crate::search::dijkstra_costs;
+use crate::search::SearchSpace;
+use ::std::collections::HashMap;
+use ::std::collections::HashSet;
+use ::std::fmt::Debug;
+use ::std::hash::Hash;
+
+/// A cluster named by its position, the Axial coordinates of its nodes divided by its width
+type Cluster = (i32, i32);
+
+/// A step from a node of one cluster to a node of another and its cost
+type Crossing<C> = (C, C, f32);
+
+/// The cluster holding `node` when clusters are `cluster_size` hexagons across
+fn cluster_of<L: GridLayout>(layout: &L, node: L::Coord, cluster_size: i32) -> Cluster {
+	let cube = layout.node_to_cubic(node);
+	(
+		cube.0.div_euclid(cluster_size),
+		cube.2.div_euclid(cluster_size),
+	)
+}
+
+/// A grid confined to a single cluster, optionally with every step reversed
+struct InCluster<'a, L: GridLayout, S> {
+	/// The grid being searched
+	grid: &'a HexGrid<L, S>,
+	/// The cluster searches are confined to
+	cluster: Cluster,
+	/// Width of a cluster
+	cluster_size: i32,
+	/// Whether to follow steps backwards, finding costs to rather than from a node
+	reversed: bool,
+}
+
+impl<'a, L: GridLayout, S: NodeStore<L::Coord>> SearchSpace for InCluster<'a, L, S> {
+	type Node = L::Coord;
+	fn successors(&self, node: L::Coord) -> Vec<(L::Coord, f32)> {
+		let mut steps = if self.reversed {
+			self.grid.predecessors(node)
+		} else {
+			self.grid.successors(node)
+		};
+		steps
+			.retain(|(n, _)| cluster_of(self.grid.layout(), *n, self.cluster_size) == self.cluster);
+		steps
+	}
+	fn heuristic(&self, node: L::Coord, goal: L::Coord) -> f32 {
+		self.grid.heuristic(node, goal)
+	}
+}
+
+/// The abstract graph of a `Hierarchy` with the start and end of a query linked in
+struct Abstract<'a, L: GridLayout, S> {
+	/// The grid the hierarchy was built from
+	grid: &'a HexGrid<L, S>,
+	/// Hops between entrances
+	edges: &'a HashMap<L::Coord, Vec<(L::Coord, f32)>>,
+	/// Hops from the start, and into the end, of the query
+	linked: HashMap<L::Coord, Vec<(L::Coord, f32)>>,
+}
+
+impl<'a, L: GridLayout, S: NodeStore<L::Coord>> SearchSpace for Abstract<'a, L, S> {
+	type Node = L::Coord;
+	fn successors(&self, node: L::Coord) -> Vec<(L::Coord, f32)> {
+		let mut successors = self.edges.get(&node).cloned().unwrap_or_default();
+		if let Some(linked) = self.linked.get(&node) {
+			successors.extend(linked.iter().copied());
+		}
+		successors
+	}
+	fn heuristic(&self, node: L::Coord, goal: L::Coord) -> f32 {
+		self.grid.heuristic(node, goal)
+	}
+}
+
+/// The clusters of a grid and the abstract graph of their entrances, see the module documentation
+#[derive(Debug, Clone)]
+pub struct Hierarchy<C> {
+	/// Width of a cluster
+	cluster_size: i32,
+	/// The entrances of each cluster
+	entrances: HashMap<Cluster, Vec<C>>,
+	/// The hops leaving each entrance, to the entrances of its own cluster and across into
+	/// neighbouring clusters
+	edges: HashMap<C, Vec<(C, f32)>>,
+}
+
+impl<C: Copy + Eq + Hash + Ord + Debug> Hierarchy<C> {
+	/// Cut the `nodes` of `grid` into clusters `cluster_size` hexagons across and build the
+	/// abstract graph of their entrances. `nodes` is every node of the grid, e.g the keys of a
+	/// `HashMap` store or every column and row of a `DenseNodeStore`. Panics if `cluster_size` is
+	/// less than `1`
+	pub fn new<L: GridLayout<Coord = C>, S: NodeStore<C>, I: IntoIterator<Item = C>>(
+		grid: &HexGrid<L, S>,
+		nodes: I,
+		cluster_size: i32,
+	) -> Hierarchy<C> {
+		if cluster_size < 1 {
+			panic!(
+				"Clusters must be at least 1 hexagon across, found {}",
+				cluster_size
+			);
+		}
+		let layout = grid.layout();
+		// every step from one cluster into another, grouped by the pair of clusters
+		let mut crossings: HashMap<(Cluster, Cluster), Vec<Crossing<C>>> = HashMap::new();
+		for node in nodes {
+			let cluster = cluster_of(layout, node, cluster_size);
+			for (n, cost) in grid.successors(node) {
+				let other = cluster_of(layout, n, cluster_size);
+				if other != cluster {
+					crossings
+						.entry((cluster, other))
+						.or_default()
+						.push((node, n, cost));
+				}
+			}
+		}
+		let mut hierarchy = Hierarchy {
+			cluster_size,
+			entrances: HashMap::new(),
+			edges: HashMap::new(),
+		};
+		// visit in order so the entrances chosen do not depend on hashing
+		let mut pairs: Vec<(Cluster, Cluster)> = crossings.keys().copied().collect();
+		pairs.sort();
+		for pair in pairs {
+			let mut steps = crossings.remove(&pair).unwrap_or_default();
+			steps.sort_by_key(|s| (s.0, s.1));
+			for run in crossing_runs(layout, &steps) {
+				// the middle crossing of each run stands for the whole run
+				let (from, to, cost) = run[run.len() / 2];
+				hierarchy.add_entrance(pair.0, from);
+				hierarchy.add_entrance(pair.1, to);
+				hierarchy.edges.entry(from).or_default().push((to, cost));
+			}
+		}
+		// join the entrances of each cluster by the cheapest routes inside it
+		let mut clusters: Vec<Cluster> = hierarchy.entrances.keys().copied().collect();
+		clusters.sort();
+		for cluster in clusters {
+			let space = InCluster {
+				grid,
+				cluster,
+				cluster_size,
+				reversed: false,
+			};
+			let entrances = hierarchy.entrances[&cluster].clone();
+			for entrance in entrances.iter() {
+				let costs = dijkstra_costs(&space, &[*entrance]);
+				for other in entrances.iter() {
+					if other == entrance {
+						continue;
+					}
+					if let Some(cost) = costs.get(other) {
+						hierarchy
+							.edges
+							.entry(*entrance)
+							.or_default()
+							.push((*other, *cost));
+					}
+				}
+			}
+		}
+		hierarchy
+	}
+	/// Width of a cluster
+	pub fn cluster_size(&self) -> i32 {
+		self.cluster_size
+	}
+	/// Number of entrances in the abstract graph
+	pub fn count_entrances(&self) -> usize {
+		self.entrances.values().map(|e| e.len()).sum()
+	}
+	/// The entrances of the cluster holding `node`
+	pub fn entrances_near<L: GridLayout<Coord = C>>(&self, layout: &L, node: C) -> &[C] {
+		match self
+			.entrances
+			.get(&cluster_of(layout, node, self.cluster_size))
+		{
+			Some(entrances) => entrances,
+			None => &[],
+		}
+	}
+	/// Find a path from `start_node` to `end_node` across `grid`, the grid the hierarchy was built
+	/// from. The path is close to, but not always, the cheapest. Returns `None` if `end_node`
+	/// cannot be reached
+	pub fn path<L: GridLayout<Coord = C>, S: NodeStore<C>>(
+		&self,
+		grid: &HexGrid<L, S>,
+		start_node: C,
+		end_node: C,
+	) -> Option<Path<C>> {
+		let layout = grid.layout();
+		let start_cluster = cluster_of(layout, start_node, self.cluster_size);
+		let end_cluster = cluster_of(layout, end_node, self.cluster_size);
+		let outbound = InCluster {
+			grid,
+			cluster: start_cluster,
+			cluster_size: self.cluster_size,
+			reversed: false,
+		};
+		if start_cluster == end_cluster {
+			if let Some(path) = astar_path(&outbound, start_node, end_node) {
+				return Some(path);
+			}
+		}
+		// link the start to the entrances it can reach and the entrances which can reach the end
+		let mut linked: HashMap<C, Vec<(C, f32)>> = HashMap::new();
+		let from_start = dijkstra_costs(&outbound, &[start_node]);
+		for entrance in self.entrances_near(layout, start_node) {
+			if let Some(cost) = from_start.get(entrance) {
+				linked
+					.entry(start_node)
+					.or_default()
+					.push((*entrance, *cost));
+			}
+		}
+		let inbound = InCluster {
+			grid,
+			cluster: end_cluster,
+			cluster_size: self.cluster_size,
+			reversed: true,
+		};
+		let to_end = dijkstra_costs(&inbound, &[end_node]);
+		for entrance in self.entrances_near(layout, end_node) {
+			if let Some(cost) = to_end.get(entrance) {
+				linked.entry(*entrance).or_default().push((end_node, *cost));
+			}
+		}
+		let graph = Abstract {
+			grid,
+			edges: &self.edges,
+			linked,
+		};
+		let hops = astar_path(&graph, start_node, end_node)?;
+		// refine each hop into hexagons
+		let mut nodes = vec![start_node];
+		let mut costs = vec![0.0];
+		for pair in hops.nodes().windows(2) {
+			let (from, to) = (pair[0], pair[1]);
+			let from_cluster = cluster_of(layout, from, self.cluster_size);
+			let to_cluster = cluster_of(layout, to, self.cluster_size);
+			let total = costs[costs.len() - 1];
+			if from_cluster != to_cluster {
+				let step = grid
+					.successors(from)
+					.into_iter()
+					.find(|(n, _)| *n == to)
+					.map(|(_, cost)| cost)?;
+				nodes.push(to);
+				costs.push(total + step);
+				continue;
+			}
+			let within = InCluster {
+				grid,
+				cluster: from_cluster,
+				cluster_size: self.cluster_size,
+				reversed: false,
+			};
+			let leg = astar_path(&within, from, to)?;
+			for (node, cost) in leg.iter().zip(leg.cumulative_costs()).skip(1) {
+				nodes.push(*node);
+				costs.push(total + cost);
+			}
+		}
+		Some(Path::new(nodes, costs))
+	}
+	/// Record `node` as an entrance of `cluster`
+	fn add_entrance(&mut self, cluster: Cluster, node: C) {
+		let entrances = self.entrances.entry(cluster).or_default();
+		if !entrances.contains(&node) {
+			entrances.push(node);
+		}
+	}
+}
+
+/// Split the crossings between one pair of clusters into runs, crossings neighbouring each other
+/// on both sides of the boundary. Every crossing of a run can reach every other without leaving
+/// either cluster
+fn crossing_runs<L: GridLayout>(
+	layout: &L,
+	steps: &[Crossing<L::Coord>],
+) -> Vec<Vec<Crossing<L::Coord>>> {
+	let touching = |a: L::Coord, b: L::Coord| a == b || layout.distance(a, b) == 1;
+	let mut assigned: HashSet<usize> = HashSet::new();
+	let mut runs = Vec::new();
+	for first in 0..steps.len() {
+		if !assigned.insert(first) {
+			continue;
+		}
+		let mut run = vec![first];
+		let mut pending = vec![first];
+		while let Some(current) = pending.pop() {
+			for other in 0..steps.len() {
+				if assigned.contains(&other) {
+					continue;
+				}
+				let (a, b, _) = steps[current];
+				let (c, d, _) = steps[other];
+				if touching(a, c) && touching(b, d) {
+					assigned.insert(other);
+					run.push(other);
+					pending.push(other);
+				}
+			}
+		}
+		run.sort_unstable();
+		runs.push(run.into_iter().map(|i| steps[i]).collect());
+	}
+	runs
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::hierarchical::*;
+	use crate::search::astar_path;
+	use crate::test_support::assert_path_valid;
+	use crate::test_support::offset_fixture;
+	use crate::GridBounds;
+	use crate::HexOrientation;
+
+	#[test]
+	/// Hierarchical paths are valid, found whenever a path exists and stay close to the cheapest
+	fn close_to_optimal() {
+		for seed in 0..4 {
+			let bounds = GridBounds::from_size(30, 30);
+			let grid = offset_fixture(bounds, HexOrientation::FlatTopOddUp, seed);
+			let nodes: Vec<(i32, i32)> = grid.nodes().keys().copied().collect();
+			let hierarchy = Hierarchy::new(&grid, nodes, 6);
+			assert!(hierarchy.count_entrances() > 0);
+			for goal in [(29, 29), (0, 29), (29, 0), (15, 14), (3, 2)] {
+				let optimal = astar_path(&grid, (0, 0), goal);
+				let found = hierarchy.path(&grid, (0, 0), goal);
+				assert_eq!(optimal.is_some(), found.is_some(), "goal {:?}", goal);
+				if let (Some(o), Some(f)) = (optimal, found) {
+					assert_path_valid(&grid, &f);
+					assert_eq!(Some(&(0, 0)), f.first());
+					assert_eq!(Some(&goal), f.last());
+					assert!(f.cost() <= o.cost() * 1.5 + 1e-4);
+				}
+			}
+		}
+	}
+}
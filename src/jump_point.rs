@@ -0,0 +1,471 @@
+//! Skip through open ground of uniform complexity rather than expanding every hexagon of it, a
+//! hexagonal analogue of Jump Point Search by Harabor and Grastien.
+//!
+//! Across open ground where every hexagon shares the same complexity there are many equally
+//! cheap paths between two nodes, any ordering of the same steps in two neighbouring directions
+//! costs the same, and A* expands every node of all of them. `jump_point_path` only considers one
+//! ordering, a straight run in one direction followed by a straight run in the next direction
+//! clockwise:
+//!
+//! ```txt
+//!         _______
+//!        /       \
+//!       /   end   \
+//!       \         /
+//!        \_______/
+//!        /       \
+//!       /    ^    \
+//!       \    |    /
+//!        \___|___/
+//!        /   |   \
+//!       /    |    \
+//!       \  corner /
+//!        \_______/
+//!       /       \
+//!      /    /    \
+//!      \   /     /
+//!       \_______/
+//!      /       \
+//!     / start   \
+//!     \         /
+//!      \_______/
+//! ```
+//!
+//! Runs are followed without queueing the nodes along them. A node is open ground when every
+//! passable neighbour shares its complexity with no edge costs, blocked edges or portals between
+//! them, and a run only stops at the end, at the edge of open ground, at corners from which a run
+//! in the next direction stops somewhere and where a wall beside the run leaves a neighbour which
+//! could not otherwise be reached as cheaply, a forced neighbour. From anywhere which is not open
+//! ground every neighbour is considered just like A*, so grids of varied terrain still give the
+//! cheapest path while large regions of equal complexity are crossed in a handful of steps.
+
+use crate::grid::GridLayout;
+use crate::grid::HexGrid;
+use crate::node_store::NodeStore;
+use crate::path::Path;
+use crate::search::MinScored;
+use crate::search::SearchSpace;
+use ::std::collections::BinaryHeap;
+use ::std::collections::HashMap;
+use ::std::hash::Hash;
+
+/// The six Cubic directions in clockwise order, so that a step in a direction and a step in the
+/// direction two further round make a step in the direction between them
+const DIRECTIONS: [(i32, i32, i32); 6] = [
+	(1, -1, 0),
+	(1, 0, -1),
+	(0, 1, -1),
+	(-1, 1, 0),
+	(-1, 0, 1),
+	(0, -1, 1),
+];
+
+/// How a node was arrived at, which decides the moves to consider from it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Arrival {
+	/// Every neighbour is considered, for the start and nodes off open ground
+	Any,
+	/// Along the first run of a path in a direction, which may carry on or turn clockwise
+	First(usize),
+	/// Along the second run of a path in a direction, which may only carry on
+	Second(usize),
+}
+
+/// A node of the search along with how it was arrived at
+type State<N> = (N, Arrival);
+
+/// A move from one state to another
+#[derive(Debug, Clone, Copy)]
+struct Leap<N> {
+	/// The state leapt from
+	from: State<N>,
+	/// The state leapt to
+	to: State<N>,
+	/// The cost of the move
+	cost: f32,
+	/// The direction of a run and the cost of each of its steps, `None` for a single step
+	run: Option<(usize, f32)>,
+}
+
+/// What a search needs to know about a node to decide whether it can be run through
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Ground {
+	/// Open ground, with the neighbours in each direction which can be stepped to
+	Open([bool; 6]),
+	/// Passable but not open ground, every neighbour must be considered
+	Rough,
+	/// Impassable or not part of the grid
+	Closed,
+}
+
+/// A grid being searched with the knowledge of which of its nodes are open ground
+struct Jumper<'a, L: GridLayout, S> {
+	/// The grid being searched
+	grid: &'a HexGrid<L, S>,
+	/// Where the search is heading
+	end_node: L::Coord,
+	/// The ground of each node asked about so far
+	ground: HashMap<L::Coord, Ground>,
+}
+
+impl<'a, L: GridLayout, S: NodeStore<L::Coord>> Jumper<'a, L, S> {
+	/// The neighbour of `node` in `direction`
+	fn step(&self, node: L::Coord, direction: usize) -> L::Coord {
+		let cube = self.grid.layout().node_to_cubic(node);
+		let (x, y, z) = DIRECTIONS[direction];
+		self.grid
+			.layout()
+			.cubic_to_node((cube.0 + x, cube.1 + y, cube.2 + z))
+	}
+	/// The direction leading from `node` to `next`, `None` if they are not neighbours in the
+	/// plain sense, e.g across the seam of a wrapping grid or through a portal
+	fn direction(&self, node: L::Coord, next: L::Coord) -> Option<usize> {
+		(0..6).find(|d| self.step(node, *d) == next)
+	}
+	/// Whether the edge between `a` and `b` costs nothing extra and is not blocked
+	fn plain_edge(&self, a: L::Coord, b: L::Coord) -> bool {
+		!self.grid.is_edge_blocked(a, b) && self.grid.edge_cost(a, b) == 0.0
+	}
+	/// The ground of `node`
+	fn ground(&mut self, node: L::Coord) -> Ground {
+		if let Some(ground) = self.ground.get(&node) {
+			return *ground;
+		}
+		let ground = self.survey(node);
+		self.ground.insert(node, ground);
+		ground
+	}
+	/// Work out the ground of `node` without remembering it. Open ground shares its complexity
+	/// with every passable neighbour and has no portals leading out of it, nor edge costs or
+	/// blocked edges between it and its neighbours or between neighbouring neighbours
+	fn survey(&self, node: L::Coord) -> Ground {
+		let complexity = match self.grid.complexity(&node) {
+			Some(c) if c.is_finite() => c,
+			_ => return Ground::Closed,
+		};
+		if !self.grid.portals_from(&node).is_empty() {
+			return Ground::Rough;
+		}
+		let around: Vec<L::Coord> = self
+			.grid
+			.layout()
+			.neighbours(node)
+			.into_iter()
+			.filter(|n| self.grid.is_passable(n))
+			.collect();
+		let ring: Vec<L::Coord> = (0..6).map(|d| self.step(node, d)).collect();
+		if around.iter().any(|n| !ring.contains(n)) {
+			return Ground::Rough;
+		}
+		let mut open = [false; 6];
+		for (d, n) in ring.iter().enumerate() {
+			if around.contains(n) {
+				if self.grid.complexity(n) != Some(complexity) || !self.plain_edge(node, *n) {
+					return Ground::Rough;
+				}
+				open[d] = true;
+			}
+		}
+		for d in 0..6 {
+			let next = (d + 1) % 6;
+			if open[d] && open[next] && !self.plain_edge(ring[d], ring[next]) {
+				return Ground::Rough;
+			}
+		}
+		Ground::Open(open)
+	}
+	/// The directions other than the natural ones which must be considered from `node` on open
+	/// ground, `open`, after arriving by `arrival`. A neighbour to one side of the direction of
+	/// travel is forced when the node behind it is closed, as the neighbour can then only be
+	/// reached cheaply by turning here
+	fn forced(open: &[bool; 6], arrival: Arrival) -> Vec<usize> {
+		let (direction, sides): (usize, &[usize]) = match arrival {
+			Arrival::First(d) => (d, &[5]),
+			Arrival::Second(d) => (d, &[5, 1]),
+			Arrival::Any => return Vec::new(),
+		};
+		sides
+			.iter()
+			.map(|side| (direction + side) % 6)
+			.filter(|turn| {
+				let behind = (2 * *turn + 6 - direction) % 6;
+				open[*turn] && !open[behind]
+			})
+			.collect()
+	}
+	/// Follow a run from the open node `node` in `direction`, arrived along by `arrival`, to the
+	/// first node where it must stop. Returns that node and the number of steps taken, `None` if
+	/// the run ends against closed ground without finding anywhere to stop. Runs stop at the
+	/// end, at rough ground, at nodes with forced neighbours and, along a first run, at corners
+	/// from which a second run would stop somewhere
+	fn run(&mut self, node: L::Coord, arrival: Arrival) -> Option<(L::Coord, i32)> {
+		let direction = match arrival {
+			Arrival::First(d) | Arrival::Second(d) => d,
+			Arrival::Any => return None,
+		};
+		let mut current = node;
+		let mut steps = 0;
+		loop {
+			match self.ground(current) {
+				Ground::Open(open) if open[direction] => {}
+				_ => return None,
+			}
+			current = self.step(current, direction);
+			steps += 1;
+			let open = match self.ground(current) {
+				Ground::Open(open) => open,
+				_ => return Some((current, steps)),
+			};
+			if current == self.end_node || !Self::forced(&open, arrival).is_empty() {
+				return Some((current, steps));
+			}
+			if let Arrival::First(_) = arrival {
+				// a corner is only worth stopping at if the turn leads somewhere
+				let turn = (direction + 1) % 6;
+				if self.run(current, Arrival::Second(turn)).is_some() {
+					return Some((current, steps));
+				}
+			}
+		}
+	}
+	/// Step to each forced neighbour of the open node of `state`, from where every direction is
+	/// considered as a path squeezing past a wall may need to turn either way
+	fn forced_leaps(
+		&self,
+		state: State<L::Coord>,
+		open: &[bool; 6],
+		leaps: &mut Vec<Leap<L::Coord>>,
+	) {
+		let step_cost = self.grid.complexity(&state.0).unwrap_or(0.0);
+		for direction in Self::forced(open, state.1) {
+			leaps.push(Leap {
+				from: state,
+				to: (self.step(state.0, direction), Arrival::Any),
+				cost: step_cost,
+				run: Some((direction, step_cost)),
+			});
+		}
+	}
+	/// The leaps which can be made from `state`
+	fn leaps(&mut self, state: State<L::Coord>) -> Vec<Leap<L::Coord>> {
+		let (node, arrival) = state;
+		let mut leaps = Vec::new();
+		let runs = match (self.ground(node), arrival) {
+			(Ground::Open(open), Arrival::First(d)) => {
+				self.forced_leaps(state, &open, &mut leaps);
+				vec![Arrival::First(d), Arrival::Second((d + 1) % 6)]
+			}
+			(Ground::Open(open), Arrival::Second(d)) => {
+				self.forced_leaps(state, &open, &mut leaps);
+				vec![Arrival::Second(d)]
+			}
+			_ => {
+				for (n, cost) in self.grid.successors(node) {
+					let next = match (self.direction(node, n), self.ground(n)) {
+						(Some(d), Ground::Open(_)) => Arrival::First(d),
+						_ => Arrival::Any,
+					};
+					leaps.push(Leap {
+						from: state,
+						to: (n, next),
+						cost,
+						run: None,
+					});
+				}
+				Vec::new()
+			}
+		};
+		// every step of a run over open ground costs the complexity of the node it began on
+		let step_cost = self.grid.complexity(&node).unwrap_or(0.0);
+		for run in runs {
+			if let Some((found, steps)) = self.run(node, run) {
+				let direction = match run {
+					Arrival::First(d) | Arrival::Second(d) => d,
+					Arrival::Any => continue,
+				};
+				let arrival = match self.ground(found) {
+					Ground::Open(_) => run,
+					_ => Arrival::Any,
+				};
+				leaps.push(Leap {
+					from: state,
+					to: (found, arrival),
+					cost: step_cost * steps as f32,
+					run: Some((direction, step_cost)),
+				});
+			}
+		}
+		leaps
+	}
+}
+
+/// Find the cheapest path from `start_node` to `end_node` across `grid`, skipping through
+/// regions of uniform complexity, see the module documentation. Returns `None` if the end node
+/// cannot be reached
+pub fn jump_point_path<L: GridLayout, S: NodeStore<L::Coord>>(
+	grid: &HexGrid<L, S>,
+	start_node: L::Coord,
+	end_node: L::Coord,
+) -> Option<Path<L::Coord>> {
+	jump_point_search(grid, start_node, end_node).0
+}
+
+/// `jump_point_path()` along with the number of states expanded to find it
+fn jump_point_search<L: GridLayout, S: NodeStore<L::Coord>>(
+	grid: &HexGrid<L, S>,
+	start_node: L::Coord,
+	end_node: L::Coord,
+) -> (Option<Path<L::Coord>>, usize) {
+	let mut jumper = Jumper {
+		grid,
+		end_node,
+		ground: HashMap::new(),
+	};
+	let start = (start_node, Arrival::Any);
+	let mut costs: HashMap<State<L::Coord>, f32> = HashMap::new();
+	let mut came_from: HashMap<State<L::Coord>, Leap<L::Coord>> = HashMap::new();
+	let mut queue = BinaryHeap::new();
+	costs.insert(start, 0.0);
+	queue.push(MinScored(grid.heuristic(start_node, end_node), start));
+	let mut expansions = 0;
+	while let Some(MinScored(score, state)) = queue.pop() {
+		let cost = costs[&state];
+		// skip stale entries where a cheaper route has since been found
+		if score > cost + grid.heuristic(state.0, end_node) {
+			continue;
+		}
+		if state.0 == end_node {
+			return (
+				Some(reconstruct_path(&jumper, &costs, &came_from, state)),
+				expansions,
+			);
+		}
+		expansions += 1;
+		for leap in jumper.leaps(state) {
+			let next = leap.to;
+			let complexity = cost + leap.cost;
+			let is_better = match costs.get(&next) {
+				Some(existing) => complexity < *existing,
+				None => true,
+			};
+			if is_better {
+				costs.insert(next, complexity);
+				came_from.insert(next, leap);
+				let score = complexity + grid.heuristic(next.0, end_node);
+				queue.push(MinScored(score, next));
+			}
+		}
+	}
+	(None, expansions)
+}
+
+/// Walk backwards from `end` through `came_from`, filling in the nodes along each run, to
+/// rebuild the path which reached it
+fn reconstruct_path<L: GridLayout, S: NodeStore<L::Coord>>(
+	jumper: &Jumper<'_, L, S>,
+	costs: &HashMap<State<L::Coord>, f32>,
+	came_from: &HashMap<State<L::Coord>, Leap<L::Coord>>,
+	end: State<L::Coord>,
+) -> Path<L::Coord> {
+	let mut leaps = Vec::new();
+	let mut current = end;
+	while let Some(leap) = came_from.get(&current) {
+		leaps.push(*leap);
+		current = leap.from;
+	}
+	let mut nodes = vec![current.0];
+	let mut path_costs = vec![0.0];
+	for leap in leaps.into_iter().rev() {
+		let reached = leap.to;
+		if let Some((direction, step_cost)) = leap.run {
+			let mut node = leap.from.0;
+			let mut cost = costs[&leap.from];
+			while node != reached.0 {
+				node = jumper.step(node, direction);
+				cost += step_cost;
+				nodes.push(node);
+				path_costs.push(cost);
+			}
+		} else {
+			nodes.push(reached.0);
+			path_costs.push(costs[&reached]);
+		}
+	}
+	Path::new(nodes, path_costs)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::AxialLayout;
+	use crate::grid::HexGrid;
+	use crate::grid::OffsetLayout;
+	use crate::jump_point::*;
+	use crate::search::astar_path;
+	use crate::test_support::assert_path_optimal;
+	use crate::test_support::axial_fixture;
+	use crate::GridBounds;
+	use crate::HexOrientation;
+
+	#[test]
+	/// Grids of varied terrain, where little is open ground, still give the cheapest path
+	fn optimal_on_varied_terrain() {
+		for seed in 0..6 {
+			let grid = axial_fixture(5, seed);
+			for goal in [(5, -2), (-5, 0), (0, 5), (2, 2), (0, 0)] {
+				let path = jump_point_path(&grid, (0, 0), goal);
+				assert_path_optimal(&grid, (0, 0), goal, path.as_ref());
+			}
+		}
+	}
+	#[test]
+	/// Open ground broken up by walls, patches of rough terrain, edge costs and a portal gives
+	/// the cheapest path from every corner of the grid
+	fn optimal_around_obstacles() {
+		let bounds = GridBounds::new(0, 19, 0, 19);
+		for orientation in [HexOrientation::FlatTopOddUp, HexOrientation::FlatTopOddDown] {
+			let mut nodes = HashMap::new();
+			for column in 0..=19 {
+				for row in 0..=19 {
+					let complexity = match (column, row) {
+						(7, r) if r < 15 => f32::INFINITY,
+						(13, r) if r > 4 => f32::INFINITY,
+						(c, r) if (2..=4).contains(&c) && (9..=12).contains(&r) => 3.0,
+						_ => 1.0,
+					};
+					nodes.insert((column, row), complexity);
+				}
+			}
+			let mut grid = HexGrid::new(OffsetLayout::with_bounds(orientation, bounds), nodes);
+			grid.set_edge_cost((10, 10), (10, 11), 4.0);
+			grid.block_edge((16, 2), (16, 3));
+			grid.add_portal((1, 18), (18, 1), 2.0);
+			for start in [(0, 0), (0, 19), (19, 0), (19, 19), (10, 10)] {
+				for goal in [(0, 0), (5, 17), (19, 10), (10, 2), (16, 3), (18, 1)] {
+					let path = jump_point_path(&grid, start, goal);
+					assert_path_optimal(&grid, start, goal, path.as_ref());
+				}
+			}
+		}
+	}
+	#[test]
+	/// Across open ground far fewer states are expanded than nodes A* would need to search
+	fn skips_open_ground() {
+		let mut nodes = HashMap::new();
+		for q in -30..=30i32 {
+			for r in -30..=30 {
+				if (q + r).abs() <= 30 {
+					nodes.insert((q, r), 2.0);
+				}
+			}
+		}
+		let grid = HexGrid::new(AxialLayout::new(30), nodes);
+		let (path, expansions) = jump_point_search(&grid, (-25, 5), (20, 8));
+		let path = path.unwrap();
+		assert_eq!(
+			astar_path(&grid, (-25, 5), (20, 8)).unwrap().cost(),
+			path.cost()
+		);
+		assert_eq!(path.len() as f32 - 1.0, path.cost() / 2.0);
+		assert!(expansions < 20, "expanded {} states", expansions);
+	}
+}
@@ -110,11 +110,52 @@
 //! ### Pointy Topped - odd rows shifted left
 //!
 //! Please refer to the README of the proect for an illustration - ascii hexagons with pointy tops are very hard to draw.
+//!
+//! ### Even shifted layouts
+//!
+//! Some map editors describe their layouts by which columns or rows are shifted being even rather than odd. Moving the even columns up is the same as moving the odd columns down, so `HexOrientation` offers even variants which behave exactly as their odd counterparts, see `HexOrientation` for the pairings.
 
+pub mod all_pairs;
+pub mod ara_star;
 pub mod astar_axial;
 pub mod astar_cubic;
 pub mod astar_offset;
+pub mod batch;
+pub mod chunked;
+pub mod convert;
+pub mod cooperative;
+pub mod dead_ends;
+pub mod detect;
+pub mod dstar_lite;
+pub mod explore;
+pub mod flat;
+pub mod flow_field;
+pub mod generators;
+pub mod grid;
+pub mod hashing;
 pub mod helpers;
+pub mod hierarchical;
+pub mod jump_point;
+pub mod metrics;
+pub mod node_store;
+pub mod path;
+pub mod path_cache;
+pub mod preprocess;
+pub mod pursuit;
+pub mod rect_grid;
+pub mod regions;
+pub mod ring_cache;
+pub mod road_network;
+pub mod scoring;
+pub mod search;
+pub mod shapes;
+pub mod smoothing;
+pub mod stepped;
+pub mod storage;
+pub mod template;
+pub mod test_support;
+pub mod traffic;
+pub mod wander;
 
 /// Specifies the orientation of the hexagon space in Offset layouts. This is
 /// important for determining the available neighbouring nodes during expansion.
@@ -133,9 +174,258 @@ pub mod helpers;
 ///  \___/ O \
 ///      \___/
 /// ```
+///
+/// Shifting one set of columns up moves the other set down relative to it, so each even variant
+/// describes the same arrangement as one of the odd variants and behaves identically. They are
+/// provided so layouts exported as even-shifted by map editors, such as the "even-q" and "even-r"
+/// layouts of the Red Blob Games reference, can be named as they were exported:
+///
+/// * `FlatTopEvenUp` is `FlatTopOddDown`, "even-q"
+/// * `FlatTopEvenDown` is `FlatTopOddUp`, "odd-q"
+/// * `PointyTopEvenRight` is `PointyTopOddLeft`, "even-r"
+/// * `PointyTopEvenLeft` is `PointyTopOddRight`, "odd-r"
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HexOrientation {
 	FlatTopOddUp,
 	FlatTopOddDown,
 	PointyTopOddRight,
 	PointyTopOddLeft,
+	FlatTopEvenUp,
+	FlatTopEvenDown,
+	PointyTopEvenRight,
+	PointyTopEvenLeft,
+}
+
+impl HexOrientation {
+	/// The orientation of an Axial grid drawn with the same hexagons, used to convert between
+	/// the two with `helpers::axial_to_offset()`
+	pub fn axial_orientation(&self) -> AxialOrientation {
+		match self {
+			HexOrientation::FlatTopOddUp
+			| HexOrientation::FlatTopOddDown
+			| HexOrientation::FlatTopEvenUp
+			| HexOrientation::FlatTopEvenDown => AxialOrientation::FlatTop,
+			HexOrientation::PointyTopOddRight
+			| HexOrientation::PointyTopOddLeft
+			| HexOrientation::PointyTopEvenRight
+			| HexOrientation::PointyTopEvenLeft => AxialOrientation::PointyTop,
+		}
+	}
+}
+
+/// Specifies how the hexagons of an Axial grid are drawn. The coordinates of a node and which
+/// nodes neighbour it are the same either way, the orientation names the directions between
+/// neighbours and so the order in which `helpers::node_neighbours_axial_oriented()` returns them.
+///
+/// Flat-top, `q` is a column and `r` a diagonal row, `(0, 1)` lies north of `(0, 0)`
+/// ```txt
+///       ___
+///   ___/0,1\___
+///  /   \___/1,0\
+///  \___/0,0\___/
+///      \___/
+/// ```
+/// Pointy-top, the flat-top grid turned by 30 degrees so `r` is a row running east to west and
+/// `q` a diagonal column, `(1, 0)` lies east of `(0, 0)` and `(0, 1)` to the north-east
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AxialOrientation {
+	FlatTop,
+	PointyTop,
+}
+
+/// One of the three axes of a Cubic grid, named by the coordinate of `(x, y, z)` which stays the
+/// same when a node is mirrored across it, `X` keeping Axial `q`, `Z` keeping Axial `r` and `Y`
+/// keeping the third coordinate sometimes called `s`. The other two coordinates swap, so an axis
+/// runs through the nodes where those two are equal.
+///
+/// With flat tops the `X` axis runs east to west and the others are turned 60 degrees from it:
+/// ```txt
+///       Z   Y
+///        \ /
+///  X -----o----- X
+///        / \
+///       Y   Z
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CubicAxis {
+	X,
+	Y,
+	Z,
+}
+
+/// A compass direction from a node to one of its neighbours. A flat topped hexagon has faces to
+/// the north and south but not the east and west, a pointy topped hexagon the other way round,
+/// so six of the eight directions apply to each orientation:
+///
+/// | Flat-top      | `(q, r)`  | Pointy-top    | `(q, r)`  |
+/// |---------------|-----------|---------------|-----------|
+/// | `North`       | `(0, 1)`  | `East`        | `(1, 0)`  |
+/// | `NorthEast`   | `(1, 0)`  | `SouthEast`   | `(1, -1)` |
+/// | `SouthEast`   | `(1, -1)` | `SouthWest`   | `(0, -1)` |
+/// | `South`       | `(0, -1)` | `West`        | `(-1, 0)` |
+/// | `SouthWest`   | `(-1, 0)` | `NorthWest`   | `(-1, 1)` |
+/// | `NorthWest`   | `(-1, 1)` | `NorthEast`   | `(0, 1)`  |
+///
+/// The neighbour in a direction is found with `helpers::node_neighbour_axial()` and its Cubic and
+/// Offset equivalents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HexDirection {
+	North,
+	NorthEast,
+	East,
+	SouthEast,
+	South,
+	SouthWest,
+	West,
+	NorthWest,
+}
+
+impl HexDirection {
+	/// The six directions of neighbours in `orientation` clockwise from the north for flat tops
+	/// and from the east for pointy tops, the order of `helpers::node_neighbours_axial_oriented()`
+	pub fn all(orientation: &AxialOrientation) -> [HexDirection; 6] {
+		match orientation {
+			AxialOrientation::FlatTop => [
+				HexDirection::North,
+				HexDirection::NorthEast,
+				HexDirection::SouthEast,
+				HexDirection::South,
+				HexDirection::SouthWest,
+				HexDirection::NorthWest,
+			],
+			AxialOrientation::PointyTop => [
+				HexDirection::East,
+				HexDirection::SouthEast,
+				HexDirection::SouthWest,
+				HexDirection::West,
+				HexDirection::NorthWest,
+				HexDirection::NorthEast,
+			],
+		}
+	}
+	/// The direction pointing the opposite way
+	pub fn opposite(&self) -> HexDirection {
+		match self {
+			HexDirection::North => HexDirection::South,
+			HexDirection::NorthEast => HexDirection::SouthWest,
+			HexDirection::East => HexDirection::West,
+			HexDirection::SouthEast => HexDirection::NorthWest,
+			HexDirection::South => HexDirection::North,
+			HexDirection::SouthWest => HexDirection::NorthEast,
+			HexDirection::West => HexDirection::East,
+			HexDirection::NorthWest => HexDirection::SouthEast,
+		}
+	}
+	/// The change in Axial coordinates `(q, r)` of a step in this direction in `orientation`,
+	/// `None` for north and south with pointy tops and east and west with flat tops
+	pub fn axial_offset(&self, orientation: &AxialOrientation) -> Option<(i32, i32)> {
+		match orientation {
+			AxialOrientation::FlatTop => match self {
+				HexDirection::North => Some((0, 1)),
+				HexDirection::NorthEast => Some((1, 0)),
+				HexDirection::SouthEast => Some((1, -1)),
+				HexDirection::South => Some((0, -1)),
+				HexDirection::SouthWest => Some((-1, 0)),
+				HexDirection::NorthWest => Some((-1, 1)),
+				HexDirection::East | HexDirection::West => None,
+			},
+			AxialOrientation::PointyTop => match self {
+				HexDirection::East => Some((1, 0)),
+				HexDirection::SouthEast => Some((1, -1)),
+				HexDirection::SouthWest => Some((0, -1)),
+				HexDirection::West => Some((-1, 0)),
+				HexDirection::NorthWest => Some((-1, 1)),
+				HexDirection::NorthEast => Some((0, 1)),
+				HexDirection::North | HexDirection::South => None,
+			},
+		}
+	}
+}
+
+/// The inclusive column and row limits of a rectangular Offset grid.
+///
+/// The Offset functions were first written taking four loose, exclusive, limits, a grid of nodes
+/// from `(0, 0)` to `(3, 3)` being described with `-1` and `4`. `GridBounds` names the first and
+/// last column and row which hold nodes instead:
+///
+/// ```
+/// use hexagonal_pathfinding_astar::GridBounds;
+/// let bounds = GridBounds::from_size(4, 4);
+/// assert_eq!(GridBounds::new(0, 3, 0, 3), bounds);
+/// assert_eq!((-1, 4, -1, 4), bounds.exclusive());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GridBounds {
+	/// First column of the grid
+	pub min_column: i32,
+	/// Last column of the grid
+	pub max_column: i32,
+	/// First row of the grid
+	pub min_row: i32,
+	/// Last row of the grid
+	pub max_row: i32,
+}
+
+impl GridBounds {
+	/// Create bounds from the first and last column and row of the grid, inclusive
+	pub fn new(min_column: i32, max_column: i32, min_row: i32, max_row: i32) -> GridBounds {
+		GridBounds {
+			min_column,
+			max_column,
+			min_row,
+			max_row,
+		}
+	}
+	/// Create bounds for a grid of `width` columns and `height` rows with `(0, 0)` at its bottom left
+	pub fn from_size(width: i32, height: i32) -> GridBounds {
+		GridBounds::new(0, width - 1, 0, height - 1)
+	}
+	/// The smallest bounds containing every one of `nodes`, `None` if there are none
+	pub fn enclosing<I: IntoIterator<Item = (i32, i32)>>(nodes: I) -> Option<GridBounds> {
+		let mut nodes = nodes.into_iter();
+		let first = nodes.next()?;
+		let mut bounds = GridBounds::new(first.0, first.0, first.1, first.1);
+		for node in nodes {
+			bounds.min_column = bounds.min_column.min(node.0);
+			bounds.max_column = bounds.max_column.max(node.0);
+			bounds.min_row = bounds.min_row.min(node.1);
+			bounds.max_row = bounds.max_row.max(node.1);
+		}
+		Some(bounds)
+	}
+	/// Create bounds from the exclusive limits taken by `astar_offset::astar_path()`
+	pub fn from_exclusive(
+		min_column: i32,
+		max_column: i32,
+		min_row: i32,
+		max_row: i32,
+	) -> GridBounds {
+		GridBounds::new(min_column + 1, max_column - 1, min_row + 1, max_row - 1)
+	}
+	/// The exclusive limits `(min_column, max_column, min_row, max_row)` taken by
+	/// `astar_offset::astar_path()`
+	pub fn exclusive(&self) -> (i32, i32, i32, i32) {
+		(
+			self.min_column - 1,
+			self.max_column + 1,
+			self.min_row - 1,
+			self.max_row + 1,
+		)
+	}
+	/// Number of columns in the grid
+	pub fn width(&self) -> i32 {
+		self.max_column - self.min_column + 1
+	}
+	/// Number of rows in the grid
+	pub fn height(&self) -> i32 {
+		self.max_row - self.min_row + 1
+	}
+	/// Whether `node` lies within the bounds
+	pub fn contains(&self, node: (i32, i32)) -> bool {
+		node.0 >= self.min_column
+			&& node.0 <= self.max_column
+			&& node.1 >= self.min_row
+			&& node.1 <= self.max_row
+	}
 }
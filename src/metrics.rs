@@ -0,0 +1,208 @@
+//! Measure how central each hexagon of a grid is.
+//!
+//! The eccentricity of a node is the cost of the cheapest path from it to the node furthest
+//! away, i.e the worst journey anyone starting there would have to make. From this:
+//!
+//! * the diameter of a grid is the greatest eccentricity, the most expensive journey between any
+//!   two nodes
+//! * the centre of a grid is the set of nodes with the smallest eccentricity, from which every
+//!   other node is as cheap as possible to reach
+//!
+//! ```txt
+//!              _______
+//!             /       \
+//!     _______/   e:2   \_______
+//!    /       \         /       \
+//!   /   e:2   \_______/   e:2   \
+//!   \         /       \         /
+//!    \_______/   e:1   \_______/
+//!    /       \ centre  /       \
+//!   /   e:2   \_______/   e:2   \
+//!   \         /       \         /
+//!    \_______/   e:2   \_______/
+//!            \         /
+//!             \_______/
+//! ```
+//!
+//! Map generators can use these to place objectives fairly and AI can seek out central
+//! positions. Costs are measured with the complexities of the grid, so a node can be central in
+//! distance but not in cost. Nodes which cannot reach each other are ignored, the eccentricity of
+//! a node only considers the nodes it can reach.
+//!
+//! When some places matter more than others, towns needing supplies or players needing to
+//! respawn, `weighted_median` finds the node minimising the total cost of travel from every
+//! place of demand, each journey scaled by its weight. This is the 1-median of the grid and
+//! suits placing depots, markets or respawn points.
+
+use crate::grid::GridLayout;
+use crate::grid::HexGrid;
+use crate::search::dijkstra_costs;
+use ::std::collections::HashMap;
+
+/// The cost of the cheapest path from `node` to the node furthest from it, `None` if `node`
+/// cannot be traversed
+pub fn eccentricity<L: GridLayout>(grid: &HexGrid<L>, node: L::Coord) -> Option<f32> {
+	if !grid.is_passable(&node) {
+		return None;
+	}
+	let costs = dijkstra_costs(grid, &[node]);
+	Some(costs.values().fold(0.0, |max: f32, c| max.max(*c)))
+}
+
+/// The eccentricity of every passable node of `grid`, in no particular order
+pub fn eccentricities<L: GridLayout>(grid: &HexGrid<L>) -> HashMap<L::Coord, f32> {
+	let mut eccentricities = HashMap::new();
+	for node in grid.nodes().keys() {
+		if let Some(e) = eccentricity(grid, *node) {
+			eccentricities.insert(*node, e);
+		}
+	}
+	eccentricities
+}
+
+/// The cost of the most expensive journey between any two nodes of `grid` which can reach each
+/// other, `0.0` for an empty grid
+pub fn diameter<L: GridLayout>(grid: &HexGrid<L>) -> f32 {
+	eccentricities(grid)
+		.values()
+		.fold(0.0, |max: f32, e| max.max(*e))
+}
+
+/// The nodes of `grid` with the smallest eccentricity, sorted
+pub fn centre<L: GridLayout>(grid: &HexGrid<L>) -> Vec<L::Coord> {
+	let eccentricities = eccentricities(grid);
+	let smallest = eccentricities
+		.values()
+		.fold(f32::INFINITY, |min: f32, e| min.min(*e));
+	let mut centre: Vec<L::Coord> = eccentricities
+		.into_iter()
+		.filter(|(_, e)| *e == smallest)
+		.map(|(n, _)| n)
+		.collect();
+	centre.sort();
+	centre
+}
+
+/// The passable node of `grid` minimising the sum over `demand` of each weight multiplied by the
+/// cost of the cheapest path from its node, along with that sum. Only nodes which every demand
+/// node with a positive weight can reach are considered. Ties go to the smallest node so the
+/// result does not depend on hashing. `None` if no node is reachable from every demand node
+pub fn weighted_median<L: GridLayout>(
+	grid: &HexGrid<L>,
+	demand: &HashMap<L::Coord, f32>,
+) -> Option<(L::Coord, f32)> {
+	// sum the demand in order of node so the floating point totals do not depend on hashing
+	let mut sources: Vec<(L::Coord, f32)> = demand.iter().map(|(n, w)| (*n, *w)).collect();
+	sources.sort_by_key(|s| s.0);
+	let mut totals: HashMap<L::Coord, f32> = HashMap::new();
+	let mut count_sources = 0;
+	for (source, weight) in sources.iter() {
+		if *weight <= 0.0 {
+			continue;
+		}
+		if !grid.is_passable(source) {
+			return None;
+		}
+		count_sources += 1;
+		let costs = dijkstra_costs(grid, &[*source]);
+		if count_sources == 1 {
+			totals = costs.into_iter().map(|(n, c)| (n, c * weight)).collect();
+		} else {
+			// drop any candidate this source cannot reach
+			totals.retain(|n, _| costs.contains_key(n));
+			for (n, total) in totals.iter_mut() {
+				*total += costs[n] * weight;
+			}
+		}
+	}
+	if count_sources == 0 {
+		return None;
+	}
+	let mut candidates: Vec<(L::Coord, f32)> = totals.into_iter().collect();
+	candidates.sort_by_key(|c| c.0);
+	candidates
+		.into_iter()
+		.fold(None, |best: Option<(L::Coord, f32)>, c| match best {
+			Some(b) if b.1 <= c.1 => Some(b),
+			_ => Some(c),
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::CubicLayout;
+	use crate::grid::HexGrid;
+	use crate::metrics::*;
+
+	/// A cubic grid of 1 ring with a complexity of 1 everywhere
+	fn ring_grid() -> HexGrid<CubicLayout> {
+		let mut nodes = HashMap::new();
+		for node in [
+			(0, 0, 0),
+			(0, -1, 1),
+			(1, -1, 0),
+			(1, 0, -1),
+			(0, 1, -1),
+			(-1, 1, 0),
+			(-1, 0, 1),
+		] {
+			nodes.insert(node, 1.0);
+		}
+		HexGrid::new(CubicLayout::new(1), nodes)
+	}
+
+	#[test]
+	/// The origin is the centre of a uniform ring and opposite sides are the diameter apart
+	fn uniform_ring_centre() {
+		let grid = ring_grid();
+		assert_eq!(Some(1.0), eccentricity(&grid, (0, 0, 0)));
+		assert_eq!(Some(2.0), eccentricity(&grid, (0, -1, 1)));
+		assert_eq!(2.0, diameter(&grid));
+		assert_eq!(vec![(0, 0, 0)], centre(&grid));
+	}
+	#[test]
+	/// A costly centre node becomes the most expensive node to reach, walking round the ring
+	/// to the opposite side is cheaper than crossing it
+	fn costly_centre() {
+		let mut grid = ring_grid();
+		grid.insert_node((0, 0, 0), 9.0);
+		assert_eq!(Some(5.0), eccentricity(&grid, (0, -1, 1)));
+		assert_eq!(5.0, diameter(&grid));
+		assert_eq!(7, centre(&grid).len());
+		grid.insert_node((0, 0, 0), f32::INFINITY);
+		assert_eq!(None, eccentricity(&grid, (0, 0, 0)));
+		assert_eq!(Some(3.0), eccentricity(&grid, (0, -1, 1)));
+	}
+	#[test]
+	/// A heavy demand pulls the median off the centre of the ring towards it
+	fn weighted_median_follows_demand() {
+		let grid = ring_grid();
+		let mut demand = HashMap::new();
+		for node in grid.nodes().keys() {
+			if *node != (0, 0, 0) {
+				demand.insert(*node, 1.0);
+			}
+		}
+		assert_eq!(Some(((0, 0, 0), 6.0)), weighted_median(&grid, &demand));
+		demand.insert((0, -1, 1), 5.0);
+		assert_eq!(Some(((0, -1, 1), 8.0)), weighted_median(&grid, &demand));
+		assert_eq!(None, weighted_median(&grid, &HashMap::new()));
+	}
+	#[test]
+	/// Fractional weights give exactly the same total however the demand map happens to hash
+	fn weighted_median_ignores_hashing() {
+		let grid = ring_grid();
+		let weights = [0.1, 0.7, 1.3, 0.01, 2.9, 0.33, 1e-4];
+		let demand = || -> HashMap<(i32, i32, i32), f32> {
+			let mut nodes: Vec<(i32, i32, i32)> = grid.nodes().keys().copied().collect();
+			nodes.sort();
+			nodes.into_iter().zip(weights.iter().copied()).collect()
+		};
+		let expected = weighted_median(&grid, &demand()).unwrap();
+		for _ in 0..20 {
+			let result = weighted_median(&grid, &demand()).unwrap();
+			assert_eq!(expected.0, result.0);
+			assert_eq!(expected.1.to_bits(), result.1.to_bits());
+		}
+	}
+}
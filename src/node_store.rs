@@ -0,0 +1,572 @@
+//! Storage of the complexity of each node in a grid.
+//!
+//! A `HashMap` keyed by node coordinates is the most flexible store as it supports any
+//! coordinate system and any shape of grid, however hashing a coordinate on every lookup is
+//! costly for large grids. For rectangular grids of Offset coordinates `DenseNodeStore` holds the
+//! complexities in a flat `Vec<f32>` where a node is found through its index:
+//!
+//! ```txt
+//! index = row * width + column
+//! ```
+//!
+//! For a grid 3 columns wide:
+//!
+//! ```txt
+//!              _______
+//!             /       \
+//!     _______/  (1,1)  \_______
+//!    /       \    4    /       \
+//!   /  (0,1)  \_______/  (2,1)  \
+//!   \    3    /       \    5    /
+//!    \_______/  (1,0)  \_______/
+//!    /       \    1    /       \
+//!   /  (0,0)  \_______/  (2,0)  \
+//!   \    0    /       \    2    /
+//!    \_______/         \_______/
+//! ```
+//!
+//! When several factions, or movement domains such as land, sea and air, share one map
+//! `LayeredNodes` holds a complexity per layer for each node, so a single grid can be searched
+//! with whichever layer suits the unit moving.
+//!
+//! Terrain whose cost is uncertain, e.g affected by weather, can be described with
+//! `UncertainNodes` where each node has a distribution of complexities. Searches plan with the
+//! expected complexity, optionally penalising unpredictable nodes to find safer routes.
+//!
+//! Worlds which are procedurally generated, and potentially infinite, cannot be held in memory up
+//! front. `ProceduralNodes` wraps a closure which supplies the complexity of a node on demand,
+//! only nodes touched by a search are ever generated.
+//!
+//! Sparse maps of any shape can be compacted with a `NodeIndex`, which numbers the nodes
+//! `0..n` and maps between coordinates and numbers in both directions. The numbering can be
+//! shared with serialisation or FFI layers which only need to send the numbers and a flat list of
+//! values, and `IndexedNodes` uses it to store complexities in a flat `Vec<f32>` like
+//! `DenseNodeStore` for grids which are not rectangular.
+
+use ::std::collections::HashMap;
+use ::std::hash::BuildHasher;
+use ::std::hash::Hash;
+
+/// Provides the complexity of traversing the nodes of a grid
+pub trait NodeStore<C> {
+	/// The complexity of traversing `node`, `None` if the node does not exist
+	fn complexity(&self, node: &C) -> Option<f32>;
+}
+
+/// A store whose nodes can be added, changed and removed after creation
+pub trait NodeStoreMut<C>: NodeStore<C> {
+	/// Set the complexity of traversing `node`, adding it to the store if it is absent. Returns
+	/// `false` if the store cannot hold `node`
+	fn insert(&mut self, node: C, complexity: f32) -> bool;
+	/// Remove `node` from the store returning its complexity, `None` if it was not present
+	fn remove(&mut self, node: &C) -> Option<f32>;
+}
+
+impl<C: Eq + Hash, H: BuildHasher> NodeStore<C> for HashMap<C, f32, H> {
+	fn complexity(&self, node: &C) -> Option<f32> {
+		self.get(node).copied()
+	}
+}
+
+impl<C: Eq + Hash, H: BuildHasher> NodeStoreMut<C> for HashMap<C, f32, H> {
+	fn insert(&mut self, node: C, complexity: f32) -> bool {
+		HashMap::insert(self, node, complexity);
+		true
+	}
+	fn remove(&mut self, node: &C) -> Option<f32> {
+		HashMap::remove(self, node)
+	}
+}
+
+/// A store of node complexities for a rectangular grid of Offset coordinates `(column, row)`
+/// with the bottom left node at `(0, 0)`, backed by a flat `Vec<f32>`.
+///
+/// A node can be removed from the store which leaves a hole in the grid, internally this is
+/// recorded as a complexity of `NaN`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DenseNodeStore {
+	/// Number of columns in the grid
+	width: i32,
+	/// Number of rows in the grid
+	height: i32,
+	/// Complexity of each node indexed by `row * width + column`
+	complexities: Vec<f32>,
+}
+
+impl DenseNodeStore {
+	/// Create a store of `width` columns and `height` rows where every node has the same `complexity`
+	pub fn new(width: i32, height: i32, complexity: f32) -> DenseNodeStore {
+		if width < 0 || height < 0 {
+			panic!(
+				"Dense node store cannot have negative dimensions ({},{})",
+				width, height
+			);
+		}
+		DenseNodeStore {
+			width,
+			height,
+			complexities: vec![complexity; (width * height) as usize],
+		}
+	}
+	/// Create a store of `width` columns and `height` rows from a map of node complexities. Nodes of
+	/// the grid absent from `nodes` are holes and nodes of `nodes` outside the grid are ignored
+	pub fn from_map<H: BuildHasher>(
+		width: i32,
+		height: i32,
+		nodes: &HashMap<(i32, i32), f32, H>,
+	) -> DenseNodeStore {
+		let mut store = DenseNodeStore::new(width, height, f32::NAN);
+		for (node, complexity) in nodes.iter() {
+			store.set_complexity(*node, *complexity);
+		}
+		store
+	}
+	/// Number of columns in the grid
+	pub fn width(&self) -> i32 {
+		self.width
+	}
+	/// Number of rows in the grid
+	pub fn height(&self) -> i32 {
+		self.height
+	}
+	/// The position of `node` in the underlying `Vec`, `None` if it lies outside the grid
+	pub fn index(&self, node: (i32, i32)) -> Option<usize> {
+		if node.0 < 0 || node.0 >= self.width || node.1 < 0 || node.1 >= self.height {
+			None
+		} else {
+			Some((node.1 * self.width + node.0) as usize)
+		}
+	}
+	/// Set the complexity of traversing `node`, returning `false` if it lies outside the grid
+	pub fn set_complexity(&mut self, node: (i32, i32), complexity: f32) -> bool {
+		match self.index(node) {
+			Some(i) => {
+				self.complexities[i] = complexity;
+				true
+			}
+			None => false,
+		}
+	}
+	/// Remove `node` from the store leaving a hole in the grid
+	pub fn remove(&mut self, node: (i32, i32)) {
+		self.set_complexity(node, f32::NAN);
+	}
+}
+
+impl NodeStore<(i32, i32)> for DenseNodeStore {
+	fn complexity(&self, node: &(i32, i32)) -> Option<f32> {
+		let complexity = self.complexities[self.index(*node)?];
+		if complexity.is_nan() {
+			None
+		} else {
+			Some(complexity)
+		}
+	}
+}
+
+impl NodeStoreMut<(i32, i32)> for DenseNodeStore {
+	fn insert(&mut self, node: (i32, i32), complexity: f32) -> bool {
+		self.set_complexity(node, complexity)
+	}
+	fn remove(&mut self, node: &(i32, i32)) -> Option<f32> {
+		let complexity = self.complexity(node)?;
+		DenseNodeStore::remove(self, *node);
+		Some(complexity)
+	}
+}
+
+/// A store holding several complexities for each node, one per layer, e.g a layer for land units
+/// and another for ships. A node impassable to a layer is given a complexity of `f32::INFINITY`
+/// in that layer.
+///
+/// As a `NodeStore` it reports the complexities of layer `0`, other layers are read with
+/// `layer_complexity` or searched through `HexGrid::layer`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayeredNodes<C: Eq + Hash> {
+	/// Number of layers held for each node
+	count_layers: usize,
+	/// The complexity of each node in each layer
+	nodes: HashMap<C, Vec<f32>>,
+}
+
+impl<C: Eq + Hash> LayeredNodes<C> {
+	/// Create an empty store with `count_layers` layers
+	pub fn new(count_layers: usize) -> LayeredNodes<C> {
+		LayeredNodes {
+			count_layers,
+			nodes: HashMap::new(),
+		}
+	}
+	/// Number of layers held for each node
+	pub fn count_layers(&self) -> usize {
+		self.count_layers
+	}
+	/// Add `node` with its complexity in each layer, replacing any existing complexities
+	pub fn insert(&mut self, node: C, complexities: Vec<f32>) {
+		if complexities.len() != self.count_layers {
+			panic!(
+				"Expected {} layer complexities, found {}",
+				self.count_layers,
+				complexities.len()
+			);
+		}
+		self.nodes.insert(node, complexities);
+	}
+	/// Remove `node` from every layer returning its complexities
+	pub fn remove(&mut self, node: &C) -> Option<Vec<f32>> {
+		self.nodes.remove(node)
+	}
+	/// The complexity of traversing `node` in `layer`, `None` if the node or layer does not exist
+	pub fn layer_complexity(&self, node: &C, layer: usize) -> Option<f32> {
+		self.nodes.get(node)?.get(layer).copied()
+	}
+	/// Set the complexity of an existing `node` in `layer`, returning `false` if the node or
+	/// layer does not exist
+	pub fn set_layer_complexity(&mut self, node: &C, layer: usize, complexity: f32) -> bool {
+		match self.nodes.get_mut(node).and_then(|c| c.get_mut(layer)) {
+			Some(c) => {
+				*c = complexity;
+				true
+			}
+			None => false,
+		}
+	}
+}
+
+impl<C: Eq + Hash> NodeStore<C> for LayeredNodes<C> {
+	fn complexity(&self, node: &C) -> Option<f32> {
+		self.layer_complexity(node, 0)
+	}
+}
+
+/// The distribution of the complexity of traversing a node, described by its mean and variance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostDistribution {
+	/// The expected complexity
+	pub mean: f32,
+	/// How widely the complexity varies around the mean
+	pub variance: f32,
+}
+
+impl CostDistribution {
+	/// A distribution with a given `mean` and `variance`
+	pub fn new(mean: f32, variance: f32) -> CostDistribution {
+		CostDistribution { mean, variance }
+	}
+	/// A complexity equally likely to be anywhere between `min` and `max`
+	pub fn uniform(min: f32, max: f32) -> CostDistribution {
+		CostDistribution {
+			mean: (min + max) * 0.5,
+			variance: (max - min) * (max - min) / 12.0,
+		}
+	}
+	/// The standard deviation of the complexity
+	pub fn std_dev(&self) -> f32 {
+		self.variance.sqrt()
+	}
+}
+
+/// A store where the complexity of each node is uncertain, each described by a
+/// `CostDistribution`.
+///
+/// The complexity reported to a search is the mean plus a number of standard deviations set by
+/// `risk_aversion`:
+///
+/// ```txt
+/// complexity = mean + risk_aversion * std_dev
+/// ```
+///
+/// A `risk_aversion` of `0.0` plans with the expected cost, larger values steer paths away from
+/// unpredictable nodes in favour of reliable ones
+#[derive(Debug, Clone, PartialEq)]
+pub struct UncertainNodes<C: Eq + Hash> {
+	/// The distribution of the complexity of each node
+	nodes: HashMap<C, CostDistribution>,
+	/// Standard deviations added to the mean complexity
+	risk_aversion: f32,
+}
+
+impl<C: Eq + Hash> UncertainNodes<C> {
+	/// Create a store from the distribution of each node planning with the expected cost
+	pub fn new(nodes: HashMap<C, CostDistribution>) -> UncertainNodes<C> {
+		UncertainNodes {
+			nodes,
+			risk_aversion: 0.0,
+		}
+	}
+	/// The distribution of the complexity of `node`
+	pub fn distribution(&self, node: &C) -> Option<CostDistribution> {
+		self.nodes.get(node).copied()
+	}
+	/// Set the distribution of the complexity of `node`, adding it if absent
+	pub fn insert(&mut self, node: C, distribution: CostDistribution) {
+		self.nodes.insert(node, distribution);
+	}
+	/// Remove `node` from the store returning its distribution
+	pub fn remove(&mut self, node: &C) -> Option<CostDistribution> {
+		self.nodes.remove(node)
+	}
+	/// Standard deviations added to the mean complexity of each node
+	pub fn risk_aversion(&self) -> f32 {
+		self.risk_aversion
+	}
+	/// Set how many standard deviations are added to the mean complexity of each node
+	pub fn set_risk_aversion(&mut self, risk_aversion: f32) {
+		self.risk_aversion = risk_aversion;
+	}
+}
+
+impl<C: Eq + Hash> NodeStore<C> for UncertainNodes<C> {
+	fn complexity(&self, node: &C) -> Option<f32> {
+		let d = self.nodes.get(node)?;
+		Some(d.mean + self.risk_aversion * d.std_dev())
+	}
+}
+
+/// A store which generates the complexity of each node on demand from a closure, for worlds too
+/// large (or infinite) to materialise up front. The closure returns `None` for nodes which do
+/// not exist
+pub struct ProceduralNodes<F> {
+	/// Supplies the complexity of a node
+	complexity: F,
+}
+
+impl<F> ProceduralNodes<F> {
+	/// Create a store which asks `complexity` for the complexity of each node
+	pub fn new(complexity: F) -> ProceduralNodes<F> {
+		ProceduralNodes { complexity }
+	}
+}
+
+impl<C, F: Fn(&C) -> Option<f32>> NodeStore<C> for ProceduralNodes<F> {
+	fn complexity(&self, node: &C) -> Option<f32> {
+		(self.complexity)(node)
+	}
+}
+
+/// A compact numbering of the nodes of a sparse map, mapping each coordinate to an id in
+/// `0..len()` and each id back to its coordinate. Ids are given in sorted coordinate order so the
+/// same nodes are always numbered the same way
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeIndex<C: Eq + Hash> {
+	/// The id of each node
+	ids: HashMap<C, usize>,
+	/// The node of each id
+	nodes: Vec<C>,
+}
+
+impl<C: Copy + Eq + Hash + Ord> NodeIndex<C> {
+	/// Number the given nodes, duplicates are numbered once
+	pub fn new<I: IntoIterator<Item = C>>(nodes: I) -> NodeIndex<C> {
+		let mut nodes: Vec<C> = nodes.into_iter().collect();
+		nodes.sort();
+		nodes.dedup();
+		let ids = nodes.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+		NodeIndex { ids, nodes }
+	}
+	/// Number the nodes of a map of complexities
+	pub fn from_map<H: BuildHasher>(nodes: &HashMap<C, f32, H>) -> NodeIndex<C> {
+		NodeIndex::new(nodes.keys().copied())
+	}
+	/// The id of `node`, `None` if it is not indexed
+	pub fn id(&self, node: &C) -> Option<usize> {
+		self.ids.get(node).copied()
+	}
+	/// The node numbered `id`, `None` if `id` is out of range
+	pub fn node(&self, id: usize) -> Option<C> {
+		self.nodes.get(id).copied()
+	}
+	/// Every indexed node in order of id
+	pub fn nodes(&self) -> &[C] {
+		&self.nodes
+	}
+	/// Number of indexed nodes
+	pub fn len(&self) -> usize {
+		self.nodes.len()
+	}
+	/// Whether no nodes are indexed
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty()
+	}
+	/// The values of a map laid out in order of id, indexed nodes absent from `map` take
+	/// `missing` and nodes of `map` which are not indexed are ignored
+	pub fn compact<V: Copy, H: BuildHasher>(&self, map: &HashMap<C, V, H>, missing: V) -> Vec<V> {
+		self.nodes
+			.iter()
+			.map(|n| map.get(n).copied().unwrap_or(missing))
+			.collect()
+	}
+	/// Rebuild a map from values laid out in order of id, as produced by `compact`. Panics if
+	/// there is not one value per indexed node
+	pub fn expand<V: Copy>(&self, values: &[V]) -> HashMap<C, V> {
+		if values.len() != self.nodes.len() {
+			panic!(
+				"Expected {} values, one per indexed node, found {}",
+				self.nodes.len(),
+				values.len()
+			);
+		}
+		self.nodes
+			.iter()
+			.copied()
+			.zip(values.iter().copied())
+			.collect()
+	}
+}
+
+/// A store of node complexities for a grid of any shape, backed by a flat `Vec<f32>` ordered by
+/// the ids of a `NodeIndex`. Only indexed nodes can be held, a removed node leaves a hole which
+/// internally is `f32::NAN` as with `DenseNodeStore`
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedNodes<C: Eq + Hash> {
+	/// The numbering of the nodes
+	index: NodeIndex<C>,
+	/// Complexity of each node in order of id
+	complexities: Vec<f32>,
+}
+
+impl<C: Copy + Eq + Hash + Ord> IndexedNodes<C> {
+	/// Compact a map of node complexities
+	pub fn from_map<H: BuildHasher>(nodes: &HashMap<C, f32, H>) -> IndexedNodes<C> {
+		let index = NodeIndex::from_map(nodes);
+		let complexities = index.compact(nodes, f32::NAN);
+		IndexedNodes {
+			index,
+			complexities,
+		}
+	}
+	/// The numbering of the nodes, which can be shared with other compacted data
+	pub fn index(&self) -> &NodeIndex<C> {
+		&self.index
+	}
+	/// Complexity of each node in order of id, holes being `f32::NAN`
+	pub fn complexities(&self) -> &[f32] {
+		&self.complexities
+	}
+}
+
+impl<C: Copy + Eq + Hash + Ord> NodeStore<C> for IndexedNodes<C> {
+	fn complexity(&self, node: &C) -> Option<f32> {
+		let complexity = self.complexities[self.index.id(node)?];
+		if complexity.is_nan() {
+			None
+		} else {
+			Some(complexity)
+		}
+	}
+}
+
+impl<C: Copy + Eq + Hash + Ord> NodeStoreMut<C> for IndexedNodes<C> {
+	fn insert(&mut self, node: C, complexity: f32) -> bool {
+		match self.index.id(&node) {
+			Some(i) => {
+				self.complexities[i] = complexity;
+				true
+			}
+			None => false,
+		}
+	}
+	fn remove(&mut self, node: &C) -> Option<f32> {
+		let complexity = self.complexity(node)?;
+		self.insert(*node, f32::NAN);
+		Some(complexity)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::node_store::*;
+
+	#[test]
+	/// Nodes are indexed by `row * width + column`
+	fn dense_index() {
+		let store = DenseNodeStore::new(3, 2, 1.0);
+		assert_eq!(Some(0), store.index((0, 0)));
+		assert_eq!(Some(2), store.index((2, 0)));
+		assert_eq!(Some(4), store.index((1, 1)));
+		assert_eq!(None, store.index((3, 0)));
+		assert_eq!(None, store.index((0, -1)));
+	}
+	#[test]
+	/// A dense store built from a map reports the same complexities as the map
+	fn dense_from_map() {
+		let mut nodes = HashMap::new();
+		nodes.insert((0, 0), 1.0);
+		nodes.insert((1, 1), 4.0);
+		nodes.insert((5, 5), 2.0);
+		let store = DenseNodeStore::from_map(2, 2, &nodes);
+		assert_eq!(Some(1.0), store.complexity(&(0, 0)));
+		assert_eq!(Some(4.0), store.complexity(&(1, 1)));
+		// absent from the map
+		assert_eq!(None, store.complexity(&(1, 0)));
+		// outside the grid
+		assert_eq!(None, store.complexity(&(5, 5)));
+	}
+	#[test]
+	/// Removing a node leaves a hole
+	fn dense_remove() {
+		let mut store = DenseNodeStore::new(2, 2, 1.0);
+		store.remove((1, 0));
+		assert_eq!(None, store.complexity(&(1, 0)));
+		assert_eq!(Some(1.0), store.complexity(&(0, 1)));
+	}
+	#[test]
+	/// A procedural store asks its closure for complexities
+	fn procedural_complexity() {
+		let store = ProceduralNodes::new(|node: &(i32, i32)| {
+			if node.0 < 0 {
+				None
+			} else {
+				Some(node.0 as f32)
+			}
+		});
+		assert_eq!(Some(3.0), store.complexity(&(3, 7)));
+		assert_eq!(None, store.complexity(&(-1, 0)));
+	}
+	#[test]
+	/// Each layer holds its own complexity for a node
+	fn layered_complexity() {
+		let mut store = LayeredNodes::new(2);
+		store.insert((0, 0), vec![1.0, f32::INFINITY]);
+		assert_eq!(Some(1.0), store.complexity(&(0, 0)));
+		assert_eq!(Some(f32::INFINITY), store.layer_complexity(&(0, 0), 1));
+		assert_eq!(None, store.layer_complexity(&(0, 0), 2));
+		assert!(store.set_layer_complexity(&(0, 0), 1, 3.0));
+		assert!(!store.set_layer_complexity(&(1, 0), 1, 3.0));
+		assert_eq!(Some(vec![1.0, 3.0]), store.remove(&(0, 0)));
+	}
+	#[test]
+	/// Uncertain nodes report their mean, plus a penalty for variance when risk averse
+	fn uncertain_complexity() {
+		let mut nodes = HashMap::new();
+		nodes.insert((0, 0), CostDistribution::new(2.0, 4.0));
+		nodes.insert((1, 0), CostDistribution::uniform(1.0, 3.0));
+		let mut store = UncertainNodes::new(nodes);
+		assert_eq!(Some(2.0), store.complexity(&(0, 0)));
+		assert_eq!(Some(2.0), store.complexity(&(1, 0)));
+		store.set_risk_aversion(1.5);
+		assert_eq!(Some(5.0), store.complexity(&(0, 0)));
+		assert_eq!(None, store.complexity(&(2, 0)));
+	}
+	#[test]
+	/// Nodes are numbered in sorted order and round trip through their ids and compacted values
+	fn index_round_trip() {
+		let mut nodes = HashMap::new();
+		nodes.insert((5, -2), 3.0);
+		nodes.insert((-1, 7), 1.0);
+		nodes.insert((0, 0), 2.0);
+		let index = NodeIndex::from_map(&nodes);
+		assert_eq!(&[(-1, 7), (0, 0), (5, -2)], index.nodes());
+		assert_eq!(Some(2), index.id(&(5, -2)));
+		assert_eq!(Some((0, 0)), index.node(1));
+		assert_eq!(None, index.id(&(1, 1)));
+		let values = index.compact(&nodes, 0.0);
+		assert_eq!(vec![1.0, 2.0, 3.0], values);
+		assert_eq!(nodes, index.expand(&values));
+		let mut store = IndexedNodes::from_map(&nodes);
+		assert_eq!(Some(3.0), store.complexity(&(5, -2)));
+		assert!(!NodeStoreMut::insert(&mut store, (1, 1), 1.0));
+		assert_eq!(Some(2.0), NodeStoreMut::remove(&mut store, &(0, 0)));
+		assert_eq!(None, store.complexity(&(0, 0)));
+	}
+}
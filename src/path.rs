@@ -0,0 +1,139 @@
+//! The result of a pathfinding query.
+//!
+//! A `Path` holds the ordered sequence of nodes to traverse, from the start node through to the
+//! end node, alongside the cumulative cost of reaching each node. The cost of moving from one
+//! node to the next is half the complexity of the node being left plus half the complexity of
+//! the node being entered, so for a path of three nodes with complexities `1`, `2` and `3`:
+//!
+//! ```txt
+//!    _______           _______           _______
+//!   /       \         /       \         /       \
+//!  /   C:1   \ ----> /   C:2   \ ----> /   C:3   \
+//!  \         /       \         /       \         /
+//!   \_______/         \_______/         \_______/
+//!     0.0               1.5               4.0
+//! ```
+//!
+//! The cumulative costs are `[0.0, 1.5, 4.0]` and the total cost of the path is `4.0`.
+
+/// An ordered sequence of nodes from a start node to an end node along with the cumulative
+/// traversal cost of reaching each node
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path<T> {
+	/// Nodes to traverse in order, the first being the start node and the last the end node
+	nodes: Vec<T>,
+	/// The cumulative cost of reaching each node in `nodes`
+	costs: Vec<f32>,
+}
+
+impl<T> Path<T> {
+	/// Create a new `Path` from a sequence of nodes and the cumulative cost of reaching each of
+	/// them. Panics if `nodes` and `costs` are of different lengths
+	pub fn new(nodes: Vec<T>, costs: Vec<f32>) -> Path<T> {
+		if nodes.len() != costs.len() {
+			panic!(
+				"Path has {} nodes but {} cumulative costs",
+				nodes.len(),
+				costs.len()
+			);
+		}
+		Path { nodes, costs }
+	}
+	/// The total cost of traversing the path from the start node to the end node
+	pub fn cost(&self) -> f32 {
+		match self.costs.last() {
+			Some(x) => *x,
+			None => 0.0,
+		}
+	}
+	/// The cumulative cost of reaching each node of the path, index `0` being the start node
+	pub fn cumulative_costs(&self) -> &[f32] {
+		&self.costs
+	}
+	/// The number of moves required to traverse the path, i.e the number of nodes minus the start node
+	pub fn hops(&self) -> usize {
+		self.nodes.len().saturating_sub(1)
+	}
+	/// The number of nodes in the path, including the start and end nodes
+	pub fn len(&self) -> usize {
+		self.nodes.len()
+	}
+	/// Whether the path contains no nodes at all
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty()
+	}
+	/// Iterate over the nodes of the path in order of traversal
+	pub fn iter(&self) -> std::slice::Iter<'_, T> {
+		self.nodes.iter()
+	}
+	/// The node the path begins at
+	pub fn first(&self) -> Option<&T> {
+		self.nodes.first()
+	}
+	/// The node the path ends at
+	pub fn last(&self) -> Option<&T> {
+		self.nodes.last()
+	}
+	/// The nodes of the path in order of traversal
+	pub fn nodes(&self) -> &[T] {
+		&self.nodes
+	}
+	/// Consume the path returning the nodes in order of traversal
+	pub fn into_nodes(self) -> Vec<T> {
+		self.nodes
+	}
+}
+
+impl<T: PartialEq> Path<T> {
+	/// Whether `node` is part of the path
+	pub fn contains(&self, node: &T) -> bool {
+		self.nodes.contains(node)
+	}
+}
+
+impl<T> IntoIterator for Path<T> {
+	type Item = T;
+	type IntoIter = std::vec::IntoIter<T>;
+	fn into_iter(self) -> Self::IntoIter {
+		self.nodes.into_iter()
+	}
+}
+
+impl<'a, T> IntoIterator for &'a Path<T> {
+	type Item = &'a T;
+	type IntoIter = std::slice::Iter<'a, T>;
+	fn into_iter(self) -> Self::IntoIter {
+		self.nodes.iter()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::path::Path;
+
+	#[test]
+	/// A path reports its total cost and number of moves
+	fn cost_and_hops() {
+		let path = Path::new(vec![(0, 0), (0, 1), (1, 1)], vec![0.0, 1.5, 4.0]);
+		assert_eq!(4.0, path.cost());
+		assert_eq!(2, path.hops());
+		assert_eq!(3, path.len());
+	}
+	#[test]
+	/// A path can be queried for its nodes
+	fn contains_and_last() {
+		let path = Path::new(vec![(0, 0), (0, 1), (1, 1)], vec![0.0, 1.5, 4.0]);
+		assert!(path.contains(&(0, 1)));
+		assert!(!path.contains(&(2, 2)));
+		assert_eq!(Some(&(0, 0)), path.first());
+		assert_eq!(Some(&(1, 1)), path.last());
+		let visited: Vec<&(i32, i32)> = path.iter().collect();
+		assert_eq!(vec![&(0, 0), &(0, 1), &(1, 1)], visited);
+	}
+	#[test]
+	#[should_panic]
+	/// Nodes and costs must line up
+	fn mismatched_costs() {
+		Path::new(vec![(0, 0), (0, 1)], vec![0.0]);
+	}
+}
@@ -0,0 +1,176 @@
+//! Remember the paths already found so asking for the same route again costs a lookup.
+//!
+//! Game AI commonly asks for the same handful of routes every frame. A `PathCache` keeps each
+//! path it finds, or that none exists, keyed by its start and end node. The cache knows nothing of
+//! changes made to the space searched, so it must be told of them:
+//!
+//! * `PathCache::clear` forgets everything, always correct
+//! * `PathCache::invalidate_through` forgets only paths crossing the given nodes, correct when
+//!   those nodes only became dearer or were removed, as no other path can have become cheaper
+//!
+//! Searching a `HexGrid` with `PathCache::grid_path` does this automatically, clearing the cache
+//! whenever the generation of the grid has advanced since the last search:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::path_cache::PathCache;
+//! use hexagonal_pathfinding_astar::test_support::axial_fixture;
+//! let mut grid = axial_fixture(4, 1);
+//! let mut cache = PathCache::new();
+//! let first = cache.grid_path(&grid, (0, 0), (4, 0));
+//! assert_eq!(first, cache.grid_path(&grid, (0, 0), (4, 0)));
+//! assert_eq!(1, cache.hits());
+//! grid.set_complexity((2, 0), 50.0);
+//! // the grid changed so the path is searched for again
+//! cache.grid_path(&grid, (0, 0), (4, 0));
+//! assert_eq!(1, cache.hits());
+//! ```
+
+use crate::grid::GridLayout;
+use crate::grid::HexGrid;
+use crate::node_store::NodeStore;
+use crate::path::Path;
+use crate::search::SearchContext;
+use crate::search::SearchSpace;
+use ::std::collections::HashMap;
+use ::std::hash::Hash;
+
+/// The paths found between pairs of nodes, see the module documentation
+#[derive(Debug, Clone)]
+pub struct PathCache<N: Copy + Eq + Hash> {
+	/// The path found from each start to each end, `None` where none exists
+	paths: HashMap<(N, N), Option<Path<N>>>,
+	/// The generation of the grid searched by `grid_path`, if it has been used
+	generation: Option<u64>,
+	/// Reused by each search
+	context: SearchContext<N>,
+	/// Number of queries answered from the cache
+	hits: usize,
+	/// Number of queries which needed a search
+	misses: usize,
+}
+
+impl<N: Copy + Eq + Hash> Default for PathCache<N> {
+	fn default() -> Self {
+		PathCache::new()
+	}
+}
+
+impl<N: Copy + Eq + Hash> PathCache<N> {
+	/// Create an empty cache
+	pub fn new() -> PathCache<N> {
+		PathCache {
+			paths: HashMap::new(),
+			generation: None,
+			context: SearchContext::new(),
+			hits: 0,
+			misses: 0,
+		}
+	}
+	/// Number of routes remembered, including those found not to exist
+	pub fn len(&self) -> usize {
+		self.paths.len()
+	}
+	/// Whether no routes are remembered
+	pub fn is_empty(&self) -> bool {
+		self.paths.is_empty()
+	}
+	/// Number of queries answered from the cache
+	pub fn hits(&self) -> usize {
+		self.hits
+	}
+	/// Number of queries which needed a search
+	pub fn misses(&self) -> usize {
+		self.misses
+	}
+	/// Forget every route
+	pub fn clear(&mut self) {
+		self.paths.clear();
+	}
+	/// Forget every path which passes through any of `nodes`, returning how many were forgotten.
+	/// Only correct when the nodes became dearer or were removed, otherwise use
+	/// `PathCache::clear`. Routes found not to exist are kept as they cannot have opened up
+	pub fn invalidate_through<I: IntoIterator<Item = N>>(&mut self, nodes: I) -> usize {
+		let nodes: Vec<N> = nodes.into_iter().collect();
+		let before = self.paths.len();
+		self.paths.retain(|_, path| match path {
+			Some(path) => !nodes.iter().any(|n| path.contains(n)),
+			None => true,
+		});
+		before - self.paths.len()
+	}
+	/// The cheapest path from `start_node` to `end_node` in `space`, searched with `astar_path`
+	/// only if it has not been already. Returns `None` if the end node cannot be reached
+	pub fn path<S: SearchSpace<Node = N>>(
+		&mut self,
+		space: &S,
+		start_node: N,
+		end_node: N,
+	) -> Option<Path<N>> {
+		if let Some(path) = self.paths.get(&(start_node, end_node)) {
+			self.hits += 1;
+			return path.clone();
+		}
+		self.misses += 1;
+		let path = self.context.astar_path(space, start_node, end_node);
+		self.paths.insert((start_node, end_node), path.clone());
+		path
+	}
+	/// As `PathCache::path`, first forgetting every route if `grid` has changed since the cache
+	/// last searched it
+	pub fn grid_path<L, S>(
+		&mut self,
+		grid: &HexGrid<L, S>,
+		start_node: N,
+		end_node: N,
+	) -> Option<Path<N>>
+	where
+		L: GridLayout<Coord = N>,
+		S: NodeStore<N>,
+	{
+		if self.generation != Some(grid.generation()) {
+			self.clear();
+			self.generation = Some(grid.generation());
+		}
+		self.path(grid, start_node, end_node)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::path_cache::*;
+	use crate::search::astar_path;
+	use crate::test_support::axial_fixture;
+
+	#[test]
+	/// Repeated queries are answered from the cache, and forgetting paths through a node made
+	/// dearer leaves the others while fresh searches remain the cheapest
+	fn forgets_paths_through_changes() {
+		let mut grid = axial_fixture(4, 2);
+		let mut cache = PathCache::new();
+		let ends = [(4, 0), (0, 4), (-4, 0), (0, -4), (9, 9)];
+		for _ in 0..3 {
+			for end in ends {
+				assert_eq!(
+					astar_path(&grid, (0, 0), end),
+					cache.path(&grid, (0, 0), end)
+				);
+			}
+		}
+		assert_eq!(5, cache.misses());
+		assert_eq!(10, cache.hits());
+		assert_eq!(5, cache.len());
+		let crossed = cache.path(&grid, (0, 0), (4, 0)).unwrap().nodes()[2];
+		grid.set_complexity(crossed, 100.0);
+		let forgotten = cache.invalidate_through([crossed]);
+		assert!(forgotten >= 1);
+		assert_eq!(5 - forgotten, cache.len());
+		for end in ends {
+			assert_eq!(
+				astar_path(&grid, (0, 0), end),
+				cache.path(&grid, (0, 0), end)
+			);
+		}
+		cache.clear();
+		assert!(cache.is_empty());
+	}
+}
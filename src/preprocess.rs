@@ -0,0 +1,196 @@
+//! Work done once up front for maps which never change, making every later search faster.
+//!
+//! `GoalBounds` implements goal bounding, by Rabin and Sturtevant. For every node and each of its
+//! neighbours it stores the bounding box of the nodes whose cheapest path from the node begins by
+//! stepping to that neighbour. A search towards a goal outside the box has no reason to take the
+//! step, so `GoalBounds::astar_path` never does, typically expanding a small fraction of the nodes
+//! of `search::astar_path()` while still finding the cheapest path.
+//!
+//! Coordinates are pairs, Axial `(q, r)` or Offset `(column, row)`, the boxes being the range of
+//! each part. Preprocessing searches from every node so takes time growing with the square of the
+//! number of nodes, suiting a build step, and any change to the map means preprocessing again:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::preprocess::GoalBounds;
+//! use hexagonal_pathfinding_astar::search::astar_path;
+//! use hexagonal_pathfinding_astar::test_support::axial_fixture;
+//! let grid = axial_fixture(4, 1);
+//! let bounds = GoalBounds::new(&grid, grid.nodes().keys().copied());
+//! let path = bounds.astar_path(&grid, (0, 0), (4, -2));
+//! assert_eq!(astar_path(&grid, (0, 0), (4, -2)), path);
+//! ```
+
+use crate::path::Path;
+use crate::search::astar_path;
+use crate::search::MinScored;
+use crate::search::SearchSpace;
+use crate::GridBounds;
+use ::std::collections::BinaryHeap;
+use ::std::collections::HashMap;
+use ::std::collections::HashSet;
+
+/// The neighbours a node steps to first along a cheapest path, with the bounding box of the goals
+/// reached that way
+type FirstSteps = Vec<((i32, i32), GridBounds)>;
+
+/// The bounding boxes of the goals reached through each step from each node, see the module
+/// documentation
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalBounds {
+	/// The first steps from each node
+	boxes: HashMap<(i32, i32), FirstSteps>,
+}
+
+impl GoalBounds {
+	/// Preprocess `nodes` of `space`, only paths which stay within `nodes` are considered.
+	/// Duplicates are ignored
+	pub fn new<S, I>(space: &S, nodes: I) -> GoalBounds
+	where
+		S: SearchSpace<Node = (i32, i32)>,
+		I: IntoIterator<Item = (i32, i32)>,
+	{
+		let nodes: HashSet<(i32, i32)> = nodes.into_iter().collect();
+		let mut boxes = HashMap::new();
+		for origin in nodes.iter() {
+			// the cheapest cost of reaching each node and the first step taken to get there
+			let mut costs: HashMap<(i32, i32), (f32, (i32, i32))> = HashMap::new();
+			let mut queue = BinaryHeap::new();
+			costs.insert(*origin, (0.0, *origin));
+			queue.push(MinScored(0.0, *origin));
+			while let Some(MinScored(cost, node)) = queue.pop() {
+				let (best, first_step) = costs[&node];
+				// skip stale entries where a cheaper route has since been found
+				if cost > best {
+					continue;
+				}
+				for (n, step_complexity) in space.successors(node) {
+					if !nodes.contains(&n) {
+						continue;
+					}
+					let complexity = cost + step_complexity;
+					let is_better = match costs.get(&n) {
+						Some((existing, _)) => complexity < *existing,
+						None => true,
+					};
+					if is_better {
+						// neighbours of the origin are their own first step
+						let step = if node == *origin { n } else { first_step };
+						costs.insert(n, (complexity, step));
+						queue.push(MinScored(complexity, n));
+					}
+				}
+			}
+			let mut steps: FirstSteps = Vec::new();
+			for (goal, (_, step)) in costs.iter() {
+				if goal == origin {
+					continue;
+				}
+				match steps.iter_mut().find(|(s, _)| s == step) {
+					Some((_, bounds)) => {
+						bounds.min_column = bounds.min_column.min(goal.0);
+						bounds.max_column = bounds.max_column.max(goal.0);
+						bounds.min_row = bounds.min_row.min(goal.1);
+						bounds.max_row = bounds.max_row.max(goal.1);
+					}
+					None => steps.push((*step, GridBounds::new(goal.0, goal.0, goal.1, goal.1))),
+				}
+			}
+			boxes.insert(*origin, steps);
+		}
+		GoalBounds { boxes }
+	}
+	/// The bounding box of the goals whose cheapest path from `node` begins by stepping to
+	/// `neighbour`, `None` if no cheapest path does or `node` was not preprocessed
+	pub fn bounds(&self, node: (i32, i32), neighbour: (i32, i32)) -> Option<GridBounds> {
+		self.boxes
+			.get(&node)?
+			.iter()
+			.find(|(step, _)| *step == neighbour)
+			.map(|(_, bounds)| *bounds)
+	}
+	/// Whether stepping from `node` to `neighbour` might lie on a cheapest path to `goal`, always
+	/// `true` for nodes which were not preprocessed
+	pub fn is_useful(&self, node: (i32, i32), neighbour: (i32, i32), goal: (i32, i32)) -> bool {
+		match self.boxes.get(&node) {
+			Some(steps) => steps
+				.iter()
+				.any(|(step, bounds)| *step == neighbour && bounds.contains(goal)),
+			None => true,
+		}
+	}
+	/// From a starting node calculate the most efficient path to the end node as
+	/// `search::astar_path()`, skipping every step which the bounding boxes rule out. `space`
+	/// must be the space which was preprocessed and unchanged since. Returns `None` if the end
+	/// node cannot be reached
+	pub fn astar_path<S: SearchSpace<Node = (i32, i32)>>(
+		&self,
+		space: &S,
+		start_node: (i32, i32),
+		end_node: (i32, i32),
+	) -> Option<Path<(i32, i32)>> {
+		let bounded = Bounded {
+			space,
+			bounds: self,
+			goal: end_node,
+		};
+		astar_path(&bounded, start_node, end_node)
+	}
+}
+
+/// A space whose successors are limited to the steps useful for reaching `goal`
+struct Bounded<'a, S> {
+	/// The space searched
+	space: &'a S,
+	/// Rules out steps
+	bounds: &'a GoalBounds,
+	/// Where the search ends
+	goal: (i32, i32),
+}
+
+impl<S: SearchSpace<Node = (i32, i32)>> SearchSpace for Bounded<'_, S> {
+	type Node = (i32, i32);
+	fn successors(&self, node: (i32, i32)) -> Vec<((i32, i32), f32)> {
+		self.space
+			.successors(node)
+			.into_iter()
+			.filter(|(n, _)| self.bounds.is_useful(node, *n, self.goal))
+			.collect()
+	}
+	fn heuristic(&self, node: (i32, i32), goal: (i32, i32)) -> f32 {
+		self.space.heuristic(node, goal)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::preprocess::*;
+	use crate::search::astar_path_with_stats;
+	use crate::test_support::assert_path_optimal;
+	use crate::test_support::axial_fixture;
+
+	#[test]
+	/// Pruned searches find paths as cheap as a full search while expanding fewer nodes, and
+	/// unreachable goals stay unreachable
+	fn prunes_without_losing_paths() {
+		for seed in 0..3 {
+			let grid = axial_fixture(5, seed);
+			let bounds = GoalBounds::new(&grid, grid.nodes().keys().copied());
+			let mut full_expanded = 0;
+			let mut pruned_expanded = 0;
+			for goal in [(5, 0), (-5, 5), (0, -5), (3, -4), (9, 9), (0, 0)] {
+				let path = bounds.astar_path(&grid, (0, 0), goal);
+				assert_path_optimal(&grid, (0, 0), goal, path.as_ref());
+				let bounded = Bounded {
+					space: &grid,
+					bounds: &bounds,
+					goal,
+				};
+				pruned_expanded += astar_path_with_stats(&bounded, (0, 0), goal)
+					.1
+					.nodes_expanded;
+				full_expanded += astar_path_with_stats(&grid, (0, 0), goal).1.nodes_expanded;
+			}
+			assert!(pruned_expanded < full_expanded);
+		}
+	}
+}
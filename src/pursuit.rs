@@ -0,0 +1,212 @@
+//! Chase a target which moves between searches, Generalized Adaptive A*.
+//!
+//! A unit pursuing another searches again every tick as both move a hexagon or two, and each
+//! search covers much the same ground as the last. `Pursuit` remembers what each search learned:
+//! once a path is found, every node the search expanded is known to cost at least the rest of
+//! the path to the target. Those costs are far better estimates than the heuristic of the
+//! search space, so the next search heads for the target with fewer detours.
+//!
+//! When the target moves the learned costs point at the wrong place. Following Sun, Koenig and
+//! Yeoh each is corrected by subtracting what was learned about the target's new position, which
+//! keeps every estimate a lower bound on the true cost, so each path found is still the cheapest.
+//!
+//! Learned costs assume no step gets cheaper. After costs of the space fall, e.g a wall is
+//! removed, call `Pursuit::reset` to forget them.
+
+use crate::path::Path;
+use crate::search::Limits;
+use crate::search::SearchContext;
+use crate::search::SearchHooks;
+use crate::search::SearchSpace;
+use ::std::collections::HashMap;
+use ::std::hash::Hash;
+
+/// Finds paths to a moving target, reusing the estimates learned by each search, see the module
+/// documentation
+#[derive(Debug, Clone)]
+pub struct Pursuit<N: Copy + Eq + Hash> {
+	/// The target of the last search
+	target: Option<N>,
+	/// The least the rest of a path to `target` can cost from each node learned so far
+	learned: HashMap<N, f32>,
+	/// Number of nodes expanded by the last search
+	expansions: usize,
+}
+
+impl<N: Copy + Eq + Hash> Default for Pursuit<N> {
+	fn default() -> Self {
+		Pursuit::new()
+	}
+}
+
+impl<N: Copy + Eq + Hash> Pursuit<N> {
+	/// Create a pursuit which has learned nothing yet
+	pub fn new() -> Pursuit<N> {
+		Pursuit {
+			target: None,
+			learned: HashMap::new(),
+			expansions: 0,
+		}
+	}
+	/// Number of nodes expanded by the last search
+	pub fn expansions(&self) -> usize {
+		self.expansions
+	}
+	/// Forget everything learned, needed after any step of the space gets cheaper
+	pub fn reset(&mut self) {
+		self.target = None;
+		self.learned.clear();
+	}
+	/// The estimate of the cost from `node` to `target`, the better of what has been learned and
+	/// the heuristic of `space`
+	fn estimate<S: SearchSpace<Node = N>>(&self, space: &S, node: N, target: N) -> f32 {
+		let heuristic = space.heuristic(node, target);
+		match self.learned.get(&node) {
+			Some(learned) => learned.max(heuristic),
+			None => heuristic,
+		}
+	}
+	/// Find the cheapest path from `start` to where `target` is now. Returns `None` if the
+	/// target cannot be reached
+	pub fn chase<S: SearchSpace<Node = N>>(
+		&mut self,
+		space: &S,
+		start: N,
+		target: N,
+	) -> Option<Path<N>> {
+		if let Some(previous) = self.target {
+			if previous != target {
+				// anything learned about the old target is lowered by what was learned of the
+				// new target's position, keeping every estimate a lower bound
+				let shift = self.estimate(space, target, previous);
+				for learned in self.learned.values_mut() {
+					*learned -= shift;
+				}
+			}
+		}
+		self.target = Some(target);
+		let mut hooks = Chase {
+			pursuit: self,
+			space,
+			target,
+			expanded: Vec::new(),
+		};
+		let mut context = SearchContext::new();
+		let reached = context.run(&[start], &mut hooks, &Limits::expansions(usize::MAX));
+		let expanded = hooks.expanded;
+		self.expansions = context.stats().nodes_expanded;
+		let end = reached.ok()?;
+		let path = context.path_to(end);
+		// every expanded node is at least the rest of this path away from the target
+		for node in expanded {
+			if let Some(cost) = context.cost(node) {
+				self.learned.insert(node, path.cost() - cost);
+			}
+		}
+		Some(path)
+	}
+}
+
+/// The hooks of a search of `Pursuit::chase`, estimating with what has been learned and noting
+/// each node expanded
+struct Chase<'a, N: Copy + Eq + Hash, S> {
+	/// The pursuit searching
+	pursuit: &'a Pursuit<N>,
+	/// The space being searched
+	space: &'a S,
+	/// Where the target is now
+	target: N,
+	/// The nodes expanded so far
+	expanded: Vec<N>,
+}
+
+impl<'a, N: Copy + Eq + Hash, S: SearchSpace<Node = N>> SearchHooks<N> for Chase<'a, N, S> {
+	fn estimate(&self, node: N) -> f32 {
+		self.pursuit.estimate(self.space, node, self.target)
+	}
+	fn is_goal(&self, node: N) -> bool {
+		node == self.target
+	}
+	fn successors(&self, node: N, _cost: f32) -> Vec<(N, f32)> {
+		self.space.successors(node)
+	}
+	fn expanding(&mut self, node: N) {
+		self.expanded.push(node);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::*;
+	use crate::pursuit::*;
+
+	/// A hexagon of three rings around the origin split by a wall `#` open only at its top end
+	fn walled() -> HexGrid<AxialLayout> {
+		let mut nodes = HashMap::new();
+		for q in -3..=3_i32 {
+			for r in (-3).max(-q - 3)..=3.min(-q + 3) {
+				nodes.insert((q, r), 1.0);
+			}
+		}
+		for r in -3..=1 {
+			nodes.insert((1, r), f32::INFINITY);
+		}
+		HexGrid::new(AxialLayout::new(3), nodes)
+	}
+	#[test]
+	/// A pursuer stepping from `1` to `4` around the end of the wall chases a target moving from
+	/// `a` to `d`. Each path is the cheapest, and once the first search has learned how far the
+	/// wall sends it out of its way the pursuit expands fewer nodes than starting afresh
+	/// ```txt
+	///   column:  -3  -2  -1   0   1   2   3
+	///                         .
+	///                     .       4
+	///                 .       3       .
+	///             .       .       #       .
+	///                 .       2       .
+	///             .       .       #       .
+	///                 .       .       .
+	///             .       1       #       a
+	///                 .       .       d
+	///             .       .       #       b
+	///                 .       .       c
+	///                     .       #
+	///                         .
+	/// ```
+	fn chases_moving_target() {
+		let grid = walled();
+		let chases = [
+			((-1, 0), (3, -2), 8.0),
+			((0, 1), (3, -3), 7.0),
+			((0, 2), (2, -3), 6.0),
+			((1, 2), (2, -2), 4.0),
+		];
+		let mut pursuit = Pursuit::new();
+		for (i, (pursuer, target, cost)) in chases.iter().enumerate() {
+			let path = pursuit.chase(&grid, *pursuer, *target).unwrap();
+			assert_eq!(Some(pursuer), path.first());
+			assert_eq!(Some(target), path.last());
+			assert_eq!(*cost, path.cost());
+			let mut afresh = Pursuit::new();
+			afresh.chase(&grid, *pursuer, *target);
+			match i {
+				0 => assert_eq!(afresh.expansions(), pursuit.expansions()),
+				1 | 2 => assert!(pursuit.expansions() < afresh.expansions()),
+				_ => assert!(pursuit.expansions() <= afresh.expansions()),
+			}
+		}
+	}
+	#[test]
+	/// Once a gap opens in the middle of the wall and what was learned is forgotten, the pursuit
+	/// takes the straight path through the gap
+	fn reset_after_costs_fall() {
+		let mut grid = walled();
+		let mut pursuit = Pursuit::new();
+		assert_eq!(8.0, pursuit.chase(&grid, (-1, 0), (3, -2)).unwrap().cost());
+		grid.set_complexity((1, 0), 1.0);
+		pursuit.reset();
+		let path = pursuit.chase(&grid, (-1, 0), (3, -2)).unwrap();
+		assert_eq!(&[(-1, 0), (0, 0), (1, 0), (2, -1), (3, -2)], path.nodes());
+		assert_eq!(4.0, path.cost());
+	}
+}
@@ -0,0 +1,334 @@
+//! A rectangular Offset grid stored as a flat array, with an A* search which never hashes.
+//!
+//! Most tile based games already hold their map as rows of tiles. A `RectGrid` holds the
+//! complexity of each node in a `DenseNodeStore` with the bounds of the grid baked in, so a node's
+//! position in memory is found from its column and row alone. `RectGrid::astar_path` keeps the
+//! cost and previous node of each discovered node in arrays laid out the same way, and the nodes
+//! already expanded in a bitset, rather than in maps, which avoids hashing a coordinate at every
+//! step of the search:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::rect_grid::RectGrid;
+//! use hexagonal_pathfinding_astar::HexOrientation;
+//! let mut grid = RectGrid::new(HexOrientation::FlatTopOddUp, 512, 512, 1.0);
+//! grid.set_complexity((0, 3), 10.0);
+//! let path = grid.astar_path((0, 0), (0, 5)).unwrap();
+//! assert_eq!(5.0 + 1.0, path.cost());
+//! ```
+//!
+//! A `RectGrid` is also a `SearchSpace`, so every search of `search` can be used on it.
+
+use crate::grid::GridLayout;
+use crate::grid::OffsetLayout;
+use crate::node_store::DenseNodeStore;
+use crate::node_store::NodeStore;
+use crate::path::Path;
+use crate::search::begin_astar;
+use crate::search::memory_path;
+use crate::search::run_astar;
+use crate::search::towards;
+use crate::search::Limits;
+use crate::search::SearchMemory;
+use crate::search::SearchSpace;
+use crate::search::SearchStats;
+use crate::HexOrientation;
+use ::std::collections::BinaryHeap;
+
+/// Marks a node which was not reached from another, the start or an undiscovered node
+const NO_NODE: u32 = u32::MAX;
+
+/// One bit for each node of a grid, a node's bit at the same index as its complexity in the store
+#[derive(Debug, Clone, PartialEq)]
+struct NodeBits {
+	/// The bits, 64 nodes to a word
+	words: Vec<u64>,
+}
+
+impl NodeBits {
+	/// Create a set of `count` bits all clear
+	fn new(count: usize) -> NodeBits {
+		NodeBits {
+			words: vec![0; (count + 63) / 64],
+		}
+	}
+	/// Whether the bit at `index` is set
+	fn contains(&self, index: usize) -> bool {
+		self.words[index / 64] & (1 << (index % 64)) != 0
+	}
+	/// Set the bit at `index`
+	fn insert(&mut self, index: usize) {
+		self.words[index / 64] |= 1 << (index % 64);
+	}
+	/// Clear the bit at `index`
+	fn remove(&mut self, index: usize) {
+		self.words[index / 64] &= !(1 << (index % 64));
+	}
+}
+
+/// The cost and previous node of each node of a grid discovered by a search, and whether it has
+/// been expanded at that cost, indexed as the complexities of the store
+#[derive(Debug, Clone, PartialEq)]
+struct NodeArrays {
+	/// Number of columns in the grid
+	width: i32,
+	/// The cheapest known complexity of reaching each node, infinite until it is discovered
+	costs: Vec<f32>,
+	/// The index of the node each node was cheapest reached from
+	came_from: Vec<u32>,
+	/// The nodes expanded at their cheapest known complexity, one bit a node so a 1024 by 1024
+	/// grid needs 128KB
+	closed: NodeBits,
+}
+
+impl NodeArrays {
+	/// Create arrays for a grid of `width` columns and `count` nodes with nothing discovered
+	fn new(width: i32, count: usize) -> NodeArrays {
+		NodeArrays {
+			width,
+			costs: vec![f32::INFINITY; count],
+			came_from: vec![NO_NODE; count],
+			closed: NodeBits::new(count),
+		}
+	}
+	/// The index of `node`, which must lie within the grid
+	fn index(&self, node: (i32, i32)) -> usize {
+		(node.1 * self.width + node.0) as usize
+	}
+}
+
+impl SearchMemory<(i32, i32)> for NodeArrays {
+	fn clear(&mut self) {
+		self.costs.fill(f32::INFINITY);
+		self.came_from.fill(NO_NODE);
+		self.closed.words.fill(0);
+	}
+	fn cost(&self, node: (i32, i32)) -> Option<f32> {
+		let cost = self.costs[self.index(node)];
+		if cost.is_finite() {
+			Some(cost)
+		} else {
+			None
+		}
+	}
+	fn came_from(&self, node: (i32, i32)) -> Option<(i32, i32)> {
+		match self.came_from[self.index(node)] {
+			NO_NODE => None,
+			index => Some((index as i32 % self.width, index as i32 / self.width)),
+		}
+	}
+	fn insert(&mut self, node: (i32, i32), cost: f32, came_from: Option<(i32, i32)>) {
+		let index = self.index(node);
+		self.costs[index] = cost;
+		// a cheaper route to an expanded node opens it again
+		self.closed.remove(index);
+		self.came_from[index] = match came_from {
+			Some(previous) => self.index(previous) as u32,
+			None => NO_NODE,
+		};
+	}
+	fn is_closed(&self, node: (i32, i32)) -> bool {
+		self.closed.contains(self.index(node))
+	}
+	fn close(&mut self, node: (i32, i32)) {
+		let index = self.index(node);
+		self.closed.insert(index);
+	}
+}
+
+/// A rectangular grid of Offset coordinates `(column, row)` with the bottom left node at
+/// `(0, 0)`, backed by a flat array, see the module documentation
+#[derive(Debug, Clone, PartialEq)]
+pub struct RectGrid {
+	/// How the nodes are arranged
+	layout: OffsetLayout,
+	/// The complexity of traversing each node
+	nodes: DenseNodeStore,
+}
+
+impl RectGrid {
+	/// Create a grid of `width` columns and `height` rows in `orientation` where every node has
+	/// the same `complexity`
+	pub fn new(orientation: HexOrientation, width: i32, height: i32, complexity: f32) -> RectGrid {
+		RectGrid::from_store(orientation, DenseNodeStore::new(width, height, complexity))
+	}
+	/// Create a grid in `orientation` covering every node of `nodes`
+	pub fn from_store(orientation: HexOrientation, nodes: DenseNodeStore) -> RectGrid {
+		let layout = OffsetLayout::new(orientation, -1, nodes.width(), -1, nodes.height());
+		RectGrid { layout, nodes }
+	}
+	/// How the nodes are arranged
+	pub fn layout(&self) -> &OffsetLayout {
+		&self.layout
+	}
+	/// The complexity of each node
+	pub fn nodes(&self) -> &DenseNodeStore {
+		&self.nodes
+	}
+	/// Number of columns in the grid
+	pub fn width(&self) -> i32 {
+		self.nodes.width()
+	}
+	/// Number of rows in the grid
+	pub fn height(&self) -> i32 {
+		self.nodes.height()
+	}
+	/// The complexity of traversing `node`, `None` if it lies outside the grid or is a hole
+	pub fn complexity(&self, node: (i32, i32)) -> Option<f32> {
+		self.nodes.complexity(&node)
+	}
+	/// Set the complexity of traversing `node`, returning `false` if it lies outside the grid
+	pub fn set_complexity(&mut self, node: (i32, i32), complexity: f32) -> bool {
+		self.nodes.set_complexity(node, complexity)
+	}
+	/// The cost of stepping from `from` to its neighbour `to`, `None` if either node cannot be
+	/// traversed
+	fn step_cost(&self, from: (i32, i32), to: (i32, i32)) -> Option<f32> {
+		let a = self.complexity(from)?;
+		let b = self.complexity(to)?;
+		if !a.is_finite() || !b.is_finite() {
+			return None;
+		}
+		// moving between nodes costs half the complexity of each
+		Some(a * 0.5 + b * 0.5)
+	}
+	/// From a starting node calculate the most efficient path to the end node, the same path
+	/// `search::astar_path` finds for this grid. Returns `None` if either node lies outside the
+	/// grid or the end node cannot be reached
+	pub fn astar_path(
+		&self,
+		start_node: (i32, i32),
+		end_node: (i32, i32),
+	) -> Option<Path<(i32, i32)>> {
+		self.nodes.index(start_node)?;
+		self.nodes.index(end_node)?;
+		// neighbours always lie within the grid so every node discovered has a place in the arrays
+		let count = (self.width() * self.height()) as usize;
+		let mut memory = NodeArrays::new(self.width(), count);
+		let mut queue = BinaryHeap::new();
+		let mut hooks = towards(self, end_node);
+		begin_astar(&mut memory, &mut queue, &[start_node], &hooks);
+		let limits = Limits::expansions(usize::MAX);
+		let mut stats = SearchStats::default();
+		let end = run_astar(&mut memory, &mut queue, &mut hooks, &limits, &mut stats).ok()?;
+		Some(memory_path(&memory, end))
+	}
+}
+
+impl SearchSpace for RectGrid {
+	type Node = (i32, i32);
+	fn successors(&self, node: (i32, i32)) -> Vec<((i32, i32), f32)> {
+		if self.complexity(node).is_none() {
+			return Vec::new();
+		}
+		let mut successors = Vec::new();
+		for n in self.layout.neighbours(node) {
+			if let Some(cost) = self.step_cost(node, n) {
+				successors.push((n, cost));
+			}
+		}
+		successors
+	}
+	fn heuristic(&self, node: (i32, i32), goal: (i32, i32)) -> f32 {
+		self.layout.distance(node, goal) as f32
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::HexGrid;
+	use crate::rect_grid::*;
+	use crate::search;
+	use crate::test_support::assert_path_optimal;
+
+	#[test]
+	/// The array backed search finds the path the general search does on a `HexGrid` of the
+	/// same nodes, in every orientation, and nothing for nodes beyond the grid
+	fn matches_hex_grid() {
+		let orientations = [
+			HexOrientation::FlatTopOddUp,
+			HexOrientation::FlatTopOddDown,
+			HexOrientation::PointyTopOddRight,
+			HexOrientation::PointyTopOddLeft,
+		];
+		for orientation in orientations {
+			let mut nodes = DenseNodeStore::new(12, 9, 1.0);
+			for column in 0..12i32 {
+				for row in 0..9i32 {
+					let complexity = ((column * 7 + row * 13) % 5) as f32 + 1.0;
+					nodes.set_complexity((column, row), complexity);
+				}
+			}
+			nodes.remove((4, 4));
+			nodes.set_complexity((5, 2), f32::INFINITY);
+			let grid = RectGrid::from_store(orientation, nodes.clone());
+			let layout = OffsetLayout::new(orientation, -1, 12, -1, 9);
+			let hex_grid = HexGrid::new(layout, nodes);
+			for end in [(11, 8), (0, 8), (11, 0), (4, 4), (6, 3)] {
+				let path = grid.astar_path((0, 0), end);
+				assert_path_optimal(&grid, (0, 0), end, path.as_ref());
+				let expected = search::astar_path(&hex_grid, (0, 0), end);
+				assert_eq!(expected.map(|p| p.cost()), path.map(|p| p.cost()));
+			}
+			assert!(grid.astar_path((0, 0), (12, 0)).is_none());
+		}
+	}
+	#[test]
+	/// The bits of nodes are set and cleared independently, across word boundaries
+	fn node_bits() {
+		let mut bits = NodeBits::new(130);
+		assert_eq!(3, bits.words.len());
+		for index in [0, 63, 64, 129] {
+			assert!(!bits.contains(index));
+			bits.insert(index);
+			assert!(bits.contains(index));
+		}
+		bits.remove(63);
+		assert!(!bits.contains(63));
+		assert!(bits.contains(64));
+	}
+	#[test]
+	/// The arrays of a search record each node apart from its neighbours in every row, reopen an
+	/// expanded node reached more cheaply and forget them all when cleared
+	fn node_arrays() {
+		let mut memory = NodeArrays::new(7, 7 * 3);
+		assert_eq!(None, memory.cost((6, 2)));
+		memory.insert((0, 0), 0.0, None);
+		memory.insert((6, 0), 2.5, Some((0, 0)));
+		memory.insert((0, 1), 3.0, Some((6, 0)));
+		memory.insert((6, 2), 4.0, Some((0, 1)));
+		assert_eq!(Some(0.0), memory.cost((0, 0)));
+		assert_eq!(None, memory.came_from((0, 0)));
+		assert_eq!(Some((6, 0)), memory.came_from((0, 1)));
+		assert_eq!(Some((0, 1)), memory.came_from((6, 2)));
+		assert_eq!(None, memory.cost((1, 0)));
+		memory.close((6, 0));
+		assert!(memory.is_closed((6, 0)));
+		assert!(!memory.is_closed((0, 1)));
+		memory.insert((6, 0), 2.0, Some((0, 0)));
+		assert!(!memory.is_closed((6, 0)));
+		memory.close((6, 2));
+		memory.clear();
+		assert_eq!(None, memory.cost((6, 2)));
+		assert_eq!(None, memory.came_from((6, 2)));
+		assert!(!memory.is_closed((6, 2)));
+	}
+	#[test]
+	/// Nodes cheaper than the distance heuristic assumes make it overestimate, so expanded nodes
+	/// are later reached more cheaply and must be opened again to find the path the general
+	/// search finds
+	fn reopens_closed_nodes() {
+		let mut grid = RectGrid::new(HexOrientation::FlatTopOddUp, 10, 10, 2.0);
+		for row in 0..10 {
+			grid.set_complexity((3, row), 0.1);
+			grid.set_complexity((row, 7), 0.1);
+		}
+		let hex_grid = HexGrid::new(*grid.layout(), grid.nodes().clone());
+		for end in [(9, 9), (9, 0), (0, 9), (5, 5)] {
+			let expected = search::astar_path(&hex_grid, (0, 0), end).unwrap();
+			assert_eq!(
+				expected.cost(),
+				grid.astar_path((0, 0), end).unwrap().cost()
+			);
+		}
+	}
+}
@@ -0,0 +1,464 @@
+//! Divide a grid into regions joined by chokepoints.
+//!
+//! Strategic AI rarely cares about individual hexagons, it reasons about areas of a map and the
+//! narrow passages connecting them. The grid is decomposed in three steps:
+//!
+//! 1. The clearance of every passable node is measured, this is the number of jumps to the nearest
+//!    obstacle, where a hole in the grid, an impassable node and the edge of the grid are all
+//!    obstacles. Nodes touching an obstacle have a clearance of `1`
+//! 2. Nodes with a clearance no greater than `erosion` are eroded away leaving the open cores of the
+//!    map, each connected core becomes a region. Narrow passages are eroded entirely which is what
+//!    separates one region from another
+//! 3. The eroded nodes are given back to whichever region reaches them first, growing every
+//!    region outwards at the same rate
+//!
+//! Wherever two regions meet there is a chokepoint:
+//!
+//! ```txt
+//!      _______                             _______
+//!     /       \                           /       \
+//!    /         \_______           _______/         \
+//!    \    A    /       \_________/       \    B    /
+//!     \       /    A   |    A  B |   B    \       /
+//!     /       \_______/ \_______/ \_______/       \
+//!    /         \                         /         \
+//!    \_________/                         \_________/
+//! ```
+//!
+//! The decomposition is exported as a `RegionGraph` where each region is a node and each
+//! chokepoint an edge recording its width and the cost of crossing it.
+//!
+//! The output does not depend on the iteration order of any `HashMap`, the same grid always
+//! produces the same labels, regions and chokepoints in the same order. Regions are labelled in
+//! the order of their smallest core node, the nodes of each region are sorted, and chokepoints
+//! are sorted by the labels of the regions they join. This keeps lockstep simulations in sync
+//! and snapshot tests stable.
+
+use crate::grid::GridLayout;
+use crate::grid::HexGrid;
+use crate::path::Path;
+use crate::search::astar_path;
+use crate::search::MinScored;
+use crate::search::SearchSpace;
+use ::std::collections::hash_map::Entry;
+use ::std::collections::BinaryHeap;
+use ::std::collections::HashMap;
+use ::std::collections::VecDeque;
+
+/// Measure the clearance of every passable node in `grid`, the number of jumps to the nearest
+/// hole, impassable node or edge of the grid. Nodes touching an obstacle have a clearance of `1`.
+/// The map is unordered
+pub fn clearance<L: GridLayout>(grid: &HexGrid<L>) -> HashMap<L::Coord, i32> {
+	let mut clearance: HashMap<L::Coord, i32> = HashMap::new();
+	let mut frontier = VecDeque::new();
+	// any node without six passable neighbours is touching an obstacle
+	let mut nodes: Vec<L::Coord> = grid.nodes().keys().copied().collect();
+	nodes.sort();
+	for node in nodes {
+		if grid.is_passable(&node) && grid.neighbours(node).len() < 6 {
+			clearance.insert(node, 1);
+			frontier.push_back(node);
+		}
+	}
+	// spread inwards from the obstacles
+	while let Some(node) = frontier.pop_front() {
+		let c = clearance[&node];
+		for n in grid.neighbours(node) {
+			if let Entry::Vacant(e) = clearance.entry(n) {
+				e.insert(c + 1);
+				frontier.push_back(n);
+			}
+		}
+	}
+	clearance
+}
+
+/// Label every passable node of `grid` with the region it belongs to. Passages with a clearance
+/// no greater than `erosion` separate regions, an `erosion` of `0` labels each connected
+/// component of the grid as its own region. Labels count up from `0` and do not depend on
+/// hashing, though the map holding them is unordered
+pub fn label_regions<L: GridLayout>(grid: &HexGrid<L>, erosion: i32) -> HashMap<L::Coord, usize> {
+	let clearance = clearance(grid);
+	// visit nodes in order so labels do not depend on hashing
+	let mut nodes: Vec<L::Coord> = clearance.keys().copied().collect();
+	nodes.sort();
+	let mut labels: HashMap<L::Coord, usize> = HashMap::new();
+	let mut next_label = 0;
+	// flood fill the open cores which survive erosion
+	let mut growth = VecDeque::new();
+	for node in nodes.iter() {
+		if clearance[node] > erosion && !labels.contains_key(node) {
+			let core = flood_fill(grid, *node, |n| clearance[n] > erosion, &labels);
+			for n in core {
+				labels.insert(n, next_label);
+				growth.push_back(n);
+			}
+			next_label += 1;
+		}
+	}
+	// hand the eroded nodes back to the regions, each region growing at the same rate
+	while let Some(node) = growth.pop_front() {
+		let label = labels[&node];
+		for n in grid.neighbours(node) {
+			if let Entry::Vacant(e) = labels.entry(n) {
+				e.insert(label);
+				growth.push_back(n);
+			}
+		}
+	}
+	// passable nodes with no core to grow from, such as an isolated corridor, form their own regions
+	for node in nodes.iter() {
+		if !labels.contains_key(node) {
+			for n in flood_fill(grid, *node, |_| true, &labels) {
+				labels.insert(n, next_label);
+			}
+			next_label += 1;
+		}
+	}
+	labels
+}
+
+/// Collect the passable nodes connected to `start` which satisfy `include` and have not
+/// already been labelled
+fn flood_fill<L: GridLayout, F: Fn(&L::Coord) -> bool>(
+	grid: &HexGrid<L>,
+	start: L::Coord,
+	include: F,
+	labels: &HashMap<L::Coord, usize>,
+) -> Vec<L::Coord> {
+	let mut filled = vec![start];
+	let mut seen = ::std::collections::HashSet::new();
+	seen.insert(start);
+	let mut i = 0;
+	while i < filled.len() {
+		for n in grid.neighbours(filled[i]) {
+			if !labels.contains_key(&n) && include(&n) && seen.insert(n) {
+				filled.push(n);
+			}
+		}
+		i += 1;
+	}
+	filled
+}
+
+/// A narrow passage joining two regions
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chokepoint<C> {
+	/// The labels of the two regions joined, the smaller label first
+	pub regions: (usize, usize),
+	/// The nodes either side of the boundary between the regions, those of the lower labelled
+	/// region first, each side sorted
+	pub nodes: Vec<C>,
+	/// The number of nodes abreast in the passage, the smaller number of boundary nodes on either side
+	pub width: usize,
+	/// The cheapest cost of stepping across the boundary from one region to the other
+	pub cost: f32,
+}
+
+/// A grid decomposed into regions, the nodes of the graph, joined by chokepoints, the edges
+#[derive(Debug, Clone)]
+pub struct RegionGraph<C> {
+	/// The region label of each passable node
+	labels: HashMap<C, usize>,
+	/// The nodes of each region indexed by label
+	regions: Vec<Vec<C>>,
+	/// The passages joining regions
+	chokepoints: Vec<Chokepoint<C>>,
+}
+
+impl<C: Copy + Eq + ::std::hash::Hash> RegionGraph<C> {
+	/// The label of the region containing `node`
+	pub fn region_of(&self, node: &C) -> Option<usize> {
+		self.labels.get(node).copied()
+	}
+	/// The nodes of each region indexed by their label, sorted
+	pub fn regions(&self) -> &[Vec<C>] {
+		&self.regions
+	}
+	/// Every chokepoint of the grid
+	pub fn chokepoints(&self) -> &[Chokepoint<C>] {
+		&self.chokepoints
+	}
+	/// The chokepoints leading out of the region `label`
+	pub fn chokepoints_of(&self, label: usize) -> Vec<&Chokepoint<C>> {
+		self.chokepoints
+			.iter()
+			.filter(|c| c.regions.0 == label || c.regions.1 == label)
+			.collect()
+	}
+}
+
+/// The nodes either side of the boundary between two regions while it is being discovered
+struct Boundary<C> {
+	/// Nodes on the side of the region with the lower label
+	low: Vec<C>,
+	/// Nodes on the side of the region with the higher label
+	high: Vec<C>,
+	/// The cheapest step across the boundary
+	cost: f32,
+}
+
+/// Decompose `grid` into regions separated by passages with a clearance no greater than `erosion`
+/// and find the chokepoints joining them
+pub fn region_graph<L: GridLayout>(grid: &HexGrid<L>, erosion: i32) -> RegionGraph<L::Coord> {
+	let labels = label_regions(grid, erosion);
+	let count_regions = labels.values().map(|l| l + 1).max().unwrap_or(0);
+	let mut regions = vec![Vec::new(); count_regions];
+	for (node, label) in labels.iter() {
+		regions[*label].push(*node);
+	}
+	for region in regions.iter_mut() {
+		region.sort();
+	}
+	// find every pair of neighbouring nodes which sit in different regions, keyed by the pair of
+	// region labels
+	let mut boundaries: HashMap<(usize, usize), Boundary<L::Coord>> = HashMap::new();
+	for (node, label) in labels.iter() {
+		for n in grid.neighbours(*node) {
+			let other = labels[&n];
+			if other <= *label {
+				continue;
+			}
+			let step_cost = match grid.step_cost(*node, n) {
+				Some(x) => x,
+				None => continue,
+			};
+			let boundary = boundaries.entry((*label, other)).or_insert(Boundary {
+				low: Vec::new(),
+				high: Vec::new(),
+				cost: f32::INFINITY,
+			});
+			if !boundary.low.contains(node) {
+				boundary.low.push(*node);
+			}
+			if !boundary.high.contains(&n) {
+				boundary.high.push(n);
+			}
+			if step_cost < boundary.cost {
+				boundary.cost = step_cost;
+			}
+		}
+	}
+	let mut chokepoints: Vec<Chokepoint<L::Coord>> = boundaries
+		.into_iter()
+		.map(|(regions, mut boundary)| {
+			let width = boundary.low.len().min(boundary.high.len());
+			boundary.low.sort();
+			boundary.high.sort();
+			let mut nodes = boundary.low;
+			nodes.extend(boundary.high);
+			Chokepoint {
+				regions,
+				nodes,
+				width,
+				cost: boundary.cost,
+			}
+		})
+		.collect();
+	chokepoints.sort_by_key(|c| c.regions);
+	RegionGraph {
+		labels,
+		regions,
+		chokepoints,
+	}
+}
+
+/// A search space restricted to the nodes with at least some clearance
+struct Cleared<'a, S: SearchSpace> {
+	/// The space being searched
+	space: &'a S,
+	/// The clearance of each node
+	clearance: &'a HashMap<S::Node, i32>,
+	/// The least clearance a node may have to be moved to
+	minimum: i32,
+}
+
+impl<'a, S: SearchSpace> SearchSpace for Cleared<'a, S> {
+	type Node = S::Node;
+	fn successors(&self, node: S::Node) -> Vec<(S::Node, f32)> {
+		let mut successors = self.space.successors(node);
+		successors.retain(|(n, _)| self.clearance.get(n).copied().unwrap_or(0) >= self.minimum);
+		successors
+	}
+	fn heuristic(&self, node: S::Node, goal: S::Node) -> f32 {
+		self.space.heuristic(node, goal)
+	}
+	fn predecessors(&self, node: S::Node) -> Vec<(S::Node, f32)> {
+		let mut predecessors = self.space.predecessors(node);
+		predecessors.retain(|(n, _)| self.clearance.get(n).copied().unwrap_or(0) >= self.minimum);
+		predecessors
+	}
+}
+
+/// Find the route from `start_node` to `end_node` which stays furthest from obstacles, for
+/// convoys preferring wide valleys to tight canyons however much further the valley is.
+///
+/// The route maximises the smallest clearance of any node along it, `clearance` usually being
+/// the output of `clearance()` but any map where higher is safer will do, such as the distance
+/// from known threats. Nodes absent from `clearance` count as `0`. Among the routes sharing the
+/// greatest smallest clearance the cheapest is returned. Returns the path with its smallest
+/// clearance, or `None` if `end_node` cannot be reached
+pub fn max_clearance_path<S: SearchSpace>(
+	space: &S,
+	clearance: &HashMap<S::Node, i32>,
+	start_node: S::Node,
+	end_node: S::Node,
+) -> Option<(Path<S::Node>, i32)> {
+	let clearance_of = |n: &S::Node| clearance.get(n).copied().unwrap_or(0);
+	// first find the greatest smallest clearance of any route, spreading out from the start
+	// through the widest routes first
+	let mut widest: HashMap<S::Node, i32> = HashMap::new();
+	let mut queue = BinaryHeap::new();
+	widest.insert(start_node, clearance_of(&start_node));
+	queue.push(MinScored(-clearance_of(&start_node) as f32, start_node));
+	let mut bottleneck = None;
+	while let Some(MinScored(score, node)) = queue.pop() {
+		let width = widest[&node];
+		// skip stale entries where a wider route has since been found
+		if -score < width as f32 {
+			continue;
+		}
+		if node == end_node {
+			bottleneck = Some(width);
+			break;
+		}
+		for (n, _) in space.successors(node) {
+			let w = width.min(clearance_of(&n));
+			let is_wider = match widest.get(&n) {
+				Some(existing) => w > *existing,
+				None => true,
+			};
+			if is_wider {
+				widest.insert(n, w);
+				queue.push(MinScored(-w as f32, n));
+			}
+		}
+	}
+	// then the cheapest route which never drops below it
+	let minimum = bottleneck?;
+	let cleared = Cleared {
+		space,
+		clearance,
+		minimum,
+	};
+	astar_path(&cleared, start_node, end_node).map(|path| (path, minimum))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::CubicLayout;
+	use crate::grid::HexGrid;
+	use crate::helpers::node_distance;
+	use crate::regions::*;
+
+	/// Two rooms of radius 2 centred on `(0, 0, 0)` and `(6, -6, 0)` joined by a corridor a
+	/// single node wide
+	/// ```txt
+	///    _____                 _____
+	///   /     \               /     \
+	///  /   A   \----o--o--o--/   B   \
+	///  \       /             \       /
+	///   \_____/               \_____/
+	/// ```
+	fn two_rooms() -> HexGrid<CubicLayout> {
+		let mut nodes = HashMap::new();
+		for x in -8..=8_i32 {
+			for y in -8..=8_i32 {
+				let node = (x, y, -x - y);
+				if node_distance(node, (0, 0, 0)) <= 2 || node_distance(node, (6, -6, 0)) <= 2 {
+					nodes.insert(node, 1.0);
+				}
+			}
+		}
+		nodes.insert((3, -3, 0), 1.0);
+		HexGrid::new(CubicLayout::new(10), nodes)
+	}
+
+	#[test]
+	/// Clearance grows towards the middle of a room
+	fn room_clearance() {
+		let grid = two_rooms();
+		let clearance = clearance(&grid);
+		assert_eq!(3, clearance[&(0, 0, 0)]);
+		assert_eq!(2, clearance[&(1, -1, 0)]);
+		assert_eq!(1, clearance[&(2, -2, 0)]);
+		assert_eq!(1, clearance[&(3, -3, 0)]);
+	}
+	#[test]
+	/// Without erosion the connected rooms form a single region
+	fn no_erosion_single_region() {
+		let grid = two_rooms();
+		let graph = region_graph(&grid, 0);
+		assert_eq!(1, graph.regions().len());
+		assert!(graph.chokepoints().is_empty());
+	}
+	#[test]
+	/// Eroding the corridor splits the rooms into two regions joined by a chokepoint
+	fn corridor_is_a_chokepoint() {
+		let grid = two_rooms();
+		let graph = region_graph(&grid, 1);
+		assert_eq!(2, graph.regions().len());
+		assert_ne!(graph.region_of(&(0, 0, 0)), graph.region_of(&(6, -6, 0)));
+		assert_eq!(1, graph.chokepoints().len());
+		let chokepoint = &graph.chokepoints()[0];
+		assert_eq!((0, 1), chokepoint.regions);
+		assert_eq!(1, chokepoint.width);
+		assert_eq!(1.0, chokepoint.cost);
+		assert_eq!(1, graph.chokepoints_of(0).len());
+	}
+	#[test]
+	/// Repeated decompositions of grids built in different orders are identical
+	fn stable_ordering() {
+		let grid = two_rooms();
+		let expected = region_graph(&grid, 1);
+		for _ in 0..8 {
+			// each map has its own random hashing so iterates in a different order
+			let nodes: HashMap<(i32, i32, i32), f32> =
+				grid.nodes().iter().map(|(k, v)| (*k, *v)).collect();
+			let graph = region_graph(&HexGrid::new(CubicLayout::new(10), nodes), 1);
+			assert_eq!(expected.regions(), graph.regions());
+			assert_eq!(expected.chokepoints(), graph.chokepoints());
+		}
+		assert_eq!(Some(0), expected.region_of(&(0, 0, 0)));
+	}
+	#[test]
+	/// The corridor is the narrowest point between the rooms
+	fn clearance_through_corridor() {
+		let grid = two_rooms();
+		let (path, bottleneck) =
+			max_clearance_path(&grid, &clearance(&grid), (0, 0, 0), (6, -6, 0)).unwrap();
+		assert_eq!(1, bottleneck);
+		assert!(path.contains(&(3, -3, 0)));
+	}
+	#[test]
+	/// Threats along the direct line push the route out around them even though it is longer,
+	/// every other node being safe
+	/// ```txt
+	///        o---o---o---o
+	///       /             \
+	///      S----1---1---1--E
+	/// ```
+	fn detours_around_threats() {
+		let mut nodes = HashMap::new();
+		for x in -3..=3_i32 {
+			for y in -3..=3_i32 {
+				let node = (x, y, -x - y);
+				if node_distance(node, (0, 0, 0)) <= 3 {
+					nodes.insert(node, 1.0);
+				}
+			}
+		}
+		let grid = HexGrid::new(CubicLayout::new(3), nodes);
+		let mut safety: HashMap<(i32, i32, i32), i32> =
+			grid.nodes().keys().map(|n| (*n, 3)).collect();
+		for danger in [(-1, 1, 0), (0, 0, 0), (1, -1, 0)] {
+			safety.insert(danger, 1);
+		}
+		let start = (-2, 2, 0);
+		let end = (2, -2, 0);
+		let (path, bottleneck) = max_clearance_path(&grid, &safety, start, end).unwrap();
+		assert_eq!(3, bottleneck);
+		assert!(path.iter().all(|n| safety[n] == 3));
+		assert!(path.hops() > node_distance(start, end) as usize);
+	}
+}
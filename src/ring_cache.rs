@@ -0,0 +1,109 @@
+//! Reuse the rings of hexagons around a node rather than walking them again on every query.
+//!
+//! Areas of effect, fields of view and range overlays ask for the same rings over and over,
+//! often thousands of times a second. In Cubic coordinates a ring is the same shape wherever its
+//! centre lies, the ring of radius `2` around `(3, -1, -2)` is the ring of radius `2` around the
+//! origin shifted by `(3, -1, -2)`. A `RingCache` walks each radius once around the origin with
+//! `helpers::node_ring_cubic()` and afterwards only adds the centre to the stored offsets:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::helpers::node_ring_cubic;
+//! use hexagonal_pathfinding_astar::ring_cache::RingCache;
+//! let mut cache = RingCache::new();
+//! let ring: Vec<(i32, i32, i32)> = cache.ring((3, -1, -2), 2).collect();
+//! assert_eq!(node_ring_cubic((3, -1, -2), 2), ring);
+//! ```
+
+use crate::grid::GridLayout;
+use crate::helpers::node_ring_cubic;
+use ::std::collections::HashMap;
+
+/// Memoises the rings around the origin by radius so rings around any node can be produced by
+/// translation
+#[derive(Debug, Clone, Default)]
+pub struct RingCache {
+	/// The nodes of each ring around the origin, in the order given by `node_ring_cubic()`
+	rings: HashMap<i32, Vec<(i32, i32, i32)>>,
+}
+
+impl RingCache {
+	/// Create an empty cache
+	pub fn new() -> RingCache {
+		RingCache::default()
+	}
+	/// The ring of `radius` around the origin, walked the first time it is requested
+	pub fn offsets(&mut self, radius: i32) -> &[(i32, i32, i32)] {
+		self.rings
+			.entry(radius)
+			.or_insert_with(|| node_ring_cubic((0, 0, 0), radius))
+	}
+	/// Iterate over the ring of `radius` around `source` in Cubic coordinates, in the same order
+	/// as `node_ring_cubic()`
+	pub fn ring(
+		&mut self,
+		source: (i32, i32, i32),
+		radius: i32,
+	) -> impl Iterator<Item = (i32, i32, i32)> + '_ {
+		self.offsets(radius)
+			.iter()
+			.map(move |o| (source.0 + o.0, source.1 + o.1, source.2 + o.2))
+	}
+	/// Iterate over the ring of `radius` around `source` in the coordinates of `layout`. The ring
+	/// is not clipped to the boundary of the layout
+	pub fn ring_in<'a, L: GridLayout>(
+		&'a mut self,
+		layout: &'a L,
+		source: L::Coord,
+		radius: i32,
+	) -> impl Iterator<Item = L::Coord> + 'a {
+		let centre = layout.node_to_cubic(source);
+		self.ring(centre, radius)
+			.map(move |cube| layout.cubic_to_node(cube))
+	}
+	/// Number of radii cached
+	pub fn len(&self) -> usize {
+		self.rings.len()
+	}
+	/// Whether no rings have been cached yet
+	pub fn is_empty(&self) -> bool {
+		self.rings.is_empty()
+	}
+	/// Forget every cached ring
+	pub fn clear(&mut self) {
+		self.rings.clear();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::AxialLayout;
+	use crate::helpers::axial_to_cubic;
+	use crate::helpers::node_distance;
+	use crate::ring_cache::*;
+
+	#[test]
+	/// Cached rings match walking each ring from scratch and each radius is only stored once
+	fn matches_walked_rings() {
+		let mut cache = RingCache::new();
+		for source in [(0, 0, 0), (3, -1, -2), (-4, 4, 0)] {
+			for radius in 1..5 {
+				let cached: Vec<(i32, i32, i32)> = cache.ring(source, radius).collect();
+				assert_eq!(node_ring_cubic(source, radius), cached);
+			}
+		}
+		assert_eq!(4, cache.len());
+		cache.clear();
+		assert!(cache.is_empty());
+	}
+	#[test]
+	/// Rings are converted to the coordinates of the layout
+	fn axial_ring() {
+		let mut cache = RingCache::new();
+		let layout = AxialLayout::unbounded();
+		let ring: Vec<(i32, i32)> = cache.ring_in(&layout, (2, -1), 1).collect();
+		assert_eq!(6, ring.len());
+		for node in ring {
+			assert_eq!(1, node_distance(axial_to_cubic(node), (2, -1, -1)));
+		}
+	}
+}
@@ -0,0 +1,178 @@
+//! Connect a set of settlements with a network of roads.
+//!
+//! Building a road across a hexagon costs the same as traversing it so the cheapest network is
+//! one which minimises the total cost of the paths making up the roads. Finding the optimal
+//! network is the Steiner tree problem which is NP-hard, instead the network is grown from the
+//! first settlement by repeatedly finding the unconnected settlement which is cheapest to reach
+//! from *any* hexagon already on the network and building a road to it. Roads can branch from
+//! the middle of an existing road which creates junctions:
+//!
+//! ```txt
+//!                     _______
+//!                    /       \
+//!            _______/   S2    \
+//!           /       \    |    /
+//!   _______/         \___|___/
+//!  /       \         /   |   \
+//! /   S1 ----------------J    \
+//! \         /       \    |    /
+//!  \_______/         \___|___/
+//!          \         /   |   \
+//!           \_______/    S3   \
+//!                   \         /
+//!                    \_______/
+//! ```
+//!
+//! Here `S1` is connected to `S2` and the road to `S3` branches from the junction `J` rather than
+//! returning to either settlement.
+
+use crate::grid::GridLayout;
+use crate::grid::HexGrid;
+use crate::node_store::NodeStore;
+use crate::path::Path;
+use crate::search::dijkstra_to_any;
+use ::std::collections::HashSet;
+
+/// A network of roads connecting a set of settlements
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoadNetwork<T> {
+	/// Every hexagon which has a road built across it in the order they were added
+	hexes: Vec<T>,
+	/// Each road built, beginning at a hexagon already on the network and ending at a settlement
+	roads: Vec<Path<T>>,
+}
+
+impl<T> RoadNetwork<T> {
+	/// Every hexagon which has a road built across it, in the order they were added to the network
+	pub fn hexes(&self) -> &[T] {
+		&self.hexes
+	}
+	/// Each road of the network. A road begins at a hexagon which was already part of the network
+	/// and ends at the settlement it was built to connect
+	pub fn roads(&self) -> &[Path<T>] {
+		&self.roads
+	}
+	/// The total cost of constructing the network
+	pub fn cost(&self) -> f32 {
+		self.roads.iter().map(|r| r.cost()).sum()
+	}
+}
+
+/// Build a network of roads connecting every node in `settlements` across the `grid`, aiming to
+/// minimise the total construction cost.
+///
+/// Returns `None` if any settlement is not part of the grid or cannot be reached from the others
+pub fn road_network<L: GridLayout, S: NodeStore<L::Coord>>(
+	grid: &HexGrid<L, S>,
+	settlements: &[L::Coord],
+) -> Option<RoadNetwork<L::Coord>> {
+	let mut network = RoadNetwork {
+		hexes: Vec::new(),
+		roads: Vec::new(),
+	};
+	let first = match settlements.first() {
+		Some(x) => *x,
+		None => return Some(network),
+	};
+	if settlements.iter().any(|s| !grid.contains(s)) {
+		return None;
+	}
+	let mut on_network: HashSet<L::Coord> = HashSet::new();
+	on_network.insert(first);
+	network.hexes.push(first);
+	let mut unconnected: HashSet<L::Coord> = settlements
+		.iter()
+		.filter(|s| **s != first)
+		.copied()
+		.collect();
+	while !unconnected.is_empty() {
+		// grow the network towards whichever settlement is cheapest to reach from any road
+		let road = dijkstra_to_any(grid, &network.hexes, &unconnected)?;
+		for hex in road.iter() {
+			// settlements passed through on the way are connected for free
+			unconnected.remove(hex);
+			if on_network.insert(*hex) {
+				network.hexes.push(*hex);
+			}
+		}
+		network.roads.push(road);
+	}
+	Some(network)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::CubicLayout;
+	use crate::grid::HexGrid;
+	use crate::road_network::road_network;
+	use std::collections::HashMap;
+
+	/// A cubic grid of 2 rings where every node has the same complexity
+	fn uniform_grid() -> HexGrid<CubicLayout> {
+		let mut nodes = HashMap::new();
+		for x in -2..=2_i32 {
+			for y in -2..=2_i32 {
+				let z = -x - y;
+				if z.abs() <= 2 {
+					nodes.insert((x, y, z), 1.0);
+				}
+			}
+		}
+		HexGrid::new(CubicLayout::new(2), nodes)
+	}
+
+	#[test]
+	/// Three settlements in a line are joined by two roads meeting at the middle settlement
+	fn settlements_in_a_line() {
+		let grid = uniform_grid();
+		let settlements = [(0, 0, 0), (0, -2, 2), (0, 2, -2)];
+		let network = road_network(&grid, &settlements).unwrap();
+		assert_eq!(2, network.roads().len());
+		assert_eq!(5, network.hexes().len());
+		assert_eq!(4.0, network.cost());
+	}
+	#[test]
+	/// A road branching from an existing road is cheaper than returning to a settlement
+	/// ```txt
+	///  S1 - o - J - o - S2
+	///           |
+	///           o
+	///           |
+	///           o
+	///           |
+	///           S3
+	/// ```
+	fn roads_branch_from_junctions() {
+		let mut nodes = HashMap::new();
+		for node in [
+			(-2, 2, 0),
+			(-1, 1, 0),
+			(0, 0, 0),
+			(1, -1, 0),
+			(2, -2, 0),
+			(0, 1, -1),
+			(0, 2, -2),
+			(0, 3, -3),
+		] {
+			nodes.insert(node, 1.0);
+		}
+		let grid = HexGrid::new(CubicLayout::new(3), nodes);
+		let settlements = [(-2, 2, 0), (2, -2, 0), (0, 3, -3)];
+		let network = road_network(&grid, &settlements).unwrap();
+		// the second road joins the first at the junction so only 3 further hexes are built
+		assert_eq!(4.0, network.roads()[0].cost());
+		assert_eq!(Some(&(0, 0, 0)), network.roads()[1].first());
+		assert_eq!(3.0, network.roads()[1].cost());
+		assert_eq!(7.0, network.cost());
+		assert_eq!(8, network.hexes().len());
+	}
+	#[test]
+	/// A settlement cut off from the others prevents a network being built
+	fn unreachable_settlement() {
+		let mut nodes = HashMap::new();
+		nodes.insert((0, 0, 0), 1.0);
+		nodes.insert((2, -2, 0), 1.0);
+		let grid = HexGrid::new(CubicLayout::new(2), nodes);
+		assert!(road_network(&grid, &[(0, 0, 0), (2, -2, 0)]).is_none());
+	}
+}
@@ -0,0 +1,150 @@
+//! Turn raw path costs into utilities an AI can compare.
+//!
+//! A decision layer weighing up candidate destinations cares less about the cost of each path
+//! than how good it is, and a cost of `40` means little on its own. Each function here maps costs
+//! to a utility between `0` and `1`, higher being better:
+//!
+//! * `path_efficiency` compares a path to the cheapest it could possibly be, the estimate of the
+//!   search space between its ends. A path straight across open ground scores `1` while one
+//!   winding around a mountain range scores far less, however short either is
+//! * `batch_percentiles` ranks each cost against the rest of a batch of queries, the cheapest
+//!   scoring `1` and the dearest `0`
+//! * `batch_normalised` places each cost between the cheapest and dearest of a batch, so the gaps
+//!   between candidates are kept rather than only their order
+//!
+//! Destinations which cannot be reached are given a cost of `f32::INFINITY` in a batch and always
+//! score `0`.
+
+use crate::path::Path;
+use crate::search::SearchSpace;
+
+/// The cheapest a path between the ends of `path` could possibly cost, the estimate of `space`,
+/// divided by what it does cost. `1` for a path which cannot be bettered, falling towards `0` the
+/// more it has to detour or cross difficult terrain. An empty path or one costing nothing scores
+/// `1`
+pub fn path_efficiency<S: SearchSpace>(space: &S, path: &Path<S::Node>) -> f32 {
+	let (first, last) = match (path.first(), path.last()) {
+		(Some(f), Some(l)) => (*f, *l),
+		_ => return 1.0,
+	};
+	let cost = path.cost();
+	if cost <= 0.0 {
+		return 1.0;
+	}
+	(space.heuristic(first, last) / cost).clamp(0.0, 1.0)
+}
+
+/// The percentile rank of each of `costs` within the batch, the share of the other costs which
+/// are dearer with ties counting as half. The cheapest scores `1` and the dearest `0`, a batch of
+/// one scores `1`. Costs which are infinite or NaN, destinations which cannot be reached, score
+/// `0`
+pub fn batch_percentiles(costs: &[f32]) -> Vec<f32> {
+	if costs.len() < 2 {
+		return costs.iter().map(|c| reachable_score(*c, 1.0)).collect();
+	}
+	let others = (costs.len() - 1) as f32;
+	costs
+		.iter()
+		.enumerate()
+		.map(|(i, cost)| {
+			if !cost.is_finite() {
+				return 0.0;
+			}
+			let mut beaten = 0.0;
+			for (j, other) in costs.iter().enumerate() {
+				if i == j {
+					continue;
+				}
+				if !other.is_finite() || other > cost {
+					beaten += 1.0;
+				} else if other == cost {
+					beaten += 0.5;
+				}
+			}
+			beaten / others
+		})
+		.collect()
+}
+
+/// Each of `costs` placed linearly between the dearest reachable cost in the batch, scoring `0`,
+/// and the cheapest, scoring `1`. When every reachable cost is the same they all score `1`. Costs
+/// which are infinite or NaN score `0`
+pub fn batch_normalised(costs: &[f32]) -> Vec<f32> {
+	let finite = costs.iter().copied().filter(|c| c.is_finite());
+	let cheapest = finite.clone().fold(f32::INFINITY, f32::min);
+	let dearest = finite.fold(f32::NEG_INFINITY, f32::max);
+	let range = dearest - cheapest;
+	costs
+		.iter()
+		.map(|cost| {
+			if range > 0.0 {
+				reachable_score(*cost, (dearest - cost) / range)
+			} else {
+				reachable_score(*cost, 1.0)
+			}
+		})
+		.collect()
+}
+
+/// `score` if `cost` can be reached, otherwise `0`
+fn reachable_score(cost: f32, score: f32) -> f32 {
+	if cost.is_finite() {
+		score
+	} else {
+		0.0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::AxialLayout;
+	use crate::grid::HexGrid;
+	use crate::scoring::*;
+	use crate::search::astar_path;
+	use ::std::collections::HashMap;
+
+	#[test]
+	/// A straight path over the cheapest terrain cannot be bettered while one over costly terrain
+	/// scores lower
+	/// ```txt
+	///     _______
+	///    /       \
+	///   /   0,1   \
+	///   \   C:5   /
+	///    \_______/
+	///    /       \
+	///   /   0,0   \
+	///   \   C:1   /
+	///    \_______/
+	///    /       \
+	///   /   0,-1  \
+	///   \   C:1   /
+	///    \_______/
+	/// ```
+	fn efficiency_against_estimate() {
+		let mut nodes = HashMap::new();
+		nodes.insert((0, -1), 1.0);
+		nodes.insert((0, 0), 1.0);
+		nodes.insert((0, 1), 5.0);
+		let grid = HexGrid::new(AxialLayout::new(1), nodes);
+		let cheap = astar_path(&grid, (0, -1), (0, 0)).unwrap();
+		assert_eq!(1.0, path_efficiency(&grid, &cheap));
+		let costly = astar_path(&grid, (0, -1), (0, 1)).unwrap();
+		assert_eq!(0.5, path_efficiency(&grid, &costly));
+		let still = astar_path(&grid, (0, 0), (0, 0)).unwrap();
+		assert_eq!(1.0, path_efficiency(&grid, &still));
+	}
+	#[test]
+	/// Batches rank the cheapest first and unreachable destinations last
+	fn batch_utilities() {
+		let costs = [4.0, 2.0, 4.0, f32::INFINITY, 10.0];
+		assert_eq!(
+			vec![0.625, 1.0, 0.625, 0.0, 0.25],
+			batch_percentiles(&costs)
+		);
+		assert_eq!(vec![0.75, 1.0, 0.75, 0.0, 0.0], batch_normalised(&costs));
+		assert_eq!(vec![1.0], batch_percentiles(&[7.0]));
+		assert_eq!(vec![1.0, 1.0], batch_normalised(&[3.0, 3.0]));
+		assert!(batch_percentiles(&[]).is_empty());
+	}
+}
@@ -0,0 +1,2528 @@
+//! The A-Star search shared by each of the coordinate system specific modules.
+//!
+//! Each coordinate system differs only in how neighbours of a node are discovered and how the
+//! distance to the end node is measured, the search itself is identical. A coordinate system
+//! describes its space by implementing `SearchSpace` and the search operates on that.
+//!
+//! `HexGrid` implements `SearchSpace` for every layout and node store so a grid can be searched
+//! directly:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::grid::{HexGrid, OffsetLayout};
+//! use hexagonal_pathfinding_astar::node_store::DenseNodeStore;
+//! use hexagonal_pathfinding_astar::search::astar_path;
+//! use hexagonal_pathfinding_astar::HexOrientation;
+//!
+//! let layout = OffsetLayout::new(HexOrientation::FlatTopOddUp, -1, 512, -1, 512);
+//! let grid = HexGrid::new(layout, DenseNodeStore::new(512, 512, 1.0));
+//! let path = astar_path(&grid, (0, 0), (0, 5)).unwrap();
+//! assert_eq!(5.0, path.cost());
+//! ```
+
+use crate::hashing::NodeMap;
+use crate::path::Path;
+use ::std::cmp::Ordering;
+use ::std::collections::BinaryHeap;
+use ::std::collections::HashMap;
+use ::std::collections::HashSet;
+use ::std::error::Error;
+use ::std::fmt;
+use ::std::fmt::Debug;
+use ::std::hash::Hash;
+use ::std::sync::atomic::AtomicBool;
+use ::std::sync::atomic::Ordering as AtomicOrdering;
+use ::std::sync::Arc;
+use ::std::time::Duration;
+use ::std::time::Instant;
+
+/// A space of nodes which can be searched
+pub trait SearchSpace {
+	/// The coordinate type used to label nodes
+	type Node: Copy + Eq + Hash + Debug;
+	/// The nodes which can be moved to from `node` along with the cost of making each move
+	fn successors(&self, node: Self::Node) -> Vec<(Self::Node, f32)>;
+	/// An estimate of the cost of moving from `node` to `goal`
+	fn heuristic(&self, node: Self::Node, goal: Self::Node) -> f32;
+	/// The nodes which can move to `node` along with the cost of making each move, used when
+	/// searching backwards from a goal. Moves are assumed to cost the same in both directions
+	/// so by default this is `successors`, a space with direction dependent costs must
+	/// override it
+	fn predecessors(&self, node: Self::Node) -> Vec<(Self::Node, f32)> {
+		self.successors(node)
+	}
+}
+
+/// A space shared between threads is searched as the space itself
+impl<T: SearchSpace + ?Sized> SearchSpace for Arc<T> {
+	type Node = T::Node;
+	fn successors(&self, node: Self::Node) -> Vec<(Self::Node, f32)> {
+		self.as_ref().successors(node)
+	}
+	fn heuristic(&self, node: Self::Node, goal: Self::Node) -> f32 {
+		self.as_ref().heuristic(node, goal)
+	}
+	fn predecessors(&self, node: Self::Node) -> Vec<(Self::Node, f32)> {
+		self.as_ref().predecessors(node)
+	}
+}
+
+/// A set of nodes with a complexity each, where the weight of a node, its distance from the end
+/// node, is calculated with `weight` only when the search asks for it. Most nodes of a large map
+/// are never discovered so their weights are never needed. `neighbours` is used to discover the
+/// nodes around a given node
+pub(crate) struct WeightedNodes<'a, N, W, F> {
+	/// Keys are nodes and values are their complexity
+	nodes: &'a HashMap<N, f32>,
+	/// Calculates the weight of a node
+	weight: W,
+	/// Discovers the nodes around a node
+	neighbours: F,
+}
+
+impl<'a, N, W, F> WeightedNodes<'a, N, W, F>
+where
+	N: Copy + Eq + Hash + Debug,
+	W: Fn(&N) -> f32,
+	F: Fn(N) -> Vec<N>,
+{
+	/// Combine everything the search needs, the weight of each node is calculated with `weight`
+	/// once the node is discovered
+	pub(crate) fn new(nodes: &'a HashMap<N, f32>, weight: W, neighbours: F) -> Self {
+		WeightedNodes {
+			nodes,
+			weight,
+			neighbours,
+		}
+	}
+}
+
+impl<'a, N, W, F> SearchSpace for WeightedNodes<'a, N, W, F>
+where
+	N: Copy + Eq + Hash + Debug,
+	W: Fn(&N) -> f32,
+	F: Fn(N) -> Vec<N>,
+{
+	type Node = N;
+	fn successors(&self, node: N) -> Vec<(N, f32)> {
+		// grab the half complexity of the currrent node
+		let current_node_complexity: f32 = match self.nodes.get(&node) {
+			Some(x) => x * 0.5,
+			None => panic!("Unable to find current node complexity for {:?}", &node),
+		};
+		let mut successors = Vec::new();
+		for n in (self.neighbours)(node).iter() {
+			// grab half the complexity of the neighbour node
+			let target_node_complexity: f32 = match self.nodes.get(n) {
+				Some(x) => x * 0.5,
+				None => panic!("Unable to find target node complexity for {:?}", &n),
+			};
+			successors.push((*n, current_node_complexity + target_node_complexity));
+		}
+		successors
+	}
+	fn heuristic(&self, node: N, _goal: N) -> f32 {
+		if !self.nodes.contains_key(&node) {
+			panic!("Unable to find node weight for {:?}", &node)
+		}
+		(self.weight)(&node)
+	}
+}
+
+/// From a starting node calculate the most efficient path to the end node. Returns `None` if
+/// the end node cannot be reached.
+///
+/// When a cheaper route to a node already waiting in the queue is found the node is pushed again
+/// with its better score rather than searching the queue for it, and the entry left behind is
+/// skipped once popped, so each update costs `O(log n)`
+pub fn astar_path<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+) -> Option<Path<S::Node>> {
+	SearchContext::new().astar_path(space, start_node, end_node)
+}
+
+/// The maps and queue of an A* search kept between searches, so that many searches one after
+/// another reuse their memory rather than allocating it afresh each time:
+///
+/// ```
+/// use hexagonal_pathfinding_astar::search::SearchContext;
+/// use hexagonal_pathfinding_astar::test_support::axial_fixture;
+/// let grid = axial_fixture(4, 1);
+/// let mut context = SearchContext::new();
+/// for goal in [(4, 0), (0, 4), (-4, 0)] {
+///     let path = context.astar_path(&grid, (0, 0), goal);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SearchContext<N> {
+	/// The cheapest known complexity of reaching each discovered node and the node it was
+	/// cheapest reached from
+	memory: NodeMemory<N>,
+	/// Nodes to be processed ordered by a-star score, the smallest first
+	queue: BinaryHeap<MinScored<N>>,
+	/// What happened during the last search
+	stats: SearchStats,
+}
+
+impl<N: Copy + Eq + Hash> Default for SearchContext<N> {
+	fn default() -> Self {
+		SearchContext::new()
+	}
+}
+
+impl<N: Copy + Eq + Hash> SearchContext<N> {
+	/// Create a context which has not yet allocated anything
+	pub fn new() -> SearchContext<N> {
+		SearchContext {
+			memory: NodeMemory::default(),
+			queue: BinaryHeap::new(),
+			stats: SearchStats::default(),
+		}
+	}
+	/// What happened during the last search, all zero before the first
+	pub fn stats(&self) -> SearchStats {
+		self.stats
+	}
+	/// From a starting node calculate the most efficient path to the end node, as the free
+	/// function `astar_path()`, clearing and reusing the memory of any earlier search. Returns
+	/// `None` if the end node cannot be reached
+	pub fn astar_path<S: SearchSpace<Node = N>>(
+		&mut self,
+		space: &S,
+		start_node: N,
+		end_node: N,
+	) -> Option<Path<N>> {
+		self.astar_path_limited(space, start_node, end_node, usize::MAX)
+			.ok()
+	}
+	/// As `SearchContext::astar_path`, giving up once `max_expansions` nodes have been expanded
+	/// without reaching the end node. Returns `SearchError::ExpansionLimit` if the search gave
+	/// up and `SearchError::Unreachable` if the end node cannot be reached
+	pub fn astar_path_limited<S: SearchSpace<Node = N>>(
+		&mut self,
+		space: &S,
+		start_node: N,
+		end_node: N,
+		max_expansions: usize,
+	) -> Result<Path<N>, SearchError> {
+		let limits = Limits::expansions(max_expansions);
+		self.measured_search(space, start_node, end_node, &limits)
+	}
+	/// As `SearchContext::astar_path`, giving up once `cancel` is set, e.g by another thread
+	/// when the player issues a new order, or once `deadline` has passed. Returns
+	/// `SearchError::Cancelled` or `SearchError::TimedOut` if the search gave up and
+	/// `SearchError::Unreachable` if the end node cannot be reached
+	pub fn astar_path_cancellable<S: SearchSpace<Node = N>>(
+		&mut self,
+		space: &S,
+		start_node: N,
+		end_node: N,
+		cancel: &AtomicBool,
+		deadline: Option<Instant>,
+	) -> Result<Path<N>, SearchError> {
+		let limits = Limits {
+			max_expansions: usize::MAX,
+			cancel: Some(cancel),
+			deadline,
+		};
+		self.measured_search(space, start_node, end_node, &limits)
+	}
+	/// Search within `limits`, recording the stats of the search
+	fn measured_search<S: SearchSpace<Node = N>>(
+		&mut self,
+		space: &S,
+		start_node: N,
+		end_node: N,
+		limits: &Limits,
+	) -> Result<Path<N>, SearchError> {
+		let started = Instant::now();
+		let mut hooks = towards(space, end_node);
+		let path = self
+			.run(&[start_node], &mut hooks, limits)
+			.map(|end| self.path_to(end));
+		self.stats.nodes_discovered = self.memory.costs.len();
+		self.stats.duration = started.elapsed();
+		path
+	}
+	/// Search from every node of `starts` for a goal of `hooks` within `limits`, clearing the
+	/// memory and stats of any earlier search. Returns the goal reached, see
+	/// `SearchContext::path_to`
+	pub(crate) fn run<H: SearchHooks<N>>(
+		&mut self,
+		starts: &[N],
+		hooks: &mut H,
+		limits: &Limits,
+	) -> Result<N, SearchError> {
+		self.begin(starts, hooks);
+		self.resume(hooks, limits)
+	}
+	/// Clear the memory and stats of any earlier search and queue every node of `starts`
+	pub(crate) fn begin<H: SearchHooks<N>>(&mut self, starts: &[N], hooks: &H) {
+		self.stats = SearchStats::default();
+		begin_astar(&mut self.memory, &mut self.queue, starts, hooks);
+	}
+	/// Carry on the search begun by `SearchContext::begin` until a goal of `hooks` is reached or
+	/// `limits` are, counting expansions from the start of the search
+	pub(crate) fn resume<H: SearchHooks<N>>(
+		&mut self,
+		hooks: &mut H,
+		limits: &Limits,
+	) -> Result<N, SearchError> {
+		run_astar(
+			&mut self.memory,
+			&mut self.queue,
+			hooks,
+			limits,
+			&mut self.stats,
+		)
+	}
+	/// The cheapest path found from a start to `end_node`
+	pub(crate) fn path_to(&self, end_node: N) -> Path<N> {
+		memory_path(&self.memory, end_node)
+	}
+	/// The cheapest known cost of reaching `node`, `None` if it was not discovered
+	pub(crate) fn cost(&self, node: N) -> Option<f32> {
+		self.memory.cost(node)
+	}
+}
+
+/// What a search run by `run_astar()` is looking for. The variants of `astar_path()` differ
+/// only in these, the loop expanding the cheapest node next is the same for each
+pub(crate) trait SearchHooks<N> {
+	/// An estimate of the cost remaining from `node` to a goal
+	fn estimate(&self, node: N) -> f32;
+	/// Whether reaching `node` ends the search
+	fn is_goal(&self, node: N) -> bool;
+	/// The moves out of `node`, reached at `cost`, along with the cost of each
+	fn successors(&self, node: N, cost: f32) -> Vec<(N, f32)>;
+	/// Called with each node about to be expanded
+	fn expanding(&mut self, _node: N) {}
+	/// Called with each node discovered or reached more cheaply, along with its cost and
+	/// estimate
+	fn discovered(&mut self, _node: N, _cost: f32, _estimate: f32) {}
+}
+
+/// Hooks made of closures, for searches needing nothing more than a goal, an estimate and the
+/// moves of each node
+pub(crate) struct FnHooks<E, G, F> {
+	/// See `SearchHooks::estimate`
+	pub(crate) estimate: E,
+	/// See `SearchHooks::is_goal`
+	pub(crate) is_goal: G,
+	/// See `SearchHooks::successors`
+	pub(crate) successors: F,
+}
+
+impl<N, E, G, F> SearchHooks<N> for FnHooks<E, G, F>
+where
+	E: Fn(N) -> f32,
+	G: Fn(N) -> bool,
+	F: Fn(N, f32) -> Vec<(N, f32)>,
+{
+	fn estimate(&self, node: N) -> f32 {
+		(self.estimate)(node)
+	}
+	fn is_goal(&self, node: N) -> bool {
+		(self.is_goal)(node)
+	}
+	fn successors(&self, node: N, cost: f32) -> Vec<(N, f32)> {
+		(self.successors)(node, cost)
+	}
+}
+
+/// The hooks of an ordinary search of `space` for `end_node`
+pub(crate) fn towards<S: SearchSpace>(
+	space: &S,
+	end_node: S::Node,
+) -> impl SearchHooks<S::Node> + '_ {
+	FnHooks {
+		estimate: move |n| space.heuristic(n, end_node),
+		is_goal: move |n| n == end_node,
+		successors: move |n, _| space.successors(n),
+	}
+}
+
+/// Where `run_astar()` keeps the cheapest known cost of reaching each discovered node and the
+/// node it was cheapest reached from
+pub(crate) trait SearchMemory<N> {
+	/// Forget every node
+	fn clear(&mut self);
+	/// The cheapest known cost of reaching `node`, `None` if it has not been discovered
+	fn cost(&self, node: N) -> Option<f32>;
+	/// The node `node` was cheapest reached from, `None` for a start or undiscovered node
+	fn came_from(&self, node: N) -> Option<N>;
+	/// Record that `node` is cheapest reached at `cost` from `came_from`, `None` for a start
+	fn insert(&mut self, node: N, cost: f32, came_from: Option<N>);
+	/// Whether `node` has been expanded since it was last reached more cheaply. A memory keeping
+	/// no closed set leaves stale queue entries to be found by their scores alone
+	fn is_closed(&self, _node: N) -> bool {
+		false
+	}
+	/// Record that `node` has been expanded at its cheapest known cost
+	fn close(&mut self, _node: N) {}
+}
+
+/// A `SearchMemory` of maps, for any space
+#[derive(Debug, Clone)]
+pub(crate) struct NodeMemory<N> {
+	/// The cheapest known complexity of reaching each discovered node
+	costs: NodeMap<N, f32>,
+	/// The node each discovered node was cheapest reached from
+	came_from: NodeMap<N, N>,
+}
+
+impl<N> Default for NodeMemory<N> {
+	fn default() -> Self {
+		NodeMemory {
+			costs: NodeMap::default(),
+			came_from: NodeMap::default(),
+		}
+	}
+}
+
+impl<N: Copy + Eq + Hash> SearchMemory<N> for NodeMemory<N> {
+	fn clear(&mut self) {
+		self.costs.clear();
+		self.came_from.clear();
+	}
+	fn cost(&self, node: N) -> Option<f32> {
+		self.costs.get(&node).copied()
+	}
+	fn came_from(&self, node: N) -> Option<N> {
+		self.came_from.get(&node).copied()
+	}
+	fn insert(&mut self, node: N, cost: f32, came_from: Option<N>) {
+		self.costs.insert(node, cost);
+		match came_from {
+			Some(previous) => self.came_from.insert(node, previous),
+			None => self.came_from.remove(&node),
+		};
+	}
+}
+
+/// Clear `memory` and `queue` then queue every node of `starts` at no cost
+pub(crate) fn begin_astar<N, M, H>(
+	memory: &mut M,
+	queue: &mut BinaryHeap<MinScored<N>>,
+	starts: &[N],
+	hooks: &H,
+) where
+	N: Copy,
+	M: SearchMemory<N>,
+	H: SearchHooks<N>,
+{
+	memory.clear();
+	queue.clear();
+	for start in starts.iter() {
+		// we haven't moved so a starting node costs nothing and its score is just its weight
+		memory.insert(*start, 0.0, None);
+		queue.push(MinScored(hooks.estimate(*start), *start));
+	}
+}
+
+/// The A* loop shared by every variant of `astar_path()`, carrying on from the queue of
+/// `begin_astar()` until a goal of `hooks` is reached, which is returned, or `limits` are. When
+/// a limit is reached the next node stays queued so the search can be resumed. Expansions are
+/// counted into `stats`
+pub(crate) fn run_astar<N, M, H>(
+	memory: &mut M,
+	queue: &mut BinaryHeap<MinScored<N>>,
+	hooks: &mut H,
+	limits: &Limits,
+	stats: &mut SearchStats,
+) -> Result<N, SearchError>
+where
+	N: Copy + PartialEq,
+	M: SearchMemory<N>,
+	H: SearchHooks<N>,
+{
+	// Rather than re-sorting the queue whenever a node is discovered or improved a fresh entry
+	// is pushed onto the heap, entries left behind by a better route are skipped when popped.
+	// A goal will eventually be popped first, meaning that we know the best path
+	while let Some(&MinScored(astar, node)) = queue.peek() {
+		// skip entries of nodes already expanded at their cheapest cost
+		if memory.is_closed(node) {
+			queue.pop();
+			continue;
+		}
+		// every queued node has been discovered
+		let complexity = memory.cost(node).unwrap();
+		// skip stale entries where a better route has since been found
+		if astar > a_star_score(complexity, hooks.estimate(node)) {
+			queue.pop();
+			continue;
+		}
+		if hooks.is_goal(node) {
+			return Ok(node);
+		}
+		// give up with the node left queued so the search can carry on
+		limits.check(stats.nodes_expanded)?;
+		queue.pop();
+		memory.close(node);
+		stats.nodes_expanded += 1;
+		hooks.expanding(node);
+		// expand the node and process each new route. If a new route to a previously
+		// discovered node is found it is only kept when it is cheaper, otherwise it is discarded
+		for (n, step_complexity) in hooks.successors(node, complexity) {
+			let new_complexity = complexity + step_complexity;
+			let is_better = match memory.cost(n) {
+				Some(existing) => new_complexity < existing,
+				None => true,
+			};
+			if is_better {
+				memory.insert(n, new_complexity, Some(node));
+				let target_weight = hooks.estimate(n);
+				hooks.discovered(n, new_complexity, target_weight);
+				queue.push(MinScored(a_star_score(new_complexity, target_weight), n));
+			}
+		}
+		stats.peak_open_len = stats.peak_open_len.max(queue.len());
+	}
+	// every reachable node has been explored without finding a goal
+	Err(SearchError::Unreachable)
+}
+
+/// The path to `end_node` recorded in `memory`, walked backwards from the end node rather than
+/// every route carrying its own copy
+pub(crate) fn memory_path<N: Copy, M: SearchMemory<N>>(memory: &M, end_node: N) -> Path<N> {
+	let mut nodes = vec![end_node];
+	let mut current = end_node;
+	while let Some(previous) = memory.came_from(current) {
+		nodes.push(previous);
+		current = previous;
+	}
+	nodes.reverse();
+	let cumulative_costs = nodes.iter().map(|n| memory.cost(*n).unwrap()).collect();
+	Path::new(nodes, cumulative_costs)
+}
+
+/// Search `hooks` from every node of `starts` with fresh memory, the path to the goal reached
+/// or `None` if there is none
+fn astar_with<N: Copy + Eq + Hash, H: SearchHooks<N>>(
+	starts: &[N],
+	hooks: &mut H,
+) -> Option<Path<N>> {
+	let mut context = SearchContext::new();
+	let end = context
+		.run(starts, hooks, &Limits::expansions(usize::MAX))
+		.ok()?;
+	Some(context.path_to(end))
+}
+
+/// When a search of a `SearchContext` should give up
+pub(crate) struct Limits<'a> {
+	/// Most nodes to expand
+	max_expansions: usize,
+	/// Set to give up
+	cancel: Option<&'a AtomicBool>,
+	/// When to give up
+	deadline: Option<Instant>,
+}
+
+impl<'a> Limits<'a> {
+	/// Number of expansions between looking at the cancel flag and the clock
+	const POLL_INTERVAL: usize = 64;
+	/// Give up only after expanding `max_expansions` nodes
+	pub(crate) fn expansions(max_expansions: usize) -> Limits<'a> {
+		Limits {
+			max_expansions,
+			cancel: None,
+			deadline: None,
+		}
+	}
+	/// Whether a search which has expanded `expansions` nodes may expand another
+	fn check(&self, expansions: usize) -> Result<(), SearchError> {
+		if expansions >= self.max_expansions {
+			return Err(SearchError::ExpansionLimit(self.max_expansions));
+		}
+		if expansions % Self::POLL_INTERVAL == 0 {
+			if let Some(cancel) = self.cancel {
+				if cancel.load(AtomicOrdering::Relaxed) {
+					return Err(SearchError::Cancelled);
+				}
+			}
+			if let Some(deadline) = self.deadline {
+				if Instant::now() >= deadline {
+					return Err(SearchError::TimedOut);
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Why a search ended without a path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchError {
+	/// Every node reachable from the start was explored without finding the end node
+	Unreachable,
+	/// The search gave up after expanding the given number of nodes
+	ExpansionLimit(usize),
+	/// The search was cancelled
+	Cancelled,
+	/// The search gave up at its deadline
+	TimedOut,
+}
+
+impl fmt::Display for SearchError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			SearchError::Unreachable => write!(f, "The end node cannot be reached"),
+			SearchError::ExpansionLimit(limit) => {
+				write!(f, "Gave up after expanding {} nodes", limit)
+			}
+			SearchError::Cancelled => write!(f, "The search was cancelled"),
+			SearchError::TimedOut => write!(f, "The search ran past its deadline"),
+		}
+	}
+}
+
+impl Error for SearchError {}
+
+/// From a starting node calculate the most efficient path to the end node, giving up once
+/// `max_expansions` nodes have been expanded without reaching it, so that a query across a huge
+/// map cannot run unbounded. Returns `SearchError::ExpansionLimit` if the search gave up and
+/// `SearchError::Unreachable` if the end node cannot be reached
+pub fn astar_path_limited<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+	max_expansions: usize,
+) -> Result<Path<S::Node>, SearchError> {
+	SearchContext::new().astar_path_limited(space, start_node, end_node, max_expansions)
+}
+
+/// From a starting node calculate the most efficient path to the end node, giving up once
+/// `cancel` is set, e.g by another thread when the player issues a new order, or once `deadline`
+/// has passed. Both are looked at every few expansions. Returns `SearchError::Cancelled` or
+/// `SearchError::TimedOut` if the search gave up and `SearchError::Unreachable` if the end node
+/// cannot be reached
+pub fn astar_path_cancellable<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+	cancel: &AtomicBool,
+	deadline: Option<Instant>,
+) -> Result<Path<S::Node>, SearchError> {
+	SearchContext::new().astar_path_cancellable(space, start_node, end_node, cancel, deadline)
+}
+
+/// What happened during a search, to help tune the complexities of a map and to catch queries
+/// which search far more of it than expected
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SearchStats {
+	/// Number of nodes whose neighbours were explored
+	pub nodes_expanded: usize,
+	/// Number of distinct nodes reached, including the start node
+	pub nodes_discovered: usize,
+	/// Most entries waiting in the queue at once, including those left behind by a cheaper route
+	pub peak_open_len: usize,
+	/// How long the search took
+	pub duration: Duration,
+}
+
+/// As `astar_path()`, also returning what happened during the search
+pub fn astar_path_with_stats<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+) -> (Option<Path<S::Node>>, SearchStats) {
+	let mut context = SearchContext::new();
+	let path = context.astar_path(space, start_node, end_node);
+	(path, context.stats())
+}
+
+/// The fringe of a Fringe Search, an ordered list of nodes which can be removed from and
+/// inserted into at any point
+struct FringeList<N> {
+	/// The first node of the list
+	head: Option<N>,
+	/// The nodes before and after each node of the list
+	links: HashMap<N, (Option<N>, Option<N>)>,
+}
+
+impl<N: Copy + Eq + Hash> FringeList<N> {
+	/// Create a list holding only `node`
+	fn new(node: N) -> FringeList<N> {
+		let mut links = HashMap::new();
+		links.insert(node, (None, None));
+		FringeList {
+			head: Some(node),
+			links,
+		}
+	}
+	/// Whether `node` is in the list
+	fn contains(&self, node: &N) -> bool {
+		self.links.contains_key(node)
+	}
+	/// The node after `node`
+	fn next(&self, node: &N) -> Option<N> {
+		self.links.get(node).and_then(|l| l.1)
+	}
+	/// Take `node` out of the list
+	fn remove(&mut self, node: &N) {
+		if let Some((previous, next)) = self.links.remove(node) {
+			match previous {
+				Some(p) => self.links.get_mut(&p).unwrap().1 = next,
+				None => self.head = next,
+			}
+			if let Some(n) = next {
+				self.links.get_mut(&n).unwrap().0 = previous;
+			}
+		}
+	}
+	/// Place `node`, which must not already be in the list, straight after `at`
+	fn insert_after(&mut self, at: N, node: N) {
+		let next = self.next(&at);
+		self.links.get_mut(&at).unwrap().1 = Some(node);
+		if let Some(n) = next {
+			self.links.get_mut(&n).unwrap().0 = Some(node);
+		}
+		self.links.insert(node, (Some(at), next));
+	}
+}
+
+/// Find the cheapest path from `start_node` to `end_node` with Fringe Search, an alternative to
+/// `astar_path()` worth benchmarking against it.
+///
+/// Like IDA* the search works within a bound on the score of the nodes it expands, raising the
+/// bound whenever nothing more can be expanded within it, but unlike IDA* it remembers the cost
+/// of every node it discovers so no node is expanded twice along the same route. Nodes waiting
+/// to be expanded are kept in a list, visited in order, rather than a priority queue, so there is
+/// no sorting or heap upkeep. On maps where many nodes share similar scores, such as hexagons of
+/// mostly uniform complexity, this often beats A*. Returns `None` if `end_node` cannot be reached
+pub fn fringe_path<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+) -> Option<Path<S::Node>> {
+	let mut costs: NodeMap<S::Node, f32> = NodeMap::default();
+	let mut came_from: NodeMap<S::Node, S::Node> = NodeMap::default();
+	costs.insert(start_node, 0.0);
+	let mut fringe = FringeList::new(start_node);
+	let mut bound = space.heuristic(start_node, end_node);
+	while fringe.head.is_some() {
+		let mut next_bound = f32::INFINITY;
+		let mut current = fringe.head;
+		while let Some(node) = current {
+			let cost = costs[&node];
+			let score = cost + space.heuristic(node, end_node);
+			if score > bound {
+				// leave the node for a later pass with a higher bound
+				if score < next_bound {
+					next_bound = score;
+				}
+				current = fringe.next(&node);
+				continue;
+			}
+			if node == end_node {
+				return Some(reconstruct_path(node, &came_from, &costs));
+			}
+			// insert the improved successors straight after the node so they are visited next
+			let mut at = node;
+			for (n, step_complexity) in space.successors(node) {
+				let complexity = cost + step_complexity;
+				let is_better = match costs.get(&n) {
+					Some(existing) => complexity < *existing,
+					None => true,
+				};
+				if n == node || !is_better {
+					continue;
+				}
+				costs.insert(n, complexity);
+				came_from.insert(n, node);
+				// listed twice by the space and already placed by the first listing
+				if n == at {
+					continue;
+				}
+				if fringe.contains(&n) {
+					fringe.remove(&n);
+				}
+				fringe.insert_after(at, n);
+				at = n;
+			}
+			current = fringe.next(&node);
+			fringe.remove(&node);
+		}
+		if next_bound == f32::INFINITY {
+			return None;
+		}
+		bound = next_bound;
+	}
+	None
+}
+
+/// Find the cheapest path from `start_node` to `end_node` with the heuristic of `space` ignored,
+/// a uniform-cost search better known as Dijkstra's algorithm. Every node cheaper to reach than
+/// the end node is explored so this is slower than `astar_path`, but the path is optimal whatever
+/// the heuristic returns, making it a baseline to check a heuristic against
+pub fn dijkstra_path<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+) -> Option<Path<S::Node>> {
+	dijkstra_to_first(space, &[start_node], |n| *n == end_node)
+}
+
+/// A view of a `SearchSpace` whose heuristic is always `0`, created with `without_heuristic`.
+/// Any search through it explores as Dijkstra's algorithm would
+pub struct Uninformed<'a, S: SearchSpace> {
+	/// The space being searched
+	space: &'a S,
+}
+
+/// A view of `space` with its heuristic disabled, so searches which take a `SearchSpace` can be
+/// run in Dijkstra mode
+pub fn without_heuristic<S: SearchSpace>(space: &S) -> Uninformed<'_, S> {
+	Uninformed { space }
+}
+
+impl<'a, S: SearchSpace> SearchSpace for Uninformed<'a, S> {
+	type Node = S::Node;
+	fn successors(&self, node: S::Node) -> Vec<(S::Node, f32)> {
+		self.space.successors(node)
+	}
+	fn heuristic(&self, _node: S::Node, _goal: S::Node) -> f32 {
+		0.0
+	}
+	fn predecessors(&self, node: S::Node) -> Vec<(S::Node, f32)> {
+		self.space.predecessors(node)
+	}
+}
+
+/// Find a path from `start_node` to `end_node` with the heuristic multiplied by `epsilon`,
+/// weighted A*. Inflating the heuristic makes the search greedier, heading straight for the end
+/// node and expanding far fewer nodes, at the price of the path no longer being guaranteed the
+/// cheapest. Its cost is bounded though, it is never more than `epsilon` times the cost of the
+/// cheapest path. An `epsilon` of `1` is an ordinary optimal A* search. Panics if `epsilon` is
+/// less than `1`. Returns `None` if `end_node` cannot be reached
+pub fn astar_path_weighted<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+	epsilon: f32,
+) -> Option<Path<S::Node>> {
+	if epsilon.is_nan() || epsilon < 1.0 {
+		panic!(
+			"Heuristic inflation factor must be at least 1, found {}",
+			epsilon
+		);
+	}
+	astar_with(
+		&[start_node],
+		&mut FnHooks {
+			estimate: |n| epsilon * space.heuristic(n, end_node),
+			is_goal: |n| n == end_node,
+			successors: |n, _| space.successors(n),
+		},
+	)
+}
+
+/// Find the cheapest path from `start_node` to whichever of `goals` is cheapest to reach, e.g the
+/// nearest of several resource nodes, with a single search rather than one per goal. The
+/// heuristic of a node is the smallest estimate to any goal so the search stops as soon as the
+/// first goal is reached, which is always the cheapest. Returns `None` if `goals` is empty or
+/// none of them can be reached
+pub fn astar_path_to_any<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	goals: &[S::Node],
+) -> Option<Path<S::Node>> {
+	if goals.is_empty() {
+		return None;
+	}
+	astar_with(
+		&[start_node],
+		&mut FnHooks {
+			// the smallest estimate to any goal
+			estimate: |n| {
+				goals
+					.iter()
+					.map(|g| space.heuristic(n, *g))
+					.fold(f32::INFINITY, f32::min)
+			},
+			is_goal: |n| goals.contains(&n),
+			successors: |n, _| space.successors(n),
+		},
+	)
+}
+
+/// Find the cheapest path to `end_node` from whichever of `starts` can reach it most cheaply,
+/// e.g which of several barracks should send a reinforcement, with a single search rather than
+/// one per start. Every start is queued at no cost so the first path to reach `end_node` is the
+/// cheapest from any of them, the path begins at the start it was found from. Returns `None` if
+/// `starts` is empty or the end node cannot be reached from any of them
+pub fn astar_path_from_any<S: SearchSpace>(
+	space: &S,
+	starts: &[S::Node],
+	end_node: S::Node,
+) -> Option<Path<S::Node>> {
+	astar_with(starts, &mut towards(space, end_node))
+}
+
+/// A view of a space with some nodes and moves taken away
+struct Excluding<'a, S: SearchSpace> {
+	/// The space being searched
+	space: &'a S,
+	/// Nodes which cannot be moved to
+	nodes: HashSet<S::Node>,
+	/// Moves which cannot be made, from the first node to the second
+	moves: HashSet<(S::Node, S::Node)>,
+}
+
+impl<'a, S: SearchSpace> SearchSpace for Excluding<'a, S> {
+	type Node = S::Node;
+	fn successors(&self, node: S::Node) -> Vec<(S::Node, f32)> {
+		let mut successors = self.space.successors(node);
+		successors.retain(|(n, _)| !self.nodes.contains(n) && !self.moves.contains(&(node, *n)));
+		successors
+	}
+	fn heuristic(&self, node: S::Node, goal: S::Node) -> f32 {
+		self.space.heuristic(node, goal)
+	}
+}
+
+/// Find up to `k` of the cheapest distinct paths from `start_node` to `end_node` with Yen's
+/// algorithm, e.g to offer credible alternative routes in a traffic simulation. No path visits
+/// a node twice and they are returned cheapest first, beginning with the path of
+/// `astar_path()`. Fewer than `k` are returned when no more distinct paths exist, none if
+/// `end_node` cannot be reached.
+///
+/// Each further path is found by taking every node of the previous path in turn, keeping the
+/// route up to it and searching on from it with the moves used by earlier paths sharing that
+/// route taken away, so expect around `k` times the length of a path in searches
+pub fn k_shortest_paths<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+	k: usize,
+) -> Vec<Path<S::Node>> {
+	let mut found: Vec<Path<S::Node>> = Vec::new();
+	if k == 0 {
+		return found;
+	}
+	match astar_path(space, start_node, end_node) {
+		Some(path) => found.push(path),
+		None => return found,
+	}
+	// alternatives discovered along the way which are yet to be taken
+	let mut candidates: Vec<Path<S::Node>> = Vec::new();
+	while found.len() < k {
+		let previous = &found[found.len() - 1];
+		for spur in 0..previous.len() - 1 {
+			let root = &previous.nodes()[..=spur];
+			let root_cost = previous.cumulative_costs()[spur];
+			// branch off at the spur node without repeating the root or a known path
+			let mut excluding = Excluding {
+				space,
+				nodes: root[..spur].iter().copied().collect(),
+				moves: HashSet::new(),
+			};
+			for path in found.iter() {
+				if path.len() > spur + 1 && &path.nodes()[..=spur] == root {
+					excluding
+						.moves
+						.insert((path.nodes()[spur], path.nodes()[spur + 1]));
+				}
+			}
+			if let Some(branch) = astar_path(&excluding, root[spur], end_node) {
+				let mut nodes = root.to_vec();
+				let mut costs = previous.cumulative_costs()[..=spur].to_vec();
+				nodes.extend(branch.nodes()[1..].iter().copied());
+				costs.extend(branch.cumulative_costs()[1..].iter().map(|c| c + root_cost));
+				let is_new = !candidates
+					.iter()
+					.chain(found.iter())
+					.any(|p| p.nodes() == nodes.as_slice());
+				if is_new {
+					candidates.push(Path::new(nodes, costs));
+				}
+			}
+		}
+		// take the cheapest alternative, the earliest found of equally cheap ones
+		let mut cheapest: Option<usize> = None;
+		for (i, candidate) in candidates.iter().enumerate() {
+			if cheapest.map_or(true, |c| candidate.cost() < candidates[c].cost()) {
+				cheapest = Some(i);
+			}
+		}
+		match cheapest {
+			Some(i) => found.push(candidates.remove(i)),
+			None => break,
+		}
+	}
+	found
+}
+
+/// Find the quickest path from `start_node` to `end_node` when the cost of entering a node
+/// depends on when it is entered, e.g tides, danger which rises at night or doors which open on
+/// a timer. The cost of a path is treated as the time it takes, setting out at `start_time`.
+///
+/// `delay(node, time)` is the cost added to stepping into `node` when setting off towards it at
+/// `time`, on top of the cost of the step in `space`, or `None` if `node` cannot be entered then.
+/// A delay can include waiting for a door to open, `open_at - time` while it is shut. Delays must
+/// never be negative and setting off later must never arrive sooner, both hold for any delay
+/// which includes the wait for a better time. Returns `None` if `end_node` cannot be reached, the
+/// cumulative costs of the path are the time taken to reach each node
+pub fn astar_path_timed<S, F>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+	start_time: f32,
+	delay: F,
+) -> Option<Path<S::Node>>
+where
+	S: SearchSpace,
+	F: Fn(S::Node, f32) -> Option<f32>,
+{
+	astar_with(
+		&[start_node],
+		&mut FnHooks {
+			estimate: |n| space.heuristic(n, end_node),
+			is_goal: |n| n == end_node,
+			successors: |node, cost| {
+				space
+					.successors(node)
+					.into_iter()
+					.filter_map(|(n, step_complexity)| {
+						delay(n, start_time + cost).map(|wait| (n, step_complexity + wait))
+					})
+					.collect()
+			},
+		},
+	)
+}
+
+/// Find the cheapest path from `start_node` to `end_node` expanding at most `max_expansions`
+/// nodes. When the limit is reached first, or the end node turns out to be unreachable, the
+/// path instead leads to the discovered node with the lowest estimate of the cost remaining to
+/// the end node, the cheapest to reach of any equally close, so a unit can set off in the right
+/// direction while the rest of the path is worked out. Returns the path and whether it reaches
+/// `end_node`
+pub fn astar_partial_path<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+	max_expansions: usize,
+) -> (Path<S::Node>, bool) {
+	let mut hooks = Partial {
+		space,
+		end_node,
+		closest: (start_node, space.heuristic(start_node, end_node), 0.0),
+	};
+	let mut context = SearchContext::new();
+	match context.run(
+		&[start_node],
+		&mut hooks,
+		&Limits::expansions(max_expansions),
+	) {
+		Ok(end) => (context.path_to(end), true),
+		Err(_) => (context.path_to(hooks.closest.0), false),
+	}
+}
+
+/// The hooks of `astar_partial_path()`, tracking the discovered node closest to the end node
+struct Partial<'a, S: SearchSpace> {
+	/// The space being searched
+	space: &'a S,
+	/// Where the path should end
+	end_node: S::Node,
+	/// The discovered node closest to the end with its estimate and cost
+	closest: (S::Node, f32, f32),
+}
+
+impl<'a, S: SearchSpace> SearchHooks<S::Node> for Partial<'a, S> {
+	fn estimate(&self, node: S::Node) -> f32 {
+		self.space.heuristic(node, self.end_node)
+	}
+	fn is_goal(&self, node: S::Node) -> bool {
+		node == self.end_node
+	}
+	fn successors(&self, node: S::Node, _cost: f32) -> Vec<(S::Node, f32)> {
+		self.space.successors(node)
+	}
+	fn discovered(&mut self, node: S::Node, cost: f32, estimate: f32) {
+		let closest = self.closest;
+		if (estimate, cost) < (closest.1, closest.2) || node == closest.0 {
+			self.closest = (node, estimate, cost);
+		}
+	}
+}
+
+/// Find a path from `start_node` through each of `waypoints` in order to `end_node`, e.g a
+/// patrol route or a scripted convoy, by joining the cheapest path of each leg. The node where
+/// two legs meet appears once and costs accumulate along the whole route. Returns `None` if any
+/// leg cannot be travelled
+pub fn astar_via<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	waypoints: &[S::Node],
+	end_node: S::Node,
+) -> Option<Path<S::Node>> {
+	let mut nodes = vec![start_node];
+	let mut costs = vec![0.0];
+	let mut from = start_node;
+	for to in waypoints.iter().copied().chain(Some(end_node)) {
+		let leg = astar_path(space, from, to)?;
+		let so_far = costs[costs.len() - 1];
+		// skip the first node of each leg, it ended the leg before
+		nodes.extend(leg.nodes()[1..].iter().copied());
+		costs.extend(leg.cumulative_costs()[1..].iter().map(|c| c + so_far));
+		from = to;
+	}
+	Some(Path::new(nodes, costs))
+}
+
+/// Find the cheapest path from `start_node` to `end_node` with iterative deepening A*, IDA*.
+///
+/// Rather than keeping open and closed sets IDA* walks routes depth first, abandoning a route
+/// once its score exceeds a bound. When every route within the bound has been walked without
+/// reaching `end_node` the bound is raised to the lowest score which exceeded it and the walk
+/// begins again. Only the route being walked and the untried successors of each node along it
+/// are held, memory growing with the length of the route rather than the area searched. There is
+/// no priority queue and nothing is kept between walks, suiting devices whose heap cannot hold
+/// the maps of `astar_path()`.
+///
+/// The saving is paid for in time. A route never revisits a node already on it, but nothing
+/// remembers the nodes reached by other routes, so a node is walked onwards from once for every
+/// route reaching it within the bound, and nodes are expanded again on every walk. Returns `None`
+/// once a walk abandons no route to the bound, as then every route which can be walked has been
+/// without finding `end_node`, which in a large open space may take a very long time. See
+/// `idastar_path_limited()` to bound the work done
+pub fn idastar_path<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+) -> Option<Path<S::Node>> {
+	idastar_path_limited(space, start_node, end_node, usize::MAX).ok()
+}
+
+/// As `idastar_path()`, giving up once `max_expansions` nodes have been expanded over all the
+/// walks without reaching `end_node`. Returns `SearchError::ExpansionLimit` if the search gave
+/// up and `SearchError::Unreachable` if the end node cannot be reached
+pub fn idastar_path_limited<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+	max_expansions: usize,
+) -> Result<Path<S::Node>, SearchError> {
+	if start_node == end_node {
+		return Ok(Path::new(vec![start_node], vec![0.0]));
+	}
+	let limits = Limits::expansions(max_expansions);
+	let mut expansions = 0;
+	let mut bound = space.heuristic(start_node, end_node);
+	loop {
+		let mut next_bound = f32::INFINITY;
+		// the route being walked, the cumulative cost of each node along it and the successors
+		// of each node yet to be tried
+		let mut nodes = vec![start_node];
+		let mut costs = vec![0.0];
+		limits.check(expansions)?;
+		expansions += 1;
+		let mut untried = vec![space.successors(start_node)];
+		while let Some(successors) = untried.last_mut() {
+			let (n, step_complexity) = match successors.pop() {
+				Some(s) => s,
+				None => {
+					// every successor tried, step back along the route
+					untried.pop();
+					nodes.pop();
+					costs.pop();
+					continue;
+				}
+			};
+			// never walk in a circle back to a node already on the route
+			if nodes.contains(&n) {
+				continue;
+			}
+			let cost = costs[costs.len() - 1] + step_complexity;
+			let score = cost + space.heuristic(n, end_node);
+			if score > bound {
+				if score < next_bound {
+					next_bound = score;
+				}
+				continue;
+			}
+			nodes.push(n);
+			costs.push(cost);
+			if n == end_node {
+				return Ok(Path::new(nodes, costs));
+			}
+			limits.check(expansions)?;
+			expansions += 1;
+			untried.push(space.successors(n));
+		}
+		// nothing was abandoned to the bound so raising it cannot reach anything new
+		if next_bound == f32::INFINITY {
+			return Err(SearchError::Unreachable);
+		}
+		bound = next_bound;
+	}
+}
+
+/// A node held in memory by `smastar_path()`, one node of the search tree
+#[derive(Debug, Clone)]
+struct SmaEntry<N> {
+	/// The node of the space
+	node: N,
+	/// The cost of reaching the node along the route from the start
+	cost: f32,
+	/// The lowest score of any route through the node, raised as its successors are scored
+	score: f32,
+	/// Number of moves from the start
+	depth: usize,
+	/// Index of the entry this node was reached from, `None` for the start
+	parent: Option<usize>,
+	/// Indices of the successors held in memory
+	children: Vec<usize>,
+	/// Successors dropped to free memory and their scores when dropped
+	forgotten: Vec<(N, f32)>,
+	/// Position in the successors of the node from which the next successor is generated
+	next: usize,
+	/// Whether every successor has been generated at least once
+	expanded: bool,
+	/// Whether successors are yet to be generated, or generated again after being dropped
+	open: bool,
+}
+
+/// Find the cheapest path from `start_node` to `end_node` holding at most `max_nodes` nodes in
+/// memory, simplified memory-bounded A*, SMA*, by Russell.
+///
+/// Nodes are generated one successor at a time as a tree of routes from `start_node`. Once
+/// `max_nodes` are held the leaf with the worst score is dropped to make room, its parent
+/// remembering the score so the route is only generated again once every better route has been
+/// ruled out. A node reached again while a copy of it reached at no more cost in no more moves
+/// is held goes no further, the held copy covering every route onward. Memory is fixed up front,
+/// unlike `astar_path()` whose maps grow with the area searched, and far more of it is put to
+/// use than by `idastar_path()`.
+///
+/// The cheapest path is found whenever it has at most `max_nodes` nodes, otherwise the cheapest
+/// path which does. The cost is time, dropped routes are searched again and each step scans the
+/// nodes held, so keep `max_nodes` to the thousands. Returns `None` once every route held or
+/// remembered has been ruled out, as no path of at most `max_nodes` nodes reaches `end_node`.
+/// Panics if `max_nodes` is less than `2`
+pub fn smastar_path<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+	max_nodes: usize,
+) -> Option<Path<S::Node>> {
+	if max_nodes < 2 {
+		panic!("SMA* must hold at least two nodes, found {}", max_nodes);
+	}
+	let mut entries: Vec<Option<SmaEntry<S::Node>>> = vec![Some(SmaEntry {
+		node: start_node,
+		cost: 0.0,
+		score: space.heuristic(start_node, end_node),
+		depth: 0,
+		parent: None,
+		children: Vec::new(),
+		forgotten: Vec::new(),
+		next: 0,
+		expanded: false,
+		open: true,
+	})];
+	// slots of dropped entries, reused before the arena grows
+	let mut free: Vec<usize> = Vec::new();
+	// the entry of the cheapest copy of each node held
+	let mut held_nodes: NodeMap<S::Node, usize> = NodeMap::default();
+	held_nodes.insert(start_node, 0);
+	loop {
+		// the open node with the lowest score, the deepest among equals
+		let mut best: Option<(f32, usize, usize)> = None;
+		for (i, e) in entries.iter().enumerate() {
+			if let Some(e) = e {
+				if e.open {
+					let is_better = match best {
+						Some((score, depth, _)) => {
+							e.score < score || (e.score == score && e.depth > depth)
+						}
+						None => true,
+					};
+					if is_better {
+						best = Some((e.score, e.depth, i));
+					}
+				}
+			}
+		}
+		let (score, _, index) = best?;
+		if score == f32::INFINITY {
+			return None;
+		}
+		let entry = entries[index].as_ref().unwrap();
+		if entry.node == end_node {
+			// walk backwards through the parents to rebuild the path
+			let mut route = vec![index];
+			while let Some(parent) = entries[route[route.len() - 1]].as_ref().unwrap().parent {
+				route.push(parent);
+			}
+			route.reverse();
+			let route: Vec<&SmaEntry<S::Node>> = route
+				.iter()
+				.map(|i| entries[*i].as_ref().unwrap())
+				.collect();
+			let nodes = route.iter().map(|e| e.node).collect();
+			let costs = route.iter().map(|e| e.cost).collect();
+			return Some(Path::new(nodes, costs));
+		}
+		// never walk in a circle
+		let mut ancestors = HashSet::new();
+		let mut ancestor = entry.parent;
+		while let Some(a) = ancestor {
+			let a = entries[a].as_ref().unwrap();
+			ancestors.insert(a.node);
+			ancestor = a.parent;
+		}
+		let successors: Vec<(S::Node, f32)> = space
+			.successors(entry.node)
+			.into_iter()
+			.filter(|(n, _)| !ancestors.contains(n))
+			.collect();
+		if successors.is_empty() {
+			// a dead end, no route through it reaches the end node
+			let entry = entries[index].as_mut().unwrap();
+			entry.score = f32::INFINITY;
+			entry.expanded = true;
+			entry.open = false;
+			let parent = entry.parent;
+			sma_backup(&mut entries, parent);
+			continue;
+		}
+		// the next successor not held in memory, wrapping around to those dropped
+		let held: Vec<S::Node> = entry
+			.children
+			.iter()
+			.map(|c| entries[*c].as_ref().unwrap().node)
+			.collect();
+		let mut position = entry.next;
+		while held.contains(&successors[position % successors.len()].0) {
+			position += 1;
+		}
+		let position = position % successors.len();
+		let (n, step_complexity) = successors[position];
+		let cost = entry.cost + step_complexity;
+		let depth = entry.depth + 1;
+		// a held copy reached at no more cost in no more moves covers every route onward
+		let covered = match held_nodes.get(&n) {
+			Some(i) => {
+				let copy = entries[*i].as_ref().unwrap();
+				copy.cost <= cost && copy.depth <= depth
+			}
+			None => false,
+		};
+		let mut n_score = if covered || (n != end_node && depth >= max_nodes - 1) {
+			// covered, or no room left for any further node so the end node cannot be reached
+			// this way
+			f32::INFINITY
+		} else {
+			(cost + space.heuristic(n, end_node)).max(entry.score)
+		};
+		let entry = entries[index].as_mut().unwrap();
+		if let Some(i) = entry.forgotten.iter().position(|(f, _)| *f == n) {
+			n_score = n_score.max(entry.forgotten.swap_remove(i).1);
+		}
+		let pass_complete = position + 1 == successors.len();
+		entry.next = if pass_complete { 0 } else { position + 1 };
+		if pass_complete {
+			entry.expanded = true;
+		}
+		if entries.len() - free.len() >= max_nodes {
+			// drop the leaf with the worst score, the shallowest among equals
+			let mut worst: Option<(f32, usize, usize)> = None;
+			for (i, e) in entries.iter().enumerate() {
+				if let Some(e) = e {
+					if i != index && e.children.is_empty() {
+						let is_worse = match worst {
+							Some((score, depth, _)) => {
+								e.score > score || (e.score == score && e.depth < depth)
+							}
+							None => true,
+						};
+						if is_worse {
+							worst = Some((e.score, e.depth, i));
+						}
+					}
+				}
+			}
+			// only a lone route fills memory, whose end cannot be expanded, so no leaf other
+			// than the node being expanded can exist
+			let (_, _, dropped) = worst?;
+			let dropped_entry = entries[dropped].take().unwrap();
+			free.push(dropped);
+			if held_nodes.get(&dropped_entry.node) == Some(&dropped) {
+				held_nodes.remove(&dropped_entry.node);
+			}
+			// the start is never a leaf other than the node being expanded
+			let parent = entries[dropped_entry.parent.unwrap()].as_mut().unwrap();
+			parent.children.retain(|c| *c != dropped);
+			parent
+				.forgotten
+				.push((dropped_entry.node, dropped_entry.score));
+			parent.open = true;
+		}
+		let child = SmaEntry {
+			node: n,
+			cost,
+			score: n_score,
+			depth,
+			parent: Some(index),
+			children: Vec::new(),
+			forgotten: Vec::new(),
+			next: 0,
+			expanded: false,
+			open: true,
+		};
+		let child_index = match free.pop() {
+			Some(i) => {
+				entries[i] = Some(child);
+				i
+			}
+			None => {
+				entries.push(Some(child));
+				entries.len() - 1
+			}
+		};
+		if !covered {
+			let is_cheapest = match held_nodes.get(&n) {
+				Some(i) => cost < entries[*i].as_ref().unwrap().cost,
+				None => true,
+			};
+			if is_cheapest {
+				held_nodes.insert(n, child_index);
+			}
+		}
+		let entry = entries[index].as_mut().unwrap();
+		entry.children.push(child_index);
+		if entry.children.len() == successors.len() {
+			entry.open = false;
+		}
+		if pass_complete {
+			sma_backup(&mut entries, Some(index));
+		}
+	}
+}
+
+/// Raise the score of the entry at `index` to the lowest score of its successors, both those held
+/// and those dropped, and so on up through its ancestors for as long as scores change
+fn sma_backup<N>(entries: &mut [Option<SmaEntry<N>>], mut index: Option<usize>) {
+	while let Some(i) = index {
+		let entry = entries[i].as_ref().unwrap();
+		if !entry.expanded {
+			return;
+		}
+		let mut score = f32::INFINITY;
+		for c in entry.children.iter() {
+			score = score.min(entries[*c].as_ref().unwrap().score);
+		}
+		for (_, forgotten) in entry.forgotten.iter() {
+			score = score.min(*forgotten);
+		}
+		let entry = entries[i].as_mut().unwrap();
+		if score == entry.score {
+			return;
+		}
+		entry.score = score;
+		index = entry.parent;
+	}
+}
+
+/// Search outwards from every node in `sources` at once, each beginning with no cost, until
+/// any node in `targets` is reached. Returns the cheapest path from one of the sources to the
+/// first target reached or `None` if no target can be reached
+pub(crate) fn dijkstra_to_any<S: SearchSpace>(
+	space: &S,
+	sources: &[S::Node],
+	targets: &HashSet<S::Node>,
+) -> Option<Path<S::Node>> {
+	dijkstra_to_first(space, sources, |n| targets.contains(n))
+}
+
+/// Search outwards from every node in `sources` at once, each beginning with no cost, until a
+/// node satisfying `is_target` is reached. Returns the cheapest path from one of the sources to
+/// the first target reached or `None` if no target can be reached
+pub(crate) fn dijkstra_to_first<S: SearchSpace, F: Fn(&S::Node) -> bool>(
+	space: &S,
+	sources: &[S::Node],
+	is_target: F,
+) -> Option<Path<S::Node>> {
+	astar_with(
+		sources,
+		&mut FnHooks {
+			estimate: |_| 0.0,
+			is_goal: |n| is_target(&n),
+			successors: |n, _| space.successors(n),
+		},
+	)
+}
+
+/// The cost of the cheapest path from `start_node` to every node of `space` which can be reached,
+/// found by a single Dijkstra search. Far faster than a search per destination when costs to
+/// many nodes are needed, such as for a movement range overlay. `start_node` costs `0` and
+/// unreachable nodes are absent. The map is unordered, sort its entries where a stable order
+/// matters such as in lockstep multiplayer
+pub fn cost_field<S: SearchSpace>(start_node: S::Node, space: &S) -> HashMap<S::Node, f32> {
+	dijkstra_costs(space, &[start_node])
+}
+
+/// Every node of `space` whose cheapest path from `start_node` costs no more than `budget`, along
+/// with the cost of that path, e.g the hexagons a unit can move to this turn. The search stops
+/// at the edge of the budget rather than covering the whole space as `cost_field` does.
+/// `start_node` costs `0` and is always included. The map is unordered
+pub fn reachable_within<S: SearchSpace>(
+	start_node: S::Node,
+	budget: f32,
+	space: &S,
+) -> HashMap<S::Node, f32> {
+	let mut costs: HashMap<S::Node, f32> = HashMap::new();
+	let mut queue = BinaryHeap::new();
+	costs.insert(start_node, 0.0);
+	queue.push(MinScored(0.0, start_node));
+	while let Some(MinScored(cost, node)) = queue.pop() {
+		// skip stale entries where a cheaper route has since been found
+		if cost > costs[&node] {
+			continue;
+		}
+		for (n, step_complexity) in space.successors(node) {
+			let complexity = cost + step_complexity;
+			if complexity > budget {
+				continue;
+			}
+			let is_better = match costs.get(&n) {
+				Some(existing) => complexity < *existing,
+				None => true,
+			};
+			if is_better {
+				costs.insert(n, complexity);
+				queue.push(MinScored(complexity, n));
+			}
+		}
+	}
+	costs
+}
+
+/// Find a path away from `threats` costing no more than `budget`, e.g for a unit fleeing its
+/// attackers. The distance of a node from the threats is the smallest estimate of the heuristic
+/// of `space` from any threat, for a `HexGrid` the number of jumps.
+///
+/// With a `safe_distance` the path leads to the cheapest node to reach at least that far from
+/// every threat, `None` if there is none within the budget. Without one the path leads to the
+/// node furthest from the threats which can be reached within the budget, the cheapest of any
+/// equally far, which may be `start_node` itself
+pub fn flee_path<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	threats: &[S::Node],
+	budget: f32,
+	safe_distance: Option<f32>,
+) -> Option<Path<S::Node>> {
+	let distance = |n: S::Node| {
+		threats
+			.iter()
+			.map(|t| space.heuristic(*t, n))
+			.fold(f32::INFINITY, f32::min)
+	};
+	let mut costs: NodeMap<S::Node, f32> = NodeMap::default();
+	let mut came_from: NodeMap<S::Node, S::Node> = NodeMap::default();
+	let mut queue = BinaryHeap::new();
+	costs.insert(start_node, 0.0);
+	queue.push(MinScored(0.0, start_node));
+	// the furthest node from the threats expanded so far and its distance
+	let mut furthest = (start_node, distance(start_node));
+	while let Some(MinScored(cost, node)) = queue.pop() {
+		// skip stale entries where a cheaper route has since been found
+		if cost > costs[&node] {
+			continue;
+		}
+		let away = distance(node);
+		match safe_distance {
+			Some(safe) if away >= safe => {
+				return Some(reconstruct_path(node, &came_from, &costs));
+			}
+			// nodes are expanded cheapest first so an equally far node is never cheaper
+			_ if away > furthest.1 => furthest = (node, away),
+			_ => {}
+		}
+		for (n, step_complexity) in space.successors(node) {
+			let complexity = cost + step_complexity;
+			if complexity > budget {
+				continue;
+			}
+			let is_better = match costs.get(&n) {
+				Some(existing) => complexity < *existing,
+				None => true,
+			};
+			if is_better {
+				costs.insert(n, complexity);
+				came_from.insert(n, node);
+				queue.push(MinScored(complexity, n));
+			}
+		}
+	}
+	if safe_distance.is_some() {
+		return None;
+	}
+	Some(reconstruct_path(furthest.0, &came_from, &costs))
+}
+
+/// The cost of the cheapest path from any node of `sources` to every node which can be reached
+pub(crate) fn dijkstra_costs<S: SearchSpace>(
+	space: &S,
+	sources: &[S::Node],
+) -> HashMap<S::Node, f32> {
+	let mut costs: HashMap<S::Node, f32> = HashMap::new();
+	let mut queue = BinaryHeap::new();
+	for source in sources.iter() {
+		costs.insert(*source, 0.0);
+		queue.push(MinScored(0.0, *source));
+	}
+	while let Some(MinScored(cost, node)) = queue.pop() {
+		// skip stale entries where a cheaper route has since been found
+		if cost > costs[&node] {
+			continue;
+		}
+		for (n, step_complexity) in space.successors(node) {
+			let complexity = cost + step_complexity;
+			let is_better = match costs.get(&n) {
+				Some(existing) => complexity < *existing,
+				None => true,
+			};
+			if is_better {
+				costs.insert(n, complexity);
+				queue.push(MinScored(complexity, n));
+			}
+		}
+	}
+	costs
+}
+
+/// Find the cheapest path from each node of `starts` to a single `goal` by searching backwards
+/// from the goal once, rather than a separate search from every start. Useful when many units
+/// converge on one target. Moves are followed in reverse with `SearchSpace::predecessors` so
+/// direction dependent costs are respected, each path is priced in its direction of travel.
+///
+/// Returns a path for each start in the same order as `starts`, `None` where the goal cannot be
+/// reached
+pub fn astar_from_goal<S: SearchSpace>(
+	space: &S,
+	starts: &[S::Node],
+	goal: S::Node,
+) -> Vec<Option<Path<S::Node>>> {
+	// the cheapest known cost of reaching the goal from each discovered node
+	let mut costs: NodeMap<S::Node, f32> = NodeMap::default();
+	// the next node towards the goal from each discovered node and the cost of stepping to it
+	let mut next_step: NodeMap<S::Node, (S::Node, f32)> = NodeMap::default();
+	let mut remaining: HashSet<S::Node> = starts.iter().copied().collect();
+	let mut queue = BinaryHeap::new();
+	costs.insert(goal, 0.0);
+	queue.push(MinScored(0.0, goal));
+	while let Some(MinScored(cost, node)) = queue.pop() {
+		// skip stale entries where a cheaper route has since been found
+		if cost > costs[&node] {
+			continue;
+		}
+		remaining.remove(&node);
+		if remaining.is_empty() {
+			break;
+		}
+		for (n, step_complexity) in space.predecessors(node) {
+			let complexity = cost + step_complexity;
+			let is_better = match costs.get(&n) {
+				Some(existing) => complexity < *existing,
+				None => true,
+			};
+			if is_better {
+				costs.insert(n, complexity);
+				next_step.insert(n, (node, step_complexity));
+				queue.push(MinScored(complexity, n));
+			}
+		}
+	}
+	starts
+		.iter()
+		.map(|start| {
+			if remaining.contains(start) {
+				return None;
+			}
+			// walk forwards towards the goal accumulating the cost of each step
+			let mut nodes = vec![*start];
+			let mut cumulative_costs = vec![0.0];
+			let mut current = *start;
+			let mut total = 0.0;
+			while let Some((next, step)) = next_step.get(&current) {
+				total += step;
+				nodes.push(*next);
+				cumulative_costs.push(total);
+				current = *next;
+			}
+			Some(Path::new(nodes, cumulative_costs))
+		})
+		.collect()
+}
+
+/// One side of a bidirectional search
+struct Frontier<N> {
+	/// The cheapest known cost between each discovered node and the side's origin
+	costs: NodeMap<N, f32>,
+	/// The node each discovered node is linked to towards the origin, and the cost of the step
+	links: NodeMap<N, (N, f32)>,
+	/// Discovered nodes yet to be expanded, scored by cost plus potential
+	queue: BinaryHeap<MinScored<N>>,
+}
+
+impl<N: Copy + Eq + Hash> Frontier<N> {
+	/// Create a side beginning at `origin` whose score is `key`
+	fn new(origin: N, key: f32) -> Frontier<N> {
+		let mut costs = NodeMap::default();
+		costs.insert(origin, 0.0);
+		let mut queue = BinaryHeap::new();
+		queue.push(MinScored(key, origin));
+		Frontier {
+			costs,
+			links: NodeMap::default(),
+			queue,
+		}
+	}
+	/// The smallest score waiting to be expanded
+	fn top_key(&self) -> f32 {
+		match self.queue.peek() {
+			Some(MinScored(key, _)) => *key,
+			None => f32::INFINITY,
+		}
+	}
+}
+
+/// Find the cheapest path from `start_node` to `end_node` by searching forwards from the start
+/// and backwards from the end at the same time until the two searches meet. Each search only
+/// needs to cover around half the distance, on large open maps this expands far fewer nodes than
+/// `astar_path`.
+///
+/// The backwards search follows `SearchSpace::predecessors` and estimates the cost from the start
+/// with `heuristic(start_node, node)`, both searches are balanced by averaging the two estimates
+/// so the path remains optimal whenever the heuristic never overestimates and obeys the triangle
+/// inequality. Returns `None` if `end_node` cannot be reached
+pub fn astar_path_bidirectional<S: SearchSpace>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+) -> Option<Path<S::Node>> {
+	if start_node == end_node {
+		return Some(Path::new(vec![start_node], vec![0.0]));
+	}
+	// a potential balancing the estimate towards the end against the estimate from the start,
+	// forwards scores add it and backwards scores subtract it
+	let potential =
+		|n: S::Node| (space.heuristic(n, end_node) - space.heuristic(start_node, n)) / 2.0;
+	let mut forwards = Frontier::new(start_node, potential(start_node));
+	let mut backwards = Frontier::new(end_node, -potential(end_node));
+	// the cost of the best path found so far and the node where its two halves meet
+	let mut best_cost = f32::INFINITY;
+	let mut meeting = None;
+	loop {
+		// no path through an unexpanded node can beat the best path found
+		if forwards.top_key() + backwards.top_key() >= best_cost {
+			break;
+		}
+		let is_forwards = forwards.top_key() <= backwards.top_key();
+		let (side, other, sign) = if is_forwards {
+			(&mut forwards, &backwards, 1.0)
+		} else {
+			(&mut backwards, &forwards, -1.0)
+		};
+		let MinScored(key, node) = match side.queue.pop() {
+			Some(x) => x,
+			None => break,
+		};
+		let cost = side.costs[&node];
+		// skip stale entries where a cheaper route has since been found
+		if key > cost + sign * potential(node) {
+			continue;
+		}
+		let steps = if is_forwards {
+			space.successors(node)
+		} else {
+			space.predecessors(node)
+		};
+		for (n, step_complexity) in steps {
+			let complexity = cost + step_complexity;
+			let is_better = match side.costs.get(&n) {
+				Some(existing) => complexity < *existing,
+				None => true,
+			};
+			if is_better {
+				side.costs.insert(n, complexity);
+				side.links.insert(n, (node, step_complexity));
+				side.queue
+					.push(MinScored(complexity + sign * potential(n), n));
+				// the other side has reached this node so the two halves form a path
+				if let Some(remaining) = other.costs.get(&n) {
+					let total = complexity + remaining;
+					if total < best_cost {
+						best_cost = total;
+						meeting = Some(n);
+					}
+				}
+			}
+		}
+	}
+	let meeting = meeting?;
+	// walk back from the meeting node to the start
+	let mut nodes = vec![meeting];
+	let mut current = meeting;
+	while let Some((previous, _)) = forwards.links.get(&current) {
+		nodes.push(*previous);
+		current = *previous;
+	}
+	nodes.reverse();
+	let mut cumulative_costs: Vec<f32> = nodes.iter().map(|n| forwards.costs[n]).collect();
+	// then forwards from the meeting node to the end
+	let mut total = forwards.costs[&meeting];
+	current = meeting;
+	while let Some((next, step)) = backwards.links.get(&current) {
+		total += step;
+		nodes.push(*next);
+		cumulative_costs.push(total);
+		current = *next;
+	}
+	Some(Path::new(nodes, cumulative_costs))
+}
+
+/// Walk backwards from `end_node` through `came_from` to rebuild the path which reached it
+pub(crate) fn reconstruct_path<N: Copy + Eq + Hash>(
+	end_node: N,
+	came_from: &NodeMap<N, N>,
+	costs: &NodeMap<N, f32>,
+) -> Path<N> {
+	let mut nodes = vec![end_node];
+	let mut current = end_node;
+	while let Some(previous) = came_from.get(&current) {
+		nodes.push(*previous);
+		current = *previous;
+	}
+	nodes.reverse();
+	let cumulative_costs = nodes.iter().map(|n| costs[n]).collect();
+	Path::new(nodes, cumulative_costs)
+}
+
+/// A node paired with a score, ordered such that a `BinaryHeap` pops the lowest score first
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MinScored<N>(pub(crate) f32, pub(crate) N);
+
+impl<N> PartialEq for MinScored<N> {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl<N> Eq for MinScored<N> {}
+
+impl<N> PartialOrd for MinScored<N> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<N> Ord for MinScored<N> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		// reversed so that the smallest score is the greatest item in the heap
+		other.0.partial_cmp(&self.0).unwrap_or(Ordering::Equal)
+	}
+}
+
+/// Determines a score to rank a chosen path, lower scores are better
+fn a_star_score(complexity: f32, weighting: f32) -> f32 {
+	complexity + weighting
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::*;
+	use crate::helpers::node_distance;
+	use crate::node_store::ProceduralNodes;
+	use crate::search::*;
+	use crate::test_support::assert_path_optimal;
+	use crate::test_support::axial_fixture;
+	use ::std::cell::RefCell;
+	use ::std::sync::atomic::AtomicBool;
+	use ::std::sync::atomic::Ordering;
+	use ::std::sync::Arc;
+	use ::std::thread;
+	use ::std::time::Duration;
+	use ::std::time::Instant;
+
+	/// A space noting the nodes whose successors and predecessors the search asks for
+	struct Recorded<'a, S: SearchSpace> {
+		/// The space being searched
+		space: &'a S,
+		/// Nodes whose successors were asked for, in the order asked
+		forwards: RefCell<Vec<S::Node>>,
+		/// Nodes whose predecessors were asked for, in the order asked
+		backwards: RefCell<Vec<S::Node>>,
+	}
+
+	impl<'a, S: SearchSpace> Recorded<'a, S> {
+		/// Record the search of `space`
+		fn new(space: &'a S) -> Recorded<'a, S> {
+			Recorded {
+				space,
+				forwards: RefCell::new(Vec::new()),
+				backwards: RefCell::new(Vec::new()),
+			}
+		}
+	}
+
+	impl<'a, S: SearchSpace> SearchSpace for Recorded<'a, S> {
+		type Node = S::Node;
+		fn successors(&self, node: S::Node) -> Vec<(S::Node, f32)> {
+			self.forwards.borrow_mut().push(node);
+			self.space.successors(node)
+		}
+		fn heuristic(&self, node: S::Node, goal: S::Node) -> f32 {
+			self.space.heuristic(node, goal)
+		}
+		fn predecessors(&self, node: S::Node) -> Vec<(S::Node, f32)> {
+			self.backwards.borrow_mut().push(node);
+			self.space.predecessors(node)
+		}
+	}
+	/// Two routes from `S` to `E`, straight through the swamp `5` or along a longer lane around
+	/// it which costs less
+	/// ```txt
+	///   column:  -2  -1   0   1   2
+	///                             E
+	///                         .
+	///                     5
+	///                 .       .
+	///             S       .
+	/// ```
+	fn fork() -> HexGrid<AxialLayout> {
+		let mut nodes = HashMap::new();
+		for node in [(-2, 0), (-1, 0), (1, 0), (2, 0), (0, -1), (1, -1)] {
+			nodes.insert(node, 1.0);
+		}
+		nodes.insert((0, 0), 5.0);
+		HexGrid::new(AxialLayout::new(2), nodes)
+	}
+	/// The cheapest path across `fork()`, around the swamp at a cost of `5`
+	const AROUND: [(i32, i32); 6] = [(-2, 0), (-1, 0), (0, -1), (1, -1), (1, 0), (2, 0)];
+	/// The path straight across `fork()` through the swamp at a cost of `8`
+	const THROUGH: [(i32, i32); 5] = [(-2, 0), (-1, 0), (0, 0), (1, 0), (2, 0)];
+	#[test]
+	/// Every search finds paths as cheap as the brute force reference across grids of random
+	/// complexities, and nothing for unreachable goals
+	fn matches_reference() {
+		for seed in 0..6 {
+			let grid = axial_fixture(3, seed);
+			for goal in [(3, 0), (-3, 3), (0, -3), (1, -2), (0, 0)] {
+				let found = astar_path(&grid, (0, 0), goal);
+				assert_path_optimal(&grid, (0, 0), goal, found.as_ref());
+				let bidirectional = astar_path_bidirectional(&grid, (0, 0), goal);
+				assert_path_optimal(&grid, (0, 0), goal, bidirectional.as_ref());
+				let weighted = astar_path_weighted(&grid, (0, 0), goal, 1.0);
+				assert_path_optimal(&grid, (0, 0), goal, weighted.as_ref());
+				if found.is_some() {
+					let deepening = idastar_path(&grid, (0, 0), goal);
+					assert_path_optimal(&grid, (0, 0), goal, deepening.as_ref());
+				}
+				let fringe = fringe_path(&grid, (0, 0), goal);
+				assert_path_optimal(&grid, (0, 0), goal, fringe.as_ref());
+				let bounded = smastar_path(&grid, (0, 0), goal, 100);
+				assert_path_optimal(&grid, (0, 0), goal, bounded.as_ref());
+			}
+		}
+	}
+	#[test]
+	/// The two halves of a bidirectional search meet beyond the swamp `3` next to the start `S`,
+	/// the forwards search stopping at its edge while the backwards search crosses the cheap
+	/// ground from the end `E`
+	/// ```txt
+	///   column:  -3  -2  -1   0   1   2   3
+	///                                     E
+	///                                 .
+	///                             .
+	///                         .
+	///                     3
+	///                 3
+	///             S
+	/// ```
+	fn bidirectional_meets_beyond_swamp() {
+		let mut nodes = HashMap::new();
+		for q in -3..=3 {
+			nodes.insert((q, 0), 1.0);
+		}
+		nodes.insert((-2, 0), 3.0);
+		nodes.insert((-1, 0), 3.0);
+		let grid = HexGrid::new(AxialLayout::new(3), nodes);
+		let recorded = Recorded::new(&grid);
+		let path = astar_path_bidirectional(&recorded, (-3, 0), (3, 0)).unwrap();
+		let corridor = [(-3, 0), (-2, 0), (-1, 0), (0, 0), (1, 0), (2, 0), (3, 0)];
+		assert_eq!(&corridor, path.nodes());
+		assert_eq!(
+			&[0.0, 2.0, 5.0, 7.0, 8.0, 9.0, 10.0],
+			path.cumulative_costs()
+		);
+		assert_eq!(vec![(-3, 0), (-2, 0)], recorded.forwards.into_inner());
+		let backwards = vec![(3, 0), (2, 0), (1, 0), (0, 0)];
+		assert_eq!(backwards, recorded.backwards.into_inner());
+		let still = astar_path_bidirectional(&grid, (0, 0), (0, 0)).unwrap();
+		assert_eq!(&[(0, 0)], still.nodes());
+		assert!(astar_path_bidirectional(&grid, (-3, 0), (3, -3)).is_none());
+	}
+	#[test]
+	/// The backwards half of a bidirectional search follows a one way portal `>` against its
+	/// direction of travel, so the portal carries `S` to `E` but offers no way back
+	/// ```txt
+	///   column:  -2  -1   0   1   2
+	///                             E
+	///                         >
+	///                 >
+	///             S
+	/// ```
+	fn bidirectional_through_portal() {
+		let mut nodes = HashMap::new();
+		for node in [(-2, 0), (-1, 0), (1, 0), (2, 0)] {
+			nodes.insert(node, 1.0);
+		}
+		let mut grid = HexGrid::new(AxialLayout::new(2), nodes);
+		grid.add_portal((-1, 0), (1, 0), 2.0);
+		let path = astar_path_bidirectional(&grid, (-2, 0), (2, 0)).unwrap();
+		assert_eq!(&[(-2, 0), (-1, 0), (1, 0), (2, 0)], path.nodes());
+		assert_eq!(4.0, path.cost());
+		assert!(astar_path_bidirectional(&grid, (2, 0), (-2, 0)).is_none());
+	}
+	#[test]
+	/// Without inflation the heuristic leads around the swamp by the cheapest path, inflated
+	/// threefold it rushes straight through for a path within three times the cost
+	fn weighted_rushes_through_swamp() {
+		let grid = fork();
+		let exact = astar_path_weighted(&grid, (-2, 0), (2, 0), 1.0).unwrap();
+		assert_eq!(&AROUND, exact.nodes());
+		assert_eq!(5.0, exact.cost());
+		let rushed = astar_path_weighted(&grid, (-2, 0), (2, 0), 3.0).unwrap();
+		assert_eq!(&THROUGH, rushed.nodes());
+		assert_eq!(8.0, rushed.cost());
+		assert!(rushed.cost() <= 3.0 * exact.cost());
+	}
+	#[test]
+	/// Iterative deepening raises its bound until the way around the swamp fits, never walking
+	/// the dearer route through it, and gives up at an expansion limit
+	fn idastar_finds_way_around() {
+		let grid = fork();
+		let path = idastar_path(&grid, (-2, 0), (2, 0)).unwrap();
+		assert_eq!(&AROUND, path.nodes());
+		assert_eq!(5.0, path.cost());
+		let limited = idastar_path_limited(&grid, (-2, 0), (2, 0), 1);
+		assert_eq!(Err(SearchError::ExpansionLimit(1)), limited);
+	}
+	#[test]
+	/// Iterative deepening reports an unreachable goal once a walk abandons nothing to its
+	/// bound, and gives up at an expansion limit where walking every route would take too long
+	/// ```txt
+	///              _______
+	///             /       \
+	///     _______/    #    \_______
+	///    /       \         /       \
+	///   /         \_______/    E    \
+	///   \         /       \         /
+	///    \_______/    #    \_______/
+	///    /       \         /       \
+	///   /    S    \_______/    #    \
+	///   \         /       \         /
+	///    \_______/         \_______/
+	///            \         /
+	///             \_______/
+	/// ```
+	fn idastar_unreachable() {
+		let mut nodes = HashMap::new();
+		for node in [(0, 0), (0, -1), (1, -1), (1, 0), (0, 1), (-1, 1), (-1, 0)] {
+			nodes.insert(node, 1.0);
+		}
+		for wall in [(0, 0), (0, 1), (1, -1)] {
+			nodes.insert(wall, f32::INFINITY);
+		}
+		let walled = HexGrid::new(AxialLayout::new(1), nodes);
+		assert_eq!(None, idastar_path(&walled, (-1, 0), (1, 0)));
+		let unreachable = idastar_path_limited(&walled, (-1, 0), (1, 0), usize::MAX);
+		assert_eq!(Err(SearchError::Unreachable), unreachable);
+		// an open hexagon has far too many routes to walk them all to a walled off corner
+		let mut nodes = HashMap::new();
+		for q in -3..=3_i32 {
+			for r in (-3).max(-q - 3)..=3.min(-q + 3) {
+				nodes.insert((q, r), 1.0);
+			}
+		}
+		for wall in [(-2, 3), (-2, 2), (-3, 2)] {
+			nodes.insert(wall, f32::INFINITY);
+		}
+		let open = HexGrid::new(AxialLayout::new(3), nodes);
+		let started = Instant::now();
+		let limited = idastar_path_limited(&open, (0, 0), (-3, 3), 10_000);
+		assert_eq!(Err(SearchError::ExpansionLimit(10_000)), limited);
+		assert!(started.elapsed() < Duration::from_secs(1));
+	}
+	#[test]
+	/// Fringe Search follows the way around the swamp in either direction and gives up on a goal
+	/// beyond the grid
+	fn fringe_finds_way_around() {
+		let grid = fork();
+		let path = fringe_path(&grid, (-2, 0), (2, 0)).unwrap();
+		assert_eq!(&AROUND, path.nodes());
+		assert_eq!(5.0, path.cost());
+		let back = fringe_path(&grid, (2, 0), (-2, 0)).unwrap();
+		let mut reversed = AROUND;
+		reversed.reverse();
+		assert_eq!(&reversed, back.nodes());
+		assert!(fringe_path(&grid, (-2, 0), (2, 2)).is_none());
+	}
+	#[test]
+	/// Of a goal in the swamp two steps away and one three steps away around it, the search
+	/// reaches the cheaper one beyond the swamp
+	fn path_to_cheapest_goal() {
+		let grid = fork();
+		let goals = [(0, 0), (1, -1)];
+		let path = astar_path_to_any(&grid, (-2, 0), &goals).unwrap();
+		assert_eq!(&[(-2, 0), (-1, 0), (0, -1), (1, -1)], path.nodes());
+		assert_eq!(3.0, path.cost());
+		assert!(astar_path_to_any(&grid, (-2, 0), &[(2, 2)]).is_none());
+		assert!(astar_path_to_any(&grid, (-2, 0), &[]).is_none());
+		let still = astar_path_to_any(&grid, (-2, 0), &[(2, 0), (-2, 0)]).unwrap();
+		assert_eq!(&[(-2, 0)], still.nodes());
+	}
+	#[test]
+	/// Of a start in the swamp two steps from the goal and one three steps away on the lane
+	/// around it, the path comes from the cheaper start on the lane
+	fn path_from_cheapest_start() {
+		let grid = fork();
+		let starts = [(0, 0), (0, -1)];
+		let path = astar_path_from_any(&grid, &starts, (2, 0)).unwrap();
+		assert_eq!(&[(0, -1), (1, -1), (1, 0), (2, 0)], path.nodes());
+		assert_eq!(3.0, path.cost());
+		assert!(astar_path_from_any(&grid, &[(2, 2)], (2, 0)).is_none());
+		assert!(astar_path_from_any(&grid, &[], (2, 0)).is_none());
+	}
+	#[test]
+	/// The seven routes across the fork which never revisit a node come cheapest first, the way
+	/// around the swamp, then straight through it, then the five crossing between the two
+	fn alternative_paths() {
+		let grid = fork();
+		let paths = k_shortest_paths(&grid, (-2, 0), (2, 0), 10);
+		let costs: Vec<f32> = paths.iter().map(|p| p.cost()).collect();
+		assert_eq!(vec![5.0, 8.0, 9.0, 9.0, 10.0, 10.0, 10.0], costs);
+		assert_eq!(&AROUND, paths[0].nodes());
+		assert_eq!(&THROUGH, paths[1].nodes());
+		for (i, path) in paths.iter().enumerate() {
+			let unique: HashSet<&(i32, i32)> = path.iter().collect();
+			assert_eq!(path.len(), unique.len());
+			assert!(paths[..i].iter().all(|other| other.nodes() != path.nodes()));
+		}
+		assert_eq!(2, k_shortest_paths(&grid, (-2, 0), (2, 0), 2).len());
+	}
+	#[test]
+	/// Only as many paths as exist are returned
+	/// ```txt
+	///    _______
+	///   /       \
+	///  /  (0,1)  \_______
+	///  \         /       \
+	///   \_______/  (1,0)  \
+	///   /       \         /
+	///  /  (0,0)  \_______/
+	///  \         /
+	///   \_______/
+	/// ```
+	fn alternative_paths_run_out() {
+		let mut nodes = HashMap::new();
+		nodes.insert((0, 0), 1.0);
+		nodes.insert((0, 1), 1.0);
+		nodes.insert((1, 0), 1.0);
+		let grid = HexGrid::new(AxialLayout::new(1), nodes);
+		let paths = k_shortest_paths(&grid, (0, 0), (1, 0), 4);
+		assert_eq!(2, paths.len());
+		assert_eq!(&[(0, 0), (1, 0)], paths[0].nodes());
+		assert_eq!(&[(0, 0), (0, 1), (1, 0)], paths[1].nodes());
+		assert_eq!(2.0, paths[1].cost());
+		assert!(k_shortest_paths(&grid, (0, 0), (1, 0), 0).is_empty());
+	}
+	#[test]
+	/// A budget of `3` reaches along the lane around the swamp but not into it, a budget of `4`
+	/// reaches the swamp and the far side of it at the same cost
+	fn reachable_within_budget() {
+		let grid = fork();
+		let mut expected = HashMap::new();
+		expected.insert((-2, 0), 0.0);
+		assert_eq!(expected, reachable_within((-2, 0), 0.0, &grid));
+		expected.insert((-1, 0), 1.0);
+		expected.insert((0, -1), 2.0);
+		expected.insert((1, -1), 3.0);
+		assert_eq!(expected, reachable_within((-2, 0), 3.0, &grid));
+		expected.insert((0, 0), 4.0);
+		expected.insert((1, 0), 4.0);
+		assert_eq!(expected, reachable_within((-2, 0), 4.0, &grid));
+	}
+	#[test]
+	/// Cut short the path leads to whichever node discovered so far looks closest to the end,
+	/// after two expansions the swamp, after four the last step of the way around it
+	fn partial_path_when_limited() {
+		let grid = fork();
+		let (still, complete) = astar_partial_path(&grid, (-2, 0), (2, 0), 0);
+		assert!(!complete);
+		assert_eq!(&[(-2, 0)], still.nodes());
+		let (swamp, complete) = astar_partial_path(&grid, (-2, 0), (2, 0), 2);
+		assert!(!complete);
+		assert_eq!(&[(-2, 0), (-1, 0), (0, 0)], swamp.nodes());
+		let (around, complete) = astar_partial_path(&grid, (-2, 0), (2, 0), 4);
+		assert!(!complete);
+		assert_eq!(&AROUND[..5], around.nodes());
+		assert_eq!(4.0, around.cost());
+		let (full, complete) = astar_partial_path(&grid, (-2, 0), (2, 0), 5);
+		assert!(complete);
+		assert_eq!(&AROUND, full.nodes());
+	}
+	#[test]
+	/// Fleeing the threat `T` down a corridor with a budget of `2` reaches two steps away from
+	/// `S`, while the first node at least `4` from the threat is one step away and the end of the
+	/// corridor `6` from the threat is beyond the budget
+	/// ```txt
+	///   column:  -3  -2  -1   0   1   2   3
+	///                                     T
+	///                                 .
+	///                             .
+	///                         S
+	///                     .
+	///                 .
+	///             .
+	/// ```
+	fn flee_from_threats() {
+		let mut nodes = HashMap::new();
+		for q in -3..=3 {
+			nodes.insert((q, 0), 1.0);
+		}
+		let grid = HexGrid::new(AxialLayout::new(3), nodes);
+		let fled = flee_path(&grid, (0, 0), &[(3, 0)], 2.0, None).unwrap();
+		assert_eq!(&[(0, 0), (-1, 0), (-2, 0)], fled.nodes());
+		let safe = flee_path(&grid, (0, 0), &[(3, 0)], 2.0, Some(4.0)).unwrap();
+		assert_eq!(&[(0, 0), (-1, 0)], safe.nodes());
+		assert!(flee_path(&grid, (0, 0), &[(3, 0)], 2.0, Some(6.0)).is_none());
+		let still = flee_path(&grid, (0, 0), &[(3, 0)], 0.0, None).unwrap();
+		assert_eq!(&[(0, 0)], still.nodes());
+	}
+	#[test]
+	/// A waypoint in the swamp drags the route straight through it, waypoints on both lanes make
+	/// it cross between them, and a waypoint where the route already is adds nothing
+	fn route_via_waypoints() {
+		let grid = fork();
+		let through = astar_via(&grid, (-2, 0), &[(0, 0)], (2, 0)).unwrap();
+		assert_eq!(&THROUGH, through.nodes());
+		assert_eq!(8.0, through.cost());
+		let crossing = astar_via(&grid, (-2, 0), &[(0, -1), (0, 0)], (2, 0)).unwrap();
+		let nodes = [(-2, 0), (-1, 0), (0, -1), (0, 0), (1, 0), (2, 0)];
+		assert_eq!(&nodes, crossing.nodes());
+		assert_eq!(9.0, crossing.cost());
+		let around = astar_via(&grid, (-2, 0), &[(-2, 0), (2, 0)], (2, 0)).unwrap();
+		assert_eq!(&AROUND, around.nodes());
+		assert!(astar_via(&grid, (-2, 0), &[(2, 3)], (2, 0)).is_none());
+	}
+	#[test]
+	/// A context reused for search after search finds the same paths as searching afresh, even
+	/// after a search which found nothing
+	fn reused_context() {
+		let grid = fork();
+		let mut context = SearchContext::new();
+		let around = context.astar_path(&grid, (-2, 0), (2, 0)).unwrap();
+		assert_eq!(&AROUND, around.nodes());
+		assert!(context.astar_path(&grid, (-2, 0), (2, 3)).is_none());
+		let back = context.astar_path(&grid, (2, 0), (-2, 0)).unwrap();
+		let mut reversed = AROUND;
+		reversed.reverse();
+		assert_eq!(&reversed, back.nodes());
+		let swamp = context.astar_path(&grid, (0, -1), (0, 0)).unwrap();
+		assert_eq!(&[(0, -1), (0, 0)], swamp.nodes());
+		assert_eq!(3.0, swamp.cost());
+		assert_eq!(astar_path(&grid, (0, -1), (0, 0)), Some(swamp));
+	}
+	#[test]
+	/// Searching backwards from a goal prices each path in its direction of travel, here units
+	/// climb a slope towards a hilltop
+	/// ```txt
+	///  (-2,2,0) - (-1,1,0) - (0,0,0) - (1,-1,0) - (2,-2,0)
+	///                         goal
+	/// ```
+	fn paths_to_goal_on_slope() {
+		let mut nodes = HashMap::new();
+		for x in -2..=2_i32 {
+			nodes.insert((x, -x, 0), 1.0);
+		}
+		let grid = HexGrid::new(CubicLayout::new(2), nodes);
+		// climbing towards the origin costs triple
+		let slope = grid.with_step_costs(|from: (i32, i32, i32), to: (i32, i32, i32)| {
+			let base = grid.step_cost(from, to)?;
+			if to.0.abs() < from.0.abs() {
+				Some(base * 3.0)
+			} else {
+				Some(base)
+			}
+		});
+		let starts = [(-2, 2, 0), (1, -1, 0), (0, 0, 0), (5, -5, 0)];
+		let paths = astar_from_goal(&slope, &starts, (0, 0, 0));
+		let far = paths[0].as_ref().unwrap();
+		assert_eq!(&vec![(-2, 2, 0), (-1, 1, 0), (0, 0, 0)], far.nodes());
+		assert_eq!(6.0, far.cost());
+		assert_eq!(far, &astar_path(&slope, (-2, 2, 0), (0, 0, 0)).unwrap());
+		assert_eq!(3.0, paths[1].as_ref().unwrap().cost());
+		assert_eq!(0, paths[2].as_ref().unwrap().hops());
+		assert!(paths[3].is_none());
+	}
+	/// A grid whose heuristic grossly overestimates the remaining cost
+	struct Overestimated<'a>(&'a HexGrid<CubicLayout>);
+
+	impl<'a> SearchSpace for Overestimated<'a> {
+		type Node = (i32, i32, i32);
+		fn successors(&self, node: (i32, i32, i32)) -> Vec<((i32, i32, i32), f32)> {
+			self.0.successors(node)
+		}
+		fn heuristic(&self, node: (i32, i32, i32), goal: (i32, i32, i32)) -> f32 {
+			self.0.heuristic(node, goal) * 100.0
+		}
+	}
+	#[test]
+	/// An inadmissible heuristic rushes through the swamp, Dijkstra mode ignores it and walks
+	/// around
+	/// ```txt
+	///  (0,0,0) -- (1,-1,0) swamp C:20 -- (2,-2,0)
+	///      \                               /
+	///   (1,0,-1) ---------------- (2,-1,-1)
+	/// ```
+	fn dijkstra_ignores_heuristic() {
+		let mut nodes = HashMap::new();
+		for node in [(0, 0, 0), (1, 0, -1), (2, -1, -1), (2, -2, 0)] {
+			nodes.insert(node, 1.0);
+		}
+		nodes.insert((1, -1, 0), 20.0);
+		let grid = HexGrid::new(CubicLayout::new(2), nodes);
+		let misleading = Overestimated(&grid);
+		let rushed = astar_path(&misleading, (0, 0, 0), (2, -2, 0)).unwrap();
+		assert!(rushed.contains(&(1, -1, 0)));
+		let optimal = dijkstra_path(&misleading, (0, 0, 0), (2, -2, 0)).unwrap();
+		assert_eq!(3.0, optimal.cost());
+		let uninformed =
+			astar_path(&without_heuristic(&misleading), (0, 0, 0), (2, -2, 0)).unwrap();
+		assert_eq!(optimal.cost(), uninformed.cost());
+	}
+	#[test]
+	/// The cost field holds the cost of the best path to every reachable node and nothing for
+	/// impassable nodes
+	fn cost_field_matches_paths() {
+		let mut nodes = HashMap::new();
+		for x in -2..=2_i32 {
+			for y in -2..=2_i32 {
+				let z = -x - y;
+				if z.abs() <= 2 {
+					nodes.insert((x, y, z), 1.0 + (x - z).rem_euclid(3) as f32);
+				}
+			}
+		}
+		nodes.insert((0, -1, 1), f32::INFINITY);
+		let mut grid = HexGrid::new(CubicLayout::new(3), nodes);
+		grid.insert_node((3, -3, 0), 1.0);
+		let field = cost_field((0, 0, 0), &grid);
+		assert_eq!(Some(&0.0), field.get(&(0, 0, 0)));
+		assert!(!field.contains_key(&(0, -1, 1)));
+		for (node, cost) in field.iter() {
+			assert_eq!(astar_path(&grid, (0, 0, 0), *node).unwrap().cost(), *cost);
+		}
+		// 19 nodes within two rings, less the impassable node, plus the node added on the third ring
+		assert_eq!(19, field.len());
+		assert!(field.contains_key(&(3, -3, 0)));
+	}
+	#[test]
+	/// A door in the middle which opens at time `5` is waited for when it opens soon enough and
+	/// walked around otherwise, a flooded middle is always walked around
+	/// ```txt
+	///    _______
+	///   /       \
+	///  / (-1,1)  \_______
+	///  \         /       \
+	///   \_______/  door   \_______
+	///   /       \  (0,0)  /       \
+	///  / (-1,0)  \_______/  (1,-1) \
+	///  \         /       \         /
+	///   \_______/  (0,-1) \_______/
+	///           \         /
+	///            \_______/
+	/// ```
+	fn timed_door() {
+		let mut nodes = HashMap::new();
+		for node in [(-1, 1), (-1, 0), (0, -1), (1, -1), (0, 0)] {
+			nodes.insert(node, 1.0);
+		}
+		let grid = HexGrid::new(AxialLayout::new(1), nodes);
+		let door = |node: (i32, i32), time: f32| {
+			if node == (0, 0) && time < 5.0 {
+				Some(5.0 - time)
+			} else {
+				Some(0.0)
+			}
+		};
+		let around = astar_path_timed(&grid, (-1, 1), (1, -1), 0.0, door).unwrap();
+		assert_eq!(&[(-1, 1), (-1, 0), (0, -1), (1, -1)], around.nodes());
+		assert_eq!(3.0, around.cost());
+		let through = astar_path_timed(&grid, (-1, 1), (1, -1), 4.5, door).unwrap();
+		assert_eq!(&[(-1, 1), (0, 0), (1, -1)], through.nodes());
+		assert_eq!(&[0.0, 1.5, 2.5], through.cumulative_costs());
+		let flooded = |node: (i32, i32), _| if node == (0, 0) { None } else { Some(0.0) };
+		let dry = astar_path_timed(&grid, (-1, 1), (1, -1), 6.0, flooded).unwrap();
+		assert!(!dry.contains(&(0, 0)));
+		let timeless = astar_path_timed(&grid, (-1, 1), (1, -1), 0.0, |_, _| Some(0.0));
+		assert_eq!(2.0, timeless.unwrap().cost());
+	}
+	#[test]
+	/// Searching a corridor expands each node before the end once, while a search for an
+	/// unreachable node expands everything it discovers
+	/// ```txt
+	///    _______
+	///   /       \
+	///  /  (0,4)  \
+	///  \         /
+	///   \_______/
+	///       :
+	///    _______
+	///   /       \
+	///  /  (0,0)  \
+	///  \         /
+	///   \_______/
+	/// ```
+	fn search_stats() {
+		let mut nodes = HashMap::new();
+		for r in 0..5 {
+			nodes.insert((0, r), 1.0);
+		}
+		let grid = HexGrid::new(AxialLayout::new(5), nodes);
+		let (path, stats) = astar_path_with_stats(&grid, (0, 0), (0, 4));
+		assert_eq!(4.0, path.unwrap().cost());
+		assert_eq!(4, stats.nodes_expanded);
+		assert_eq!(5, stats.nodes_discovered);
+		assert_eq!(1, stats.peak_open_len);
+		let (path, stats) = astar_path_with_stats(&grid, (0, 0), (3, 0));
+		assert!(path.is_none());
+		assert_eq!(5, stats.nodes_expanded);
+		assert_eq!(5, stats.nodes_discovered);
+		let mut context = SearchContext::new();
+		context.astar_path(&grid, (0, 2), (0, 4));
+		assert_eq!(2, context.stats().nodes_expanded);
+		assert_eq!(4, context.stats().nodes_discovered);
+	}
+	#[test]
+	/// A search which needs more expansions than allowed gives up with an error distinct from
+	/// the end node being unreachable
+	fn expansion_limit() {
+		let mut nodes = HashMap::new();
+		for r in 0..5 {
+			nodes.insert((0, r), 1.0);
+		}
+		let grid = HexGrid::new(AxialLayout::new(5), nodes);
+		let limited = astar_path_limited(&grid, (0, 0), (0, 4), 3);
+		assert_eq!(Err(SearchError::ExpansionLimit(3)), limited);
+		assert_eq!(
+			"Gave up after expanding 3 nodes",
+			limited.unwrap_err().to_string()
+		);
+		let path = astar_path_limited(&grid, (0, 0), (0, 4), 4).unwrap();
+		assert_eq!(4.0, path.cost());
+		let unreachable = astar_path_limited(&grid, (0, 0), (3, 0), 100);
+		assert_eq!(Err(SearchError::Unreachable), unreachable);
+		let mut context = SearchContext::new();
+		assert!(context
+			.astar_path_limited(&grid, (0, 0), (0, 4), 0)
+			.is_err());
+		assert_eq!(0, context.stats().nodes_expanded);
+	}
+	#[test]
+	/// A search of an infinite world for a walled off node would never finish, it is cancelled
+	/// from another thread or gives up at its deadline
+	fn cancel_endless_search() {
+		let walled_off = (40, -40, 0);
+		let world = Arc::new(HexGrid::new(
+			CubicLayout::unbounded(),
+			ProceduralNodes::new(move |node: &(i32, i32, i32)| {
+				if node_distance(*node, walled_off) == 1 {
+					None
+				} else {
+					Some(1.0)
+				}
+			}),
+		));
+		let cancel = Arc::new(AtomicBool::new(false));
+		let searcher = {
+			let world = Arc::clone(&world);
+			let cancel = Arc::clone(&cancel);
+			thread::spawn(move || {
+				astar_path_cancellable(&world, (0, 0, 0), walled_off, &cancel, None)
+			})
+		};
+		thread::sleep(Duration::from_millis(50));
+		cancel.store(true, Ordering::Relaxed);
+		assert_eq!(Err(SearchError::Cancelled), searcher.join().unwrap());
+		let deadline = Instant::now() + Duration::from_millis(50);
+		let timed = astar_path_cancellable(
+			&world,
+			(0, 0, 0),
+			walled_off,
+			&AtomicBool::new(false),
+			Some(deadline),
+		);
+		assert_eq!(Err(SearchError::TimedOut), timed);
+		let path = astar_path_cancellable(
+			&world,
+			(0, 0, 0),
+			(3, -3, 0),
+			&AtomicBool::new(false),
+			Some(deadline + Duration::from_secs(60)),
+		);
+		assert_eq!(3.0, path.unwrap().cost());
+	}
+	#[test]
+	/// The end node is first queued from a dear neighbour which looks closer, and the cheaper
+	/// detour found afterwards replaces that route before the end node is expanded
+	fn improves_queued_node() {
+		let mut nodes = HashMap::new();
+		for node in [(0, 0), (0, -1), (1, -2), (2, -2), (2, -1)] {
+			nodes.insert(node, 1.0);
+		}
+		nodes.insert((1, -1), 4.0);
+		let grid = HexGrid::new(AxialLayout::new(2), nodes);
+		let (path, stats) = astar_path_with_stats(&grid, (0, 0), (2, -1));
+		let path = path.unwrap();
+		assert_eq!(&[(0, 0), (0, -1), (1, -2), (2, -2), (2, -1)], path.nodes());
+		assert_eq!(4.0, path.cost());
+		assert_eq!(6, stats.nodes_discovered);
+	}
+	#[test]
+	/// Memory bounded A* holding the six nodes of the way around the swamp finds it, with room
+	/// for only the five nodes of the route through the swamp it settles for that, and with
+	/// less it finds nothing
+	fn smastar_within_memory() {
+		let grid = fork();
+		for max_nodes in [6, 7, 100] {
+			let path = smastar_path(&grid, (-2, 0), (2, 0), max_nodes).unwrap();
+			assert_eq!(&AROUND, path.nodes());
+			assert_eq!(5.0, path.cost());
+		}
+		let tight = smastar_path(&grid, (-2, 0), (2, 0), 5).unwrap();
+		assert_eq!(&THROUGH, tight.nodes());
+		assert_eq!(8.0, tight.cost());
+		assert!(smastar_path(&grid, (-2, 0), (2, 0), 4).is_none());
+	}
+	#[test]
+	/// SMA* gives up on an unreachable goal quickly whether memory is tight or plentiful, rather
+	/// than walking every route through the grid
+	fn smastar_unreachable() {
+		let grid = axial_fixture(3, 0);
+		assert!(astar_path(&grid, (0, 0), (-3, 3)).is_none());
+		for max_nodes in [40, 100_000] {
+			let started = std::time::Instant::now();
+			assert!(smastar_path(&grid, (0, 0), (-3, 3), max_nodes).is_none());
+			assert!(started.elapsed() < std::time::Duration::from_secs(1));
+		}
+	}
+}
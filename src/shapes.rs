@@ -0,0 +1,267 @@
+//! Every node of the common shapes of map, ready to fill in as the `nodes` of a search.
+//!
+//! Each shape is given in Axial, Cubic and Offset coordinates:
+//!
+//! * hexagons of rings around the origin, the shape searched by `astar_axial::astar_path()` and
+//!   `astar_cubic::astar_path()`
+//! * rectangles of columns and rows with `(0, 0)` at the bottom left, the shape searched by
+//!   `astar_offset::astar_path()`
+//! * parallelograms with `(0, 0)` at a corner, the sides running along the Axial `q` and `r` axes
+//! * triangles with `(0, 0)` at a corner, the sides running along the Axial `q` and `r` axes and
+//!   the third side joining their ends
+//!
+//! A shape of no size holds no nodes. `fill` gives every node of a shape the same complexity,
+//! which can then be changed where the terrain differs:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::astar_offset;
+//! use hexagonal_pathfinding_astar::shapes::{fill, rectangle_offset};
+//! use hexagonal_pathfinding_astar::{GridBounds, HexOrientation};
+//! let mut nodes = fill(rectangle_offset(6, 4), 1.0);
+//! nodes.insert((2, 1), 8.0);
+//! let path = astar_offset::astar_path_in_bounds(
+//!     (0, 0),
+//!     nodes,
+//!     (5, 3),
+//!     GridBounds::from_size(6, 4),
+//!     HexOrientation::FlatTopOddUp,
+//! );
+//! assert!(!path.contains(&(2, 1)));
+//! ```
+
+use crate::helpers::axial_to_cubic;
+use crate::helpers::axial_to_offset;
+use crate::helpers::cubic_to_axial;
+use crate::helpers::cubic_to_offset;
+use crate::helpers::nodes_within_range_cubic;
+use crate::helpers::offset_to_cubic;
+use crate::HexOrientation;
+use ::std::collections::HashMap;
+use ::std::hash::Hash;
+
+/// A map giving every one of `nodes` the same `complexity`. Duplicates are ignored
+pub fn fill<N, I>(nodes: I, complexity: f32) -> HashMap<N, f32>
+where
+	N: Eq + Hash,
+	I: IntoIterator<Item = N>,
+{
+	nodes.into_iter().map(|n| (n, complexity)).collect()
+}
+
+/// The Cubic nodes within `radius` rings of the origin, see
+/// `helpers::nodes_within_range_cubic()`
+pub fn hexagon_cubic(radius: i32) -> Vec<(i32, i32, i32)> {
+	nodes_within_range_cubic((0, 0, 0), radius)
+}
+/// The Axial nodes within `radius` rings of the origin
+pub fn hexagon_axial(radius: i32) -> Vec<(i32, i32)> {
+	hexagon_cubic(radius)
+		.into_iter()
+		.map(cubic_to_axial)
+		.collect()
+}
+/// The Offset nodes in `orientation` within `radius` rings of `(0, 0)`
+pub fn hexagon_offset(radius: i32, orientation: &HexOrientation) -> Vec<(i32, i32)> {
+	hexagon_cubic(radius)
+		.into_iter()
+		.map(|n| cubic_to_offset(n, orientation))
+		.collect()
+}
+
+/// The Offset nodes of `width` columns and `height` rows with `(0, 0)` at the bottom left, the
+/// nodes within `GridBounds::from_size()`
+pub fn rectangle_offset(width: i32, height: i32) -> Vec<(i32, i32)> {
+	let mut nodes = Vec::new();
+	for column in 0..width {
+		for row in 0..height {
+			nodes.push((column, row));
+		}
+	}
+	nodes
+}
+/// The Axial nodes of the hexagons drawn by `rectangle_offset()` in `orientation`
+pub fn rectangle_axial(width: i32, height: i32, orientation: &HexOrientation) -> Vec<(i32, i32)> {
+	rectangle_cubic(width, height, orientation)
+		.into_iter()
+		.map(cubic_to_axial)
+		.collect()
+}
+/// The Cubic nodes of the hexagons drawn by `rectangle_offset()` in `orientation`
+pub fn rectangle_cubic(
+	width: i32,
+	height: i32,
+	orientation: &HexOrientation,
+) -> Vec<(i32, i32, i32)> {
+	rectangle_offset(width, height)
+		.into_iter()
+		.map(|n| offset_to_cubic(n, orientation))
+		.collect()
+}
+
+/// The Axial nodes `(q, r)` with `q` from `0` to `width - 1` and `r` from `0` to `height - 1`
+pub fn parallelogram_axial(width: i32, height: i32) -> Vec<(i32, i32)> {
+	let mut nodes = Vec::new();
+	for q in 0..width {
+		for r in 0..height {
+			nodes.push((q, r));
+		}
+	}
+	nodes
+}
+/// The Cubic nodes of the hexagons drawn by `parallelogram_axial()`
+pub fn parallelogram_cubic(width: i32, height: i32) -> Vec<(i32, i32, i32)> {
+	parallelogram_axial(width, height)
+		.into_iter()
+		.map(axial_to_cubic)
+		.collect()
+}
+/// The Offset nodes in `orientation` of the hexagons drawn by `parallelogram_axial()`
+pub fn parallelogram_offset(
+	width: i32,
+	height: i32,
+	orientation: &HexOrientation,
+) -> Vec<(i32, i32)> {
+	parallelogram_axial(width, height)
+		.into_iter()
+		.map(|n| axial_to_offset(n, orientation))
+		.collect()
+}
+
+/// The Axial nodes `(q, r)` with `q` and `r` not negative and `q + r` less than `size`, a
+/// triangle with `size` nodes along each side
+pub fn triangle_axial(size: i32) -> Vec<(i32, i32)> {
+	let mut nodes = Vec::new();
+	for q in 0..size {
+		for r in 0..size - q {
+			nodes.push((q, r));
+		}
+	}
+	nodes
+}
+/// The Cubic nodes of the hexagons drawn by `triangle_axial()`
+pub fn triangle_cubic(size: i32) -> Vec<(i32, i32, i32)> {
+	triangle_axial(size)
+		.into_iter()
+		.map(axial_to_cubic)
+		.collect()
+}
+/// The Offset nodes in `orientation` of the hexagons drawn by `triangle_axial()`
+pub fn triangle_offset(size: i32, orientation: &HexOrientation) -> Vec<(i32, i32)> {
+	triangle_axial(size)
+		.into_iter()
+		.map(|n| axial_to_offset(n, orientation))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::generators::cubic_map;
+	use crate::generators::Terrain;
+	use crate::shapes::*;
+	use crate::GridBounds;
+	use ::std::collections::HashSet;
+
+	/// Every orientation of an Offset grid
+	const ORIENTATIONS: [HexOrientation; 4] = [
+		HexOrientation::FlatTopOddUp,
+		HexOrientation::FlatTopOddDown,
+		HexOrientation::PointyTopOddRight,
+		HexOrientation::PointyTopOddLeft,
+	];
+
+	#[test]
+	/// Hexagons hold every node within the rings once, whichever system they are written in
+	fn hexagons() {
+		for radius in 0..6 {
+			let cubic = hexagon_cubic(radius);
+			assert_eq!((3 * radius * (radius + 1) + 1) as usize, cubic.len());
+			let unique: HashSet<_> = cubic.iter().copied().collect();
+			assert_eq!(cubic.len(), unique.len());
+			let keys: HashSet<_> = cubic_map(radius, Terrain::Uniform, 0).into_keys().collect();
+			assert_eq!(keys, unique);
+			let axial: Vec<_> = cubic.iter().map(|n| cubic_to_axial(*n)).collect();
+			assert_eq!(axial, hexagon_axial(radius));
+			for orientation in ORIENTATIONS.iter() {
+				let offset: Vec<_> = hexagon_offset(radius, orientation)
+					.into_iter()
+					.map(|n| offset_to_cubic(n, orientation))
+					.collect();
+				assert_eq!(cubic, offset);
+			}
+		}
+		assert!(hexagon_cubic(-1).is_empty());
+	}
+	#[test]
+	/// Rectangles fill the grid bounds of their size, whichever system they are written in
+	fn rectangles() {
+		let offset = rectangle_offset(5, 3);
+		assert_eq!(15, offset.len());
+		let bounds = GridBounds::from_size(5, 3);
+		assert!(offset.iter().all(|n| bounds.contains(*n)));
+		assert_eq!(Some(bounds), GridBounds::enclosing(offset.iter().copied()));
+		for orientation in ORIENTATIONS.iter() {
+			let cubic = rectangle_cubic(5, 3, orientation);
+			let axial = rectangle_axial(5, 3, orientation);
+			for ((o, c), a) in offset.iter().zip(cubic.iter()).zip(axial.iter()) {
+				assert_eq!(offset_to_cubic(*o, orientation), *c);
+				assert_eq!(cubic_to_axial(*c), *a);
+			}
+		}
+		assert!(rectangle_offset(0, 3).is_empty());
+	}
+	#[test]
+	/// Parallelograms and triangles are bounded by the Axial axes, triangles of size 3 holding
+	/// the nodes
+	/// ```txt
+	///     _______
+	///    /       \
+	///   /  (0,2)  \_______
+	///   \         /       \
+	///    \_______/  (1,1)  \_______
+	///    /       \         /       \
+	///   /  (0,1)  \_______/  (2,0)  \
+	///   \         /       \         /
+	///    \_______/  (1,0)  \_______/
+	///    /       \         /
+	///   /  (0,0)  \_______/
+	///   \         /
+	///    \_______/
+	/// ```
+	fn parallelograms_and_triangles() {
+		let parallelogram = parallelogram_axial(4, 2);
+		assert_eq!(8, parallelogram.len());
+		assert!(parallelogram
+			.iter()
+			.all(|(q, r)| (0..4).contains(q) && (0..2).contains(r)));
+		let triangle = triangle_axial(3);
+		let expected = [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (2, 0)];
+		assert_eq!(expected.to_vec(), triangle);
+		assert_eq!(55, triangle_axial(10).len());
+		assert!(triangle_axial(0).is_empty());
+		let cubic: Vec<_> = triangle.iter().map(|n| axial_to_cubic(*n)).collect();
+		assert_eq!(cubic, triangle_cubic(3));
+		let cubic: Vec<_> = parallelogram.iter().map(|n| axial_to_cubic(*n)).collect();
+		assert_eq!(cubic, parallelogram_cubic(4, 2));
+		for orientation in ORIENTATIONS.iter() {
+			let offset: Vec<_> = triangle
+				.iter()
+				.map(|n| axial_to_offset(*n, orientation))
+				.collect();
+			assert_eq!(offset, triangle_offset(3, orientation));
+			let offset: Vec<_> = parallelogram
+				.iter()
+				.map(|n| axial_to_offset(*n, orientation))
+				.collect();
+			assert_eq!(offset, parallelogram_offset(4, 2, orientation));
+		}
+	}
+	#[test]
+	/// Filling a shape gives each node the complexity once
+	fn filled() {
+		let nodes = fill(hexagon_axial(2), 3.0);
+		assert_eq!(19, nodes.len());
+		assert!(nodes.values().all(|c| *c == 3.0));
+		let nodes = fill(vec![(0, 0), (0, 0), (1, 0)], 1.5);
+		assert_eq!(2, nodes.len());
+	}
+}
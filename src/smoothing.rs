@@ -0,0 +1,127 @@
+//! Smooth the complexities of a grid by blending each node with its neighbours.
+//!
+//! Imported complexity data, such as sensor readings or heightmaps, is often noisy and a lone
+//! spike or dip can drag a path to one side of a map for no sensible reason. Each smoothing
+//! iteration moves the complexity of every node towards the average complexity of its
+//! neighbours:
+//!
+//! ```txt
+//! smoothed = (1 - strength) * complexity + strength * average_neighbour_complexity
+//! ```
+//!
+//! Impassable nodes are left untouched and do not contribute to the average of their
+//! neighbours, so walls and cliffs keep their shape. A node is impassable if it is a hole in the
+//! grid or has a complexity which is not finite (e.g `f32::INFINITY`).
+
+use crate::grid::GridLayout;
+use crate::grid::HexGrid;
+use ::std::collections::HashMap;
+
+/// Smooth the complexities of `grid` over a number of `iterations`. `strength` is how far each
+/// node moves towards the average of its neighbours per iteration and is clamped between `0.0`,
+/// no change, and `1.0`, replacing the complexity with the average.
+///
+/// Returns a new grid with the same layout and the smoothed complexities
+pub fn smooth_complexities<L: GridLayout + Clone>(
+	grid: &HexGrid<L>,
+	strength: f32,
+	iterations: usize,
+) -> HexGrid<L> {
+	let strength = strength.clamp(0.0, 1.0);
+	let mut nodes = grid.nodes().clone();
+	for _ in 0..iterations {
+		let mut smoothed: HashMap<L::Coord, f32> = HashMap::with_capacity(nodes.len());
+		for (node, complexity) in nodes.iter() {
+			if !complexity.is_finite() {
+				smoothed.insert(*node, *complexity);
+				continue;
+			}
+			let mut total = 0.0;
+			let mut count = 0;
+			for n in grid.layout().neighbours(*node) {
+				if let Some(c) = nodes.get(&n) {
+					if c.is_finite() {
+						total += c;
+						count += 1;
+					}
+				}
+			}
+			if count == 0 {
+				// isolated nodes have nothing to blend with
+				smoothed.insert(*node, *complexity);
+			} else {
+				let average = total / count as f32;
+				smoothed.insert(*node, (1.0 - strength) * complexity + strength * average);
+			}
+		}
+		nodes = smoothed;
+	}
+	// keep the edge costs of the original grid
+	let mut smoothed = grid.clone();
+	for (node, complexity) in nodes {
+		smoothed.insert_node(node, complexity);
+	}
+	smoothed
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::CubicLayout;
+	use crate::grid::HexGrid;
+	use crate::smoothing::smooth_complexities;
+	use std::collections::HashMap;
+
+	/// A grid of 1 ring with a complexity of 1 everywhere except the centre node
+	fn spike_grid(centre: f32) -> HexGrid<CubicLayout> {
+		let mut nodes = HashMap::new();
+		nodes.insert((0, 0, 0), centre);
+		nodes.insert((0, -1, 1), 1.0);
+		nodes.insert((1, -1, 0), 1.0);
+		nodes.insert((1, 0, -1), 1.0);
+		nodes.insert((0, 1, -1), 1.0);
+		nodes.insert((-1, 1, 0), 1.0);
+		nodes.insert((-1, 0, 1), 1.0);
+		HexGrid::new(CubicLayout::new(1), nodes)
+	}
+
+	#[test]
+	/// A spike of complexity is flattened towards its surroundings
+	/// ```txt
+	///              _______
+	///             /       \
+	///     _______/   C:1   \_______
+	///    /       \         /       \
+	///   /   C:1   \_______/   C:1   \
+	///   \         /       \         /
+	///    \_______/  C:13   \_______/
+	///    /       \         /       \
+	///   /   C:1   \_______/   C:1   \
+	///   \         /       \         /
+	///    \_______/   C:1   \_______/
+	///            \         /
+	///             \_______/
+	/// ```
+	fn spike_is_flattened() {
+		let grid = spike_grid(13.0);
+		let smoothed = smooth_complexities(&grid, 0.5, 1);
+		// centre is half way between 13 and the average of 1
+		assert_eq!(Some(7.0), smoothed.complexity(&(0, 0, 0)));
+		// each outer node has three neighbours, the centre and two others, averaging 5
+		assert_eq!(Some(3.0), smoothed.complexity(&(0, -1, 1)));
+	}
+	#[test]
+	/// A strength of zero leaves the grid unchanged
+	fn zero_strength() {
+		let grid = spike_grid(13.0);
+		let smoothed = smooth_complexities(&grid, 0.0, 3);
+		assert_eq!(grid.nodes(), smoothed.nodes());
+	}
+	#[test]
+	/// Impassable nodes keep their value and do not bleed into their neighbours
+	fn impassable_untouched() {
+		let grid = spike_grid(f32::INFINITY);
+		let smoothed = smooth_complexities(&grid, 1.0, 2);
+		assert_eq!(Some(f32::INFINITY), smoothed.complexity(&(0, 0, 0)));
+		assert_eq!(Some(1.0), smoothed.complexity(&(1, -1, 0)));
+	}
+}
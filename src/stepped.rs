@@ -0,0 +1,169 @@
+//! Spread one expensive search over several frames without threads.
+//!
+//! A `SteppedSearch` holds everything `search::astar_path()` keeps while it runs, so the search
+//! can stop after any number of expansions and carry on later where it left off. A game calls
+//! `SteppedSearch::step` once a frame with as many expansions as the frame can afford until the
+//! search settles:
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::stepped::{SearchState, SteppedSearch};
+//! use hexagonal_pathfinding_astar::test_support::axial_fixture;
+//! let grid = axial_fixture(6, 1);
+//! let mut search = SteppedSearch::new(&grid, (0, 0), (6, -3));
+//! let path = loop {
+//!     match search.step(&grid, 10) {
+//!         SearchState::InProgress => continue, // wait for the next frame
+//!         SearchState::Found(path) => break Some(path),
+//!         SearchState::Exhausted => break None,
+//!     }
+//! };
+//! assert!(path.is_some());
+//! ```
+//!
+//! The space must not change between steps, otherwise start a new search.
+
+use crate::path::Path;
+use crate::search::towards;
+use crate::search::Limits;
+use crate::search::SearchContext;
+use crate::search::SearchError;
+use crate::search::SearchSpace;
+use ::std::hash::Hash;
+
+/// Where a `SteppedSearch` has got to
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchState<N> {
+	/// The search needs more steps
+	InProgress,
+	/// The cheapest path to the end node
+	Found(Path<N>),
+	/// Every node reachable from the start was explored without finding the end node
+	Exhausted,
+}
+
+/// An A* search which runs a few expansions at a time, see the module documentation
+#[derive(Debug, Clone)]
+pub struct SteppedSearch<N> {
+	/// Where the path ends
+	end_node: N,
+	/// The memory and queue of the search, which carries on from where the last step stopped
+	context: SearchContext<N>,
+	/// Number of nodes expanded so far
+	nodes_expanded: usize,
+	/// How the search ended, `None` while it is running
+	settled: Option<SearchState<N>>,
+}
+
+impl<N: Copy + Eq + Hash> SteppedSearch<N> {
+	/// Begin a search of `space` from `start_node` to `end_node`, nothing is expanded until the
+	/// first step
+	pub fn new<S: SearchSpace<Node = N>>(
+		space: &S,
+		start_node: N,
+		end_node: N,
+	) -> SteppedSearch<N> {
+		let mut context = SearchContext::new();
+		context.begin(&[start_node], &towards(space, end_node));
+		SteppedSearch {
+			end_node,
+			context,
+			nodes_expanded: 0,
+			settled: None,
+		}
+	}
+	/// Number of nodes expanded so far
+	pub fn nodes_expanded(&self) -> usize {
+		self.nodes_expanded
+	}
+	/// Whether the search has found the path or shown there is none, further steps return the
+	/// same state without expanding anything
+	pub fn is_settled(&self) -> bool {
+		self.settled.is_some()
+	}
+	/// Expand at most `max_expansions` nodes of `space`, the space the search began in. Returns
+	/// `SearchState::InProgress` if the search needs more steps
+	pub fn step<S: SearchSpace<Node = N>>(
+		&mut self,
+		space: &S,
+		max_expansions: usize,
+	) -> SearchState<N> {
+		if let Some(state) = &self.settled {
+			return state.clone();
+		}
+		let limits = Limits::expansions(self.nodes_expanded.saturating_add(max_expansions));
+		let reached = self
+			.context
+			.resume(&mut towards(space, self.end_node), &limits);
+		self.nodes_expanded = self.context.stats().nodes_expanded;
+		match reached {
+			Ok(end_node) => {
+				let path = self.context.path_to(end_node);
+				self.settle(SearchState::Found(path))
+			}
+			// the next node is left queued for the next step
+			Err(SearchError::ExpansionLimit(_)) => SearchState::InProgress,
+			// every reachable node has been explored without finding the end node
+			Err(_) => self.settle(SearchState::Exhausted),
+		}
+	}
+	/// End the search in `state`, freeing the memory it no longer needs
+	fn settle(&mut self, state: SearchState<N>) -> SearchState<N> {
+		self.context = SearchContext::new();
+		self.settled = Some(state.clone());
+		state
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::*;
+	use crate::search::astar_path_with_stats;
+	use crate::stepped::*;
+	use ::std::collections::HashMap;
+
+	#[test]
+	/// A search from `S` to `E` expands the five nodes before `E` along the way around the swamp
+	/// `5`, one per step when allowed a single expansion a step, settling on the same path as a
+	/// single search. A goal beyond the grid is given up once all seven nodes are expanded
+	/// ```txt
+	///   column:  -2  -1   0   1   2
+	///                             E
+	///                         .
+	///                     5
+	///                 .       .
+	///             S       .
+	/// ```
+	fn matches_single_search() {
+		let mut nodes = HashMap::new();
+		for node in [(-2, 0), (-1, 0), (1, 0), (2, 0), (0, -1), (1, -1)] {
+			nodes.insert(node, 1.0);
+		}
+		nodes.insert((0, 0), 5.0);
+		let grid = HexGrid::new(AxialLayout::new(2), nodes);
+		let (expected, stats) = astar_path_with_stats(&grid, (-2, 0), (2, 0));
+		let expected = expected.unwrap();
+		assert_eq!(5, stats.nodes_expanded);
+		let mut search = SteppedSearch::new(&grid, (-2, 0), (2, 0));
+		assert_eq!(SearchState::InProgress, search.step(&grid, 0));
+		assert_eq!(0, search.nodes_expanded());
+		for expanded in 1..5 {
+			assert_eq!(SearchState::InProgress, search.step(&grid, 1));
+			assert_eq!(expanded, search.nodes_expanded());
+			assert!(!search.is_settled());
+		}
+		let found = SearchState::Found(expected.clone());
+		assert_eq!(found, search.step(&grid, 1));
+		assert_eq!(5, search.nodes_expanded());
+		assert!(search.is_settled());
+		assert_eq!(found, search.step(&grid, 1));
+		let around = [(-2, 0), (-1, 0), (0, -1), (1, -1), (1, 0), (2, 0)];
+		assert_eq!(&around, expected.nodes());
+		let mut search = SteppedSearch::new(&grid, (-2, 0), (2, 0));
+		assert_eq!(SearchState::InProgress, search.step(&grid, 2));
+		assert_eq!(SearchState::InProgress, search.step(&grid, 2));
+		assert_eq!(found, search.step(&grid, 2));
+		let mut search = SteppedSearch::new(&grid, (-2, 0), (2, 3));
+		assert_eq!(SearchState::Exhausted, search.step(&grid, 100));
+		assert_eq!(7, search.nodes_expanded());
+	}
+}
@@ -0,0 +1,183 @@
+//! Search grids too large for the bookkeeping of a search to fit in memory.
+//!
+//! For every node it discovers a search records the cheapest known cost of reaching it, the node
+//! it was reached from and whether it has been expanded. On a GIS-scale grid of billions of
+//! hexagons these records outgrow memory long before the grid itself, which may well be
+//! generated procedurally or read lazily from disk.
+//!
+//! `astar_path_with_storage` keeps every record in a caller-provided `SearchStorage`, which might
+//! be backed by a file, a memory-mapped table or an embedded database. Only the queue of nodes
+//! waiting to be expanded, a node and a score each, stays in memory. Every step of the search
+//! goes through the storage so expect it to be as slow as the backend.
+//!
+//! A `HashMap` implements `SearchStorage` to keep the records in memory, which is convenient for
+//! testing a backend against.
+
+use crate::path::Path;
+use crate::search::MinScored;
+use crate::search::SearchSpace;
+use ::std::collections::BinaryHeap;
+use ::std::collections::HashMap;
+use ::std::hash::BuildHasher;
+use ::std::hash::Hash;
+
+/// What a search knows about a single node
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchRecord<N> {
+	/// The cheapest known cost of reaching the node from the start
+	pub cost: f32,
+	/// The node it was cheapest reached from, `None` for the start
+	pub came_from: Option<N>,
+	/// Whether the node has been expanded, in which case its cost is final
+	pub closed: bool,
+}
+
+/// Somewhere to keep the record of each node discovered by a search
+pub trait SearchStorage<N> {
+	/// The record of `node`, `None` if the search has not discovered it
+	fn get(&self, node: &N) -> Option<SearchRecord<N>>;
+	/// Store the record of `node`, replacing any earlier record
+	fn put(&mut self, node: N, record: SearchRecord<N>);
+}
+
+impl<N: Copy + Eq + Hash, H: BuildHasher> SearchStorage<N> for HashMap<N, SearchRecord<N>, H> {
+	fn get(&self, node: &N) -> Option<SearchRecord<N>> {
+		HashMap::get(self, node).copied()
+	}
+	fn put(&mut self, node: N, record: SearchRecord<N>) {
+		self.insert(node, record);
+	}
+}
+
+/// Find the cheapest path from `start_node` to `end_node` keeping the record of every discovered
+/// node in `storage`. `storage` should be empty, records from an earlier search would be mistaken
+/// for discoveries of this one. Returns `None` if `end_node` cannot be reached
+pub fn astar_path_with_storage<S: SearchSpace, R: SearchStorage<S::Node>>(
+	space: &S,
+	start_node: S::Node,
+	end_node: S::Node,
+	storage: &mut R,
+) -> Option<Path<S::Node>> {
+	storage.put(
+		start_node,
+		SearchRecord {
+			cost: 0.0,
+			came_from: None,
+			closed: false,
+		},
+	);
+	let mut queue = BinaryHeap::new();
+	queue.push(MinScored(space.heuristic(start_node, end_node), start_node));
+	while let Some(MinScored(_, node)) = queue.pop() {
+		let record = match storage.get(&node) {
+			Some(r) => r,
+			None => continue,
+		};
+		// skip stale entries for nodes already expanded by a cheaper route
+		if record.closed {
+			continue;
+		}
+		if node == end_node {
+			return Some(stored_path(end_node, storage));
+		}
+		storage.put(
+			node,
+			SearchRecord {
+				closed: true,
+				..record
+			},
+		);
+		for (n, step_complexity) in space.successors(node) {
+			let cost = record.cost + step_complexity;
+			let is_better = match storage.get(&n) {
+				Some(existing) => !existing.closed && cost < existing.cost,
+				None => true,
+			};
+			if is_better {
+				storage.put(
+					n,
+					SearchRecord {
+						cost,
+						came_from: Some(node),
+						closed: false,
+					},
+				);
+				queue.push(MinScored(cost + space.heuristic(n, end_node), n));
+			}
+		}
+	}
+	None
+}
+
+/// Follow the stored records back from `end_node` to the start
+fn stored_path<N: Copy, R: SearchStorage<N>>(end_node: N, storage: &R) -> Path<N> {
+	let mut nodes = Vec::new();
+	let mut costs = Vec::new();
+	let mut current = Some(end_node);
+	while let Some(node) = current {
+		let record = match storage.get(&node) {
+			Some(r) => r,
+			None => break,
+		};
+		nodes.push(node);
+		costs.push(record.cost);
+		current = record.came_from;
+	}
+	nodes.reverse();
+	costs.reverse();
+	Path::new(nodes, costs)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::HexGrid;
+	use crate::grid::OffsetLayout;
+	use crate::search::astar_path;
+	use crate::storage::*;
+	use crate::HexOrientation;
+
+	/// Storage counting how often it is written to, standing in for a slow backend
+	struct CountingStorage {
+		/// The records
+		records: HashMap<(i32, i32), SearchRecord<(i32, i32)>>,
+		/// Number of calls to `put`
+		puts: usize,
+	}
+
+	impl SearchStorage<(i32, i32)> for CountingStorage {
+		fn get(&self, node: &(i32, i32)) -> Option<SearchRecord<(i32, i32)>> {
+			self.records.get(node).copied()
+		}
+		fn put(&mut self, node: (i32, i32), record: SearchRecord<(i32, i32)>) {
+			self.puts += 1;
+			self.records.insert(node, record);
+		}
+	}
+
+	#[test]
+	/// Searching through external storage finds a path of the same cost as the in-memory search
+	/// and leaves the records behind in the storage
+	fn matches_in_memory_search() {
+		let mut nodes = HashMap::new();
+		for column in 0..6 {
+			for row in 0..6 {
+				nodes.insert((column, row), 1.0 + ((column * row) % 4) as f32);
+			}
+		}
+		let layout = OffsetLayout::new(HexOrientation::PointyTopOddLeft, -1, 6, -1, 6);
+		let grid = HexGrid::new(layout, nodes);
+		let expected = astar_path(&grid, (0, 0), (5, 5)).unwrap();
+		let mut storage = CountingStorage {
+			records: HashMap::new(),
+			puts: 0,
+		};
+		let path = astar_path_with_storage(&grid, (0, 0), (5, 5), &mut storage).unwrap();
+		assert_eq!(expected.cost(), path.cost());
+		assert_eq!(Some(&(0, 0)), path.first());
+		assert!(storage.puts > 0);
+		assert_eq!(Some(path.cost()), storage.get(&(5, 5)).map(|r| r.cost));
+		let mut in_memory = HashMap::new();
+		let again = astar_path_with_storage(&grid, (0, 0), (5, 5), &mut in_memory).unwrap();
+		assert_eq!(path, again);
+	}
+}
@@ -0,0 +1,192 @@
+//! Stamp patterns of hexagons onto a grid.
+//!
+//! Building footprints, spell areas and prefabricated chunks of map are all a pattern of hexagons
+//! placed relative to some anchor. A `Template` records the pattern as Cubic offsets from the
+//! anchor, with a value for each hexagon, so it can be placed anywhere on a grid of any layout
+//! and turned to face any of the six directions:
+//!
+//! ```txt
+//!              _______                                     _______
+//!             /       \                                   /       \
+//!     _______/    B    \                                 /    B    \
+//!    /       \         /                                 \         /
+//!   /    B    \_______/        rotate once                \_______/
+//!   \         /       \      ------------->               /       \
+//!    \_______/    A    \                          _______/    A    \_______
+//!            \         /                         /       \         /       \
+//!             \_______/                         /    B    \_______/    B    \
+//!                                               \         /       \         /
+//!                                                \_______/         \_______/
+//! ```
+//!
+//! `A` is the anchor, the template is placed by choosing the node the anchor lands on.
+
+use crate::grid::GridEdit;
+use crate::grid::GridLayout;
+use crate::grid::HexGrid;
+use crate::helpers::node_rotate_cubic;
+use crate::node_store::NodeStore;
+use crate::node_store::NodeStoreMut;
+
+/// A pattern of hexagons described by their Cubic offsets from an anchor, each holding a value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template<V> {
+	/// The offset of each hexagon from the anchor and its value
+	cells: Vec<((i32, i32, i32), V)>,
+}
+
+impl<V> Default for Template<V> {
+	fn default() -> Self {
+		Template { cells: Vec::new() }
+	}
+}
+
+impl<V: Clone> Template<V> {
+	/// Create an empty template
+	pub fn new() -> Template<V> {
+		Template::default()
+	}
+	/// Create a template from the Cubic offset of each hexagon from the anchor, `(0, 0, 0)` being
+	/// the anchor itself, and its value
+	pub fn from_cells<I: IntoIterator<Item = ((i32, i32, i32), V)>>(cells: I) -> Template<V> {
+		let mut template = Template::new();
+		for (offset, value) in cells {
+			template.insert(offset, value);
+		}
+		template
+	}
+	/// Add a hexagon at `offset` from the anchor, replacing the value of an existing one. Panics
+	/// if `offset` is not a valid Cubic coordinate
+	pub fn insert(&mut self, offset: (i32, i32, i32), value: V) {
+		if offset.0 + offset.1 + offset.2 != 0 {
+			panic!(
+				"Template offset ({},{},{}) does not satisfy x + y + z = 0",
+				offset.0, offset.1, offset.2
+			);
+		}
+		match self.cells.iter_mut().find(|(o, _)| *o == offset) {
+			Some(cell) => cell.1 = value,
+			None => self.cells.push((offset, value)),
+		}
+	}
+	/// The offset of each hexagon from the anchor and its value, in the order they were added
+	pub fn cells(&self) -> &[((i32, i32, i32), V)] {
+		&self.cells
+	}
+	/// Number of hexagons in the template
+	pub fn len(&self) -> usize {
+		self.cells.len()
+	}
+	/// Whether the template holds no hexagons
+	pub fn is_empty(&self) -> bool {
+		self.cells.is_empty()
+	}
+	/// The nodes of `layout` covered by placing the anchor on `anchor` after turning the template
+	/// by `rotation` sixths of a full turn, see `helpers::node_rotate_cubic()`, along with their
+	/// values
+	pub fn placed<L: GridLayout>(
+		&self,
+		layout: &L,
+		anchor: L::Coord,
+		rotation: i32,
+	) -> Vec<(L::Coord, V)> {
+		let centre = layout.node_to_cubic(anchor);
+		let mut placed = Vec::with_capacity(self.cells.len());
+		for (offset, value) in self.cells.iter() {
+			let turned = node_rotate_cubic(*offset, (0, 0, 0), rotation);
+			let cube = (
+				centre.0 + turned.0,
+				centre.1 + turned.1,
+				centre.2 + turned.2,
+			);
+			placed.push((layout.cubic_to_node(cube), value.clone()));
+		}
+		placed
+	}
+	/// Whether every node covered by placing the template is part of `grid` and passable, e.g
+	/// whether a building footprint has room
+	pub fn fits<L: GridLayout, S: NodeStore<L::Coord>>(
+		&self,
+		grid: &HexGrid<L, S>,
+		anchor: L::Coord,
+		rotation: i32,
+	) -> bool {
+		self.placed(grid.layout(), anchor, rotation)
+			.iter()
+			.all(|(n, _)| grid.is_passable(n))
+	}
+}
+
+impl Template<f32> {
+	/// The edits which stamp the template onto a grid of `layout`, setting the complexity of each
+	/// covered node to the value of its hexagon
+	pub fn edits<L: GridLayout>(
+		&self,
+		layout: &L,
+		anchor: L::Coord,
+		rotation: i32,
+	) -> Vec<GridEdit<L::Coord>> {
+		self.placed(layout, anchor, rotation)
+			.into_iter()
+			.map(|(node, complexity)| GridEdit::SetComplexity(node, complexity))
+			.collect()
+	}
+	/// Stamp the template onto `grid`, setting the complexity of each covered node to the value
+	/// of its hexagon. Covered nodes which are not part of the grid, such as those hanging over
+	/// its edge, are left out. Returns the number of nodes changed
+	pub fn stamp<L: GridLayout, S: NodeStoreMut<L::Coord>>(
+		&self,
+		grid: &mut HexGrid<L, S>,
+		anchor: L::Coord,
+		rotation: i32,
+	) -> usize {
+		let edits = self.edits(grid.layout(), anchor, rotation);
+		grid.apply(edits)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::AxialLayout;
+	use crate::grid::OffsetLayout;
+	use crate::template::*;
+	use crate::HexOrientation;
+	use ::std::collections::HashMap;
+
+	/// A template of three hexagons, the anchor and the two hexagons in front of it
+	fn wedge() -> Template<f32> {
+		Template::from_cells(vec![((0, 0, 0), 5.0), ((1, -1, 0), 3.0), ((0, -1, 1), 3.0)])
+	}
+	#[test]
+	/// Placing on an Axial grid offsets the pattern from the anchor and rotating turns it
+	fn place_and_rotate() {
+		let layout = AxialLayout::new(3);
+		let placed = wedge().placed(&layout, (1, 0), 0);
+		assert_eq!(vec![((1, 0), 5.0), ((2, 0), 3.0), ((1, 1), 3.0)], placed);
+		let turned = wedge().placed(&layout, (1, 0), 1);
+		assert_eq!(vec![((1, 0), 5.0), ((1, 1), 3.0), ((0, 1), 3.0)], turned);
+	}
+	#[test]
+	/// Hexagons hanging over the edge of the grid are not stamped and the template does not fit
+	fn clipped_at_boundary() {
+		let mut nodes = HashMap::new();
+		for column in 0..3 {
+			for row in 0..3 {
+				nodes.insert((column, row), 1.0);
+			}
+		}
+		let layout = OffsetLayout::new(HexOrientation::FlatTopOddUp, -1, 3, -1, 3);
+		let mut grid = HexGrid::new(layout, nodes);
+		assert!(wedge().fits(&grid, (0, 1), 0));
+		assert!(!wedge().fits(&grid, (2, 1), 0));
+		assert_eq!(3, wedge().stamp(&mut grid, (0, 1), 0));
+		assert_eq!(Some(5.0), grid.complexity(&(0, 1)));
+		assert_eq!(2, wedge().stamp(&mut grid, (2, 1), 0));
+	}
+	#[test]
+	#[should_panic]
+	/// Offsets must be valid Cubic coordinates
+	fn invalid_offset() {
+		Template::new().insert((1, 1, 0), 1.0);
+	}
+}
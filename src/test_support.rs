@@ -0,0 +1,248 @@
+//! Fixtures and reference implementations for checking pathfinding against this crate's semantics.
+//!
+//! Crates which embed their own cost model, by implementing `SearchSpace` or wrapping a `HexGrid`,
+//! can use this module from their tests to confirm the paths they get back are optimal:
+//!
+//! * `axial_fixture` and `offset_fixture` build grids of pseudo-random complexities from a seed,
+//!   the same seed always giving the same grid
+//! * `reference_cost` finds the cheapest cost between two nodes by brute force, repeatedly relaxing
+//!   every step until no cost improves. It is slow but simple enough to trust
+//! * `assert_path_valid` and `assert_path_optimal` panic with a description of the first problem
+//!   found in a path
+//!
+//! ```
+//! use hexagonal_pathfinding_astar::search::astar_path;
+//! use hexagonal_pathfinding_astar::test_support::*;
+//! let grid = axial_fixture(3, 7);
+//! for goal in [(3, 0), (-2, 3), (0, -3)] {
+//!     let path = astar_path(&grid, (0, 0), goal);
+//!     assert_path_optimal(&grid, (0, 0), goal, path.as_ref());
+//! }
+//! ```
+
+use crate::grid::AxialLayout;
+use crate::grid::HexGrid;
+use crate::grid::OffsetLayout;
+use crate::path::Path;
+use crate::search::SearchSpace;
+use crate::GridBounds;
+use crate::HexOrientation;
+use ::std::collections::HashMap;
+
+/// Difference in cost tolerated between a path and the reference, relative to the larger cost
+const TOLERANCE: f32 = 1e-4;
+
+/// A small deterministic generator of complexities so fixtures need no dependencies
+struct Complexities {
+	/// State of the xorshift generator, never zero
+	state: u64,
+}
+
+impl Complexities {
+	/// Create a generator from a seed
+	fn new(seed: u64) -> Complexities {
+		Complexities {
+			state: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1,
+		}
+	}
+	/// The next complexity, a whole number from `1` to `9` or one time in eight impassable
+	fn next(&mut self) -> f32 {
+		self.state ^= self.state << 13;
+		self.state ^= self.state >> 7;
+		self.state ^= self.state << 17;
+		match self.state % 80 {
+			0..=9 => f32::INFINITY,
+			x => (x % 9 + 1) as f32,
+		}
+	}
+}
+
+/// A hexagon of Axial nodes `count_rings` rings around the origin with complexities generated
+/// from `seed`. The origin is always passable with a complexity of `1`
+pub fn axial_fixture(count_rings: i32, seed: u64) -> HexGrid<AxialLayout> {
+	let mut complexities = Complexities::new(seed);
+	let mut nodes = HashMap::new();
+	for q in -count_rings..=count_rings {
+		for r in -count_rings..=count_rings {
+			if (q + r).abs() <= count_rings {
+				nodes.insert((q, r), complexities.next());
+			}
+		}
+	}
+	nodes.insert((0, 0), 1.0);
+	HexGrid::new(AxialLayout::new(count_rings), nodes)
+}
+
+/// A rectangle of Offset nodes filling `bounds` with complexities generated from `seed`. The
+/// bottom left node is always passable with a complexity of `1`
+pub fn offset_fixture(
+	bounds: GridBounds,
+	orientation: HexOrientation,
+	seed: u64,
+) -> HexGrid<OffsetLayout> {
+	let mut complexities = Complexities::new(seed);
+	let mut nodes = HashMap::new();
+	for column in bounds.min_column..=bounds.max_column {
+		for row in bounds.min_row..=bounds.max_row {
+			nodes.insert((column, row), complexities.next());
+		}
+	}
+	nodes.insert((bounds.min_column, bounds.min_row), 1.0);
+	HexGrid::new(OffsetLayout::with_bounds(orientation, bounds), nodes)
+}
+
+/// The cheapest cost of travelling from `start` to `goal` across `space`, found by brute force
+/// without a heuristic, or `None` if `goal` cannot be reached
+pub fn reference_cost<S: SearchSpace>(space: &S, start: S::Node, goal: S::Node) -> Option<f32> {
+	let mut costs: HashMap<S::Node, f32> = HashMap::new();
+	costs.insert(start, 0.0);
+	let mut improved = true;
+	while improved {
+		improved = false;
+		let known: Vec<(S::Node, f32)> = costs.iter().map(|(n, c)| (*n, *c)).collect();
+		for (node, cost) in known {
+			for (next, step) in space.successors(node) {
+				let candidate = cost + step;
+				let better = match costs.get(&next) {
+					Some(existing) => candidate < *existing,
+					None => true,
+				};
+				if better {
+					costs.insert(next, candidate);
+					improved = true;
+				}
+			}
+		}
+	}
+	costs.get(&goal).copied()
+}
+
+/// Panic unless every step of `path` is a successor of the node before it in `space` and the
+/// cumulative costs of the path add up the cost of each step
+pub fn assert_path_valid<S: SearchSpace>(space: &S, path: &Path<S::Node>) {
+	let costs = path.cumulative_costs();
+	if let Some(first) = costs.first() {
+		if *first != 0.0 {
+			panic!("Path starts with a cost of {} rather than 0", first);
+		}
+	}
+	for (i, pair) in path.nodes().windows(2).enumerate() {
+		let step = space
+			.successors(pair[0])
+			.into_iter()
+			.filter(|(n, _)| *n == pair[1])
+			.map(|(_, c)| c)
+			.fold(None, |best: Option<f32>, c| match best {
+				Some(b) if b <= c => Some(b),
+				_ => Some(c),
+			});
+		match step {
+			Some(step) => {
+				let expected = costs[i] + step;
+				if !approx_eq(expected, costs[i + 1]) {
+					panic!(
+						"Step {} from {:?} to {:?} reaches a cumulative cost of {} rather than {}",
+						i,
+						pair[0],
+						pair[1],
+						costs[i + 1],
+						expected
+					);
+				}
+			}
+			None => panic!(
+				"Step {} from {:?} to {:?} is not a successor",
+				i, pair[0], pair[1]
+			),
+		}
+	}
+}
+
+/// Panic unless `path` is a valid path from `start` to `goal` in `space` costing no more than
+/// the reference, or is `None` when `goal` cannot be reached
+pub fn assert_path_optimal<S: SearchSpace>(
+	space: &S,
+	start: S::Node,
+	goal: S::Node,
+	path: Option<&Path<S::Node>>,
+) {
+	let reference = reference_cost(space, start, goal);
+	match (path, reference) {
+		(Some(path), Some(reference)) => {
+			if path.first() != Some(&start) || path.last() != Some(&goal) {
+				panic!(
+					"Path runs from {:?} to {:?} rather than {:?} to {:?}",
+					path.first(),
+					path.last(),
+					start,
+					goal
+				);
+			}
+			assert_path_valid(space, path);
+			if !approx_eq(path.cost(), reference) {
+				panic!(
+					"Path from {:?} to {:?} costs {} but the cheapest costs {}",
+					start,
+					goal,
+					path.cost(),
+					reference
+				);
+			}
+		}
+		(None, None) => {}
+		(Some(_), None) => panic!(
+			"A path was found from {:?} to {:?} but the goal is unreachable",
+			start, goal
+		),
+		(None, Some(reference)) => panic!(
+			"No path was found from {:?} to {:?} but one costing {} exists",
+			start, goal, reference
+		),
+	}
+}
+
+/// Whether two costs are equal within `TOLERANCE`
+fn approx_eq(a: f32, b: f32) -> bool {
+	if a == b {
+		return true;
+	}
+	(a - b).abs() <= TOLERANCE * a.abs().max(b.abs()).max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::search::astar_path;
+	use crate::test_support::*;
+
+	#[test]
+	/// The search finds optimal paths across a range of fixtures in each layout
+	fn astar_matches_reference() {
+		for seed in 0..8 {
+			let grid = axial_fixture(3, seed);
+			for goal in [(3, 0), (-3, 3), (0, -3), (2, 1)] {
+				let path = astar_path(&grid, (0, 0), goal);
+				assert_path_optimal(&grid, (0, 0), goal, path.as_ref());
+			}
+			let bounds = GridBounds::from_size(5, 4);
+			let grid = offset_fixture(bounds, HexOrientation::PointyTopOddRight, seed);
+			for goal in [(4, 3), (0, 3), (4, 0)] {
+				let path = astar_path(&grid, (0, 0), goal);
+				assert_path_optimal(&grid, (0, 0), goal, path.as_ref());
+			}
+		}
+	}
+	#[test]
+	#[should_panic]
+	/// A path taking a detour around the cheapest route is rejected
+	fn detour_is_not_optimal() {
+		let grid = HexGrid::new(
+			AxialLayout::new(1),
+			[(0, 0), (1, 0), (1, -1), (0, -1)]
+				.iter()
+				.map(|n| (*n, 1.0))
+				.collect::<HashMap<_, _>>(),
+		);
+		let detour = Path::new(vec![(0, 0), (1, -1), (0, -1)], vec![0.0, 1.0, 2.0]);
+		assert_path_optimal(&grid, (0, 0), (0, -1), Some(&detour));
+	}
+}
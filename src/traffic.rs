@@ -0,0 +1,198 @@
+//! Keep track of the routes units have committed to so that later units plan around them.
+//!
+//! When many units path across the same map they tend to pick the same cheapest route and pile
+//! into one corridor. Once a unit chooses a path it is committed to a `Traffic` table which:
+//!
+//! * adds a congestion penalty to every hexagon on the path, so searches made through
+//!   `Traffic::costs` favour quieter routes
+//! * reserves each hexagon of the path for the step the unit expects to occupy it, the first
+//!   node at step `0`, the next at step `1` and so on
+//!
+//! When the unit finishes or abandons its route the commitment is released which removes both
+//! the penalties and the reservations:
+//!
+//! ```txt
+//!  commit(path) ---> CommitmentId ---> release(id)
+//!      |                                   |
+//!      +-- load += 1 on each hex           +-- load -= 1 on each hex
+//!      +-- reserve (hex, step)             +-- free (hex, step)
+//! ```
+
+use crate::path::Path;
+use crate::search::SearchSpace;
+use ::std::collections::hash_map::Entry;
+use ::std::collections::HashMap;
+use ::std::hash::Hash;
+
+/// Identifies a path committed to a `Traffic` table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CommitmentId(usize);
+
+/// The nodes crossed by a committed path and the reservations it holds
+#[derive(Debug, Clone)]
+struct Commitment<C> {
+	/// Every node of the path
+	nodes: Vec<C>,
+	/// The `(node, step)` reservations held
+	reserved: Vec<(C, usize)>,
+}
+
+/// The paths units have committed to, their congestion and the reservations they hold
+#[derive(Debug, Clone)]
+pub struct Traffic<C> {
+	/// Cost added to a node for each committed path crossing it
+	penalty_per_unit: f32,
+	/// Number of committed paths crossing each node
+	load: HashMap<C, u32>,
+	/// Which commitment holds each node at each step
+	reservations: HashMap<(C, usize), CommitmentId>,
+	/// The nodes and reservations held by each commitment
+	commitments: HashMap<CommitmentId, Commitment<C>>,
+	/// Identifier of the next commitment
+	next_id: usize,
+}
+
+impl<C: Copy + Eq + Hash> Traffic<C> {
+	/// Create an empty table where each path committed across a node adds `penalty_per_unit`
+	/// to the cost of entering it
+	pub fn new(penalty_per_unit: f32) -> Traffic<C> {
+		Traffic {
+			penalty_per_unit,
+			load: HashMap::new(),
+			reservations: HashMap::new(),
+			commitments: HashMap::new(),
+			next_id: 0,
+		}
+	}
+	/// Commit to `path`, adding congestion to each of its nodes and reserving each node for the
+	/// step it is reached at. A reservation already held by another commitment is left with
+	/// that commitment
+	pub fn commit(&mut self, path: &Path<C>) -> CommitmentId {
+		let id = CommitmentId(self.next_id);
+		self.next_id += 1;
+		let mut nodes = Vec::with_capacity(path.len());
+		let mut reserved = Vec::new();
+		for (step, node) in path.iter().enumerate() {
+			*self.load.entry(*node).or_insert(0) += 1;
+			nodes.push(*node);
+			if let Entry::Vacant(e) = self.reservations.entry((*node, step)) {
+				e.insert(id);
+				reserved.push((*node, step));
+			}
+		}
+		self.commitments.insert(id, Commitment { nodes, reserved });
+		id
+	}
+	/// Remove the congestion and reservations of a commitment, returning `false` if it had
+	/// already been released
+	pub fn release(&mut self, id: CommitmentId) -> bool {
+		let commitment = match self.commitments.remove(&id) {
+			Some(x) => x,
+			None => return false,
+		};
+		for node in commitment.nodes.iter() {
+			if let Some(load) = self.load.get_mut(node) {
+				*load -= 1;
+				if *load == 0 {
+					self.load.remove(node);
+				}
+			}
+		}
+		for key in commitment.reserved.iter() {
+			self.reservations.remove(key);
+		}
+		true
+	}
+	/// Number of committed paths crossing `node`
+	pub fn load(&self, node: &C) -> u32 {
+		self.load.get(node).copied().unwrap_or(0)
+	}
+	/// The cost added to entering `node` by congestion
+	pub fn penalty(&self, node: &C) -> f32 {
+		self.load(node) as f32 * self.penalty_per_unit
+	}
+	/// The commitment holding `node` at `step`, if any
+	pub fn reserved_by(&self, node: &C, step: usize) -> Option<CommitmentId> {
+		self.reservations.get(&(*node, step)).copied()
+	}
+	/// Number of commitments which have not been released
+	pub fn count_commitments(&self) -> usize {
+		self.commitments.len()
+	}
+	/// A view of `space` where entering a node also costs its congestion penalty
+	pub fn costs<'a, S: SearchSpace<Node = C>>(&'a self, space: &'a S) -> TrafficCosts<'a, S> {
+		TrafficCosts {
+			space,
+			traffic: self,
+		}
+	}
+}
+
+/// A `SearchSpace` with the congestion penalties of a `Traffic` table added, created with
+/// `Traffic::costs`
+pub struct TrafficCosts<'a, S: SearchSpace> {
+	/// The space being searched
+	space: &'a S,
+	/// The congestion of each node
+	traffic: &'a Traffic<S::Node>,
+}
+
+impl<'a, S: SearchSpace> SearchSpace for TrafficCosts<'a, S> {
+	type Node = S::Node;
+	fn successors(&self, node: S::Node) -> Vec<(S::Node, f32)> {
+		self.space
+			.successors(node)
+			.into_iter()
+			.map(|(n, cost)| (n, cost + self.traffic.penalty(&n)))
+			.collect()
+	}
+	fn heuristic(&self, node: S::Node, goal: S::Node) -> f32 {
+		self.space.heuristic(node, goal)
+	}
+	fn predecessors(&self, node: S::Node) -> Vec<(S::Node, f32)> {
+		let penalty = self.traffic.penalty(&node);
+		self.space
+			.predecessors(node)
+			.into_iter()
+			.map(|(n, cost)| (n, cost + penalty))
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::CubicLayout;
+	use crate::grid::HexGrid;
+	use crate::search::astar_path;
+	use crate::traffic::*;
+
+	#[test]
+	/// A second unit avoids the route committed by the first, and takes it again once released
+	/// ```txt
+	///        (1,-1,0)
+	///       /        \
+	///  (0,0,0)      (2,-1,-1)
+	///       \        /
+	///        (1,0,-1)
+	/// ```
+	fn commit_and_release() {
+		let mut nodes = HashMap::new();
+		for node in [(0, 0, 0), (1, -1, 0), (1, 0, -1), (2, -1, -1)] {
+			nodes.insert(node, 1.0);
+		}
+		let grid = HexGrid::new(CubicLayout::new(2), nodes);
+		let mut traffic = Traffic::new(5.0);
+		let first = astar_path(&traffic.costs(&grid), (0, 0, 0), (2, -1, -1)).unwrap();
+		let id = traffic.commit(&first);
+		let middle = first.nodes()[1];
+		assert_eq!(1, traffic.load(&middle));
+		assert_eq!(Some(id), traffic.reserved_by(&middle, 1));
+		let second = astar_path(&traffic.costs(&grid), (0, 0, 0), (2, -1, -1)).unwrap();
+		assert!(!second.contains(&middle));
+		assert!(traffic.release(id));
+		assert!(!traffic.release(id));
+		assert_eq!(0, traffic.load(&middle));
+		assert_eq!(None, traffic.reserved_by(&middle, 1));
+		assert_eq!(0, traffic.count_commitments());
+	}
+}
@@ -0,0 +1,156 @@
+//! Cheap ambient movement which drifts down a scalar field instead of searching.
+//!
+//! Crowds, wildlife and other background agents rarely need a planned route, they only need to
+//! move away from danger or towards comfort. Given a value for each hexagon, such as the danger
+//! from an influence map, an agent drifts downhill by repeatedly stepping to its lowest
+//! neighbour until no neighbour is lower:
+//!
+//! ```txt
+//!              _______
+//!             /       \
+//!     _______/    5    \_______
+//!    /       \         /       \
+//!   /    4    \_______/    3    \
+//!   \         /       \         /
+//!    \_______/    2    \_______/
+//!    /       \    S    /       \
+//!   /    1    \_______/    6    \
+//!   \    E    /       \         /
+//!    \_______/    7    \_______/
+//!            \         /
+//!             \_______/
+//! ```
+//!
+//! Starting at `S` the agent steps to the `1` at `E`, its lowest neighbour, where it stays as no
+//! neighbour of `E` is lower.
+//!
+//! Where several neighbours are equally low the choice is left to a caller-supplied `pick`
+//! function, usually backed by a random number generator, so agents sharing a field spread out
+//! rather than queue along the same line. Every step only looks at six neighbours, making this
+//! far cheaper than a search per agent.
+
+use crate::grid::GridLayout;
+use crate::grid::HexGrid;
+use crate::node_store::NodeStore;
+use ::std::collections::HashMap;
+
+/// The neighbour of `node` a drift moves to, the passable neighbour with the lowest value in
+/// `field` provided it is lower than the value of `node`. When several neighbours share the
+/// lowest value `pick` is called with their count and returns the index of the one to take.
+/// Nodes absent from `field` are never moved to. `None` when `node` is at a local minimum
+pub fn drift_step<L: GridLayout, S: NodeStore<L::Coord>, F: FnMut(usize) -> usize>(
+	grid: &HexGrid<L, S>,
+	field: &HashMap<L::Coord, f32>,
+	node: L::Coord,
+	pick: &mut F,
+) -> Option<L::Coord> {
+	let current = field.get(&node).copied().unwrap_or(f32::INFINITY);
+	let mut lowest = current;
+	let mut candidates: Vec<L::Coord> = Vec::new();
+	for n in grid.neighbours(node) {
+		let value = match field.get(&n) {
+			Some(v) => *v,
+			None => continue,
+		};
+		if value < lowest {
+			lowest = value;
+			candidates.clear();
+			candidates.push(n);
+		} else if value == lowest && value < current {
+			candidates.push(n);
+		}
+	}
+	match candidates.len() {
+		0 => None,
+		1 => Some(candidates[0]),
+		count => {
+			// sorted so the same pick always chooses the same node regardless of layout order
+			candidates.sort();
+			Some(candidates[pick(count) % count])
+		}
+	}
+}
+
+/// Drift from `start` for at most `max_steps` steps, stopping early at a local minimum of
+/// `field`. Returns the nodes visited beginning with `start`, see `drift_step` for how each step
+/// is chosen
+pub fn drift<L: GridLayout, S: NodeStore<L::Coord>, F: FnMut(usize) -> usize>(
+	grid: &HexGrid<L, S>,
+	field: &HashMap<L::Coord, f32>,
+	start: L::Coord,
+	max_steps: usize,
+	mut pick: F,
+) -> Vec<L::Coord> {
+	let mut nodes = vec![start];
+	let mut current = start;
+	for _ in 0..max_steps {
+		match drift_step(grid, field, current, &mut pick) {
+			Some(next) => {
+				nodes.push(next);
+				current = next;
+			}
+			None => break,
+		}
+	}
+	nodes
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::CubicLayout;
+	use crate::helpers::node_distance;
+	use crate::wander::*;
+
+	/// A ring of 1 with a complexity of 1 everywhere
+	fn ring_grid() -> HexGrid<CubicLayout> {
+		let mut nodes = HashMap::new();
+		for node in [
+			(0, 0, 0),
+			(0, -1, 1),
+			(1, -1, 0),
+			(1, 0, -1),
+			(0, 1, -1),
+			(-1, 1, 0),
+			(-1, 0, 1),
+		] {
+			nodes.insert(node, 1.0);
+		}
+		HexGrid::new(CubicLayout::new(1), nodes)
+	}
+	/// A field of the distance of each node of the ring from `(1, -1, 0)`
+	fn valley() -> HashMap<(i32, i32, i32), f32> {
+		ring_grid()
+			.nodes()
+			.keys()
+			.map(|n| (*n, node_distance(*n, (1, -1, 0)) as f32))
+			.collect()
+	}
+	#[test]
+	/// Drifting heads straight downhill and stops at the bottom of the valley
+	fn drift_to_minimum() {
+		let grid = ring_grid();
+		let field = valley();
+		let path = drift(&grid, &field, (-1, 1, 0), 10, |_| 0);
+		assert_eq!(vec![(-1, 1, 0), (0, 0, 0), (1, -1, 0)], path);
+		assert_eq!(None, drift_step(&grid, &field, (1, -1, 0), &mut |_| 0));
+		assert_eq!(2, drift(&grid, &field, (-1, 1, 0), 1, |_| 0).len());
+	}
+	#[test]
+	/// Equally low neighbours are chosen between by the pick function
+	fn ties_use_pick() {
+		let grid = ring_grid();
+		let mut field = valley();
+		field.insert((1, -1, 0), 2.0);
+		field.insert((0, 0, 0), 3.0);
+		// (0, -1, 1) and (1, 0, -1) are now both lowest around the origin
+		let mut counts = Vec::new();
+		let first = drift_step(&grid, &field, (0, 0, 0), &mut |n| {
+			counts.push(n);
+			0
+		});
+		let second = drift_step(&grid, &field, (0, 0, 0), &mut |_| 1);
+		assert_eq!(vec![2], counts);
+		assert_eq!(Some((0, -1, 1)), first);
+		assert_eq!(Some((1, 0, -1)), second);
+	}
+}